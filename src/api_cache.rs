@@ -0,0 +1,244 @@
+//! Cache of Web API responses that rarely change, like album or artist lookups, so revisiting a
+//! view doesn't fetch the same data again.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+use chrono::{DateTime, Duration, Utc};
+use log::debug;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::config;
+use crate::serialization::{Serializer, CBOR};
+
+/// The directory inside the cache directory that responses are stored in.
+const DIRECTORY: &str = "api";
+
+/// How long responses are kept by default, in seconds.
+pub const DEFAULT_TTL: u64 = 3600;
+
+/// A response as stored on disk.
+#[derive(Serialize, Deserialize)]
+struct DiskEntry<T> {
+    fetched_at: DateTime<Utc>,
+    value: T,
+}
+
+/// The part of a [DiskEntry] needed to tell whether it expired, regardless of its value.
+#[derive(Serialize, Deserialize)]
+struct DiskEntryAge {
+    fetched_at: DateTime<Utc>,
+}
+
+/// Responses are kept in memory for the current session and on disk across sessions. Entries
+/// older than the time to live are ignored and fetched again.
+pub struct ApiCache {
+    ttl: Duration,
+    directory: PathBuf,
+    memory: RwLock<HashMap<String, (DateTime<Utc>, Arc<dyn Any + Send + Sync>)>>,
+}
+
+impl ApiCache {
+    /// Create a cache that keeps responses for `ttl` seconds. A `ttl` of 0 disables the cache.
+    pub fn new(ttl: u64) -> Self {
+        Self::in_directory(ttl, config::cache_path(DIRECTORY))
+    }
+
+    /// Create a cache that stores responses in `directory`, dropping the ones that expired.
+    fn in_directory(ttl: u64, directory: PathBuf) -> Self {
+        let cache = Self {
+            ttl: Duration::seconds(ttl as i64),
+            directory,
+            memory: RwLock::new(HashMap::new()),
+        };
+        cache.prune();
+        cache
+    }
+
+    /// Remove the stored responses that expired, or all of them if the cache is disabled.
+    fn prune(&self) {
+        let Ok(entries) = std::fs::read_dir(&self.directory) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let age: Result<DiskEntryAge, String> = CBOR.load(entry.path());
+            let expired = !age.is_ok_and(|age| self.is_fresh(&age.fetched_at));
+            if (expired || !self.enabled()) && std::fs::remove_file(entry.path()).is_ok() {
+                debug!("removed expired response {}", entry.path().display());
+            }
+        }
+    }
+
+    fn enabled(&self) -> bool {
+        !self.ttl.is_zero()
+    }
+
+    fn is_fresh(&self, fetched_at: &DateTime<Utc>) -> bool {
+        Utc::now() - *fetched_at < self.ttl
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        let file_name = key.replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+        self.directory.join(format!("{file_name}.cbor"))
+    }
+
+    /// Get the response stored under `key` if it hasn't expired yet.
+    pub fn get<T>(&self, key: &str) -> Option<T>
+    where
+        T: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+    {
+        if !self.enabled() {
+            return None;
+        }
+
+        if let Some((fetched_at, value)) = self.memory.read().unwrap().get(key) {
+            if self.is_fresh(fetched_at) {
+                return value.downcast_ref::<T>().cloned();
+            }
+        }
+
+        let entry: DiskEntry<T> = CBOR.load(self.path(key)).ok()?;
+        if !self.is_fresh(&entry.fetched_at) {
+            return None;
+        }
+        debug!("using cached response for {}", key);
+        self.memory.write().unwrap().insert(
+            key.to_string(),
+            (entry.fetched_at, Arc::new(entry.value.clone())),
+        );
+        Some(entry.value)
+    }
+
+    /// Store `value` as the response for `key`.
+    pub fn insert<T>(&self, key: &str, value: &T)
+    where
+        T: Clone + Send + Sync + Serialize + 'static,
+    {
+        if !self.enabled() {
+            return;
+        }
+
+        let fetched_at = Utc::now();
+        self.memory
+            .write()
+            .unwrap()
+            .insert(key.to_string(), (fetched_at, Arc::new(value.clone())));
+
+        if std::fs::create_dir_all(&self.directory).is_ok() {
+            let entry = DiskEntry { fetched_at, value };
+            if let Err(e) = CBOR.write(self.path(key), entry) {
+                debug!("can't store response for {}: {}", key, e);
+            }
+        }
+    }
+
+    /// Get the response stored under `key`, or call `fetch` and store its result.
+    pub fn get_or_fetch<T, F>(&self, key: &str, fetch: F) -> Result<T, ()>
+    where
+        T: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+        F: FnOnce() -> Result<T, ()>,
+    {
+        if let Some(value) = self.get(key) {
+            return Ok(value);
+        }
+        let value = fetch()?;
+        self.insert(key, &value);
+        Ok(value)
+    }
+
+    /// Drop the response stored under `key`, so it is fetched again on the next request.
+    pub fn remove(&self, key: &str) {
+        self.memory.write().unwrap().remove(key);
+        let _ = std::fs::remove_file(self.path(key));
+    }
+
+    /// Drop all stored responses, so they are fetched again on the next request.
+    pub fn clear(&self) {
+        self.memory.write().unwrap().clear();
+        let _ = std::fs::remove_dir_all(&self.directory);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_directory(name: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("ncspot-api-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        directory
+    }
+
+    fn write_entry(cache: &ApiCache, key: &str, fetched_at: DateTime<Utc>, value: &str) {
+        std::fs::create_dir_all(&cache.directory).unwrap();
+        CBOR.write(cache.path(key), DiskEntry { fetched_at, value })
+            .unwrap();
+    }
+
+    #[test]
+    fn response_is_read_back_from_disk() {
+        let directory = test_directory("round-trip");
+        let cache = ApiCache::in_directory(DEFAULT_TTL, directory.clone());
+        cache.insert("album:1", &vec!["a".to_string(), "b".to_string()]);
+
+        let reopened = ApiCache::in_directory(DEFAULT_TTL, directory.clone());
+        let value: Option<Vec<String>> = reopened.get("album:1");
+        assert_eq!(value, Some(vec!["a".to_string(), "b".to_string()]));
+        assert_eq!(reopened.get::<Vec<String>>("album:2"), None);
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn expired_response_is_ignored() {
+        let directory = test_directory("expired");
+        let cache = ApiCache::in_directory(60, directory.clone());
+        write_entry(
+            &cache,
+            "track:old",
+            Utc::now() - Duration::seconds(61),
+            "old",
+        );
+        write_entry(
+            &cache,
+            "track:new",
+            Utc::now() - Duration::seconds(59),
+            "new",
+        );
+
+        assert_eq!(cache.get::<String>("track:old"), None);
+        assert_eq!(cache.get::<String>("track:new"), Some("new".to_string()));
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn expired_responses_are_pruned_on_load() {
+        let directory = test_directory("prune");
+        let cache = ApiCache::in_directory(60, directory.clone());
+        write_entry(
+            &cache,
+            "show:old",
+            Utc::now() - Duration::seconds(61),
+            "old",
+        );
+        write_entry(&cache, "show:new", Utc::now(), "new");
+
+        let reopened = ApiCache::in_directory(60, directory.clone());
+        assert!(!reopened.path("show:old").exists());
+        assert!(reopened.path("show:new").exists());
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn disabled_cache_stores_nothing() {
+        let directory = test_directory("disabled");
+        let cache = ApiCache::in_directory(0, directory.clone());
+        cache.insert("episode:1", &"value".to_string());
+
+        assert_eq!(cache.get::<String>("episode:1"), None);
+        assert!(!directory.exists());
+    }
+}
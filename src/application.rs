@@ -2,27 +2,41 @@ use std::error::Error;
 use std::path::Path;
 use std::rc::Rc;
 use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 
 use cursive::traits::Nameable;
+use cursive::view::Margins;
+use cursive::views::{Dialog, OnEventView};
 use cursive::{Cursive, CursiveRunner};
 use log::{error, info, trace};
+use ncspot::BIN_NAME;
 
 #[cfg(unix)]
-use signal_hook::{consts::SIGHUP, consts::SIGTERM, iterator::Signals};
+use signal_hook::{consts::SIGHUP, consts::SIGTERM, consts::SIGUSR1, iterator::Signals};
 
 use crate::command::Command;
 use crate::commands::CommandManager;
 use crate::config::{Config, PlaybackState};
-use crate::events::{Event, EventManager};
+use crate::events::{Event, EventManager, RemoteChange};
+use crate::ext_traits::CursiveExt;
+use crate::i18n::{tr, tr_args};
 use crate::library::Library;
-use crate::queue::Queue;
+use crate::model::playable::Playable;
+use crate::queue::{Queue, RepeatSetting};
 use crate::spotify::{PlayerEvent, Spotify};
+use crate::spotify_url::SpotifyUrl;
+use crate::status_file::StatusFile;
 use crate::ui::create_cursive;
-use crate::{authentication, ui, utils};
+use crate::ui::modal::Modal;
+use crate::{authentication, i18n, ui, utils};
 use crate::{command, queue, spotify};
 
+#[cfg(feature = "inhibit")]
+use crate::inhibit::SuspendInhibitor;
 #[cfg(feature = "mpris")]
 use crate::mpris::MprisManager;
+#[cfg(feature = "tray")]
+use crate::tray::TrayIcon;
 
 #[cfg(unix)]
 use crate::ipc::{self, IpcSocket};
@@ -56,6 +70,13 @@ pub struct UserDataInner {
     pub cmd: CommandManager,
 }
 
+/// How often the worker is restarted automatically before the user is asked to reconnect.
+const MAX_WORKER_RESTARTS: u32 = 3;
+
+/// How long to wait before the first automatic restart of the worker. The delay doubles with every
+/// further attempt, so a failing connection isn't retried in a tight loop.
+const WORKER_RESTART_DELAY: Duration = Duration::from_secs(1);
+
 /// The global Tokio runtime for running asynchronous tasks.
 pub static ASYNC_RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
 
@@ -63,6 +84,8 @@ pub static ASYNC_RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
 pub struct Application {
     /// The music queue which controls playback order.
     queue: Arc<Queue>,
+    /// The user's saved items, loaded once the session is established.
+    library: Arc<Library>,
     /// Internally shared
     spotify: Spotify,
     /// Internally shared
@@ -70,8 +93,24 @@ pub struct Application {
     /// An IPC implementation using the D-Bus MPRIS protocol, used to control and inspect ncspot.
     #[cfg(unix)]
     ipc: Option<IpcSocket>,
+    /// Keeps the system awake during playback, if enabled in the configuration.
+    #[cfg(feature = "inhibit")]
+    inhibitor: Option<SuspendInhibitor>,
+    /// Shows the current track in the system tray.
+    #[cfg(feature = "tray")]
+    tray: TrayIcon,
     /// The object to render to the terminal.
     cursive: CursiveRunner<Cursive>,
+    /// How often the worker was restarted since the session was last established.
+    worker_restarts: u32,
+    /// The last announcement of the playback state, to only announce changes.
+    last_announcement: Option<String>,
+    /// The title last given to the terminal window, to only update it on changes.
+    window_title: Option<String>,
+    /// Receives a line about the playing item for status lines, if `status_file` is set.
+    status_file: Option<StatusFile>,
+    /// The position of the playing item in the queue and the queue length last sent to MPRIS.
+    queue_position: Option<(usize, usize)>,
 }
 
 impl Application {
@@ -80,7 +119,11 @@ impl Application {
     /// # Arguments
     ///
     /// * `configuration_file_path` - Relative path to the configuration file inside the base path
-    pub fn new(configuration_file_path: Option<String>) -> Result<Self, Box<dyn Error>> {
+    /// * `url` - Item to play instead of resuming the last played track
+    pub fn new(
+        configuration_file_path: Option<String>,
+        url: Option<SpotifyUrl>,
+    ) -> Result<Self, Box<dyn Error>> {
         // Things here may cause the process to abort; we must do them before creating curses
         // windows otherwise the error message will not be seen by a user
 
@@ -94,6 +137,21 @@ impl Application {
             .unwrap();
 
         let configuration = Arc::new(Config::new(configuration_file_path));
+        i18n::set_language(configuration.values().language);
+
+        // Leave the playback to the instance that already uses this cache directory.
+        #[cfg(unix)]
+        if configuration.values().single_instance.unwrap_or(false) {
+            let commands: Vec<String> = url
+                .iter()
+                .map(|url| Command::PlayUrl(url.clone()).to_string())
+                .collect();
+            if ipc::hand_off(&commands) {
+                println!("{}", tr("ncspot is already running"));
+                std::process::exit(0);
+            }
+        }
+
         let credentials = authentication::get_credentials(&configuration)?;
         let theme = configuration.build_theme();
 
@@ -103,6 +161,7 @@ impl Application {
         let mut cursive = create_cursive().map_err(|error| error.to_string())?;
 
         cursive.set_theme(theme.clone());
+        crate::theme::watch_appearance(configuration.clone(), cursive.cb_sink().clone());
 
         #[cfg(all(unix, feature = "pancurses_backend"))]
         cursive.add_global_callback(cursive::event::Event::CtrlChar('z'), |_s| unsafe {
@@ -124,6 +183,7 @@ impl Application {
             spotify.clone(),
             configuration.clone(),
             library.clone(),
+            event_manager.clone(),
         ));
 
         #[cfg(feature = "mpris")]
@@ -137,11 +197,16 @@ impl Application {
         #[cfg(feature = "mpris")]
         spotify.set_mpris(mpris_manager.clone());
 
-        // Load the last played track into the player
+        #[cfg(feature = "tray")]
+        let tray = TrayIcon::new(event_manager.clone(), queue.clone(), library.clone());
+
+        // Play the item from the command line, or load the last played track into the player
         let playback_state = configuration.state().playback_state.clone();
         let queue_state = configuration.state().queuestate.clone();
 
-        if let Some(playable) = queue.get_current() {
+        if let Some(url) = url {
+            queue.play_url(&url);
+        } else if let Some(playable) = queue.get_current() {
             spotify.load(
                 &playable,
                 playback_state == PlaybackState::Playing,
@@ -165,6 +230,7 @@ impl Application {
                     ASYNC_RUNTIME.get().unwrap().handle(),
                     runtime_directory.join("ncspot.sock"),
                     event_manager.clone(),
+                    queue.queue.clone(),
                 )
                 .map_err(|e| e.to_string())?,
             )
@@ -173,6 +239,19 @@ impl Application {
             None
         };
 
+        #[cfg(feature = "inhibit")]
+        crate::sleep::watch_sleep_and_lock(
+            spotify.clone(),
+            configuration.values().pause_on_lock.unwrap_or(false),
+        );
+
+        #[cfg(feature = "inhibit")]
+        let inhibitor = configuration
+            .values()
+            .inhibit_suspend
+            .unwrap_or(false)
+            .then(SuspendInhibitor::new);
+
         let mut cmd_manager = CommandManager::new(
             spotify.clone(),
             queue.clone(),
@@ -183,6 +262,7 @@ impl Application {
 
         cmd_manager.register_all();
         cmd_manager.register_keybindings(&mut cursive);
+        let bindings = cmd_manager.bindings();
 
         cursive.set_user_data(Rc::new(UserDataInner { cmd: cmd_manager }));
 
@@ -193,6 +273,13 @@ impl Application {
 
         let queueview = ui::queue::QueueView::new(queue.clone(), library.clone());
 
+        let nowplayingview = ui::nowplaying::NowPlayingView::new(
+            queue.clone(),
+            library.clone(),
+            #[cfg(feature = "cover")]
+            &configuration,
+        );
+
         #[cfg(feature = "cover")]
         let coverview = ui::cover::CoverView::new(queue.clone(), library.clone(), &configuration);
 
@@ -202,10 +289,16 @@ impl Application {
             ui::layout::Layout::new(status, &event_manager, theme, Arc::clone(&configuration))
                 .screen("search", search.with_name("search"))
                 .screen("library", libraryview.with_name("library"))
-                .screen("queue", queueview);
+                .screen("queue", queueview)
+                .screen("nowplaying", nowplayingview);
 
         #[cfg(feature = "cover")]
         layout.add_screen("cover", coverview.with_name("cover"));
+        layout.set_bindings(bindings);
+        layout.set_mini_queue(ui::mini::MiniQueueView::new(
+            queue.clone(),
+            Arc::clone(&library),
+        ));
 
         // initial screen is library
         let initial_screen = configuration
@@ -220,15 +313,37 @@ impl Application {
             layout.set_screen("library");
         }
 
+        if let Some(message) = CommandManager::keybinding_conflicts_message(&configuration) {
+            layout.set_result(Err(message));
+        } else {
+            layout.set_result(Ok(Some(tr("Connecting to Spotify…").to_string())));
+        }
+
         cursive.add_fullscreen_layer(layout.with_name("main"));
 
+        let status_file = configuration
+            .values()
+            .status_file
+            .clone()
+            .map(StatusFile::new);
+
         Ok(Self {
             queue,
+            library,
             spotify,
             event_manager,
             #[cfg(unix)]
             ipc,
+            #[cfg(feature = "inhibit")]
+            inhibitor,
+            #[cfg(feature = "tray")]
+            tray,
             cursive,
+            worker_restarts: 0,
+            last_announcement: None,
+            window_title: None,
+            status_file,
+            queue_position: None,
         })
     }
 
@@ -236,18 +351,26 @@ impl Application {
     pub fn run(&mut self) -> Result<(), String> {
         #[cfg(unix)]
         let mut signals =
-            Signals::new([SIGTERM, SIGHUP]).expect("could not register signal handler");
+            Signals::new([SIGTERM, SIGHUP, SIGUSR1]).expect("could not register signal handler");
 
         // cursive event loop
         while self.cursive.is_running() {
             self.cursive.step();
             #[cfg(unix)]
             for signal in signals.pending() {
-                if signal == SIGTERM || signal == SIGHUP {
-                    info!("Caught {}, cleaning up and closing", signal);
-                    if let Some(data) = self.cursive.user_data::<UserData>().cloned() {
+                let Some(data) = self.cursive.user_data::<UserData>().cloned() else {
+                    continue;
+                };
+                match signal {
+                    SIGTERM | SIGHUP => {
+                        info!("Caught {}, cleaning up and closing", signal);
                         data.cmd.handle(&mut self.cursive, Command::Quit);
                     }
+                    SIGUSR1 => {
+                        info!("Caught {}, reloading configuration", signal);
+                        data.cmd.handle(&mut self.cursive, Command::ReloadConfig);
+                    }
+                    _ => {}
                 }
             }
             for event in self.event_manager.msg_iter() {
@@ -261,37 +384,243 @@ impl Application {
                             ipc.publish(&state, self.queue.get_current());
                         }
 
+                        #[cfg(feature = "inhibit")]
+                        if let Some(ref inhibitor) = self.inhibitor {
+                            inhibitor.update(&state);
+                        }
+
+                        #[cfg(feature = "tray")]
+                        self.tray.update();
+
                         if state == PlayerEvent::FinishedTrack {
                             self.queue.next(false);
+                        } else {
+                            self.announce_playback(&state);
+                            self.update_window_title(&state);
+                            self.update_status_file(&state);
                         }
                     }
+                    Event::Buffer(state) => {
+                        trace!("buffer state: {:?}", state);
+                        self.spotify.update_buffer_state(state);
+                    }
                     Event::Queue(event) => {
                         self.queue.handle_event(event);
                     }
-                    Event::SessionDied => {
-                        if self.spotify.start_worker(None).is_err() {
-                            let data: UserData = self
-                                .cursive
-                                .user_data()
-                                .cloned()
-                                .expect("user data should be set");
-                            data.cmd.handle(&mut self.cursive, Command::Quit);
-                        };
+                    Event::Connected => {
+                        self.worker_restarts = 0;
+                        if self.library.user_id().is_some() {
+                            info!("Reconnected");
+                            continue;
+                        }
+                        info!("Connected, loading library");
+                        self.library.sync_library();
+                        self.cursive.on_layout(|_, mut layout| {
+                            let message = tr("Loading library…").to_string();
+                            layout.set_result(Ok(Some(message)));
+                        });
+                    }
+                    Event::SessionDied(reason) => {
+                        if self.worker_restarts < MAX_WORKER_RESTARTS {
+                            self.worker_restarts += 1;
+                            self.restart_worker(reason);
+                        } else {
+                            self.cursive.add_layer(worker_died_dialog(&reason));
+                        }
                     }
                     Event::IpcInput(input) => match command::parse(&input) {
                         Ok(commands) => {
                             if let Some(data) = self.cursive.user_data::<UserData>().cloned() {
                                 for cmd in commands {
                                     info!("Executing command from IPC: {cmd}");
+                                    let change = remote_change(&cmd);
                                     data.cmd.handle(&mut self.cursive, cmd);
+                                    if let Some(change) = change {
+                                        self.report_remote_change(change);
+                                    }
                                 }
                             }
                         }
                         Err(e) => error!("Parsing error: {e}"),
                     },
+                    Event::Remote(change) => self.report_remote_change(change),
                 }
             }
+            self.remind_listening();
+            self.queue.skip_outro();
+            self.update_queue_position();
         }
+        ui::restore_window_title();
         Ok(())
     }
+
+    /// Suggest a break or report the listening goal in the result bar once either is due.
+    fn remind_listening(&mut self) {
+        if let Some(reminder) = self.spotify.listening_reminder() {
+            info!("{}", reminder);
+            self.cursive
+                .on_layout(|_, mut layout| layout.set_result(Ok(Some(reminder))));
+        }
+    }
+
+    /// Update the MPRIS metadata once the playing item moved in the queue or the queue length
+    /// changed. The statusbar picks up the change when it is drawn.
+    fn update_queue_position(&mut self) {
+        let position = self.queue.position();
+        if position != self.queue_position {
+            self.queue_position = position;
+            #[cfg(feature = "mpris")]
+            self.spotify.notify_metadata();
+        }
+    }
+
+    /// Start the worker again in the background after it died for the given `reason`, once the
+    /// delay for the current attempt has passed.
+    fn restart_worker(&self, reason: String) {
+        let delay = WORKER_RESTART_DELAY * 2u32.pow(self.worker_restarts - 1);
+        info!(
+            "Restarting worker in {:?}, attempt {}/{}",
+            delay, self.worker_restarts, MAX_WORKER_RESTARTS
+        );
+        let spotify = self.spotify.clone();
+        let events = self.event_manager.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(delay);
+            // The user may have reconnected in the meantime.
+            if spotify.has_worker() {
+                return;
+            }
+            if let Err(e) = spotify.start_worker() {
+                error!("could not restart worker: {}", e);
+                events.send(Event::SessionDied(reason));
+            }
+        });
+    }
+
+    /// Show the new state in the result bar after it was changed over MPRIS or IPC, as it would go
+    /// unnoticed otherwise.
+    fn report_remote_change(&mut self, change: RemoteChange) {
+        let message = match change {
+            RemoteChange::Volume => {
+                let volume = (self.spotify.volume() as f64 / 65535_f64 * 100.0).round() as u16;
+                tr_args("Volume {}%", &[&volume.to_string()])
+            }
+            RemoteChange::Shuffle if self.queue.get_shuffle() => tr("Shuffle enabled").to_string(),
+            RemoteChange::Shuffle => tr("Shuffle disabled").to_string(),
+            RemoteChange::Repeat => match self.queue.get_repeat() {
+                RepeatSetting::None => tr("Repeat disabled").to_string(),
+                RepeatSetting::RepeatPlaylist => tr("Repeating the queue").to_string(),
+                RepeatSetting::RepeatTrack => tr("Repeating the track").to_string(),
+            },
+            RemoteChange::Track => match self.queue.get_current() {
+                Some(playable) => tr_args(
+                    "Now playing: {}",
+                    &[&Playable::format(
+                        &playable,
+                        "%artists - %title",
+                        &self.library,
+                    )],
+                ),
+                None => tr("Stopped").to_string(),
+            },
+        };
+        self.cursive
+            .on_layout(|_, mut layout| layout.set_result(Ok(Some(message))));
+    }
+
+    /// Announce track changes and pauses on the result line when accessibility mode is enabled,
+    /// so terminal screen readers pick them up.
+    fn announce_playback(&mut self, state: &PlayerEvent) {
+        if !self.library.cfg.values().accessibility.unwrap_or(false) {
+            return;
+        }
+        let announcement = match (state, self.queue.get_current()) {
+            (PlayerEvent::Playing(_), Some(playable)) => tr_args(
+                "Now playing: {}",
+                &[&Playable::format(
+                    &playable,
+                    "%artists - %title",
+                    &self.library,
+                )],
+            ),
+            (PlayerEvent::Paused(_), Some(_)) => tr("Paused").to_string(),
+            _ => tr("Stopped").to_string(),
+        };
+        if self.last_announcement.as_ref() != Some(&announcement) {
+            self.last_announcement = Some(announcement.clone());
+            self.cursive
+                .on_layout(|_, mut layout| layout.set_result(Ok(Some(announcement))));
+        }
+    }
+
+    /// Show what is playing in the title of the terminal window, using `window_title_format`.
+    fn update_window_title(&mut self, state: &PlayerEvent) {
+        let Some(format) = self.library.cfg.values().window_title_format.clone() else {
+            return;
+        };
+        let title = match (state, self.queue.get_current()) {
+            (PlayerEvent::Playing(_) | PlayerEvent::Paused(_), Some(playable)) => {
+                Playable::format(&playable, &format, &self.library)
+            }
+            _ => BIN_NAME.to_string(),
+        };
+        if self.window_title.as_ref() != Some(&title) {
+            self.cursive.set_window_title(title.clone());
+            self.window_title = Some(title);
+        }
+    }
+
+    /// Write the playing item to the `status_file`, in the `status_file_format`. The line is
+    /// empty while nothing is playing.
+    fn update_status_file(&mut self, state: &PlayerEvent) {
+        let Some(status_file) = self.status_file.as_mut() else {
+            return;
+        };
+        let line = match (state, self.queue.get_current()) {
+            (PlayerEvent::Playing(_) | PlayerEvent::Paused(_), Some(playable)) => {
+                let format = self
+                    .library
+                    .cfg
+                    .values()
+                    .status_file_format
+                    .clone()
+                    .unwrap_or_else(|| "%artists - %title".to_string());
+                Playable::format(&playable, &format, &self.library)
+            }
+            _ => String::new(),
+        };
+        status_file.update(line);
+    }
+}
+
+/// A dialog that reports why the connection to Spotify was lost and offers to reconnect, also by
+/// pressing `r`.
+fn worker_died_dialog(reason: &str) -> Modal<OnEventView<Dialog>> {
+    let reconnect = |s: &mut Cursive| {
+        s.pop_layer();
+        if let Some(data) = s.user_data::<UserData>().cloned() {
+            data.cmd.handle(s, Command::Reconnect);
+        }
+    };
+    let dialog = Dialog::text(tr_args("The connection to Spotify was lost: {}", &[reason]))
+        .title(tr("Disconnected"))
+        .padding(Margins::lrtb(1, 1, 1, 0))
+        .button(tr("Reconnect"), reconnect)
+        .button(tr("Quit"), |s| {
+            if let Some(data) = s.user_data::<UserData>().cloned() {
+                data.cmd.handle(s, Command::Quit);
+            }
+        });
+    Modal::new(OnEventView::new(dialog).on_event('r', reconnect))
+}
+
+/// The part of the playback state that `cmd` changes, if it is reported when sent over IPC.
+fn remote_change(cmd: &Command) -> Option<RemoteChange> {
+    match cmd {
+        Command::VolumeUp(_) | Command::VolumeDown(_) => Some(RemoteChange::Volume),
+        Command::Shuffle(_) => Some(RemoteChange::Shuffle),
+        Command::Repeat(_) => Some(RemoteChange::Repeat),
+        Command::Next | Command::Previous => Some(RemoteChange::Track),
+        _ => None,
+    }
 }
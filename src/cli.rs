@@ -1,9 +1,17 @@
-use crate::config::{user_cache_directory, user_configuration_directory};
+use ncspot::USER_STATE_FILE_NAME;
+
+use crate::config::{
+    state_path, user_cache_directory, user_configuration_directory, user_state_directory,
+    UserState, CACHE_VERSION,
+};
+use crate::library::{self, CacheCheck};
+use crate::serialization::{Serializer, CBOR};
 
 /// Print platform info like which platform directories will be used.
 pub fn info() -> Result<(), String> {
     let user_configuration_directory = user_configuration_directory();
     let user_cache_directory = user_cache_directory();
+    let user_state_directory = user_state_directory();
 
     println!(
         "USER_CONFIGURATION_PATH {}",
@@ -17,6 +25,12 @@ pub fn info() -> Result<(), String> {
             .map(|path| path.to_string_lossy().to_string())
             .unwrap_or("not found".into())
     );
+    println!(
+        "USER_STATE_PATH {}",
+        user_state_directory
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or("not found".into())
+    );
 
     #[cfg(unix)]
     {
@@ -33,3 +47,30 @@ pub fn info() -> Result<(), String> {
 
     Ok(())
 }
+
+/// Check the cached library and move the files that can't be read aside, so they're fetched again
+/// on the next start.
+pub fn verify_cache() -> Result<(), String> {
+    let state: UserState = CBOR
+        .load(state_path(USER_STATE_FILE_NAME))
+        .unwrap_or_default();
+    if state.cache_version < CACHE_VERSION {
+        println!("The cache is from an older version and will be fetched again");
+    }
+
+    for (file, check) in library::verify_caches() {
+        match check {
+            CacheCheck::Missing => println!("{file}: missing"),
+            CacheCheck::Valid(items) => println!("{file}: {items} items"),
+            CacheCheck::Unreadable(error) => println!("{file}: unreadable ({error})"),
+            CacheCheck::Quarantined(path, error) => {
+                println!("{file}: corrupt ({error}), moved to {}", path.display())
+            }
+            CacheCheck::Corrupt(error, move_error) => {
+                println!("{file}: corrupt ({error}), could not move it aside ({move_error})")
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -4,7 +4,7 @@ use std::collections::HashMap;
 use std::fmt;
 use std::sync::OnceLock;
 
-use strum_macros::Display;
+use strum_macros::{Display, EnumIter};
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub enum SeekInterval {
@@ -53,6 +53,9 @@ pub enum SortKey {
     Artist,
     Album,
     Added,
+    Bpm,
+    Energy,
+    Tracks,
 }
 
 #[derive(Display, Clone, Serialize, Deserialize, Debug)]
@@ -82,12 +85,153 @@ pub enum ShiftMode {
 pub enum GotoMode {
     Album,
     Artist,
+    Playing,
+}
+
+/// How the playback time of the current track is shown in the statusbar.
+#[derive(Display, Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+#[strum(serialize_all = "lowercase")]
+pub enum TimeFormat {
+    /// The elapsed time and the duration of the track.
+    #[default]
+    Total,
+    /// The elapsed time and the time that is left, e.g. `1:20 / -2:40`.
+    Remaining,
+}
+
+/// Audio attributes the recommendations can be tuned towards.
+#[derive(Display, Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase")]
+pub enum TuneAttribute {
+    Energy,
+    Tempo,
+    Valence,
+}
+
+/// Modifications of the seeds used to generate recommendations.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub enum SeedAction {
+    /// Add the selected track as a seed.
+    Track,
+    /// Add the first artist of the selected track as a seed.
+    Artist,
+    Genre(String),
+    /// Remove the seed at the given position, starting at 1.
+    Remove(usize),
+}
+
+/// A range of release years, including both ends.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct YearRange {
+    pub from: u16,
+    pub to: u16,
+}
+
+impl YearRange {
+    /// Parse a single year (`1995`), a decade (`1990s`) or a range of years (`1990-1999`).
+    fn parse(arg: &str) -> Option<Self> {
+        if let Some(decade) = arg.strip_suffix('s') {
+            let from = decade.parse::<u16>().ok().filter(|year| year % 10 == 0)?;
+            return Some(Self { from, to: from + 9 });
+        }
+        let (from, to) = arg.split_once('-').unwrap_or((arg, arg));
+        let from = from.parse().ok()?;
+        let to = to.parse().ok()?;
+        (from <= to).then_some(Self { from, to })
+    }
+
+    pub fn contains(&self, year: u16) -> bool {
+        (self.from..=self.to).contains(&year)
+    }
+}
+
+impl fmt::Display for YearRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.from == self.to {
+            write!(f, "{}", self.from)
+        } else {
+            write!(f, "{}-{}", self.from, self.to)
+        }
+    }
+}
+
+impl fmt::Display for SeedAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let repr = match self {
+            Self::Track => "track".into(),
+            Self::Artist => "artist".into(),
+            Self::Genre(genre) => format!("genre {genre}"),
+            Self::Remove(position) => format!("remove {position}"),
+        };
+        write!(f, "{repr}")
+    }
+}
+
+/// Changes to the bookmarks of the playing track or episode.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub enum MarkAction {
+    /// Bookmark the current position under the given name.
+    Add(String),
+    /// Seek to the bookmark with the given name.
+    Goto(String),
+    Remove(String),
+}
+
+impl fmt::Display for MarkAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let repr = match self {
+            Self::Add(name) => format!("add {name}"),
+            Self::Goto(name) => format!("goto {name}"),
+            Self::Remove(name) => format!("remove {name}"),
+        };
+        write!(f, "{repr}")
+    }
+}
+
+/// The kinds of devices that can be chosen with the `device` command.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub enum DeviceKind {
+    Audio,
+}
+
+impl fmt::Display for DeviceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let repr = match self {
+            Self::Audio => "audio",
+        };
+        write!(f, "{repr}")
+    }
+}
+
+/// The players whose queue can be imported with the `import queue` command.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub enum ImportSource {
+    /// Another running ncspot instance, optionally at the given IPC socket.
+    #[cfg(unix)]
+    Ncspot(Option<String>),
+    /// The MPD server from the configuration.
+    Mpd,
+}
+
+impl fmt::Display for ImportSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let repr = match self {
+            #[cfg(unix)]
+            Self::Ncspot(Some(path)) => format!("ncspot {path}"),
+            #[cfg(unix)]
+            Self::Ncspot(None) => "ncspot".into(),
+            Self::Mpd => "mpd".into(),
+        };
+        write!(f, "{repr}")
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub enum SeekDirection {
     Relative(i32),
     Absolute(u32),
+    RelativePercent(i32),
+    AbsolutePercent(u32),
 }
 
 impl fmt::Display for SeekDirection {
@@ -97,6 +241,10 @@ impl fmt::Display for SeekDirection {
             Self::Relative(delta) => {
                 format!("{}{}", if delta > &0 { "+" } else { "" }, delta)
             }
+            Self::AbsolutePercent(percent) => format!("{percent}%"),
+            Self::RelativePercent(delta) => {
+                format!("{}{}%", if delta > &0 { "+" } else { "" }, delta)
+            }
         };
         write!(f, "{repr}")
     }
@@ -129,8 +277,13 @@ pub enum Command {
     Next,
     Clear,
     Queue,
+    QueueAll,
+    DedupQueue,
+    RandomizeQueue,
     PlayNext,
     Play,
+    PlayAll,
+    PlayTop,
     UpdateLibrary,
     Save,
     SaveCurrent,
@@ -144,9 +297,12 @@ pub enum Command {
     VolumeDown(u16),
     Repeat(Option<RepeatSetting>),
     Shuffle(Option<bool>),
+    Automix(Option<bool>),
     #[cfg(feature = "share_clipboard")]
     Share(TargetMode),
     Back,
+    Forward,
+    History,
     Open(TargetMode),
     Goto(GotoMode),
     Move(MoveMode, MoveAmount),
@@ -161,15 +317,49 @@ pub enum Command {
     Sort(SortKey, SortDirection),
     Logout,
     ShowRecommendations(TargetMode),
+    Tune(TuneAttribute, Option<f32>),
+    Seed(SeedAction),
+    Refresh,
     Redraw,
     Execute(String),
+    ExecuteDetached(String),
     Reconnect,
+    DescribeKey,
+    Record(Option<char>),
+    Replay(char),
+    Restore,
+    HidePlayed,
+    Snapshot,
+    Snapshots,
+    Device(DeviceKind),
+    Bitrate(u32),
+    DataSaver(Option<bool>),
+    Headers(Option<bool>),
+    ImportQueue(ImportSource),
+    Party(Option<bool>),
+    PlayUrl(SpotifyUrl),
+    Set(String, String),
+    CopyMetadata(TargetMode, Option<String>),
+    Mark(MarkAction),
+    Clean(Option<bool>),
+    Tasks,
+    PrivateSession(Option<bool>),
+    Select,
+    Filter(Option<YearRange>),
+    TimeFormat(Option<TimeFormat>),
+    Mini(Option<bool>),
+    Favorite,
+    OpenFavorite(usize),
+    /// Commands separated by `&&`, run one after another until one of them fails.
+    Chain(Vec<Command>),
+    /// Show the cover of the selected item, or save the cover of the playing item to a file.
+    Cover(Option<String>),
 }
 
 impl fmt::Display for Command {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut repr_tokens = vec![self.basename().to_owned()];
-        let mut extras_args = match self {
+        let extras_args = match self {
             Self::Focus(tab) => vec![tab.to_owned()],
             Self::Seek(direction) => vec![direction.to_string()],
             Self::VolumeUp(amount) => vec![amount.to_string()],
@@ -178,12 +368,24 @@ impl fmt::Display for Command {
                 Some(mode) => vec![mode.to_string()],
                 None => vec![],
             },
-            Self::Shuffle(on) => match on {
+            Self::Shuffle(on)
+            | Self::Automix(on)
+            | Self::DataSaver(on)
+            | Self::Headers(on)
+            | Self::Party(on)
+            | Self::Clean(on)
+            | Self::PrivateSession(on)
+            | Self::Mini(on) => match on {
                 Some(b) => vec![(if *b { "on" } else { "off" }).into()],
                 None => vec![],
             },
             #[cfg(feature = "share_clipboard")]
             Self::Share(mode) => vec![mode.to_string()],
+            Self::CopyMetadata(mode, path) => {
+                let mut args = vec!["metadata".into(), mode.to_string()];
+                args.extend(path.clone());
+                args
+            }
             Self::Open(mode) => vec![mode.to_string()],
             Self::Goto(mode) => vec![mode.to_string()],
             Self::Move(mode, amount) => match (mode, amount) {
@@ -202,10 +404,35 @@ impl fmt::Display for Command {
                 JumpMode::Query(term) => vec![term.to_owned()],
             },
             Self::Insert(source) => vec![source.to_string()],
+            Self::PlayUrl(url) => vec![url.to_string()],
+            Self::Set(option, value) => vec![option.to_owned(), value.to_owned()],
             Self::NewPlaylist(name) => vec![name.to_owned()],
             Self::Sort(key, direction) => vec![key.to_string(), direction.to_string()],
             Self::ShowRecommendations(mode) => vec![mode.to_string()],
-            Self::Execute(cmd) => vec![cmd.to_owned()],
+            Self::Tune(attribute, value) => vec![
+                attribute.to_string(),
+                value.map_or_else(|| "off".into(), |v| v.to_string()),
+            ],
+            Self::Seed(action) => vec![action.to_string()],
+            Self::Filter(Some(range)) => vec!["year".into(), range.to_string()],
+            Self::Filter(None) => vec!["clear".into()],
+            Self::TimeFormat(format) => format.iter().map(|f| f.to_string()).collect(),
+            Self::OpenFavorite(position) => vec![position.to_string()],
+            Self::Cover(path) => match path {
+                Some(path) => vec!["save".into(), path.to_owned()],
+                None => vec![],
+            },
+            Self::Chain(commands) => {
+                let commands: Vec<String> = commands.iter().map(|c| c.to_string()).collect();
+                return write!(f, "{}", commands.join(" && "));
+            }
+            Self::Mark(action) => vec![action.to_string()],
+            Self::Execute(cmd) | Self::ExecuteDetached(cmd) => vec![cmd.to_owned()],
+            Self::Record(register) => register.iter().map(|r| r.to_string()).collect(),
+            Self::Replay(register) => vec![register.to_string()],
+            Self::Device(kind) => vec![kind.to_string()],
+            Self::Bitrate(bitrate) => vec![bitrate.to_string()],
+            Self::ImportQueue(source) => vec!["queue".into(), source.to_string()],
             Self::Quit
             | Self::TogglePlay
             | Self::Stop
@@ -219,23 +446,116 @@ impl fmt::Display for Command {
             | Self::Save
             | Self::SaveCurrent
             | Self::SaveQueue
+            | Self::QueueAll
+            | Self::DedupQueue
+            | Self::RandomizeQueue
+            | Self::PlayAll
+            | Self::PlayTop
             | Self::Add
             | Self::AddCurrent
             | Self::Delete
             | Self::Back
+            | Self::Forward
+            | Self::History
             | Self::Help
             | Self::ReloadConfig
             | Self::Noop
             | Self::Logout
             | Self::Reconnect
+            | Self::DescribeKey
+            | Self::Restore
+            | Self::HidePlayed
+            | Self::Snapshot
+            | Self::Snapshots
+            | Self::Tasks
+            | Self::Select
+            | Self::Favorite
+            | Self::Refresh
             | Self::Redraw => vec![],
         };
-        repr_tokens.append(&mut extras_args);
+        // A "&&" in an argument would start a chain when parsed again, so escape it.
+        repr_tokens.extend(extras_args.iter().map(|arg| {
+            arg.split(' ')
+                .map(|word| if word == "&&" { "&&&&" } else { word })
+                .collect::<Vec<_>>()
+                .join(" ")
+        }));
         write!(f, "{}", repr_tokens.join(" "))
     }
 }
 
 impl Command {
+    /// Apply a count prefix such as the `5` of `5j` to this command. Movements are multiplied,
+    /// skipping tracks or matches is repeated and other commands are unaffected.
+    pub fn with_count(self, count: Option<u32>) -> Vec<Self> {
+        let Some(count) = count else {
+            return vec![self];
+        };
+        let factor = i32::try_from(count).unwrap_or(i32::MAX);
+        match self {
+            Self::Move(mode, MoveAmount::Integer(amount)) => vec![Self::Move(
+                mode,
+                MoveAmount::Integer(amount.saturating_mul(factor)),
+            )],
+            Self::Move(mode, MoveAmount::Float(amount)) => {
+                vec![Self::Move(mode, MoveAmount::Float(amount * count as f32))]
+            }
+            Self::Shift(mode, amount) => vec![Self::Shift(
+                mode,
+                Some(amount.unwrap_or(1).saturating_mul(factor)),
+            )],
+            Self::VolumeUp(amount) => vec![Self::VolumeUp(
+                amount.saturating_mul(u16::try_from(count).unwrap_or(u16::MAX)),
+            )],
+            Self::VolumeDown(amount) => vec![Self::VolumeDown(
+                amount.saturating_mul(u16::try_from(count).unwrap_or(u16::MAX)),
+            )],
+            Self::Seek(SeekDirection::Relative(amount)) => vec![Self::Seek(
+                SeekDirection::Relative(amount.saturating_mul(factor)),
+            )],
+            Self::Seek(SeekDirection::RelativePercent(amount)) => vec![Self::Seek(
+                SeekDirection::RelativePercent(amount.saturating_mul(factor)),
+            )],
+            // `50%` seeks to the middle of the track, like `50%` jumps to the middle of a file
+            // in Vim.
+            Self::Seek(SeekDirection::AbsolutePercent(_)) => {
+                vec![Self::Seek(SeekDirection::AbsolutePercent(count.min(100)))]
+            }
+            Self::Next
+            | Self::Previous
+            | Self::Jump(JumpMode::Next)
+            | Self::Jump(JumpMode::Previous)
+            | Self::Replay(_) => vec![self; count as usize],
+            _ => vec![self],
+        }
+    }
+
+    /// Whether this command changes the library, playlists, the account or the configuration, or
+    /// runs programs, which is not allowed in party mode.
+    pub fn is_destructive(&self) -> bool {
+        if let Self::Chain(commands) = self {
+            return commands.iter().any(Self::is_destructive);
+        }
+        matches!(
+            self,
+            Self::Clear
+                | Self::Save
+                | Self::SaveCurrent
+                | Self::SaveQueue
+                | Self::Add
+                | Self::AddCurrent
+                | Self::Delete
+                | Self::Shift(..)
+                | Self::NewPlaylist(_)
+                | Self::Logout
+                | Self::Execute(_)
+                | Self::ExecuteDetached(_)
+                | Self::Restore
+                | Self::Set(..)
+                | Self::ReloadConfig
+        )
+    }
+
     pub fn basename(&self) -> &str {
         match self {
             Self::Quit => "quit",
@@ -245,8 +565,13 @@ impl Command {
             Self::Next => "next",
             Self::Clear => "clear",
             Self::Queue => "queue",
+            Self::QueueAll => "queue all",
+            Self::DedupQueue => "queue dedup",
+            Self::RandomizeQueue => "queue randomize",
             Self::PlayNext => "playnext",
             Self::Play => "play",
+            Self::PlayAll => "play all",
+            Self::PlayTop => "play top",
             Self::UpdateLibrary => "update",
             Self::Save => "save",
             Self::SaveCurrent => "save current",
@@ -260,9 +585,12 @@ impl Command {
             Self::VolumeDown(_) => "voldown",
             Self::Repeat(_) => "repeat",
             Self::Shuffle(_) => "shuffle",
+            Self::Automix(_) => "automix",
             #[cfg(feature = "share_clipboard")]
             Self::Share(_) => "share",
             Self::Back => "back",
+            Self::Forward => "forward",
+            Self::History => "history",
             Self::Open(_) => "open",
             Self::Goto(_) => "goto",
             Self::Move(_, _) => "move",
@@ -273,19 +601,491 @@ impl Command {
             Self::Jump(JumpMode::Query(_)) => "jump",
             Self::Help => "help",
             Self::ReloadConfig => "reload",
+            Self::Set(_, _) => "set",
+            Self::CopyMetadata(_, _) => "copy",
+            Self::Mark(_) => "mark",
             Self::Noop => "noop",
             Self::Insert(_) => "insert",
+            Self::PlayUrl(_) => "play",
             Self::NewPlaylist(_) => "newplaylist",
             Self::Sort(_, _) => "sort",
             Self::Logout => "logout",
             Self::ShowRecommendations(_) => "similar",
+            Self::Tune(_, _) => "tune",
+            Self::Seed(_) => "seed",
+            Self::Refresh => "refresh",
             Self::Redraw => "redraw",
             Self::Execute(_) => "exec",
+            Self::ExecuteDetached(_) => "exec!",
             Self::Reconnect => "reconnect",
+            Self::Device(_) => "device",
+            Self::Bitrate(_) => "bitrate",
+            Self::DataSaver(_) => "datasaver",
+            Self::Headers(_) => "headers",
+            Self::Party(_) => "party",
+            Self::Clean(_) => "clean",
+            Self::ImportQueue(_) => "import",
+            Self::DescribeKey => "describekey",
+            Self::Record(_) => "record",
+            Self::Replay(_) => "replay",
+            Self::Restore => "restore",
+            Self::HidePlayed => "hideplayed",
+            Self::Snapshot => "snapshot",
+            Self::Snapshots => "snapshots",
+            Self::Tasks => "tasks",
+            Self::PrivateSession(_) => "private",
+            Self::Select => "select",
+            Self::Filter(_) => "filter",
+            Self::TimeFormat(_) => "timefmt",
+            Self::Mini(_) => "mini",
+            Self::Favorite => "favorite",
+            Self::OpenFavorite(_) => "playlist",
+            Self::Chain(_) => "&&",
+            Self::Cover(_) => "cover",
+        }
+    }
+}
+
+/// The group a command is listed under in the help screen.
+#[derive(EnumIter, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommandCategory {
+    Playback,
+    Navigation,
+    Library,
+    Playlist,
+    General,
+}
+
+impl CommandCategory {
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::Playback => "Playback",
+            Self::Navigation => "Navigation",
+            Self::Library => "Library",
+            Self::Playlist => "Playlist",
+            Self::General => "General",
         }
     }
 }
 
+/// Description of a command for the help screen.
+pub struct CommandInfo {
+    /// The name of the command as returned by [Command::basename].
+    pub name: &'static str,
+    pub category: CommandCategory,
+    pub description: &'static str,
+}
+
+/// All commands in the order they are listed in the help screen.
+pub const COMMANDS: &[CommandInfo] = &[
+    CommandInfo {
+        name: "playpause",
+        category: CommandCategory::Playback,
+        description: "Toggle playback",
+    },
+    CommandInfo {
+        name: "play",
+        category: CommandCategory::Playback,
+        description: "Play the selected item",
+    },
+    CommandInfo {
+        name: "play all",
+        category: CommandCategory::Playback,
+        description: "Replace the queue with all items of the list and play them",
+    },
+    CommandInfo {
+        name: "play top",
+        category: CommandCategory::Playback,
+        description: "Replace the queue with the top tracks of the selected artist and play them",
+    },
+    CommandInfo {
+        name: "playnext",
+        category: CommandCategory::Playback,
+        description: "Play the selected item after the current track",
+    },
+    CommandInfo {
+        name: "queue",
+        category: CommandCategory::Playback,
+        description: "Add the selected item to the queue",
+    },
+    CommandInfo {
+        name: "queue all",
+        category: CommandCategory::Playback,
+        description: "Add all items of the list to the queue",
+    },
+    CommandInfo {
+        name: "queue dedup",
+        category: CommandCategory::Playback,
+        description: "Remove tracks that are in the queue more than once",
+    },
+    CommandInfo {
+        name: "queue randomize",
+        category: CommandCategory::Playback,
+        description: "Shuffle the order of the queue once",
+    },
+    CommandInfo {
+        name: "stop",
+        category: CommandCategory::Playback,
+        description: "Stop playback",
+    },
+    CommandInfo {
+        name: "previous",
+        category: CommandCategory::Playback,
+        description: "Play the previous track",
+    },
+    CommandInfo {
+        name: "next",
+        category: CommandCategory::Playback,
+        description: "Play the next track",
+    },
+    CommandInfo {
+        name: "seek",
+        category: CommandCategory::Playback,
+        description: "Seek to a position in the current track",
+    },
+    CommandInfo {
+        name: "volup",
+        category: CommandCategory::Playback,
+        description: "Increase the volume",
+    },
+    CommandInfo {
+        name: "voldown",
+        category: CommandCategory::Playback,
+        description: "Decrease the volume",
+    },
+    CommandInfo {
+        name: "repeat",
+        category: CommandCategory::Playback,
+        description: "Set or cycle the repeat mode",
+    },
+    CommandInfo {
+        name: "shuffle",
+        category: CommandCategory::Playback,
+        description: "Enable or disable shuffle",
+    },
+    CommandInfo {
+        name: "automix",
+        category: CommandCategory::Playback,
+        description: "Order the queue by tempo and key and fade between tracks",
+    },
+    CommandInfo {
+        name: "clear",
+        category: CommandCategory::Playback,
+        description: "Clear the queue",
+    },
+    CommandInfo {
+        name: "focus",
+        category: CommandCategory::Navigation,
+        description: "Switch to a different view",
+    },
+    CommandInfo {
+        name: "back",
+        category: CommandCategory::Navigation,
+        description: "Go back to the previous view",
+    },
+    CommandInfo {
+        name: "forward",
+        category: CommandCategory::Navigation,
+        description: "Open the view that was closed with back again",
+    },
+    CommandInfo {
+        name: "history",
+        category: CommandCategory::Navigation,
+        description: "Open one of the recently visited artists, albums and playlists",
+    },
+    CommandInfo {
+        name: "open",
+        category: CommandCategory::Navigation,
+        description: "Open the selected or current item",
+    },
+    CommandInfo {
+        name: "goto",
+        category: CommandCategory::Navigation,
+        description: "Open the album or artist of the selected item, or jump to the playing track",
+    },
+    CommandInfo {
+        name: "move",
+        category: CommandCategory::Navigation,
+        description: "Move the selection or scroll the current view",
+    },
+    CommandInfo {
+        name: "search",
+        category: CommandCategory::Navigation,
+        description: "Search for tracks, albums, artists and more",
+    },
+    CommandInfo {
+        name: "jump",
+        category: CommandCategory::Navigation,
+        description: "Jump to the first item matching a query",
+    },
+    CommandInfo {
+        name: "jumpnext",
+        category: CommandCategory::Navigation,
+        description: "Jump to the next match",
+    },
+    CommandInfo {
+        name: "jumpprevious",
+        category: CommandCategory::Navigation,
+        description: "Jump to the previous match",
+    },
+    CommandInfo {
+        name: "similar",
+        category: CommandCategory::Navigation,
+        description: "Show recommendations based on an item",
+    },
+    CommandInfo {
+        name: "tune",
+        category: CommandCategory::Navigation,
+        description: "Set a target audio attribute of the recommendations",
+    },
+    CommandInfo {
+        name: "mark",
+        category: CommandCategory::Playback,
+        description: "Bookmark a position in the playing track or seek to a bookmark",
+    },
+    CommandInfo {
+        name: "seed",
+        category: CommandCategory::Navigation,
+        description: "Change the seeds of the recommendations",
+    },
+    CommandInfo {
+        name: "hideplayed",
+        category: CommandCategory::Navigation,
+        description: "Hide or show played podcast episodes",
+    },
+    CommandInfo {
+        name: "refresh",
+        category: CommandCategory::Navigation,
+        description: "Reload the contents of the current view",
+    },
+    CommandInfo {
+        name: "help",
+        category: CommandCategory::Navigation,
+        description: "Show this help screen",
+    },
+    CommandInfo {
+        name: "describekey",
+        category: CommandCategory::Navigation,
+        description: "Show the commands bound to the next key pressed",
+    },
+    CommandInfo {
+        name: "update",
+        category: CommandCategory::Library,
+        description: "Update the library from Spotify",
+    },
+    CommandInfo {
+        name: "save",
+        category: CommandCategory::Library,
+        description: "Save the selected item to the library",
+    },
+    CommandInfo {
+        name: "save current",
+        category: CommandCategory::Library,
+        description: "Save the current track to the library",
+    },
+    CommandInfo {
+        name: "delete",
+        category: CommandCategory::Library,
+        description: "Delete the selected playlist or remove the selected track",
+    },
+    #[cfg(feature = "share_clipboard")]
+    CommandInfo {
+        name: "share",
+        category: CommandCategory::Library,
+        description: "Copy a shareable URL of an item to the clipboard",
+    },
+    CommandInfo {
+        name: "copy",
+        category: CommandCategory::Library,
+        description: "Copy all metadata of an item as JSON to the clipboard or a file",
+    },
+    CommandInfo {
+        name: "cover",
+        category: CommandCategory::Library,
+        description: "Show the cover of the selected item, or save the playing one to a file",
+    },
+    CommandInfo {
+        name: "add",
+        category: CommandCategory::Playlist,
+        description: "Add the selected track to a playlist",
+    },
+    CommandInfo {
+        name: "add current",
+        category: CommandCategory::Playlist,
+        description: "Add the current track to a playlist",
+    },
+    CommandInfo {
+        name: "save queue",
+        category: CommandCategory::Playlist,
+        description: "Save the queue as a playlist",
+    },
+    CommandInfo {
+        name: "newplaylist",
+        category: CommandCategory::Playlist,
+        description: "Create a new playlist",
+    },
+    CommandInfo {
+        name: "insert",
+        category: CommandCategory::Playlist,
+        description: "Insert an item from a Spotify URL",
+    },
+    CommandInfo {
+        name: "shift",
+        category: CommandCategory::Playlist,
+        description: "Move the selected item up or down",
+    },
+    CommandInfo {
+        name: "favorite",
+        category: CommandCategory::Playlist,
+        description: "Pin the selected playlist to the top of the playlists or unpin it",
+    },
+    CommandInfo {
+        name: "playlist",
+        category: CommandCategory::Playlist,
+        description: "Open the favorite playlist at the given position",
+    },
+    CommandInfo {
+        name: "filter",
+        category: CommandCategory::Navigation,
+        description: "Only show the saved albums released in a range of years",
+    },
+    CommandInfo {
+        name: "select",
+        category: CommandCategory::Playlist,
+        description: "Select or deselect the item under the cursor, to move several queue items",
+    },
+    CommandInfo {
+        name: "sort",
+        category: CommandCategory::Playlist,
+        description: "Sort the playlist",
+    },
+    CommandInfo {
+        name: "record",
+        category: CommandCategory::General,
+        description: "Start recording commands to a register, or stop recording",
+    },
+    CommandInfo {
+        name: "replay",
+        category: CommandCategory::General,
+        description: "Execute the commands recorded to a register",
+    },
+    CommandInfo {
+        name: "restore",
+        category: CommandCategory::Playlist,
+        description: "Recreate a playlist that was archived when deleting it",
+    },
+    CommandInfo {
+        name: "snapshot",
+        category: CommandCategory::Playlist,
+        description: "Store the metadata of all tracks of the open playlist or album",
+    },
+    CommandInfo {
+        name: "snapshots",
+        category: CommandCategory::Playlist,
+        description: "Browse stored snapshots",
+    },
+    CommandInfo {
+        name: "quit",
+        category: CommandCategory::General,
+        description: "Quit ncspot",
+    },
+    CommandInfo {
+        name: "reload",
+        category: CommandCategory::General,
+        description: "Reload the configuration from disk",
+    },
+    CommandInfo {
+        name: "set",
+        category: CommandCategory::General,
+        description: "Change an option in the configuration file and reload it",
+    },
+    CommandInfo {
+        name: "reconnect",
+        category: CommandCategory::General,
+        description: "Reconnect to Spotify",
+    },
+    CommandInfo {
+        name: "device",
+        category: CommandCategory::Playback,
+        description: "Choose the audio device to play on",
+    },
+    CommandInfo {
+        name: "bitrate",
+        category: CommandCategory::Playback,
+        description: "Change the streaming bitrate",
+    },
+    CommandInfo {
+        name: "datasaver",
+        category: CommandCategory::Playback,
+        description: "Toggle the data saver",
+    },
+    CommandInfo {
+        name: "party",
+        category: CommandCategory::Playback,
+        description: "Lock commands that change the library, playlists or the account",
+    },
+    CommandInfo {
+        name: "clean",
+        category: CommandCategory::Playback,
+        description: "Hide and skip explicit content",
+    },
+    CommandInfo {
+        name: "timefmt",
+        category: CommandCategory::Playback,
+        description: "Show the duration or the remaining time of the playing track",
+    },
+    CommandInfo {
+        name: "private",
+        category: CommandCategory::Playback,
+        description: "Toggle a private session that doesn't share what you listen to",
+    },
+    CommandInfo {
+        name: "mini",
+        category: CommandCategory::Navigation,
+        description: "Only show the statusbar and the next items of the queue",
+    },
+    CommandInfo {
+        name: "headers",
+        category: CommandCategory::Navigation,
+        description: "Show or hide the details above albums and playlists",
+    },
+    CommandInfo {
+        name: "import",
+        category: CommandCategory::General,
+        description: "Import the queue of another ncspot instance or an MPD server",
+    },
+    CommandInfo {
+        name: "tasks",
+        category: CommandCategory::General,
+        description: "List and cancel the operations running in the background",
+    },
+    CommandInfo {
+        name: "logout",
+        category: CommandCategory::General,
+        description: "Remove cached credentials and quit",
+    },
+    CommandInfo {
+        name: "redraw",
+        category: CommandCategory::General,
+        description: "Redraw the screen",
+    },
+    CommandInfo {
+        name: "exec",
+        category: CommandCategory::General,
+        description: "Run a program and show its output",
+    },
+    CommandInfo {
+        name: "exec!",
+        category: CommandCategory::General,
+        description: "Start a program without waiting for it",
+    },
+    CommandInfo {
+        name: "noop",
+        category: CommandCategory::General,
+        description: "Do nothing, used to disable default bindings",
+    },
+];
+
 fn register_aliases(map: &mut HashMap<&str, &str>, cmd: &'static str, names: Vec<&'static str>) {
     for a in names {
         map.insert(a, cmd);
@@ -307,6 +1107,7 @@ fn handle_aliases(input: &str) -> &str {
             vec!["pause", "toggleplay", "toggleplayback"],
         );
         register_aliases(&mut m, "repeat", vec!["loop"]);
+        register_aliases(&mut m, "describekey", vec!["describe-key"]);
         m
     });
 
@@ -366,6 +1167,18 @@ impl fmt::Display for CommandParseError {
     }
 }
 
+/// Parse the name of a macro register, which is a single letter or digit.
+fn parse_register(arg: &str) -> Result<char, CommandParseError> {
+    let mut chars = arg.chars();
+    match (chars.next(), chars.next()) {
+        (Some(register), None) if register.is_ascii_alphanumeric() => Ok(register),
+        _ => Err(CommandParseError::ArgParseError {
+            arg: arg.into(),
+            err: "Registers are a single letter or digit".into(),
+        }),
+    }
+}
+
 pub fn parse(input: &str) -> Result<Vec<Command>, CommandParseError> {
     let mut command_inputs = vec!["".to_string()];
     let mut command_idx = 0;
@@ -396,6 +1209,22 @@ pub fn parse(input: &str) -> Result<Vec<Command>, CommandParseError> {
     for command_input in command_inputs {
         let components: Vec<_> = command_input.split_whitespace().collect();
 
+        // "&&" separates the commands of a chain and is escaped using "&&&&", which the parts of a
+        // chain keep until they are parsed on their own.
+        if components.contains(&"&&") {
+            // The parts are parsed on their own, so escape the ";" they contain again.
+            let chain = components
+                .split(|component| *component == "&&")
+                .map(|part| parse(&part.join(" ").replace(';', ";;")))
+                .collect::<Result<Vec<_>, _>>()?;
+            commands.push(Command::Chain(chain.into_iter().flatten().collect()));
+            continue;
+        }
+        let components: Vec<_> = components
+            .into_iter()
+            .map(|component| if component == "&&&&" { "&&" } else { component })
+            .collect();
+
         if let Some((command, args)) = components.split_first() {
             let command = handle_aliases(command);
             use CommandParseError as E;
@@ -406,9 +1235,30 @@ pub fn parse(input: &str) -> Result<Vec<Command>, CommandParseError> {
                 "previous" => Command::Previous,
                 "next" => Command::Next,
                 "clear" => Command::Clear,
-                "queue" => Command::Queue,
+                "queue" => match args.first().cloned() {
+                    Some("all") => Ok(Command::QueueAll),
+                    Some("dedup") => Ok(Command::DedupQueue),
+                    Some("randomize") => Ok(Command::RandomizeQueue),
+                    Some(arg) => Err(E::BadEnumArg {
+                        arg: arg.into(),
+                        accept: vec!["all".into(), "dedup".into(), "randomize".into()],
+                        optional: true,
+                    }),
+                    None => Ok(Command::Queue),
+                }?,
                 "playnext" => Command::PlayNext,
-                "play" => Command::Play,
+                "play" => match args.first().cloned() {
+                    Some("all") => Ok(Command::PlayAll),
+                    Some("top") => Ok(Command::PlayTop),
+                    Some(arg) => SpotifyUrl::from_uri_or_url(arg)
+                        .map(Command::PlayUrl)
+                        .ok_or(E::BadEnumArg {
+                            arg: arg.into(),
+                            accept: vec!["all".into(), "top".into(), "URI".into()],
+                            optional: true,
+                        }),
+                    None => Ok(Command::Play),
+                }?,
                 "update" => Command::UpdateLibrary,
                 "add" => match args.first().cloned() {
                     Some("current") => Ok(Command::AddCurrent),
@@ -455,32 +1305,51 @@ pub fn parse(input: &str) -> Result<Vec<Command>, CommandParseError> {
                         }
                         _ => arg,
                     };
-                    let unsigned_millis = match duration_raw.parse() {
-                        // accept raw milliseconds
-                        Ok(millis) => millis,
-                        Err(_) => parse_duration::parse(&duration_raw) // accept fancy duration
-                            .map_err(|err| E::ArgParseError {
+                    let seek_direction = if let Some(percent_raw) = duration_raw.strip_suffix('%') {
+                        let percent = percent_raw
+                            .trim()
+                            .parse::<u32>()
+                            .ok()
+                            .filter(|percent| *percent <= 100)
+                            .ok_or_else(|| E::ArgParseError {
                                 arg: duration_raw.clone(),
-                                err: err.to_string(),
-                            })
-                            .and_then(|dur| {
-                                dur.as_millis().try_into().map_err(|_| E::ArgParseError {
+                                err: "Percentage must be between 0 and 100".into(),
+                            })?;
+                        match first_char {
+                            Some('+') => SeekDirection::RelativePercent(percent as i32),
+                            Some('-') => SeekDirection::RelativePercent(-(percent as i32)),
+                            _ => SeekDirection::AbsolutePercent(percent),
+                        }
+                    } else {
+                        let unsigned_millis = match duration_raw.parse() {
+                            // accept raw milliseconds
+                            Ok(millis) => millis,
+                            Err(_) => parse_duration::parse(&duration_raw) // accept fancy duration
+                                .map_err(|err| E::ArgParseError {
                                     arg: duration_raw.clone(),
-                                    err: "Duration value too large".into(),
+                                    err: err.to_string(),
                                 })
-                            })?,
+                                .and_then(|dur| {
+                                    dur.as_millis().try_into().map_err(|_| E::ArgParseError {
+                                        arg: duration_raw.clone(),
+                                        err: "Duration value too large".into(),
+                                    })
+                                })?,
+                        };
+                        match first_char {
+                            // handle i32::MAX < unsigned_millis < u32::MAX gracefully
+                            Some('+') => {
+                                i32::try_from(unsigned_millis).map(SeekDirection::Relative)
+                            }
+                            Some('-') => i32::try_from(unsigned_millis)
+                                .map(|millis| SeekDirection::Relative(-millis)),
+                            _ => Ok(SeekDirection::Absolute(unsigned_millis)),
+                        }
+                        .map_err(|_| E::ArgParseError {
+                            arg: duration_raw,
+                            err: "Duration value too large".into(),
+                        })?
                     };
-                    let seek_direction = match first_char {
-                        // handle i32::MAX < unsigned_millis < u32::MAX gracefully
-                        Some('+') => i32::try_from(unsigned_millis).map(SeekDirection::Relative),
-                        Some('-') => i32::try_from(unsigned_millis)
-                            .map(|millis| SeekDirection::Relative(-millis)),
-                        _ => Ok(SeekDirection::Absolute(unsigned_millis)),
-                    }
-                    .map_err(|_| E::ArgParseError {
-                        arg: duration_raw,
-                        err: "Duration value too large".into(),
-                    })?;
                     Command::Seek(seek_direction)
                 }
                 "volup" => {
@@ -545,6 +1414,97 @@ pub fn parse(input: &str) -> Result<Vec<Command>, CommandParseError> {
                     }?;
                     Command::Shuffle(switch)
                 }
+                "automix" => {
+                    let switch = match args.first().cloned() {
+                        Some("on") => Ok(Some(true)),
+                        Some("off") => Ok(Some(false)),
+                        Some(arg) => Err(E::BadEnumArg {
+                            arg: arg.into(),
+                            accept: vec!["on".into(), "off".into()],
+                            optional: true,
+                        }),
+                        None => Ok(None),
+                    }?;
+                    Command::Automix(switch)
+                }
+                "datasaver" => {
+                    let switch = match args.first().cloned() {
+                        Some("on") => Ok(Some(true)),
+                        Some("off") => Ok(Some(false)),
+                        Some(arg) => Err(E::BadEnumArg {
+                            arg: arg.into(),
+                            accept: vec!["on".into(), "off".into()],
+                            optional: true,
+                        }),
+                        None => Ok(None),
+                    }?;
+                    Command::DataSaver(switch)
+                }
+                "headers" => {
+                    let switch = match args.first().cloned() {
+                        Some("on") => Ok(Some(true)),
+                        Some("off") => Ok(Some(false)),
+                        Some(arg) => Err(E::BadEnumArg {
+                            arg: arg.into(),
+                            accept: vec!["on".into(), "off".into()],
+                            optional: true,
+                        }),
+                        None => Ok(None),
+                    }?;
+                    Command::Headers(switch)
+                }
+                "mini" => {
+                    let switch = match args.first().cloned() {
+                        Some("on") => Ok(Some(true)),
+                        Some("off") => Ok(Some(false)),
+                        Some(arg) => Err(E::BadEnumArg {
+                            arg: arg.into(),
+                            accept: vec!["on".into(), "off".into()],
+                            optional: true,
+                        }),
+                        None => Ok(None),
+                    }?;
+                    Command::Mini(switch)
+                }
+                "party" => {
+                    let switch = match args.first().cloned() {
+                        Some("on") => Ok(Some(true)),
+                        Some("off") => Ok(Some(false)),
+                        Some(arg) => Err(E::BadEnumArg {
+                            arg: arg.into(),
+                            accept: vec!["on".into(), "off".into()],
+                            optional: true,
+                        }),
+                        None => Ok(None),
+                    }?;
+                    Command::Party(switch)
+                }
+                "clean" => {
+                    let switch = match args.first().cloned() {
+                        Some("on") => Ok(Some(true)),
+                        Some("off") => Ok(Some(false)),
+                        Some(arg) => Err(E::BadEnumArg {
+                            arg: arg.into(),
+                            accept: vec!["on".into(), "off".into()],
+                            optional: true,
+                        }),
+                        None => Ok(None),
+                    }?;
+                    Command::Clean(switch)
+                }
+                "private" => {
+                    let switch = match args.first().cloned() {
+                        Some("on") => Ok(Some(true)),
+                        Some("off") => Ok(Some(false)),
+                        Some(arg) => Err(E::BadEnumArg {
+                            arg: arg.into(),
+                            accept: vec!["on".into(), "off".into()],
+                            optional: true,
+                        }),
+                        None => Ok(None),
+                    }?;
+                    Command::PrivateSession(switch)
+                }
                 #[cfg(feature = "share_clipboard")]
                 "share" => {
                     let &target_mode_raw = args.first().ok_or(E::InsufficientArgs {
@@ -562,7 +1522,40 @@ pub fn parse(input: &str) -> Result<Vec<Command>, CommandParseError> {
                     }?;
                     Command::Share(target_mode)
                 }
+                "copy" => {
+                    match args.first() {
+                        Some(&"metadata") => {}
+                        Some(arg) => {
+                            return Err(E::BadEnumArg {
+                                arg: (*arg).into(),
+                                accept: vec!["metadata".into()],
+                                optional: false,
+                            })
+                        }
+                        None => {
+                            return Err(E::InsufficientArgs {
+                                cmd: command.into(),
+                                hint: Some("metadata".into()),
+                            })
+                        }
+                    }
+                    let target_mode = match args.get(1) {
+                        Some(&"selected") | None => TargetMode::Selected,
+                        Some(&"current") => TargetMode::Current,
+                        Some(arg) => {
+                            return Err(E::BadEnumArg {
+                                arg: (*arg).into(),
+                                accept: vec!["selected".into(), "current".into()],
+                                optional: true,
+                            })
+                        }
+                    };
+                    let path = (args.len() > 2).then(|| args[2..].join(" "));
+                    Command::CopyMetadata(target_mode, path)
+                }
                 "back" => Command::Back,
+                "forward" => Command::Forward,
+                "history" => Command::History,
                 "open" => {
                     let &target_mode_raw = args.first().ok_or(E::InsufficientArgs {
                         cmd: command.into(),
@@ -582,14 +1575,15 @@ pub fn parse(input: &str) -> Result<Vec<Command>, CommandParseError> {
                 "goto" => {
                     let &goto_mode_raw = args.first().ok_or(E::InsufficientArgs {
                         cmd: command.into(),
-                        hint: Some("album|artist".into()),
+                        hint: Some("album|artist|playing".into()),
                     })?;
                     let goto_mode = match goto_mode_raw {
                         "album" => Ok(GotoMode::Album),
                         "artist" => Ok(GotoMode::Artist),
+                        "playing" => Ok(GotoMode::Playing),
                         _ => Err(E::BadEnumArg {
                             arg: goto_mode_raw.into(),
-                            accept: vec!["album".into(), "artist".into()],
+                            accept: vec!["album".into(), "artist".into(), "playing".into()],
                             optional: false,
                         }),
                     }?;
@@ -695,6 +1689,17 @@ pub fn parse(input: &str) -> Result<Vec<Command>, CommandParseError> {
                 "jumpprevious" => Command::Jump(JumpMode::Previous),
                 "help" => Command::Help,
                 "reload" => Command::ReloadConfig,
+                "set" => match args.split_first() {
+                    Some((option, value)) if !value.is_empty() => {
+                        Command::Set(option.to_string(), value.join(" "))
+                    }
+                    _ => {
+                        return Err(E::InsufficientArgs {
+                            cmd: command.into(),
+                            hint: Some("an option and a value".into()),
+                        })
+                    }
+                },
                 "noop" => Command::Noop,
                 "insert" => {
                     let insert_source = match args.first().cloned() {
@@ -736,6 +1741,9 @@ pub fn parse(input: &str) -> Result<Vec<Command>, CommandParseError> {
                         "album" => Ok(SortKey::Album),
                         "added" => Ok(SortKey::Added),
                         "artist" => Ok(SortKey::Artist),
+                        "bpm" => Ok(SortKey::Bpm),
+                        "energy" => Ok(SortKey::Energy),
+                        "tracks" => Ok(SortKey::Tracks),
                         _ => Err(E::BadEnumArg {
                             arg: key_raw.into(),
                             accept: vec![
@@ -744,6 +1752,9 @@ pub fn parse(input: &str) -> Result<Vec<Command>, CommandParseError> {
                                 "album".into(),
                                 "added".into(),
                                 "artist".into(),
+                                "bpm".into(),
+                                "energy".into(),
+                                "tracks".into(),
                             ],
                             optional: false,
                         }),
@@ -784,9 +1795,284 @@ pub fn parse(input: &str) -> Result<Vec<Command>, CommandParseError> {
                     }?;
                     Command::ShowRecommendations(target_mode)
                 }
+                "tune" => {
+                    let &attribute_raw = args.first().ok_or(E::InsufficientArgs {
+                        cmd: command.into(),
+                        hint: Some("energy|tempo|valence".into()),
+                    })?;
+                    let attribute = match attribute_raw {
+                        "energy" => Ok(TuneAttribute::Energy),
+                        "tempo" => Ok(TuneAttribute::Tempo),
+                        "valence" => Ok(TuneAttribute::Valence),
+                        _ => Err(E::BadEnumArg {
+                            arg: attribute_raw.into(),
+                            accept: vec!["energy".into(), "tempo".into(), "valence".into()],
+                            optional: false,
+                        }),
+                    }?;
+                    let &value_raw = args.get(1).ok_or(E::InsufficientArgs {
+                        cmd: command.into(),
+                        hint: Some("a value or \"off\"".into()),
+                    })?;
+                    let value = match value_raw {
+                        "off" => None,
+                        _ => {
+                            let value =
+                                value_raw.parse::<f32>().map_err(|err| E::ArgParseError {
+                                    arg: value_raw.into(),
+                                    err: err.to_string(),
+                                })?;
+                            let valid = match attribute {
+                                TuneAttribute::Energy | TuneAttribute::Valence => {
+                                    (0.0..=1.0).contains(&value)
+                                }
+                                TuneAttribute::Tempo => value > 0.0,
+                            };
+                            if !valid {
+                                return Err(E::ArgParseError {
+                                    arg: value_raw.into(),
+                                    err: "value out of range".into(),
+                                });
+                            }
+                            Some(value)
+                        }
+                    };
+                    Command::Tune(attribute, value)
+                }
+                "mark" => {
+                    let &action_raw = args.first().ok_or(E::InsufficientArgs {
+                        cmd: command.into(),
+                        hint: Some("add|goto|remove".into()),
+                    })?;
+                    let action: fn(String) -> MarkAction = match action_raw {
+                        "add" => MarkAction::Add,
+                        "goto" => MarkAction::Goto,
+                        "remove" => MarkAction::Remove,
+                        _ => {
+                            return Err(E::BadEnumArg {
+                                arg: action_raw.into(),
+                                accept: vec!["add".into(), "goto".into(), "remove".into()],
+                                optional: false,
+                            })
+                        }
+                    };
+                    match args.get(1..).filter(|name| !name.is_empty()) {
+                        Some(name) => Command::Mark(action(name.join(" "))),
+                        None => {
+                            return Err(E::InsufficientArgs {
+                                cmd: command.into(),
+                                hint: Some("a bookmark name".into()),
+                            })
+                        }
+                    }
+                }
+                "seed" => {
+                    let &action_raw = args.first().ok_or(E::InsufficientArgs {
+                        cmd: command.into(),
+                        hint: Some("track|artist|genre|remove".into()),
+                    })?;
+                    let action = match action_raw {
+                        "track" => Ok(SeedAction::Track),
+                        "artist" => Ok(SeedAction::Artist),
+                        "genre" => match args.get(1..).filter(|genre| !genre.is_empty()) {
+                            Some(genre) => Ok(SeedAction::Genre(genre.join(" "))),
+                            None => Err(E::InsufficientArgs {
+                                cmd: command.into(),
+                                hint: Some("a genre".into()),
+                            }),
+                        },
+                        "remove" => {
+                            let &position_raw = args.get(1).ok_or(E::InsufficientArgs {
+                                cmd: command.into(),
+                                hint: Some("a seed position".into()),
+                            })?;
+                            position_raw
+                                .parse::<usize>()
+                                .map(SeedAction::Remove)
+                                .map_err(|err| E::ArgParseError {
+                                    arg: position_raw.into(),
+                                    err: err.to_string(),
+                                })
+                        }
+                        _ => Err(E::BadEnumArg {
+                            arg: action_raw.into(),
+                            accept: vec![
+                                "track".into(),
+                                "artist".into(),
+                                "genre".into(),
+                                "remove".into(),
+                            ],
+                            optional: false,
+                        }),
+                    }?;
+                    Command::Seed(action)
+                }
+                "refresh" => Command::Refresh,
                 "redraw" => Command::Redraw,
                 "exec" => Command::Execute(args.join(" ")),
+                "exec!" => Command::ExecuteDetached(args.join(" ")),
                 "reconnect" => Command::Reconnect,
+                "describekey" => Command::DescribeKey,
+                "record" => match args.first() {
+                    Some(register) => Command::Record(Some(parse_register(register)?)),
+                    None => Command::Record(None),
+                },
+                "restore" => Command::Restore,
+                "hideplayed" => Command::HidePlayed,
+                "snapshot" => Command::Snapshot,
+                "snapshots" => Command::Snapshots,
+                "tasks" => Command::Tasks,
+                "select" => Command::Select,
+                "favorite" => Command::Favorite,
+                "cover" => match args.first() {
+                    Some(&"save") if args.len() > 1 => Command::Cover(Some(args[1..].join(" "))),
+                    Some(&"save") => {
+                        return Err(E::InsufficientArgs {
+                            cmd: command.into(),
+                            hint: Some("a file".into()),
+                        })
+                    }
+                    Some(arg) => {
+                        return Err(E::BadEnumArg {
+                            arg: (*arg).into(),
+                            accept: vec!["save".into()],
+                            optional: true,
+                        })
+                    }
+                    None => Command::Cover(None),
+                },
+                "playlist" => {
+                    let &position_raw = args.first().ok_or(E::InsufficientArgs {
+                        cmd: command.into(),
+                        hint: Some("the position of a favorite playlist".into()),
+                    })?;
+                    let position = position_raw
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|position| *position > 0)
+                        .ok_or_else(|| E::ArgParseError {
+                            arg: position_raw.into(),
+                            err: "Expected the position of a favorite playlist, starting at 1"
+                                .into(),
+                        })?;
+                    Command::OpenFavorite(position)
+                }
+                "filter" => match args.first().cloned() {
+                    Some("year") => {
+                        let &range_raw = args.get(1).ok_or(E::InsufficientArgs {
+                            cmd: command.into(),
+                            hint: Some("a year, decade or range of years".into()),
+                        })?;
+                        let range = YearRange::parse(range_raw).ok_or(E::ArgParseError {
+                            arg: range_raw.into(),
+                            err: "Expected a year (1995), a decade (1990s) or a range (1990-1999)"
+                                .into(),
+                        })?;
+                        Command::Filter(Some(range))
+                    }
+                    Some("clear") | None => Command::Filter(None),
+                    Some(arg) => {
+                        return Err(E::BadEnumArg {
+                            arg: arg.into(),
+                            accept: vec!["year".into(), "clear".into()],
+                            optional: true,
+                        })
+                    }
+                },
+                "timefmt" => match args.first().cloned() {
+                    Some("total") => Command::TimeFormat(Some(TimeFormat::Total)),
+                    Some("remaining") => Command::TimeFormat(Some(TimeFormat::Remaining)),
+                    Some(arg) => {
+                        return Err(E::BadEnumArg {
+                            arg: arg.into(),
+                            accept: vec!["total".into(), "remaining".into()],
+                            optional: true,
+                        })
+                    }
+                    None => Command::TimeFormat(None),
+                },
+                "device" => match args.first() {
+                    Some(&"audio") => Command::Device(DeviceKind::Audio),
+                    Some(arg) => {
+                        return Err(E::BadEnumArg {
+                            arg: (*arg).into(),
+                            accept: vec!["audio".into()],
+                            optional: false,
+                        })
+                    }
+                    None => {
+                        return Err(E::InsufficientArgs {
+                            cmd: command.into(),
+                            hint: Some("audio".into()),
+                        })
+                    }
+                },
+                "bitrate" => match args.first() {
+                    Some(&"96") => Command::Bitrate(96),
+                    Some(&"160") => Command::Bitrate(160),
+                    Some(&"320") => Command::Bitrate(320),
+                    Some(arg) => {
+                        return Err(E::BadEnumArg {
+                            arg: (*arg).into(),
+                            accept: vec!["96".into(), "160".into(), "320".into()],
+                            optional: false,
+                        })
+                    }
+                    None => {
+                        return Err(E::InsufficientArgs {
+                            cmd: command.into(),
+                            hint: Some("96|160|320".into()),
+                        })
+                    }
+                },
+                "import" => {
+                    #[cfg(unix)]
+                    const SOURCES: &str = "ncspot|mpd";
+                    #[cfg(not(unix))]
+                    const SOURCES: &str = "mpd";
+                    match args.first() {
+                        Some(&"queue") => {}
+                        Some(arg) => {
+                            return Err(E::BadEnumArg {
+                                arg: (*arg).into(),
+                                accept: vec!["queue".into()],
+                                optional: false,
+                            })
+                        }
+                        None => {
+                            return Err(E::InsufficientArgs {
+                                cmd: command.into(),
+                                hint: Some(format!("queue {SOURCES}")),
+                            })
+                        }
+                    }
+                    let source = match args.get(1) {
+                        #[cfg(unix)]
+                        Some(&"ncspot") => ImportSource::Ncspot(args.get(2).map(|s| s.to_string())),
+                        Some(&"mpd") => ImportSource::Mpd,
+                        Some(arg) => {
+                            return Err(E::BadEnumArg {
+                                arg: (*arg).into(),
+                                accept: SOURCES.split('|').map(String::from).collect(),
+                                optional: false,
+                            })
+                        }
+                        None => {
+                            return Err(E::InsufficientArgs {
+                                cmd: command.into(),
+                                hint: Some(SOURCES.into()),
+                            })
+                        }
+                    };
+                    Command::ImportQueue(source)
+                }
+                "replay" => {
+                    let register = args.first().ok_or(E::InsufficientArgs {
+                        cmd: command.into(),
+                        hint: Some("a register".into()),
+                    })?;
+                    Command::Replay(parse_register(register)?)
+                }
                 _ => {
                     return Err(E::NoSuchCommand {
                         cmd: command.into(),
@@ -798,3 +2084,85 @@ pub fn parse(input: &str) -> Result<Vec<Command>, CommandParseError> {
     }
     Ok(commands)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(input: &str) -> Vec<String> {
+        parse(input)
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    fn counted(input: &str, count: Option<u32>) -> Vec<String> {
+        parse(input)
+            .unwrap()
+            .into_iter()
+            .flat_map(|command| command.with_count(count))
+            .map(|command| command.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn count_multiplies_amounts() {
+        assert_eq!(counted("move down 1", Some(5)), parsed("move down 5"));
+        assert_eq!(counted("seek +1000", Some(3)), parsed("seek +3000"));
+        assert_eq!(counted("seek -10%", Some(2)), parsed("seek -20%"));
+        assert_eq!(counted("volup 5", Some(4)), parsed("volup 20"));
+    }
+
+    #[test]
+    fn count_repeats_skips() {
+        assert_eq!(counted("next", Some(3)), parsed("next; next; next"));
+        assert_eq!(counted("previous", Some(2)), parsed("previous; previous"));
+    }
+
+    #[test]
+    fn count_seeks_to_percentage() {
+        assert_eq!(counted("seek 0%", Some(50)), parsed("seek 50%"));
+        assert_eq!(counted("seek 0%", Some(250)), parsed("seek 100%"));
+    }
+
+    #[test]
+    fn count_leaves_other_commands_alone() {
+        assert_eq!(counted("move down 1", None), parsed("move down 1"));
+        assert_eq!(counted("shuffle", Some(4)), parsed("shuffle"));
+    }
+
+    #[test]
+    fn chain_is_parsed_into_its_commands() {
+        assert_eq!(
+            parsed("save current && similar current"),
+            vec![format!(
+                "{} && {}",
+                parsed("save current")[0],
+                parsed("similar current")[0]
+            )]
+        );
+        let commands = parse("save current && similar current; next").unwrap();
+        assert_eq!(commands.len(), 2);
+        assert!(matches!(&commands[0], Command::Chain(chain) if chain.len() == 2));
+    }
+
+    #[test]
+    fn chain_part_keeps_escaped_separator() {
+        let commands = parse("search a;;b && next").unwrap();
+        assert!(matches!(
+            &commands[0],
+            Command::Chain(chain) if matches!(&chain[..], [Command::Search(term), Command::Next] if term == "a;b")
+        ));
+    }
+
+    #[test]
+    fn escaped_chain_separator_is_an_argument() {
+        let commands = parse("search rock &&&& roll").unwrap();
+        assert!(matches!(&commands[..], [Command::Search(term)] if term == "rock && roll"));
+        assert_eq!(
+            parsed(&commands[0].to_string()),
+            parsed("search rock &&&& roll")
+        );
+    }
+}
@@ -1,32 +1,63 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Duration;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use crate::application::UserData;
 use crate::command::{
-    parse, Command, GotoMode, JumpMode, MoveAmount, MoveMode, SeekDirection, ShiftMode, TargetMode,
+    parse, Command, DeviceKind, GotoMode, JumpMode, MarkAction, MoveAmount, MoveMode,
+    SeekDirection, ShiftMode, TargetMode, TimeFormat,
+};
+use crate::config::{
+    user_configuration_directory, Config, ConfigValues, Confirmations, UserState,
+    DEFAULT_SEEK_STEP_LARGE_MS, DEFAULT_SEEK_STEP_MS,
 };
-use crate::config::{user_configuration_directory, Config};
 use crate::events::EventManager;
+use crate::exec;
 use crate::ext_traits::CursiveExt;
+use crate::i18n::{self, tr, tr_args};
 use crate::library::Library;
+use crate::model::playable::Playable;
 use crate::queue::{Queue, RepeatSetting};
+use crate::queue_import;
 use crate::spotify::{Spotify, VOLUME_PERCENT};
+use crate::spotify_url::SpotifyUrl;
 use crate::traits::{IntoBoxedViewExt, ListItem, ViewExt};
+use crate::ui::accelerators;
+use crate::ui::audio_device;
 use crate::ui::contextmenu::{
     AddToPlaylistMenu, ContextMenu, SelectArtistActionMenu, SelectArtistMenu,
 };
+#[cfg(feature = "cover")]
+use crate::ui::cover::CoverModal;
 use crate::ui::help::HelpView;
+use crate::ui::history;
 use crate::ui::layout::Layout;
 use crate::ui::modal::Modal;
+use crate::ui::playlist::PlaylistView;
+use crate::ui::playlists;
 use crate::ui::search_results::SearchResultsView;
-use cursive::event::{Event, Key};
-use cursive::traits::View;
-use cursive::views::Dialog;
+use crate::ui::snapshots;
+use crate::ui::tasks;
+use crate::utils::{self, ms_to_hms};
+use cursive::event::{Event, Key, MouseEvent};
+use cursive::traits::{Resizable, View};
+use cursive::view::Margins;
+use cursive::views::{Dialog, EditView};
 use cursive::Cursive;
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use ncspot::CONFIGURATION_FILE_NAME;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+
+/// The largest count prefix accepted before a keybinding.
+const MAX_COUNT: u32 = 9999;
+
+/// Relative seeks in the same direction less than this apart, e.g. while a seek key is held, make
+/// the seek step grow.
+const SEEK_ACCELERATION_WINDOW: Duration = Duration::from_millis(400);
+/// The largest factor the seek step grows by.
+const MAX_SEEK_ACCELERATION: i32 = 8;
 
 pub enum CommandResult {
     Consumed(Option<String>),
@@ -35,9 +66,82 @@ pub enum CommandResult {
     Ignored,
 }
 
+/// Mouse actions on the items of a list, which can be bound to commands in the `[mousebindings]`
+/// section of the configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MouseGesture {
+    /// A click on the selected item. Terminals don't report double clicks, so this emulates them.
+    DoubleClick,
+    MiddleClick,
+    RightClick,
+    WheelUp,
+    WheelDown,
+}
+
+impl MouseGesture {
+    /// The gesture bound by a key of `[mousebindings]`, along with the screen it is limited to,
+    /// e.g. `queue.MiddleClick`.
+    fn parse_binding(binding: &str) -> Option<(Option<String>, Self)> {
+        let (screen, name) = match binding.rsplit_once('.') {
+            Some((screen, name)) => (Some(screen.to_string()), name),
+            None => (None, binding),
+        };
+        let gesture = match name {
+            "DoubleClick" => Self::DoubleClick,
+            "MiddleClick" => Self::MiddleClick,
+            "RightClick" => Self::RightClick,
+            "WheelUp" => Self::WheelUp,
+            "WheelDown" => Self::WheelDown,
+            _ => return None,
+        };
+        Some((screen, gesture))
+    }
+
+    /// The wheel gesture of a mouse `event`, if it is one.
+    pub fn from_wheel(event: MouseEvent) -> Option<Self> {
+        match event {
+            MouseEvent::WheelUp => Some(Self::WheelUp),
+            MouseEvent::WheelDown => Some(Self::WheelDown),
+            _ => None,
+        }
+    }
+
+    /// Whether the gesture is bound to commands in any screen.
+    pub fn is_bound(self, config: &Config) -> bool {
+        self.bindings(config).next().is_some()
+    }
+
+    /// Whether the gesture is bound to commands in the given `screen` or in all screens.
+    pub fn is_bound_in(self, config: &Config, screen: Option<&str>) -> bool {
+        self.bindings(config)
+            .any(|bound_screen| bound_screen.is_none() || bound_screen.as_deref() == screen)
+    }
+
+    /// The screens the gesture is bound in, `None` for bindings in all screens.
+    fn bindings(self, config: &Config) -> impl Iterator<Item = Option<String>> {
+        let bindings = config.values().mousebindings.clone().unwrap_or_default();
+        bindings
+            .into_keys()
+            .filter_map(|binding| Self::parse_binding(&binding))
+            .filter(move |(_, gesture)| *gesture == self)
+            .map(|(screen, _)| screen)
+    }
+}
+
 pub struct CommandManager {
     aliases: HashMap<String, String>,
     bindings: RefCell<HashMap<String, Vec<Command>>>,
+    /// The commands bound to mouse gestures, either in a single screen or in all of them.
+    mouse_bindings: RefCell<HashMap<(Option<String>, MouseGesture), Vec<Command>>>,
+    /// The count prefix typed so far, e.g. the `5` of `5j`.
+    count: RefCell<Option<u32>>,
+    /// The register and commands of the macro being recorded.
+    recording: RefCell<Option<(char, Vec<Command>)>>,
+    /// Whether a macro is being replayed, in which case commands aren't recorded.
+    replaying: Cell<bool>,
+    /// When the last relative seek happened, whether it went forward and how many seeks before it
+    /// followed each other within the [SEEK_ACCELERATION_WINDOW].
+    last_seek: Cell<Option<(Instant, bool, i32)>>,
     spotify: Spotify,
     queue: Arc<Queue>,
     library: Arc<Library>,
@@ -54,9 +158,15 @@ impl CommandManager {
         events: EventManager,
     ) -> Self {
         let bindings = RefCell::new(Self::get_bindings(&config));
+        let mouse_bindings = RefCell::new(Self::get_mouse_bindings(&config));
         Self {
             aliases: HashMap::new(),
             bindings,
+            mouse_bindings,
+            count: RefCell::new(None),
+            recording: RefCell::new(None),
+            replaying: Cell::new(false),
+            last_seek: Cell::new(None),
             spotify,
             queue,
             library,
@@ -68,7 +178,7 @@ impl CommandManager {
     pub fn get_bindings(config: &Config) -> HashMap<String, Vec<Command>> {
         let config = config.values();
         let mut kb = if config.default_keybindings.unwrap_or(true) {
-            Self::default_keybindings()
+            Self::default_keybindings(&config)
         } else {
             HashMap::new()
         };
@@ -92,6 +202,130 @@ impl CommandManager {
         kb
     }
 
+    fn get_mouse_bindings(
+        config: &Config,
+    ) -> HashMap<(Option<String>, MouseGesture), Vec<Command>> {
+        let mut bindings = HashMap::new();
+        for (binding, commands) in config.values().mousebindings.clone().unwrap_or_default() {
+            let Some(gesture) = MouseGesture::parse_binding(&binding) else {
+                error!("Could not parse mouse binding: \"{}\"", binding);
+                continue;
+            };
+            match parse(&commands) {
+                Ok(cmds) => {
+                    info!("Mouse binding: {} -> {:?}", binding, cmds);
+                    bindings.insert(gesture, cmds);
+                }
+                Err(err) => {
+                    error!(
+                        "Invalid command(s) for mouse binding {}-\"{}\": {}",
+                        binding, commands, err
+                    );
+                }
+            }
+        }
+        bindings
+    }
+
+    /// Run the commands bound to `gesture` in the focused screen. Returns `false` if it isn't bound
+    /// there, so the view can fall back to its built-in behavior.
+    pub fn handle_mouse(&self, s: &mut Cursive, gesture: MouseGesture) -> bool {
+        let screen = s
+            .call_on_name("main", |layout: &mut Layout| layout.screen_id())
+            .flatten();
+        let commands = {
+            let bindings = self.mouse_bindings.borrow();
+            bindings
+                .get(&(screen, gesture))
+                .or_else(|| bindings.get(&(None, gesture)))
+                .cloned()
+        };
+        let Some(commands) = commands else {
+            return false;
+        };
+        for command in commands {
+            self.handle(s, command);
+        }
+        true
+    }
+
+    /// The keybindings currently in use.
+    pub fn bindings(&self) -> HashMap<String, Vec<Command>> {
+        self.bindings.borrow().clone()
+    }
+
+    /// Find custom keybindings that override default ones and different keybindings that resolve
+    /// to the same key. Custom bindings to `noop` are considered intentional and not reported.
+    pub fn keybinding_conflicts(config: &Config) -> Vec<String> {
+        let values = config.values();
+        let mut custom_keys: Vec<(String, String)> = values
+            .keybindings
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        custom_keys.sort();
+        let mut conflicts = Vec::new();
+
+        if values.default_keybindings.unwrap_or(true) {
+            let defaults = Self::default_keybindings(&values);
+            let mut default_keys: Vec<&String> = defaults.keys().collect();
+            default_keys.sort();
+
+            for (key, commands) in custom_keys.iter() {
+                if commands.trim() == "noop" {
+                    continue;
+                }
+                let event = Self::parse_keybinding(key);
+                let custom = parse(commands).ok().map(|c| Self::format_commands(&c));
+                for default_key in default_keys.iter() {
+                    let default = Self::format_commands(&defaults[*default_key]);
+                    if event.is_some()
+                        && Self::parse_keybinding(default_key) == event
+                        && custom.as_ref() != Some(&default)
+                    {
+                        conflicts.push(tr_args(
+                            "{} overrides the default binding {} -> {}",
+                            &[key.as_str(), default_key.as_str(), &default],
+                        ));
+                    }
+                }
+            }
+        }
+
+        for (i, (key, _)) in custom_keys.iter().enumerate() {
+            let event = Self::parse_keybinding(key);
+            for (other, _) in custom_keys.iter().skip(i + 1) {
+                if event.is_some() && Self::parse_keybinding(other) == event {
+                    conflicts.push(tr_args(
+                        "{} and {} are the same key",
+                        &[key.as_str(), other.as_str()],
+                    ));
+                }
+            }
+        }
+
+        conflicts
+    }
+
+    /// A summary of the keybinding conflicts of `config` for the result bar, if there are any.
+    pub fn keybinding_conflicts_message(config: &Config) -> Option<String> {
+        let conflicts = Self::keybinding_conflicts(config);
+        for conflict in conflicts.iter() {
+            warn!("Keybinding conflict: {}", conflict);
+        }
+        (!conflicts.is_empty())
+            .then(|| tr_args("Keybinding conflicts: {}", &[&conflicts.join(", ")]))
+    }
+
+    fn format_commands(commands: &[Command]) -> String {
+        commands
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
     pub fn register_aliases<S: Into<String>>(&mut self, name: S, aliases: Vec<S>) {
         let name = name.into();
         for a in aliases {
@@ -105,6 +339,75 @@ impl CommandManager {
         self.register_aliases("repeat", vec!["loop"]);
     }
 
+    /// Persist the current queue and playback position to the user state file.
+    pub fn save_state(&self) {
+        let queue = self.queue.queue.read().unwrap();
+        self.config.with_state_mut(move |s| {
+            debug!(
+                "saving state, {} items, current track: {:?}",
+                queue.len(),
+                self.queue.get_current_index()
+            );
+            s.queuestate.queue.clone_from(&queue);
+            s.queuestate.random_order = self.queue.get_random_order();
+            s.queuestate.current_track = self.queue.get_current_index();
+            s.queuestate.track_progress = self.spotify.get_current_progress();
+        });
+        self.config.save_state();
+    }
+
+    /// Split an `exec` command into its arguments and replace their placeholders like `{title}`
+    /// with the values of the current item.
+    fn exec_args(&self, cmd: &str) -> Vec<String> {
+        exec::split_args(cmd)
+            .iter()
+            .map(|arg| self.expand_placeholders(arg))
+            .collect()
+    }
+
+    /// Replace the placeholders in `arg` with the values of the current item, which are empty if
+    /// nothing is playing.
+    fn expand_placeholders(&self, arg: &str) -> String {
+        let current = self.queue.get_current();
+        let value = |f: &dyn Fn(&Playable) -> Option<String>| {
+            current.as_ref().and_then(f).unwrap_or_default()
+        };
+        let progress = self.spotify.get_current_progress().as_secs();
+
+        arg.replace(
+            "{title}",
+            &value(&|p| {
+                Some(match p {
+                    Playable::Track(track) => track.title.clone(),
+                    Playable::Episode(episode) => episode.name.clone(),
+                })
+            }),
+        )
+        .replace(
+            "{artist}",
+            &value(&|p| {
+                p.artists().map(|artists| {
+                    artists
+                        .iter()
+                        .map(|artist| artist.name.clone())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })
+            }),
+        )
+        .replace(
+            "{album}",
+            &value(&|p| p.track().and_then(|track| track.album)),
+        )
+        .replace("{url}", &value(&|p| p.share_url()))
+        .replace("{uri}", &value(&|p| Some(p.uri())))
+        .replace(
+            "{duration}",
+            &value(&|p| Some((p.duration() / 1000).to_string())),
+        )
+        .replace("{progress}", &value(&|_| Some(progress.to_string())))
+    }
+
     fn handle_default_commands(
         &self,
         s: &mut Cursive,
@@ -113,19 +416,8 @@ impl CommandManager {
         match cmd {
             Command::Noop => Ok(None),
             Command::Quit => {
-                let queue = self.queue.queue.read().unwrap();
-                self.config.with_state_mut(move |s| {
-                    debug!(
-                        "saving state, {} items, current track: {:?}",
-                        queue.len(),
-                        self.queue.get_current_index()
-                    );
-                    s.queuestate.queue.clone_from(&queue);
-                    s.queuestate.random_order = self.queue.get_random_order();
-                    s.queuestate.current_track = self.queue.get_current_index();
-                    s.queuestate.track_progress = self.spotify.get_current_progress();
-                });
-                self.config.save_state();
+                self.save_state();
+                self.spotify.shutdown();
                 s.quit();
                 Ok(None)
             }
@@ -150,20 +442,38 @@ impl CommandManager {
                 self.queue.next(true);
                 Ok(None)
             }
+            Command::DedupQueue => {
+                let removed = self.queue.dedup();
+                Ok(Some(tr_args(
+                    "Removed {} duplicates from the queue",
+                    &[&removed.to_string()],
+                )))
+            }
+            Command::RandomizeQueue => {
+                self.queue.randomize();
+                Ok(Some(tr("Shuffled the queue").to_string()))
+            }
             Command::Clear => {
+                if !self.confirmations().clear_queue() {
+                    self.queue.clear();
+                    return Ok(None);
+                }
                 let queue = self.queue.clone();
-                let confirmation = Dialog::text("Clear queue?")
-                    .button("Yes", move |s| {
+                let confirmation = Dialog::text(tr("Clear queue?"))
+                    .button(tr("Yes"), move |s| {
                         s.pop_layer();
                         queue.clear()
                     })
-                    .dismiss_button("No");
-                s.add_layer(Modal::new(confirmation));
+                    .dismiss_button(tr("No"));
+                s.add_layer(Modal::new(accelerators::numbered_buttons(confirmation)));
                 Ok(None)
             }
             Command::UpdateLibrary => {
-                self.library.update_library();
-                Ok(None)
+                if self.library.update_library() {
+                    Ok(None)
+                } else {
+                    Err(tr("The library is already being updated").to_string())
+                }
             }
             Command::TogglePlay => {
                 self.queue.toggleplayback();
@@ -174,6 +484,11 @@ impl CommandManager {
                 self.queue.set_shuffle(mode);
                 Ok(None)
             }
+            Command::Automix(mode) => {
+                let mode = mode.unwrap_or_else(|| !self.queue.get_automix());
+                self.queue.set_automix(mode);
+                Ok(None)
+            }
             Command::Repeat(mode) => {
                 let mode = mode.unwrap_or_else(|| match self.queue.get_repeat() {
                     RepeatSetting::None => RepeatSetting::RepeatPlaylist,
@@ -186,8 +501,20 @@ impl CommandManager {
             }
             Command::Seek(direction) => {
                 match *direction {
-                    SeekDirection::Relative(rel) => self.spotify.seek_relative(rel),
+                    SeekDirection::Relative(rel) => {
+                        self.spotify.seek_relative(self.accelerate_seek(rel))
+                    }
                     SeekDirection::Absolute(abs) => self.spotify.seek(abs),
+                    SeekDirection::RelativePercent(rel) => {
+                        if let Some(current) = self.queue.get_current() {
+                            self.spotify.seek_relative_percent(rel, current.duration());
+                        }
+                    }
+                    SeekDirection::AbsolutePercent(abs) => {
+                        if let Some(current) = self.queue.get_current() {
+                            self.spotify.seek_percent(abs, current.duration());
+                        }
+                    }
                 }
                 Ok(None)
             }
@@ -208,24 +535,94 @@ impl CommandManager {
                 self.spotify.set_volume(volume, true);
                 Ok(None)
             }
+            Command::OpenFavorite(position) => {
+                let Some(playlist) = self.library.favorite_playlists().get(position - 1).cloned()
+                else {
+                    return Err(tr_args(
+                        "There is no favorite playlist {}",
+                        &[&position.to_string()],
+                    ));
+                };
+                let view = Box::new(PlaylistView::new(
+                    self.queue.clone(),
+                    self.library.clone(),
+                    &playlist,
+                ));
+                s.call_on_name("main", move |v: &mut Layout| v.push_view(view));
+                Ok(None)
+            }
+            Command::Chain(commands) => {
+                let mut output = None;
+                for command in commands {
+                    output = self.run(s, command)?;
+                }
+                Ok(output)
+            }
+            Command::Cover(Some(path)) => {
+                let Some(url) = self.queue.get_current().and_then(|p| p.cover_url()) else {
+                    return Err(tr("Nothing with a cover is playing").to_string());
+                };
+                let path = PathBuf::from(path);
+                let sink = s.cb_sink().clone();
+                thread::spawn(move || {
+                    // Covers that were shown before are in the cache already.
+                    let cached = utils::cache_path_for_url(url.clone());
+                    let saved = if cached.exists() {
+                        std::fs::copy(&cached, &path).map(|_| ())
+                    } else {
+                        utils::download(url, path.clone())
+                    };
+                    let result = match saved {
+                        Ok(()) => Ok(Some(tr_args(
+                            "Saved the cover to {}",
+                            &[&path.display().to_string()],
+                        ))),
+                        Err(e) => Err(tr_args("Could not save the cover: {}", &[&e.to_string()])),
+                    };
+                    sink.send(Box::new(move |s: &mut Cursive| {
+                        s.on_layout(|_, mut layout| layout.set_result(result));
+                    }))
+                    .ok();
+                });
+                Ok(None)
+            }
+            #[cfg(feature = "cover")]
+            Command::Cover(None) => {
+                let Some(url) = self.queue.get_current().and_then(|p| p.cover_url()) else {
+                    return Err(tr("Nothing with a cover is playing").to_string());
+                };
+                let modal = CoverModal::new(self.queue.clone(), self.library.clone(), url);
+                s.add_layer(Modal::new(modal));
+                Ok(None)
+            }
+            #[cfg(not(feature = "cover"))]
+            Command::Cover(None) => {
+                Err(tr("Showing covers requires the cover feature").to_string())
+            }
             Command::Help => {
-                let view = Box::new(HelpView::new(self.bindings.borrow().clone()));
+                let view = Box::new(HelpView::new(self.bindings()));
                 s.call_on_name("main", move |v: &mut Layout| v.push_view(view));
                 Ok(None)
             }
             Command::ReloadConfig => {
                 self.config.reload().map_err(|_| {
-                    format!(
+                    let path = user_configuration_directory()
+                        .map(|ref mut path| {
+                            path.push(CONFIGURATION_FILE_NAME);
+                            path.to_string_lossy().to_string()
+                        })
+                        .expect("configuration directory expected but not found");
+                    tr_args(
                         "Failed to reload configuration. Fix errors in {} and try again.",
-                        user_configuration_directory()
-                            .map(|ref mut path| {
-                                path.push(CONFIGURATION_FILE_NAME);
-                                path.to_string_lossy().to_string()
-                            })
-                            .expect("configuration directory expected but not found")
+                        &[&path],
                     )
                 })?;
 
+                i18n::set_language(self.config.values().language);
+
+                // apply the volume curve
+                self.spotify.set_volume(self.spotify.volume(), false);
+
                 // update theme
                 let theme = self.config.build_theme();
                 s.set_theme(theme);
@@ -233,12 +630,20 @@ impl CommandManager {
                 // update bindings
                 self.unregister_keybindings(s);
                 self.bindings.replace(Self::get_bindings(&self.config));
+                self.mouse_bindings
+                    .replace(Self::get_mouse_bindings(&self.config));
                 self.register_keybindings(s);
-                Ok(None)
+                let bindings = self.bindings();
+                s.on_layout(|_, mut layout| layout.set_bindings(bindings));
+
+                // The configuration was applied, conflicts are only worth a warning.
+                Ok(Self::keybinding_conflicts_message(&self.config))
             }
             Command::NewPlaylist(name) => {
                 match self.spotify.api.create_playlist(name, None, None) {
-                    Ok(_) => self.library.update_library(),
+                    Ok(_) => {
+                        self.library.update_library();
+                    }
                     Err(_) => error!("could not create playlist {}", name),
                 }
                 Ok(None)
@@ -263,26 +668,302 @@ impl CommandManager {
                 Ok(None)
             }
             Command::Logout => {
-                self.spotify.shutdown();
-
-                let mut credentials_path = crate::config::cache_path("librespot");
-                credentials_path.push("credentials.json");
-                std::fs::remove_file(credentials_path).unwrap();
-
-                s.quit();
+                if !self.confirmations().logout() {
+                    Self::logout(s, &self.spotify);
+                    return Ok(None);
+                }
+                let spotify = self.spotify.clone();
+                let confirmation = Dialog::text(tr("Log out and quit?"))
+                    .button(tr("Yes"), move |s| Self::logout(s, &spotify))
+                    .dismiss_button(tr("No"));
+                s.add_layer(Modal::new(accelerators::numbered_buttons(confirmation)));
                 Ok(None)
             }
             Command::Execute(cmd) => {
-                log::info!("Executing command: {}", cmd);
-                let cmd = std::ffi::CString::new(cmd.clone()).unwrap();
-                let result = unsafe { libc::system(cmd.as_ptr()) };
-                log::info!("Exit code: {}", result);
+                let args = self.exec_args(cmd);
+                log::info!("Executing command: {:?}", args);
+                exec::run(args, s.cb_sink().clone());
+                Ok(None)
+            }
+            Command::ExecuteDetached(cmd) => {
+                let args = self.exec_args(cmd);
+                log::info!("Executing detached command: {:?}", args);
+                exec::spawn_detached(&args)?;
                 Ok(None)
             }
             Command::Reconnect => {
-                self.spotify.shutdown();
+                if self.spotify.has_worker() {
+                    // The worker is restarted once it has shut down.
+                    self.spotify.shutdown();
+                } else {
+                    self.spotify.start_worker().map_err(|e| e.to_string())?;
+                }
+                Ok(None)
+            }
+            Command::Record(Some(register)) => {
+                self.stop_recording();
+                self.recording.replace(Some((*register, Vec::new())));
+                Ok(Some(tr_args(
+                    "Recording to register {}",
+                    &[&register.to_string()],
+                )))
+            }
+            Command::Record(None) => match self.stop_recording() {
+                Some((register, count)) => Ok(Some(tr_args(
+                    "Recorded {} commands to register {}",
+                    &[&count.to_string(), &register.to_string()],
+                ))),
+                None => Err(tr("Not recording").to_string()),
+            },
+            Command::Replay(register) => {
+                if self.replaying.get() {
+                    return Err(tr("Macros can't be replayed from a macro").to_string());
+                }
+                let recorded = self.config.state().macros.get(register).cloned();
+                let Some(recorded) = recorded else {
+                    return Err(tr_args("Register {} is empty", &[&register.to_string()]));
+                };
+                let commands = recorded
+                    .iter()
+                    .map(|command| parse(&command.replace(';', ";;")))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| {
+                        tr_args(
+                            "Could not replay register {}: {}",
+                            &[&register.to_string(), &e.to_string()],
+                        )
+                    })?
+                    .concat();
+                self.replaying.set(true);
+                for command in commands {
+                    self.handle(s, command);
+                }
+                self.replaying.set(false);
                 Ok(None)
             }
+            Command::Restore => match playlists::restore_dialog(self.library.clone()) {
+                Some(dialog) => {
+                    s.add_layer(dialog);
+                    Ok(None)
+                }
+                None => Err(tr("There are no archived playlists").to_string()),
+            },
+            Command::Snapshots => {
+                match snapshots::snapshots_dialog(self.queue.clone(), self.library.clone()) {
+                    Some(dialog) => {
+                        s.add_layer(dialog);
+                        Ok(None)
+                    }
+                    None => Err(tr("There are no snapshots").to_string()),
+                }
+            }
+            Command::Tasks => match tasks::tasks_dialog(self.library.tasks.clone()) {
+                Some(dialog) => {
+                    s.add_layer(dialog);
+                    Ok(None)
+                }
+                None => Ok(Some(tr("No tasks are running").to_string())),
+            },
+            Command::History => {
+                let items = s.on_layout(|_, layout| layout.history());
+                match history::history_dialog(items, self.queue.clone(), self.library.clone()) {
+                    Some(dialog) => {
+                        s.add_layer(dialog);
+                        Ok(None)
+                    }
+                    None => Err(tr("There are no visited views").to_string()),
+                }
+            }
+            Command::Bitrate(bitrate) => {
+                self.spotify.set_bitrate(*bitrate);
+                self.queue.restart_player();
+                Ok(Some(tr_args(
+                    "Streaming at {} kbit/s",
+                    &[&bitrate.to_string()],
+                )))
+            }
+            Command::DataSaver(mode) => {
+                let mode = mode.unwrap_or_else(|| !self.spotify.data_saver());
+                self.spotify.set_data_saver(mode);
+                self.queue.restart_player();
+                Ok(Some(if mode {
+                    tr("Data saver enabled").to_string()
+                } else {
+                    tr("Data saver disabled").to_string()
+                }))
+            }
+            Command::TimeFormat(format) => {
+                let format = format.unwrap_or_else(|| match self.config.state().time_format {
+                    TimeFormat::Total => TimeFormat::Remaining,
+                    TimeFormat::Remaining => TimeFormat::Total,
+                });
+                self.config.with_state_mut(|s| s.time_format = format);
+                Ok(None)
+            }
+            Command::Mini(mode) => {
+                let mode = *mode;
+                s.on_layout(|_, mut layout| layout.set_mini(mode));
+                Ok(None)
+            }
+            Command::Headers(mode) => {
+                let shown = mode.unwrap_or_else(|| self.config.state().compact_headers);
+                self.config.with_state_mut(|s| s.compact_headers = !shown);
+                Ok(None)
+            }
+            Command::Party(mode) => {
+                let enable = mode.unwrap_or_else(|| !self.config.state().party_mode);
+                if enable {
+                    if self.config.values().party_passphrase.is_none() {
+                        return Err(tr("Set a party_passphrase to use party mode").to_string());
+                    }
+                    self.config.with_state_mut(|s| s.party_mode = true);
+                    Ok(Some(tr("Party mode enabled").to_string()))
+                } else if !self.config.state().party_mode {
+                    Ok(None)
+                } else if self.config.values().party_passphrase.is_none() {
+                    // The passphrase was removed from the configuration, so there is nothing to
+                    // ask for.
+                    self.config.with_state_mut(|s| s.party_mode = false);
+                    Ok(Some(tr("Party mode disabled").to_string()))
+                } else {
+                    s.add_layer(Self::passphrase_dialog(
+                        self.config.clone(),
+                        |values| values.party_passphrase.clone(),
+                        |state| state.party_mode = false,
+                        "Party mode disabled",
+                    ));
+                    Ok(None)
+                }
+            }
+            Command::Clean(mode) => {
+                let enable = mode.unwrap_or_else(|| !self.config.state().clean_mode);
+                let passphrase = self.config.values().clean_passphrase.clone();
+                if enable {
+                    self.config.with_state_mut(|s| s.clean_mode = true);
+                    Ok(Some(tr("Clean mode enabled").to_string()))
+                } else if !self.config.state().clean_mode {
+                    Ok(None)
+                } else if passphrase.is_none() {
+                    self.config.with_state_mut(|s| s.clean_mode = false);
+                    Ok(Some(tr("Clean mode disabled").to_string()))
+                } else {
+                    s.add_layer(Self::passphrase_dialog(
+                        self.config.clone(),
+                        |values| values.clean_passphrase.clone(),
+                        |state| state.clean_mode = false,
+                        "Clean mode disabled",
+                    ));
+                    Ok(None)
+                }
+            }
+            Command::PrivateSession(mode) => {
+                let mode = mode.unwrap_or_else(|| !self.spotify.private_session());
+                self.spotify.set_private_session(mode);
+                if self.spotify.has_worker() {
+                    // The session is reconnected with the new setting once the worker has shut
+                    // down.
+                    self.spotify.shutdown();
+                }
+                Ok(Some(if mode {
+                    tr("Private session enabled").to_string()
+                } else {
+                    tr("Private session disabled").to_string()
+                }))
+            }
+            Command::Set(option, value) => {
+                self.config
+                    .set_value(option, value)
+                    .map_err(|e| tr_args("Could not set {}: {}", &[option.as_str(), &e]))?;
+                self.handle_default_commands(s, &Command::ReloadConfig)
+            }
+            Command::Mark(action) => {
+                let Some(playable) = self.queue.get_current() else {
+                    return Err(tr("Nothing is playing").to_string());
+                };
+                let uri = playable.uri();
+                match action {
+                    MarkAction::Add(name) => {
+                        let position = self.spotify.get_current_progress().as_millis();
+                        let position = u32::try_from(position).unwrap_or(u32::MAX);
+                        self.library.add_bookmark(&uri, name, position);
+                        Ok(Some(tr_args(
+                            "Bookmarked {} at {}",
+                            &[name.as_str(), &ms_to_hms(position)],
+                        )))
+                    }
+                    MarkAction::Goto(name) => {
+                        let bookmark = self
+                            .library
+                            .bookmarks(&uri)
+                            .into_iter()
+                            .find(|bookmark| &bookmark.name == name)
+                            .ok_or_else(|| tr_args("There is no bookmark {}", &[name.as_str()]))?;
+                        self.spotify.seek(bookmark.position);
+                        Ok(None)
+                    }
+                    MarkAction::Remove(name) => {
+                        if self.library.remove_bookmark(&uri, name) {
+                            Ok(None)
+                        } else {
+                            Err(tr_args("There is no bookmark {}", &[name.as_str()]))
+                        }
+                    }
+                }
+            }
+            Command::PlayUrl(url) => {
+                self.queue.play_url(url);
+                Ok(None)
+            }
+            Command::ImportQueue(source) => {
+                queue_import::import(
+                    source.clone(),
+                    self.queue.clone(),
+                    self.config.clone(),
+                    &self.library.tasks,
+                    s.cb_sink().clone(),
+                );
+                Ok(None)
+            }
+            Command::Device(DeviceKind::Audio) => {
+                s.add_layer(audio_device::audio_device_dialog(self.queue.clone()));
+                Ok(None)
+            }
+            Command::Refresh => {
+                s.on_layout(|_, mut layout| layout.set_refreshing(true));
+                // Only the cached response of the item in the focused view is fetched again. This
+                // happens in the background, so the spinner is drawn in the meantime.
+                let url = s
+                    .on_layout(|_, mut layout| layout.current_item())
+                    .and_then(|item| item.share_url())
+                    .and_then(SpotifyUrl::from_url);
+                let api = self.spotify.api.clone();
+                let sink = s.cb_sink().clone();
+                thread::spawn(move || {
+                    if let Some(url) = url.as_ref() {
+                        if !api.refetch(url) {
+                            warn!("could not fetch {} again", url);
+                        }
+                    }
+                    sink.send(Box::new(move |s: &mut Cursive| {
+                        s.on_layout(|_, mut layout| {
+                            let refreshed = layout.refresh_view();
+                            layout.set_refreshing(false);
+                            // Views without anything to fetch drop all cached responses instead.
+                            if !refreshed {
+                                api.clear_cache();
+                                let message = tr("Cleared cached responses").to_string();
+                                layout.set_result(Ok(Some(message)));
+                            }
+                        });
+                    }))
+                    .ok();
+                });
+                Ok(None)
+            }
+            Command::DescribeKey => {
+                s.on_layout(|_, mut layout| layout.describe_next_key());
+                Ok(Some(tr("Press a key to show its bindings").to_string()))
+            }
             Command::AddCurrent => {
                 if let Some(track) = self.queue.get_current() {
                     if let Some(track) = track.track() {
@@ -303,30 +984,50 @@ impl CommandManager {
                 Ok(None)
             }
 
+            Command::Goto(GotoMode::Playing) => {
+                s.call_on_name("main", |v: &mut Layout| v.set_screen("queue"));
+                s.on_layout(|siv, mut l| {
+                    l.on_command(siv, &Command::Move(MoveMode::Playing, Default::default()))
+                })?;
+                Ok(None)
+            }
+
             Command::Queue
+            | Command::QueueAll
             | Command::PlayNext
             | Command::Play
+            | Command::PlayAll
+            | Command::PlayTop
             | Command::Save
             | Command::SaveQueue
             | Command::Add
             | Command::Delete
             | Command::Focus(_)
             | Command::Back
+            | Command::Forward
             | Command::Open(_)
             | Command::Goto(_)
             | Command::Move(_, _)
             | Command::Shift(_, _)
+            | Command::Select
+            | Command::Favorite
+            | Command::Filter(_)
             | Command::Jump(_)
             | Command::Insert(_)
+            | Command::CopyMetadata(_, _)
             | Command::ShowRecommendations(_)
-            | Command::Sort(_, _) => Err(format!(
+            | Command::Tune(_, _)
+            | Command::Seed(_)
+            | Command::HidePlayed
+            | Command::Snapshot
+            | Command::Sort(_, _) => Err(tr_args(
                 "The command \"{}\" is unsupported in this view",
-                cmd.basename()
+                &[cmd.basename()],
             )),
             #[cfg(feature = "share_clipboard")]
-            Command::Share(_) => Err(format!(
+            Command::Share(_) => Err(tr_args(
                 "The command \"{}\" is unsupported in this view",
-                cmd.basename()
+                &[cmd.basename()],
             )),
         }
     }
@@ -362,8 +1063,22 @@ impl CommandManager {
         }
     }
 
+    fn run(&self, s: &mut Cursive, cmd: &Command) -> Result<Option<String>, String> {
+        if cmd.is_destructive() && self.config.state().party_mode {
+            Err(tr("Not available in party mode").to_string())
+        } else {
+            self.handle_callbacks(s, cmd)
+        }
+    }
+
     pub fn handle(&self, s: &mut Cursive, cmd: Command) {
-        let result = self.handle_callbacks(s, &cmd);
+        if let Some((_, commands)) = self.recording.borrow_mut().as_mut() {
+            if !self.replaying.get() && !matches!(cmd, Command::Record(_)) {
+                commands.push(cmd.clone());
+            }
+        }
+
+        let result = self.run(s, &cmd);
 
         s.call_on_name("main", |v: &mut Layout| {
             v.set_result(result);
@@ -372,6 +1087,66 @@ impl CommandManager {
         s.on_event(Event::Refresh);
     }
 
+    fn confirmations(&self) -> Confirmations {
+        self.config
+            .values()
+            .confirmations
+            .clone()
+            .unwrap_or_default()
+    }
+
+    /// A dialog that calls `unlock` once the passphrase returned by `expected` is entered, ending
+    /// party mode or clean mode.
+    fn passphrase_dialog(
+        config: Arc<Config>,
+        expected: fn(&ConfigValues) -> Option<String>,
+        unlock: fn(&mut UserState),
+        message: &'static str,
+    ) -> Modal<Dialog> {
+        let edit = EditView::new()
+            .secret()
+            .on_submit(move |s: &mut Cursive, passphrase| {
+                s.pop_layer();
+                let result = if expected(&config.values()).as_deref() == Some(passphrase) {
+                    config.with_state_mut(unlock);
+                    Ok(Some(tr(message).to_string()))
+                } else {
+                    Err(tr("Wrong passphrase").to_string())
+                };
+                s.on_layout(|_, mut layout| layout.set_result(result));
+            })
+            .fixed_width(20);
+        let dialog = Dialog::new()
+            .title(tr("Enter passphrase"))
+            .dismiss_button(tr("Cancel"))
+            .padding(Margins::lrtb(1, 1, 1, 0))
+            .content(edit);
+        Modal::new(dialog)
+    }
+
+    /// Remove the cached credentials and quit.
+    fn logout(s: &mut Cursive, spotify: &Spotify) {
+        spotify.shutdown();
+
+        let mut credentials_path = crate::config::cache_path("librespot");
+        credentials_path.push("credentials.json");
+        std::fs::remove_file(credentials_path).unwrap();
+
+        s.quit();
+    }
+
+    /// Stop recording a macro and store it in the user state. Returns the register and the
+    /// amount of recorded commands.
+    fn stop_recording(&self) -> Option<(char, usize)> {
+        let (register, commands) = self.recording.take()?;
+        let count = commands.len();
+        self.config.with_state_mut(|state| {
+            let commands = commands.iter().map(ToString::to_string).collect();
+            state.macros.insert(register, commands);
+        });
+        Some((register, count))
+    }
+
     pub fn register_keybinding<E: Into<cursive::event::Event>>(
         &self,
         cursive: &mut Cursive,
@@ -380,13 +1155,79 @@ impl CommandManager {
     ) {
         cursive.add_global_callback(event, move |s| {
             if let Some(data) = s.user_data::<UserData>().cloned() {
+                let count = data.cmd.count.take();
                 for command in commands.clone().into_iter() {
-                    data.cmd.handle(s, command);
+                    for command in command.with_count(count) {
+                        data.cmd.handle(s, command);
+                    }
                 }
             }
         });
     }
 
+    /// Grow the relative seek `delta` while seeks in the same direction follow each other quickly.
+    /// Replayed macros always seek by the recorded amount.
+    fn accelerate_seek(&self, delta: i32) -> i32 {
+        let now = Instant::now();
+        let forward = delta > 0;
+        let repeats = match self.last_seek.get() {
+            Some((last, last_forward, repeats))
+                if last_forward == forward && now - last < SEEK_ACCELERATION_WINDOW =>
+            {
+                repeats + 1
+            }
+            _ => 0,
+        };
+        self.last_seek.set(Some((now, forward, repeats)));
+
+        if self.replaying.get() || !self.config.values().seek_acceleration.unwrap_or(true) {
+            return delta;
+        }
+        let factor = (1 + repeats / 5).min(MAX_SEEK_ACCELERATION);
+        delta.saturating_mul(factor)
+    }
+
+    /// Add `digit` to the count prefix of the next keybinding.
+    fn push_count_digit(&self, digit: u32) {
+        let mut count = self.count.borrow_mut();
+        *count = append_count_digit(*count, digit);
+    }
+
+    /// Describe the commands bound to the key of `event`, including all bindings that resolve to
+    /// the same key.
+    pub fn describe_key(&self, event: &Event) -> String {
+        let bindings = self.bindings.borrow();
+        let mut matches: Vec<String> = bindings
+            .iter()
+            .filter(|(key, _)| Self::parse_keybinding(key).as_ref() == Some(event))
+            .map(|(key, commands)| format!("{key} -> {}", Self::format_commands(commands)))
+            .collect();
+        matches.sort();
+
+        if matches.is_empty() {
+            let name = Self::key_name(event).unwrap_or_default();
+            tr_args("{} is not bound to any command", &[&name])
+        } else {
+            matches.join(" | ")
+        }
+    }
+
+    /// The name of the key of `event` in the keybinding notation, or `None` if it isn't a key
+    /// press.
+    pub fn key_name(event: &Event) -> Option<String> {
+        match event {
+            Event::Char(' ') => Some("Space".into()),
+            Event::Char(c) => Some(c.to_string()),
+            Event::AltChar(c) => Some(format!("Alt+{c}")),
+            Event::CtrlChar(c) => Some(format!("Ctrl+{c}")),
+            Event::Key(key) => Some(format!("{key:?}")),
+            Event::Shift(key) => Some(format!("Shift+{key:?}")),
+            Event::Alt(key) => Some(format!("Alt+{key:?}")),
+            Event::Ctrl(key) => Some(format!("Ctrl+{key:?}")),
+            _ => None,
+        }
+    }
+
     pub fn unregister_keybindings(&self, cursive: &mut Cursive) {
         let kb = self.bindings.borrow();
 
@@ -395,6 +1236,9 @@ impl CommandManager {
                 cursive.clear_global_callbacks(binding);
             }
         }
+        for digit in '0'..='9' {
+            cursive.clear_global_callbacks(digit);
+        }
     }
 
     pub fn register_keybindings(&self, cursive: &mut Cursive) {
@@ -407,10 +1251,23 @@ impl CommandManager {
                 error!("Could not parse keybinding: \"{}\"", k);
             }
         }
+
+        for digit in count_digits(&kb) {
+            cursive.add_global_callback(digit, move |s| {
+                if let Some(data) = s.user_data::<UserData>().cloned() {
+                    data.cmd
+                        .push_count_digit(digit.to_digit(10).unwrap_or_default());
+                }
+            });
+        }
     }
 
-    fn default_keybindings() -> HashMap<String, Vec<Command>> {
+    fn default_keybindings(config: &ConfigValues) -> HashMap<String, Vec<Command>> {
         let mut kb = HashMap::new();
+        let seek_step = config.seek_step_ms.unwrap_or(DEFAULT_SEEK_STEP_MS) as i32;
+        let seek_step_large = config
+            .seek_step_large_ms
+            .unwrap_or(DEFAULT_SEEK_STEP_LARGE_MS) as i32;
 
         kb.insert("q".into(), vec![Command::Quit]);
         kb.insert("Ctrl+l".into(), vec![Command::Redraw]);
@@ -442,20 +1299,33 @@ impl CommandManager {
         kb.insert("d".into(), vec![Command::Delete]);
         kb.insert(
             "f".into(),
-            vec![Command::Seek(SeekDirection::Relative(1000))],
+            vec![Command::Seek(SeekDirection::Relative(seek_step))],
         );
         kb.insert(
             "b".into(),
-            vec![Command::Seek(SeekDirection::Relative(-1000))],
+            vec![Command::Seek(SeekDirection::Relative(-seek_step))],
         );
         kb.insert(
             "Shift+f".into(),
-            vec![Command::Seek(SeekDirection::Relative(10000))],
+            vec![Command::Seek(SeekDirection::Relative(seek_step_large))],
         );
         kb.insert(
             "Shift+b".into(),
-            vec![Command::Seek(SeekDirection::Relative(-10000))],
+            vec![Command::Seek(SeekDirection::Relative(-seek_step_large))],
+        );
+        kb.insert(
+            "%".into(),
+            vec![Command::Seek(SeekDirection::AbsolutePercent(0))],
         );
+        // The digits are count prefixes unless they are bound to seeking like in earlier versions.
+        if config.seek_digits.unwrap_or(false) {
+            for digit in 0..=9 {
+                kb.insert(
+                    digit.to_string(),
+                    vec![Command::Seek(SeekDirection::AbsolutePercent(digit * 10))],
+                );
+            }
+        }
         kb.insert("+".into(), vec![Command::VolumeUp(1)]);
         kb.insert("]".into(), vec![Command::VolumeUp(5)]);
         kb.insert("-".into(), vec![Command::VolumeDown(1)]);
@@ -473,15 +1343,19 @@ impl CommandManager {
         kb.insert("F1".into(), vec![Command::Focus("queue".into())]);
         kb.insert("F2".into(), vec![Command::Focus("search".into())]);
         kb.insert("F3".into(), vec![Command::Focus("library".into())]);
+        kb.insert("F4".into(), vec![Command::Focus("nowplaying".into())]);
         #[cfg(feature = "cover")]
         kb.insert("F8".into(), vec![Command::Focus("cover".into())]);
         kb.insert("?".into(), vec![Command::Help]);
         kb.insert("Backspace".into(), vec![Command::Back]);
+        kb.insert("Alt+Left".into(), vec![Command::Back]);
+        kb.insert("Alt+Right".into(), vec![Command::Forward]);
 
         kb.insert("o".into(), vec![Command::Open(TargetMode::Selected)]);
         kb.insert("Shift+o".into(), vec![Command::Open(TargetMode::Current)]);
         kb.insert("a".into(), vec![Command::Goto(GotoMode::Album)]);
         kb.insert("Shift+a".into(), vec![Command::Goto(GotoMode::Artist)]);
+        kb.insert("i".into(), vec![Command::Headers(None)]);
 
         kb.insert(
             "m".into(),
@@ -567,6 +1441,8 @@ impl CommandManager {
             "Shift+Down".into(),
             vec![Command::Shift(ShiftMode::Down, None)],
         );
+        kb.insert("v".into(), vec![Command::Select]);
+        kb.insert("Shift+f".into(), vec![Command::Filter(None)]);
 
         #[cfg(feature = "share_clipboard")]
         kb.insert(
@@ -641,3 +1517,66 @@ impl CommandManager {
         }
     }
 }
+
+/// The digit keys that aren't bound to a command in `bindings`. They are count prefixes, like in
+/// Vim.
+fn count_digits(bindings: &HashMap<String, Vec<Command>>) -> Vec<char> {
+    ('0'..='9')
+        .filter(|digit| !bindings.contains_key(&digit.to_string()))
+        .collect()
+}
+
+/// Add `digit` to the typed `count`, which can't exceed [MAX_COUNT]. A leading zero is ignored.
+fn append_count_digit(count: Option<u32>, digit: u32) -> Option<u32> {
+    if count.is_none() && digit == 0 {
+        return None;
+    }
+    Some((count.unwrap_or(0) * 10 + digit).min(MAX_COUNT))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(digits: &[u32]) -> Option<u32> {
+        digits
+            .iter()
+            .fold(None, |count, digit| append_count_digit(count, *digit))
+    }
+
+    #[test]
+    fn count_digits_accumulate() {
+        assert_eq!(count(&[5]), Some(5));
+        assert_eq!(count(&[1, 2]), Some(12));
+        assert_eq!(count(&[1, 0, 0]), Some(100));
+    }
+
+    #[test]
+    fn leading_zero_is_not_a_count() {
+        assert_eq!(count(&[0]), None);
+        assert_eq!(count(&[0, 7]), Some(7));
+    }
+
+    #[test]
+    fn count_is_clamped() {
+        assert_eq!(count(&[9, 9, 9, 9, 9, 9]), Some(MAX_COUNT));
+        assert_eq!(count(&[4, 2, 9, 4, 9, 6, 7, 2, 9, 5]), Some(MAX_COUNT));
+    }
+
+    #[test]
+    fn bound_digits_are_not_counts() {
+        let defaults = CommandManager::default_keybindings(&ConfigValues::default());
+        assert_eq!(count_digits(&defaults).len(), 10);
+
+        let mut bindings = defaults.clone();
+        bindings.insert("5".into(), vec![Command::Next]);
+        assert!(!count_digits(&bindings).contains(&'5'));
+        assert_eq!(count_digits(&bindings).len(), 9);
+
+        let seek_digits = ConfigValues {
+            seek_digits: Some(true),
+            ..Default::default()
+        };
+        assert!(count_digits(&CommandManager::default_keybindings(&seek_digits)).is_empty());
+    }
+}
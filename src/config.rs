@@ -1,21 +1,27 @@
 use std::collections::HashMap;
 use std::error::Error;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::sync::{RwLock, RwLockReadGuard};
 use std::{fs, process};
 
 use cursive::theme::Theme;
-use log::{debug, error};
+use log::{debug, error, info};
 use ncspot::{CONFIGURATION_FILE_NAME, USER_STATE_FILE_NAME};
 use platform_dirs::AppDirs;
+use toml_edit::{DocumentMut, Item, Table, Value};
 
-use crate::command::{SortDirection, SortKey};
+use crate::command::{Command, SortDirection, SortKey, TimeFormat};
+use crate::i18n::Language;
 use crate::model::playable::Playable;
 use crate::queue;
-use crate::serialization::{Serializer, CBOR, TOML};
+use crate::serialization::{write_atomic, Serializer, CBOR, TOML};
+use crate::theme::Appearance;
 
 pub const CACHE_VERSION: u16 = 1;
 pub const DEFAULT_COMMAND_KEY: char = ':';
+pub const DEFAULT_SEEK_STEP_MS: u32 = 1000;
+pub const DEFAULT_SEEK_STEP_LARGE_MS: u32 = 10000;
 
 /// The playback state when ncspot is started.
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
@@ -36,6 +42,8 @@ pub enum LibraryTab {
     Playlists,
     Podcasts,
     Browse,
+    #[serde(rename = "made_for_you")]
+    MadeForYou,
 }
 
 /// The format used to represent tracks in a list.
@@ -72,6 +80,120 @@ impl NotificationFormat {
     }
 }
 
+/// Which actions ask for confirmation before they are executed.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct Confirmations {
+    pub delete_playlist: Option<bool>,
+    pub remove_track: Option<bool>,
+    pub clear_queue: Option<bool>,
+    pub logout: Option<bool>,
+}
+
+impl Confirmations {
+    pub fn delete_playlist(&self) -> bool {
+        self.delete_playlist.unwrap_or(true)
+    }
+
+    pub fn remove_track(&self) -> bool {
+        self.remove_track.unwrap_or(false)
+    }
+
+    pub fn clear_queue(&self) -> bool {
+        self.clear_queue.unwrap_or(true)
+    }
+
+    pub fn logout(&self) -> bool {
+        self.logout.unwrap_or(false)
+    }
+}
+
+/// Playback settings that are applied when playback is started from a playlist.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct PlaylistPlayback {
+    pub shuffle: Option<bool>,
+    pub repeat: Option<queue::RepeatSetting>,
+}
+
+/// The playlist that is played once the queue runs out, instead of stopping.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct FallbackPlaylist {
+    /// The ID or name of a playlist in the library.
+    pub playlist: String,
+    pub shuffle: Option<bool>,
+}
+
+/// How eagerly `auto_skip` treats the quiet parts at the start and end of tracks as intro or outro.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipAggressiveness {
+    /// Only skip the fade in and fade out.
+    #[default]
+    Gentle,
+    /// Also skip sections that are clearly quieter than the rest of the track.
+    Moderate,
+    /// Also skip sections that are only a bit quieter than the rest of the track.
+    Aggressive,
+}
+
+impl SkipAggressiveness {
+    /// How many dB quieter than the whole track a section has to be to be skipped.
+    pub fn threshold(self) -> Option<f32> {
+        match self {
+            Self::Gentle => None,
+            Self::Moderate => Some(6.0),
+            Self::Aggressive => Some(3.0),
+        }
+    }
+}
+
+/// Skipping the quiet intros and outros of tracks, as found by the audio analysis of Spotify.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct AutoSkip {
+    pub intro: Option<bool>,
+    pub outro: Option<bool>,
+    pub aggressiveness: Option<SkipAggressiveness>,
+    /// The most seconds skipped at the start and at the end of a track.
+    pub max_seconds: Option<u32>,
+}
+
+/// How the volume shown in ncspot maps to the volume of the audio output.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VolumeCurve {
+    /// Every step changes the loudness by the same number of decibels, over a range of 60 dB.
+    #[default]
+    Logarithmic,
+    Linear,
+    /// The volume raised to the power of `volume_curve_factor`.
+    Exponential,
+}
+
+impl VolumeCurve {
+    /// The dynamic range of the logarithmic curve in decibels.
+    const LOG_RANGE_DB: f64 = 60.0;
+    /// The default power of the exponential curve.
+    const DEFAULT_EXPONENT: f64 = 2.0;
+
+    /// Map `volume` as shown in ncspot to the volume of the audio output, both from 0 to
+    /// [u16::MAX]. `factor` is the power of the exponential curve.
+    pub fn apply(self, volume: u16, factor: Option<f64>) -> u16 {
+        let x = f64::from(volume) / f64::from(u16::MAX);
+        let mapped = match self {
+            Self::Logarithmic => {
+                let ratio = 10f64.powf(Self::LOG_RANGE_DB / 20.0);
+                (ratio.powf(x) - 1.0) / (ratio - 1.0)
+            }
+            Self::Linear => x,
+            Self::Exponential => x.powf(
+                factor
+                    .filter(|f| *f > 0.0)
+                    .unwrap_or(Self::DEFAULT_EXPONENT),
+            ),
+        };
+        (mapped.clamp(0.0, 1.0) * f64::from(u16::MAX)).round() as u16
+    }
+}
+
 /// The configuration of ncspot.
 #[derive(Clone, Serialize, Deserialize, Debug, Default)]
 pub struct ConfigValues {
@@ -79,6 +201,7 @@ pub struct ConfigValues {
     pub initial_screen: Option<String>,
     pub default_keybindings: Option<bool>,
     pub keybindings: Option<HashMap<String, String>>,
+    pub mousebindings: Option<HashMap<String, String>>,
     pub theme: Option<ConfigTheme>,
     pub use_nerdfont: Option<bool>,
     pub flip_status_indicators: Option<bool>,
@@ -101,6 +224,47 @@ pub struct ConfigValues {
     pub library_tabs: Option<Vec<LibraryTab>>,
     pub hide_display_names: Option<bool>,
     pub ap_port: Option<u16>,
+    pub inhibit_suspend: Option<bool>,
+    pub pause_on_lock: Option<bool>,
+    pub automix_fade: Option<u32>,
+    pub language: Option<Language>,
+    pub confirmations: Option<Confirmations>,
+    pub playlist_playback: Option<HashMap<String, PlaylistPlayback>>,
+    pub market: Option<String>,
+    pub api_cache_ttl: Option<u64>,
+    pub data_saver: Option<bool>,
+    pub accessibility: Option<bool>,
+    pub mpd_host: Option<String>,
+    pub mpd_port: Option<u16>,
+    pub break_reminder: Option<u32>,
+    pub listening_goal: Option<u32>,
+    pub volume_curve: Option<VolumeCurve>,
+    pub volume_curve_factor: Option<f64>,
+    pub dedup_queue: Option<bool>,
+    pub party_passphrase: Option<String>,
+    pub follow_playback: Option<bool>,
+    pub seek_step_ms: Option<u32>,
+    pub seek_step_large_ms: Option<u32>,
+    pub seek_acceleration: Option<bool>,
+    pub seek_digits: Option<bool>,
+    pub single_instance: Option<bool>,
+    pub cache_dir: Option<PathBuf>,
+    pub state_dir: Option<PathBuf>,
+    pub key_hints: Option<bool>,
+    pub clean_passphrase: Option<String>,
+    pub clean_categories: Option<Vec<String>>,
+    pub group_albums: Option<bool>,
+    pub search_podcasts: Option<bool>,
+    pub private_session: Option<bool>,
+    pub playlist_added_at: Option<bool>,
+    pub queue_source: Option<bool>,
+    pub default_playlist_sort: Option<String>,
+    pub mini_mode_height: Option<usize>,
+    pub window_title_format: Option<String>,
+    pub status_file: Option<PathBuf>,
+    pub status_file_format: Option<String>,
+    pub fallback_playlist: Option<FallbackPlaylist>,
+    pub auto_skip: Option<AutoSkip>,
 }
 
 /// The ncspot theme.
@@ -125,6 +289,27 @@ pub struct ConfigTheme {
     pub cmdline: Option<String>,
     pub cmdline_bg: Option<String>,
     pub search_match: Option<String>,
+    /// The built-in theme the colors above are based on.
+    pub preset: Option<ThemePreset>,
+    /// Colors that replace the ones above while the appearance is light.
+    pub light: Option<Box<ConfigTheme>>,
+    /// Colors that replace the ones above while the appearance is dark.
+    pub dark: Option<Box<ConfigTheme>>,
+    /// The time range in which the appearance is dark, like `"19:00-07:00"`. Without it the
+    /// appearance is detected from the system or terminal.
+    pub dark_hours: Option<String>,
+}
+
+/// Built-in themes that a [ConfigTheme] can be based on.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemePreset {
+    #[default]
+    Default,
+    /// Bright colors on black, for low vision or bright surroundings.
+    HighContrast,
+    /// Only the terminal's default colors, for terminals without color support.
+    Monochrome,
 }
 
 /// The ordering that is used when representing a playlist.
@@ -134,6 +319,22 @@ pub struct SortingOrder {
     pub direction: SortDirection,
 }
 
+impl SortingOrder {
+    /// Parse an order written like the arguments of the `sort` command, e.g. `added desc`.
+    pub fn parse(order: &str) -> Option<Self> {
+        match crate::command::parse(&format!("sort {order}"))
+            .ok()?
+            .first()?
+        {
+            Command::Sort(key, direction) => Some(Self {
+                key: key.clone(),
+                direction: direction.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
 /// The runtime state of the music queue.
 #[derive(Serialize, Default, Deserialize, Debug, Clone)]
 pub struct QueueState {
@@ -153,6 +354,35 @@ pub struct UserState {
     pub playlist_orders: HashMap<String, SortingOrder>,
     pub cache_version: u16,
     pub playback_state: PlaybackState,
+    #[serde(default)]
+    pub automix: bool,
+    /// Commands recorded with `record`, by register. They are stored as text, so changes to
+    /// [Command] don't make the state unreadable.
+    #[serde(default)]
+    pub macros: HashMap<char, Vec<String>>,
+    /// How far episodes have been listened to in milliseconds, by episode ID.
+    #[serde(default)]
+    pub episode_progress: HashMap<String, u32>,
+    /// The last volume of every audio backend and device, by `backend:device`.
+    #[serde(default)]
+    pub device_volumes: HashMap<String, u16>,
+    /// Whether the details above albums and playlists are hidden.
+    #[serde(default)]
+    pub compact_headers: bool,
+    /// Whether party mode locks the commands that change the library, see
+    /// [crate::command::Command::is_destructive].
+    #[serde(default)]
+    pub party_mode: bool,
+    /// Whether the clean listening profile hides and skips explicit content.
+    #[serde(default)]
+    pub clean_mode: bool,
+    /// How the playback time is shown in the statusbar, switched with `timefmt`.
+    #[serde(default)]
+    pub time_format: TimeFormat,
+    /// The IDs of the playlists pinned to the top of the playlists, in the order they were
+    /// marked as favorites.
+    #[serde(default)]
+    pub favorite_playlists: Vec<String>,
 }
 
 impl Default for UserState {
@@ -165,6 +395,15 @@ impl Default for UserState {
             playlist_orders: HashMap::new(),
             cache_version: 0,
             playback_state: PlaybackState::Default,
+            automix: false,
+            macros: HashMap::new(),
+            episode_progress: HashMap::new(),
+            device_volumes: HashMap::new(),
+            compact_headers: false,
+            party_mode: false,
+            clean_mode: false,
+            time_format: TimeFormat::default(),
+            favorite_playlists: Vec::new(),
         }
     }
 }
@@ -172,6 +411,12 @@ impl Default for UserState {
 /// Configuration files are read/written relative to this directory.
 static BASE_PATH: RwLock<Option<PathBuf>> = RwLock::new(None);
 
+/// The cache directory configured with `cache_dir`, which takes precedence over [BASE_PATH].
+static CACHE_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// The state directory configured with `state_dir`, which takes precedence over [BASE_PATH].
+static STATE_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
 /// The complete configuration (state + user configuration) of ncspot.
 pub struct Config {
     /// The configuration file path.
@@ -180,6 +425,8 @@ pub struct Config {
     values: RwLock<ConfigValues>,
     /// Runtime state which can't be edited by the user, read/write.
     state: RwLock<UserState>,
+    /// Whether the light or dark colors of the theme are in use.
+    appearance: RwLock<Option<Appearance>>,
 }
 
 impl Config {
@@ -199,9 +446,10 @@ impl Config {
             );
             process::exit(1);
         });
+        set_directories(&values);
 
         let mut userstate = {
-            let path = config_path(USER_STATE_FILE_NAME);
+            let path = state_path(USER_STATE_FILE_NAME);
             CBOR.load_or_generate_default(path, || Ok(UserState::default()), true)
                 .expect("could not load user state")
         };
@@ -218,11 +466,14 @@ impl Config {
             userstate.playback_state = playback_state;
         }
 
-        Self {
+        let config = Self {
             filename,
             values: RwLock::new(values),
             state: RwLock::new(userstate),
-        }
+            appearance: RwLock::new(None),
+        };
+        config.update_appearance();
+        config
     }
 
     /// Get the user configuration values.
@@ -254,7 +505,7 @@ impl Config {
     pub fn save_state(&self) {
         self.update_state_cache_version();
 
-        let path = config_path(USER_STATE_FILE_NAME);
+        let path = state_path(USER_STATE_FILE_NAME);
         debug!("saving user state to {}", path.display());
         if let Err(e) = CBOR.write(path, &*self.state()) {
             error!("Could not save user state: {}", e);
@@ -263,7 +514,77 @@ impl Config {
 
     /// Create a [Theme] from the user supplied theme in the configuration file.
     pub fn build_theme(&self) -> Theme {
-        crate::theme::load(&self.values().theme)
+        crate::theme::load(&self.active_theme(), self.theme_preset())
+    }
+
+    /// The user supplied theme with the colors for the current appearance, if it has any.
+    fn active_theme(&self) -> Option<ConfigTheme> {
+        let appearance = *self.appearance.read().unwrap();
+        self.values()
+            .theme
+            .clone()
+            .map(|theme| crate::theme::with_appearance(theme, appearance))
+    }
+
+    /// Detect whether the light or dark colors of the theme should be used. Returns whether this
+    /// changed, so the theme has to be applied again.
+    pub fn update_appearance(&self) -> bool {
+        let appearance = self
+            .values()
+            .theme
+            .as_ref()
+            .filter(|t| t.light.is_some() || t.dark.is_some())
+            .and_then(crate::theme::appearance);
+        let mut current = self.appearance.write().unwrap();
+        let changed = *current != appearance;
+        *current = appearance;
+        changed
+    }
+
+    /// The built-in theme in use, detected from the terminal if none is configured.
+    pub fn theme_preset(&self) -> ThemePreset {
+        self.active_theme()
+            .and_then(|t| t.preset)
+            .unwrap_or_else(ThemePreset::from_env)
+    }
+
+    /// Change the option `key` in the configuration file to `value`. Nested options like
+    /// `theme.primary` are separated by dots. The file is edited in place, so comments and the
+    /// order of the options are kept. The file is created if it doesn't exist yet. The new value
+    /// only takes effect after [Config::reload].
+    pub fn set_value(&self, key: &str, value: &str) -> Result<(), String> {
+        let path = config_path(&self.filename);
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.to_string()),
+        };
+        let mut document = contents
+            .parse::<DocumentMut>()
+            .map_err(|e| format!("Unable to parse toml {}: {}", path.display(), e))?;
+
+        let mut keys: Vec<&str> = key.split('.').collect();
+        let name = keys
+            .pop()
+            .filter(|name| !name.is_empty())
+            .ok_or("no option")?;
+        let mut table = document.as_table_mut();
+        for key in keys {
+            table = table
+                .entry(key)
+                .or_insert_with(toml_edit::table)
+                .as_table_mut()
+                .ok_or_else(|| format!("{key} is not a table"))?;
+        }
+        set_item(table, name, value);
+
+        let edited = document.to_string();
+        toml::from_str::<ConfigValues>(&edited).map_err(|e| e.to_string())?;
+        write_atomic(&path, |writer| {
+            writer
+                .write_all(edited.as_bytes())
+                .map_err(|e| e.to_string())
+        })
     }
 
     /// Attempt to reload the configuration from the configuration file.
@@ -273,16 +594,54 @@ impl Config {
     pub fn reload(&self) -> Result<(), Box<dyn Error>> {
         let cfg = load(&self.filename)?;
         *self.values.write().unwrap() = cfg;
+        self.update_appearance();
         Ok(())
     }
 }
 
+/// Set `name` in `table` to `value`, which is interpreted as TOML if possible and as a string
+/// otherwise. The comments around an existing value are kept.
+fn set_item(table: &mut Table, name: &str, value: &str) {
+    let mut value = value
+        .parse::<Value>()
+        .unwrap_or_else(|_| Value::from(value));
+    match table.get_mut(name).and_then(Item::as_value_mut) {
+        Some(existing) => {
+            *value.decor_mut() = existing.decor().clone();
+            *existing = value;
+        }
+        None => {
+            table.insert(name, Item::Value(value));
+        }
+    }
+}
+
 /// Parse the configuration file with name `filename` at the configuration base path.
 fn load(filename: &str) -> Result<ConfigValues, String> {
     let path = config_path(filename);
     TOML.load_or_generate_default(path, || Ok(ConfigValues::default()), false)
 }
 
+/// Use the cache and state directories set in the configuration. They are only read at startup,
+/// as the files in them are in use afterwards.
+fn set_directories(values: &ConfigValues) {
+    *CACHE_DIR.write().unwrap() = values.cache_dir.clone();
+    *STATE_DIR.write().unwrap() = values.state_dir.clone();
+}
+
+/// Use the cache and state directories set in the configuration file with name `filename` without
+/// loading the rest of the configuration, for commands that run without starting ncspot.
+pub fn load_directories(filename: Option<String>) {
+    let filename = filename.unwrap_or(CONFIGURATION_FILE_NAME.to_owned());
+    let Some(path) = user_configuration_directory().map(|directory| directory.join(filename))
+    else {
+        return;
+    };
+    if let Ok(values) = TOML.load::<_, ConfigValues>(path) {
+        set_directories(&values);
+    }
+}
+
 /// Returns the plaform app directories for ncspot if they could be determined,
 /// or an error otherwise.
 pub fn try_proj_dirs() -> Result<AppDirs, String> {
@@ -311,10 +670,23 @@ pub fn user_configuration_directory() -> Option<PathBuf> {
 /// Return the path to the current user's cache directory, or None if one couldn't be found. This
 /// function does not guarantee correct permissions or ownership of the directory!
 pub fn user_cache_directory() -> Option<PathBuf> {
+    if let Some(directory) = CACHE_DIR.read().unwrap().clone() {
+        return Some(directory);
+    }
     let project_directories = try_proj_dirs().ok()?;
     Some(project_directories.cache_dir)
 }
 
+/// Return the path to the current user's state directory, or None if one couldn't be found. This
+/// function does not guarantee correct permissions or ownership of the directory!
+pub fn user_state_directory() -> Option<PathBuf> {
+    if let Some(directory) = STATE_DIR.read().unwrap().clone() {
+        return Some(directory);
+    }
+    let project_directories = try_proj_dirs().ok()?;
+    Some(project_directories.state_dir)
+}
+
 /// Force create the configuration directory at the default project location, removing anything that
 /// isn't a directory but has the same name. Return the path to the configuration file inside the
 /// directory.
@@ -347,6 +719,45 @@ pub fn cache_path(file: &str) -> PathBuf {
     pb
 }
 
+/// Create the state directory at the default project location, preserving it if it already exists,
+/// and return the path to the state file inside the directory. State files that older versions
+/// kept in the configuration directory are moved here.
+///
+/// This doesn't create the file, only the containing directory.
+pub fn state_path(file: &str) -> PathBuf {
+    let state_dir = user_state_directory().unwrap();
+    if !state_dir.exists() {
+        fs::create_dir_all(&state_dir).expect("can't create state folder");
+    }
+    let path = state_dir.join(file);
+    if !path.exists() {
+        migrate_state_file(file, &path);
+    }
+    path
+}
+
+/// Move the state file `file` from the configuration directory, where older versions kept it, to
+/// `path`.
+fn migrate_state_file(file: &str, path: &Path) {
+    let Some(legacy_path) = user_configuration_directory().map(|directory| directory.join(file))
+    else {
+        return;
+    };
+    if !legacy_path.is_file() {
+        return;
+    }
+
+    // Renaming fails if the directories are on different file systems.
+    let result = fs::rename(&legacy_path, path).or_else(|_| {
+        fs::copy(&legacy_path, path)?;
+        fs::remove_file(&legacy_path)
+    });
+    match result {
+        Ok(()) => info!("moved {} to {}", legacy_path.display(), path.display()),
+        Err(e) => error!("could not move {}: {}", legacy_path.display(), e),
+    }
+}
+
 /// Set the configuration base path. All configuration files are read/written relative to this path.
 pub fn set_configuration_base_path(base_path: Option<PathBuf>) {
     if let Some(basepath) = base_path {
@@ -2,14 +2,29 @@ use crossbeam_channel::{unbounded, Receiver, Sender, TryIter};
 use cursive::{CbSink, Cursive};
 
 use crate::queue::QueueEvent;
-use crate::spotify::PlayerEvent;
+use crate::spotify::{BufferState, PlayerEvent};
 
 /// Events that can be sent to and handled by the main event loop (the one drawing the TUI).
 pub enum Event {
     Player(PlayerEvent),
+    Buffer(BufferState),
     Queue(QueueEvent),
-    SessionDied,
+    /// The session is established and the web API can be used.
+    Connected,
+    /// The worker thread stopped, for the given reason.
+    SessionDied(String),
     IpcInput(String),
+    /// Playback was changed over MPRIS or IPC, which is reported in the statusbar.
+    Remote(RemoteChange),
+}
+
+/// The parts of the playback state that can be changed without using the user interface.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RemoteChange {
+    Volume,
+    Shuffle,
+    Repeat,
+    Track,
 }
 
 /// Manager that can be used to send and receive messages across threads.
@@ -43,6 +58,11 @@ impl EventManager {
         self.trigger();
     }
 
+    /// Run `callback` in the Cursive event loop, to update the user interface from other threads.
+    pub fn run_in_ui(&self, callback: impl FnOnce(&mut Cursive) + Send + 'static) {
+        self.cursive_sink.send(Box::new(callback)).unwrap();
+    }
+
     /// Send a no-op to the Cursive event loop to trigger immediate processing of events.
     pub fn trigger(&self) {
         self.cursive_sink.send(Box::new(Cursive::noop)).unwrap();
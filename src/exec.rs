@@ -0,0 +1,117 @@
+//! Running external programs for the `exec` command, without a shell in between.
+
+use std::process::{Command, Stdio};
+use std::thread;
+
+use cursive::{CbSink, Cursive};
+use log::{info, warn};
+
+use crate::ext_traits::CursiveExt;
+use crate::i18n::tr_args;
+
+/// Split `cmd` into the program and its arguments. Arguments are separated by whitespace, which
+/// can be kept in an argument by quoting it with `'` or `"`, or by escaping it with `\`.
+pub fn split_args(cmd: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quote = None;
+    let mut chars = cmd.chars();
+
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', q) if q != Some('\'') => {
+                if let Some(escaped) = chars.next() {
+                    current.get_or_insert_with(String::new).push(escaped);
+                }
+            }
+            (c, Some(q)) if c == q => quote = None,
+            ('\'' | '"', None) => {
+                quote = Some(c);
+                current.get_or_insert_with(String::new);
+            }
+            (c, None) if c.is_whitespace() => args.extend(current.take()),
+            (c, _) => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    args.extend(current);
+    args
+}
+
+fn command(args: &[String]) -> Result<Command, String> {
+    let (program, args) = args
+        .split_first()
+        .ok_or_else(|| "no program given".to_string())?;
+    let mut command = Command::new(program);
+    command.args(args).stdin(Stdio::null());
+    Ok(command)
+}
+
+/// The last line of `output` that isn't empty.
+fn last_line(output: &str) -> Option<String> {
+    output
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .map(|line| line.trim().to_string())
+}
+
+/// Run the program `args` in the background. Once it exits, the last line of its output is shown
+/// in the result bar, and all of it is logged.
+pub fn run(args: Vec<String>, sink: CbSink) {
+    thread::spawn(move || {
+        let program = args.first().cloned().unwrap_or_default();
+        let result = match command(&args).and_then(|mut c| c.output().map_err(|e| e.to_string())) {
+            Ok(output) => {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                info!("{} exited with {}", program, output.status);
+                if !stdout.trim().is_empty() {
+                    info!("output of {}:\n{}", program, stdout.trim_end());
+                }
+                if !stderr.trim().is_empty() {
+                    warn!("errors of {}:\n{}", program, stderr.trim_end());
+                }
+
+                if output.status.success() {
+                    Ok(last_line(&stdout))
+                } else {
+                    Err(last_line(&stderr).unwrap_or_else(|| {
+                        tr_args("{} exited with {}", &[&program, &output.status.to_string()])
+                    }))
+                }
+            }
+            Err(e) => Err(tr_args("Could not run {}: {}", &[&program, &e])),
+        };
+
+        sink.send(Box::new(move |s: &mut Cursive| {
+            s.on_layout(|_, mut layout| layout.set_result(result));
+        }))
+        .ok();
+    });
+}
+
+/// Start the program `args` without waiting for it or showing its output.
+pub fn spawn_detached(args: &[String]) -> Result<(), String> {
+    let program = args.first().cloned().unwrap_or_default();
+    let mut child = command(args)?
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| tr_args("Could not run {}: {}", &[&program, &e.to_string()]))?;
+    // Reap the process once it exits, so it doesn't linger as a zombie.
+    thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arguments_are_split_on_whitespace_outside_quotes() {
+        assert_eq!(
+            split_args(r#"notify-send "Now playing" it\'s 'a  b' "" end"#),
+            vec!["notify-send", "Now playing", "it's", "a  b", "", "end"]
+        );
+    }
+}
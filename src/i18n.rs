@@ -0,0 +1,482 @@
+//! Translations of user facing strings.
+//!
+//! Strings are looked up by their English text, so anything without a translation is shown in
+//! English. Placeholders are written as `{}` and filled in by [tr_args].
+
+use std::sync::RwLock;
+
+/// The languages the user interface is available in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "de")]
+    German,
+}
+
+impl Language {
+    /// The translations of this language, as pairs of English and translated text.
+    fn translations(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::English => &[],
+            Self::German => GERMAN,
+        }
+    }
+
+    /// Guess the language from the `LANG` environment variable.
+    fn from_env() -> Self {
+        match std::env::var("LANG") {
+            Ok(lang) if lang.starts_with("de") => Self::German,
+            _ => Self::English,
+        }
+    }
+}
+
+static LANGUAGE: RwLock<Language> = RwLock::new(Language::English);
+
+/// Set the language of the user interface. If no language is given, it is taken from the
+/// environment.
+pub fn set_language(language: Option<Language>) {
+    *LANGUAGE.write().unwrap() = language.unwrap_or_else(Language::from_env);
+}
+
+/// Translate `text` into the language of the user interface.
+pub fn tr(text: &'static str) -> &'static str {
+    LANGUAGE
+        .read()
+        .unwrap()
+        .translations()
+        .iter()
+        .find(|(english, _)| *english == text)
+        .map_or(text, |(_, translated)| *translated)
+}
+
+/// Translate `text` and replace its `{}` placeholders with `args` in order.
+pub fn tr_args(text: &'static str, args: &[&str]) -> String {
+    let mut args = args.iter();
+    let mut result = String::new();
+    for (i, part) in tr(text).split("{}").enumerate() {
+        if i > 0 {
+            result.push_str(args.next().unwrap_or(&""));
+        }
+        result.push_str(part);
+    }
+    result
+}
+
+const GERMAN: &[(&str, &str)] = &[
+    // Views and tabs
+    ("Albums", "Alben"),
+    ("Artists", "Künstler"),
+    ("Browse", "Entdecken"),
+    ("Commands", "Befehle"),
+    ("Episodes", "Folgen"),
+    ("Help", "Hilfe"),
+    ("Library", "Bibliothek"),
+    ("Library of {}", "Bibliothek von {}"),
+    ("Made For You", "Für dich"),
+    ("Not saved", "Nicht gespeichert"),
+    ("Nothing is playing", "Es wird nichts abgespielt"),
+    ("Now Playing", "Aktuelle Wiedergabe"),
+    ("Saved in your library", "In der Bibliothek gespeichert"),
+    ("Up next", "Als Nächstes"),
+    ("Played", "Gespielt"),
+    ("Playlists", "Playlists"),
+    ("Podcasts", "Podcasts"),
+    ("Queue", "Warteschlange"),
+    ("Related Artists", "Ähnliche Künstler"),
+    ("Saved Tracks", "Gespeicherte Titel"),
+    ("Search", "Suche"),
+    ("Search: {}", "Suche: {}"),
+    ("Shows", "Sendungen"),
+    ("Singles", "Singles"),
+    ("Top 10", "Top 10"),
+    ("Tracks", "Titel"),
+    ("{} tracks", "{} Titel"),
+    ("By {}", "Von {}"),
+    ("{} followers", "{} Follower"),
+    ("Popularity {}%", "Beliebtheit {}%"),
+    ("{} of {} tracks", "{} von {} Titeln"),
+    ("{} left, ends at {}", "noch {}, endet um {}"),
+    // Help
+    (
+        "Custom bindings can be set in {} within the [keybindings] section. Press / to filter.",
+        "Eigene Tastenbelegungen können in {} im Abschnitt [keybindings] festgelegt werden. Mit / filtern.",
+    ),
+    ("Filter: {}", "Filter: {}"),
+    ("General", "Allgemein"),
+    ("Navigation", "Navigation"),
+    ("No commands match \"{}\"", "Keine Befehle passen zu \"{}\""),
+    (
+        "Press a key to show its bindings",
+        "Taste drücken, um ihre Belegung anzuzeigen",
+    ),
+    ("{} and {} are the same key", "{} und {} sind dieselbe Taste"),
+    (
+        "{} overrides the default binding {} -> {}",
+        "{} überschreibt die Standardbelegung {} -> {}",
+    ),
+    ("Keybinding conflicts: {}", "Konflikte in der Tastenbelegung: {}"),
+    (
+        "Macros can't be replayed from a macro",
+        "Makros können nicht aus einem Makro abgespielt werden",
+    ),
+    ("Not recording", "Keine laufende Aufnahme"),
+    (
+        "Recorded {} commands to register {}",
+        "{} Befehle in Register {} aufgenommen",
+    ),
+    ("Recording to register {}", "Aufnahme in Register {}"),
+    ("Register {} is empty", "Register {} ist leer"),
+    (
+        "Could not replay register {}: {}",
+        "Register {} konnte nicht abgespielt werden: {}",
+    ),
+    (
+        "{} is not bound to any command",
+        "{} ist keinem Befehl zugeordnet",
+    ),
+    ("Playback", "Wiedergabe"),
+    ("Playlist", "Playlist"),
+    // Context menu
+    ("Copy to playlist…", "In Playlist kopieren…"),
+    ("Move to playlist…", "In Playlist verschieben…"),
+    ("Artist", "Künstler"),
+    ("Audio features", "Audiomerkmale"),
+    ("Pause", "Pausieren"),
+    ("Play", "Abspielen"),
+    ("Play next", "Als Nächstes abspielen"),
+    ("Queued from: {}", "Eingereiht aus: {}"),
+    ("Resume", "Fortsetzen"),
+    ("Save", "Speichern"),
+    ("Save album", "Album speichern"),
+    ("Share", "Teilen"),
+    ("Share album", "Album teilen"),
+    ("Show album", "Album anzeigen"),
+    ("Similar tracks", "Ähnliche Titel"),
+    ("Start radio", "Radio starten"),
+    ("Unsave", "Nicht mehr speichern"),
+    ("Unsave album", "Album nicht mehr speichern"),
+    ("Follow Artist", "Künstler folgen"),
+    ("Unfollow Artist", "Künstler entfolgen"),
+    ("Queue discography", "Diskografie einreihen"),
+    ("Play top tracks", "Top-Titel abspielen"),
+    (
+        "Could not load the top tracks of {}",
+        "Die Top-Titel von {} konnten nicht geladen werden",
+    ),
+    ("The selected item has no artist", "Das ausgewählte Element hat keinen Künstler"),
+    ("Mark as played", "Als gespielt markieren"),
+    ("Mark as unplayed", "Als ungespielt markieren"),
+    ("View Artist", "Künstler anzeigen"),
+    ("explicit content is disabled", "explizite Inhalte sind deaktiviert"),
+    ("not available in your region", "in deiner Region nicht verfügbar"),
+    (
+        "not available with your subscription",
+        "mit deinem Abo nicht verfügbar",
+    ),
+    ("unavailable", "nicht verfügbar"),
+    // Dialogs
+    ("Add anyway", "Trotzdem hinzufügen"),
+    ("Add track to playlist", "Titel zu Playlist hinzufügen"),
+    ("Added track to {}", "Titel zu {} hinzugefügt"),
+    (
+        "Added track to {}, but could not remove it from {}",
+        "Titel zu {} hinzugefügt, konnte ihn aber nicht aus {} entfernen",
+    ),
+    ("Could not add track to {}", "Titel konnte nicht zu {} hinzugefügt werden"),
+    ("Move track to playlist", "Titel in Playlist verschieben"),
+    ("Moved track to {}", "Titel nach {} verschoben"),
+    ("Archive", "Archivieren"),
+    (
+        "Are you sure you want to delete this playlist?",
+        "Soll diese Playlist wirklich gelöscht werden?",
+    ),
+    ("Cancel", "Abbrechen"),
+    ("Clear queue?", "Warteschlange leeren?"),
+    ("Log out and quit?", "Abmelden und beenden?"),
+    (
+        "Remove this track from the playlist?",
+        "Diesen Titel aus der Playlist entfernen?",
+    ),
+    ("Remove track", "Titel entfernen"),
+    ("Close", "Schließen"),
+    (
+        "Create new or overwrite existing playlist?",
+        "Neue Playlist erstellen oder bestehende überschreiben?",
+    ),
+    ("[Create new]", "[Neu erstellen]"),
+    ("Delete playlist", "Playlist löschen"),
+    ("Enter name", "Name eingeben"),
+    ("Restore archived playlist", "Archivierte Playlist wiederherstellen"),
+    ("Restored playlist {}", "Playlist {} wiederhergestellt"),
+    ("Saved snapshot to {}", "Schnappschuss in {} gespeichert"),
+    ("Snapshots", "Schnappschüsse"),
+    ("There are no snapshots", "Es gibt keine Schnappschüsse"),
+    ("History", "Verlauf"),
+    ("ncspot is already running", "ncspot läuft bereits"),
+    ("There are no visited views", "Es gibt keine besuchten Ansichten"),
+    (
+        "There is no view to go forward to",
+        "Es gibt keine Ansicht, zu der vorwärts gegangen werden kann",
+    ),
+    ("{} from {} ({} tracks)", "{} vom {} ({} Titel)"),
+    (
+        "There are no archived playlists",
+        "Es gibt keine archivierten Playlists",
+    ),
+    ("{} ({} tracks)", "{} ({} Titel)"),
+    (
+        "No audio features available for this track",
+        "Für diesen Titel sind keine Audiomerkmale verfügbar",
+    ),
+    ("No", "Nein"),
+    (
+        "Select action for artist: {}",
+        "Aktion für Künstler wählen: {}",
+    ),
+    ("Select artist", "Künstler wählen"),
+    (
+        "This track is already in your playlist",
+        "Dieser Titel ist bereits in der Playlist",
+    ),
+    ("Track already exists", "Titel bereits vorhanden"),
+    ("Yes", "Ja"),
+    // Messages and errors
+    (
+        "At least one seed is required",
+        "Mindestens ein Startwert ist nötig",
+    ),
+    (
+        "At most {} seeds can be used",
+        "Es können höchstens {} Startwerte verwendet werden",
+    ),
+    (
+        "There is no seed at position {}",
+        "An Position {} gibt es keinen Startwert",
+    ),
+    (
+        "Could not delete track.",
+        "Titel konnte nicht gelöscht werden.",
+    ),
+    ("Could not move track.", "Titel konnte nicht verschoben werden."),
+    (
+        "Sorted playlists can't be reordered",
+        "Sortierte Playlists können nicht umgeordnet werden",
+    ),
+    (
+        "The playlist is still loading",
+        "Die Playlist wird noch geladen",
+    ),
+    (
+        "Could not load recommendations",
+        "Empfehlungen konnten nicht geladen werden",
+    ),
+    (
+        "Failed to reload configuration. Fix errors in {} and try again.",
+        "Konfiguration konnte nicht neu geladen werden. Fehler in {} beheben und erneut versuchen.",
+    ),
+    (
+        "Could not set {}: {}",
+        "{} konnte nicht gesetzt werden: {}",
+    ),
+    (
+        "Saved metadata to {}",
+        "Metadaten in {} gespeichert",
+    ),
+    (
+        "Copied metadata to the clipboard",
+        "Metadaten in die Zwischenablage kopiert",
+    ),
+    (
+        "Give a file to save the metadata to",
+        "Datei zum Speichern der Metadaten angeben",
+    ),
+    ("Bookmarked {} at {}", "Lesezeichen {} bei {} gesetzt"),
+    ("There is no bookmark {}", "Es gibt kein Lesezeichen {}"),
+    (
+        "The command \"{}\" is unsupported in this view",
+        "Der Befehl \"{}\" wird in dieser Ansicht nicht unterstützt",
+    ),
+    (
+        "Queueing discography of {}...",
+        "Diskografie von {} wird eingereiht...",
+    ),
+    (
+        "Queueing discography of {}: {}/{} albums",
+        "Diskografie von {} wird eingereiht: {}/{} Alben",
+    ),
+    ("Queued {} tracks by {}", "{} Titel von {} eingereiht"),
+    (
+        "Adding {} items to the queue",
+        "{} Einträge werden eingereiht",
+    ),
+    (
+        "Episodes can't be sorted by {}",
+        "Folgen können nicht nach {} sortiert werden",
+    ),
+    ("{} played episodes hidden", "{} gespielte Folgen ausgeblendet"),
+    ("Cleared cached responses", "Zwischengespeicherte Antworten verworfen"),
+    ("Loading…", "Wird geladen…"),
+    ("Connecting to Spotify…", "Verbindung zu Spotify wird hergestellt…"),
+    ("Loading library…", "Bibliothek wird geladen…"),
+    ("Disconnected", "Verbindung getrennt"),
+    ("Quit", "Beenden"),
+    ("Reconnect", "Neu verbinden"),
+    (
+        "The connection to Spotify was lost: {}",
+        "Die Verbindung zu Spotify wurde unterbrochen: {}",
+    ),
+    ("Audio device", "Audiogerät"),
+    ("Default device", "Standardgerät"),
+    ("Enter device name", "Gerätenamen eingeben"),
+    ("[Other device…]", "[Anderes Gerät…]"),
+    ("Streaming at {} kbit/s", "Streaming mit {} kbit/s"),
+    ("Data saver enabled", "Datensparmodus aktiviert"),
+    ("Data saver disabled", "Datensparmodus deaktiviert"),
+    ("Private session enabled", "Private Sitzung aktiviert"),
+    ("Private session disabled", "Private Sitzung deaktiviert"),
+    // Accessibility
+    ("Now playing: {}", "Aktuelle Wiedergabe: {}"),
+    ("Playing", "Wiedergabe"),
+    ("Paused", "Pausiert"),
+    ("Stopped", "Gestoppt"),
+    ("[playing] ", "[spielt] "),
+    ("[selected] ", "[ausgewählt] "),
+    ("[unavailable] ", "[nicht verfügbar] "),
+    ("[local] ", "[lokal] "),
+    ("{} exited with {}", "{} wurde beendet mit {}"),
+    ("Could not run {}: {}", "{} konnte nicht ausgeführt werden: {}"),
+    (
+        "Could not load top tracks",
+        "Top-Titel konnten nicht geladen werden",
+    ),
+    (
+        "Could not load related artists",
+        "Ähnliche Künstler konnten nicht geladen werden",
+    ),
+    ("Importing queue…", "Warteschlange wird importiert…"),
+    ("Importing queue: {}/{}", "Warteschlange wird importiert: {}/{}"),
+    ("Imported {} of {} items", "{} von {} Einträgen importiert"),
+    (
+        "Could not import queue: {}",
+        "Warteschlange konnte nicht importiert werden: {}",
+    ),
+    (
+        "No other ncspot instance found",
+        "Keine andere ncspot-Instanz gefunden",
+    ),
+    (
+        "You have been listening for {} without a break, maybe it's time for one",
+        "Du hörst seit {} ohne Pause, vielleicht ist es Zeit für eine",
+    ),
+    (
+        "You reached your listening goal of {} for today",
+        "Du hast dein Hörziel von {} für heute erreicht",
+    ),
+    (
+        "Skipped {} tracks that are already in the queue",
+        "{} Titel übersprungen, die schon in der Warteschlange sind",
+    ),
+    (
+        "Removed {} duplicates from the queue",
+        "{} Duplikate aus der Warteschlange entfernt",
+    ),
+    ("Shuffled the queue", "Warteschlange gemischt"),
+    ("Not available in party mode", "Im Partymodus nicht verfügbar"),
+    (
+        "Set a party_passphrase to use party mode",
+        "Setze eine party_passphrase, um den Partymodus zu nutzen",
+    ),
+    ("Party mode enabled", "Partymodus aktiviert"),
+    ("Nothing is queued after this", "Danach ist nichts eingereiht"),
+    ("Pinned {}", "{} angeheftet"),
+    ("Unpinned {}", "{} nicht mehr angeheftet"),
+    ("There is no favorite playlist {}", "Es gibt keine Favoriten-Playlist {}"),
+    ("Volume {}%", "Lautstärke {}%"),
+    ("Shuffle enabled", "Zufallswiedergabe aktiviert"),
+    ("Shuffle disabled", "Zufallswiedergabe deaktiviert"),
+    ("Repeat disabled", "Wiederholung deaktiviert"),
+    ("Repeating the queue", "Warteschlange wird wiederholt"),
+    ("Repeating the track", "Titel wird wiederholt"),
+    ("The selected item has no cover", "Das ausgewählte Element hat kein Cover"),
+    ("Nothing with a cover is playing", "Es wird nichts mit einem Cover abgespielt"),
+    ("Saved the cover to {}", "Cover unter {} gespeichert"),
+    ("Could not save the cover: {}", "Cover konnte nicht gespeichert werden: {}"),
+    (
+        "Showing covers requires the cover feature",
+        "Zum Anzeigen von Covern wird das Feature cover benötigt",
+    ),
+    ("Follow all artists", "Allen Künstlern folgen"),
+    ("Follow playlist", "Playlist folgen"),
+    ("Unfollow playlist", "Playlist nicht mehr folgen"),
+    ("Followed {} new artists from {}", "{} neuen Künstlern aus {} gefolgt"),
+    (
+        "Could not follow all artists from {}",
+        "Konnte nicht allen Künstlern aus {} folgen",
+    ),
+    ("Could not load {}", "{} konnte nicht geladen werden"),
+    ("Party mode disabled", "Partymodus deaktiviert"),
+    ("Clean mode enabled", "Jugendschutzmodus aktiviert"),
+    ("Clean mode disabled", "Jugendschutzmodus deaktiviert"),
+    ("{} albums", "{} Alben"),
+    ("Albums ({})", "Alben ({})"),
+    ("Updating library", "Bibliothek wird aktualisiert"),
+    ("Queueing discography of {}", "Diskografie von {} wird eingereiht"),
+    ("Importing queue", "Warteschlange wird importiert"),
+    ("Cancelled importing the queue", "Import der Warteschlange abgebrochen"),
+    ("Tasks", "Aufgaben"),
+    ("No tasks are running", "Es laufen keine Aufgaben"),
+    ("Cancelling task…", "Aufgabe wird abgebrochen…"),
+    ("The task already finished", "Die Aufgabe ist bereits beendet"),
+    ("The library is already being updated", "Die Bibliothek wird bereits aktualisiert"),
+    ("Playlists can't be sorted by {}", "Playlists können nicht nach {} sortiert werden"),
+    ("Tracks can't be sorted by {}", "Titel können nicht nach {} sortiert werden"),
+    ("Enter passphrase", "Passphrase eingeben"),
+    ("Wrong passphrase", "Falsche Passphrase"),
+    // Tray icon
+    ("Previous", "Zurück"),
+    ("Next", "Weiter"),
+    // Deleting and archiving playlists
+    ("The library is still loading", "Die Bibliothek wird noch geladen"),
+    ("Playlist {} not found", "Playlist {} nicht gefunden"),
+    ("Could not delete playlist {}", "Playlist {} konnte nicht gelöscht werden"),
+    ("Could not archive playlist: {}", "Playlist konnte nicht archiviert werden: {}"),
+    ("Could not create playlist {}", "Playlist {} konnte nicht erstellt werden"),
+    (
+        "Could not remove archived playlist: {}",
+        "Archivierte Playlist konnte nicht entfernt werden: {}",
+    ),
+    (
+        "Could not load the search results",
+        "Die Suchergebnisse konnten nicht geladen werden",
+    ),
+    // Reasons for losing the connection to Spotify
+    ("the session was closed", "die Sitzung wurde beendet"),
+    ("the player stopped responding", "die Wiedergabe reagiert nicht mehr"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translations_keep_placeholders() {
+        for (english, translated) in GERMAN {
+            assert_eq!(
+                english.matches("{}").count(),
+                translated.matches("{}").count(),
+                "placeholders of \"{english}\" don't match its translation"
+            );
+        }
+    }
+
+    #[test]
+    fn placeholders_are_replaced_in_order() {
+        assert_eq!(
+            tr_args("{} left, ends at {}", &["5m", "12:00"]),
+            "5m left, ends at 12:00"
+        );
+    }
+}
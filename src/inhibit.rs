@@ -0,0 +1,103 @@
+use std::error::Error;
+
+use log::{debug, warn};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tokio_stream::StreamExt;
+use zbus::zvariant::OwnedFd;
+use zbus::Connection;
+
+use crate::application::ASYNC_RUNTIME;
+use crate::spotify::PlayerEvent;
+
+/// Commands that can be sent to the task holding the inhibitor lock.
+#[derive(Debug)]
+enum InhibitCommand {
+    Acquire,
+    Release,
+}
+
+/// Prevents the system from suspending while music is playing by taking a systemd-logind
+/// inhibitor lock. The lock is held for as long as the returned file descriptor stays open.
+///
+/// Only systemd-logind and elogind are supported, so this has no effect on macOS or Windows.
+///
+/// This is internally shared and cloning it will yield a reference to the same lock.
+#[derive(Clone)]
+pub struct SuspendInhibitor {
+    tx: mpsc::UnboundedSender<InhibitCommand>,
+}
+
+impl SuspendInhibitor {
+    pub fn new() -> Self {
+        let (tx, rx) = mpsc::unbounded_channel::<InhibitCommand>();
+
+        ASYNC_RUNTIME.get().unwrap().spawn(async {
+            let result = Self::serve(UnboundedReceiverStream::new(rx)).await;
+            if let Err(e) = result {
+                log::error!("suspend inhibitor error: {e}");
+            }
+        });
+
+        Self { tx }
+    }
+
+    async fn serve(
+        mut rx: UnboundedReceiverStream<InhibitCommand>,
+    ) -> Result<(), Box<dyn Error + Sync + Send>> {
+        let conn = Connection::system().await?;
+        let mut lock: Option<OwnedFd> = None;
+
+        while let Some(command) = rx.next().await {
+            match command {
+                InhibitCommand::Acquire if lock.is_none() => match Self::inhibit(&conn).await {
+                    Ok(fd) => {
+                        debug!("acquired suspend inhibitor lock");
+                        lock = Some(fd);
+                    }
+                    Err(e) => warn!("could not inhibit system suspend: {e}"),
+                },
+                InhibitCommand::Release => {
+                    // Closing the file descriptor releases the lock.
+                    if lock.take().is_some() {
+                        debug!("released suspend inhibitor lock");
+                    }
+                }
+                InhibitCommand::Acquire => {}
+            }
+        }
+        Ok(())
+    }
+
+    async fn inhibit(conn: &Connection) -> zbus::Result<OwnedFd> {
+        let reply = conn
+            .call_method(
+                Some("org.freedesktop.login1"),
+                "/org/freedesktop/login1",
+                Some("org.freedesktop.login1.Manager"),
+                "Inhibit",
+                &("sleep:idle", "ncspot", "Playing music", "block"),
+            )
+            .await?;
+        reply.body().deserialize::<OwnedFd>()
+    }
+
+    /// Take or release the lock depending on the new playback `status`.
+    pub fn update(&self, status: &PlayerEvent) {
+        let command = match status {
+            PlayerEvent::Playing(_) => InhibitCommand::Acquire,
+            PlayerEvent::Paused(_) | PlayerEvent::Stopped => InhibitCommand::Release,
+            // The next track will start playing right away, keep the current state.
+            PlayerEvent::FinishedTrack => return,
+        };
+        if let Err(e) = self.tx.send(command) {
+            warn!("Could not update suspend inhibitor: {e}");
+        }
+    }
+}
+
+impl Default for SuspendInhibitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,3 +1,7 @@
+use std::io::{Read, Write};
+use std::net::Shutdown;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 use std::{io, path::PathBuf};
 
 use futures::SinkExt;
@@ -9,10 +13,21 @@ use tokio_stream::wrappers::WatchStream;
 use tokio_stream::StreamExt;
 use tokio_util::codec::{FramedRead, FramedWrite, LinesCodec};
 
+use crate::config::state_path;
 use crate::events::{Event, EventManager};
 use crate::model::playable::Playable;
 use crate::spotify::PlayerEvent;
 
+/// The line a client sends to receive the queue of this instance, instead of running a command.
+pub const QUEUE_REQUEST: &str = "get queue";
+
+/// The file in the state directory that holds the path of the IPC socket of the instance using
+/// that state directory.
+const INSTANCE_FILE_NAME: &str = "instance";
+
+/// How long to wait for the running instance when handing off commands to it.
+const HAND_OFF_TIMEOUT: Duration = Duration::from_secs(5);
+
 pub struct IpcSocket {
     tx: Sender<Status>,
     path: PathBuf,
@@ -24,14 +39,62 @@ struct Status {
     playable: Option<Playable>,
 }
 
+/// The answer to a [QUEUE_REQUEST].
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QueueResponse {
+    /// The id of the process that answered, so an instance can tell its own queue apart.
+    pub pid: u32,
+    pub queue: Vec<Playable>,
+}
+
 impl Drop for IpcSocket {
     fn drop(&mut self) {
         self.try_remove_socket();
+
+        let instance_file = state_path(INSTANCE_FILE_NAME);
+        // Another instance may have taken over the state directory in the meantime.
+        if std::fs::read(&instance_file)
+            .is_ok_and(|content| content == self.path.as_os_str().as_encoded_bytes())
+        {
+            let _ = std::fs::remove_file(instance_file);
+        }
     }
 }
 
+/// Send `commands` to the instance of ncspot that uses the same state directory, if one is
+/// running. Returns whether there is such an instance.
+pub fn hand_off(commands: &[String]) -> bool {
+    let Ok(path) = std::fs::read_to_string(state_path(INSTANCE_FILE_NAME)) else {
+        return false;
+    };
+    let Ok(mut stream) = std::os::unix::net::UnixStream::connect(path) else {
+        return false;
+    };
+
+    info!("Handing off {commands:?} to the running instance");
+    let result = stream
+        .set_read_timeout(Some(HAND_OFF_TIMEOUT))
+        .and_then(|_| {
+            for command in commands {
+                writeln!(stream, "{command}")?;
+            }
+            stream.shutdown(Shutdown::Write)
+        })
+        // The running instance closes the connection once it read all commands.
+        .and_then(|_| stream.read_to_end(&mut Vec::new()));
+    if let Err(e) = result {
+        error!("Could not hand off commands to the running instance: {e}");
+    }
+    true
+}
+
 impl IpcSocket {
-    pub fn new(handle: &Handle, path: PathBuf, ev: EventManager) -> io::Result<Self> {
+    pub fn new(
+        handle: &Handle,
+        path: PathBuf,
+        ev: EventManager,
+        queue: Arc<RwLock<Vec<Playable>>>,
+    ) -> io::Result<Self> {
         let path = if path.exists() && Self::is_open_socket(&path) {
             let mut new_path = path;
             new_path.set_file_name(format!("ncspot.{}.sock", std::process::id()));
@@ -44,6 +107,12 @@ impl IpcSocket {
         };
 
         info!("Creating IPC domain socket at {path:?}");
+        if let Err(e) = std::fs::write(
+            state_path(INSTANCE_FILE_NAME),
+            path.as_os_str().as_encoded_bytes(),
+        ) {
+            error!("Could not write the instance file: {e}");
+        }
 
         let status = Status {
             mode: PlayerEvent::Stopped,
@@ -55,7 +124,7 @@ impl IpcSocket {
         handle.spawn(async move {
             let listener =
                 UnixListener::bind(listener_path).expect("Could not create IPC domain socket");
-            Self::worker(listener, ev, rx.clone()).await;
+            Self::worker(listener, ev, rx.clone(), queue).await;
         });
 
         Ok(Self { tx, path })
@@ -73,7 +142,12 @@ impl IpcSocket {
         self.tx.send(status).unwrap();
     }
 
-    async fn worker(listener: UnixListener, ev: EventManager, tx: Receiver<Status>) {
+    async fn worker(
+        listener: UnixListener,
+        ev: EventManager,
+        tx: Receiver<Status>,
+        queue: Arc<RwLock<Vec<Playable>>>,
+    ) {
         loop {
             match listener.accept().await {
                 Ok((stream, sockaddr)) => {
@@ -82,6 +156,7 @@ impl IpcSocket {
                         stream,
                         ev.clone(),
                         WatchStream::new(tx.clone()),
+                        queue.clone(),
                     ));
                 }
                 Err(e) => error!("Error accepting connection: {e}"),
@@ -93,6 +168,7 @@ impl IpcSocket {
         mut stream: UnixStream,
         ev: EventManager,
         mut rx: WatchStream<Status>,
+        queue: Arc<RwLock<Vec<Playable>>>,
     ) -> Result<(), String> {
         let (reader, writer) = stream.split();
         let mut framed_reader = FramedRead::new(reader, LinesCodec::new());
@@ -102,6 +178,14 @@ impl IpcSocket {
             tokio::select! {
                 line = framed_reader.next() => {
                     match line {
+                        Some(Ok(line)) if line.trim() == QUEUE_REQUEST => {
+                            let response = QueueResponse {
+                                pid: std::process::id(),
+                                queue: queue.read().unwrap().clone(),
+                            };
+                            let response_str = serde_json::to_string(&response).map_err(|e| e.to_string())?;
+                            framed_writer.send(response_str).await.map_err(|e| e.to_string())?;
+                        }
                         Some(Ok(line)) => {
                             debug!("Received line: \"{line}\"");
                             ev.send(Event::IpcInput(line));
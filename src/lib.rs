@@ -44,5 +44,20 @@ pub fn program_arguments() -> clap::Command {
                 .help("Filename of config file in basepath")
                 .default_value(CONFIGURATION_FILE_NAME),
         )
-        .subcommands([clap::Command::new("info").about("Print platform information like paths")])
+        .arg(
+            clap::Arg::new("uri")
+                .value_name("URI")
+                .help("Spotify URI or open.spotify.com URL to play after startup"),
+        )
+        .args_conflicts_with_subcommands(true)
+        .subcommands([
+            clap::Command::new("info").about("Print platform information like paths"),
+            clap::Command::new("cache")
+                .about("Manage the library cache")
+                .subcommand_required(true)
+                .subcommand(
+                    clap::Command::new("verify")
+                        .about("Check the cached library and move unreadable files aside"),
+                ),
+        ])
 }
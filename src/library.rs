@@ -1,10 +1,13 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::iter::Iterator;
-use std::path::Path;
-use std::sync::{Arc, RwLock};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
 use std::thread;
+use std::time::{Duration, SystemTime};
 
+use cursive::Cursive;
 use log::{debug, error, info};
 use rspotify::model::Id;
 use serde::de::DeserializeOwned;
@@ -13,13 +16,21 @@ use serde::Serialize;
 use crate::config::Config;
 use crate::config::{self, CACHE_VERSION};
 use crate::events::EventManager;
+use crate::i18n::{tr, tr_args};
 use crate::model::album::Album;
 use crate::model::artist::Artist;
+use crate::model::audio_features::AudioFeatures;
+use crate::model::bookmark::Bookmark;
+use crate::model::episode::Episode;
 use crate::model::playable::Playable;
 use crate::model::playlist::Playlist;
 use crate::model::show::Show;
+use crate::model::snapshot::Snapshot;
 use crate::model::track::Track;
+use crate::serialization::write_atomic;
 use crate::spotify::Spotify;
+use crate::spotify_api::BATCH_TOKEN_VALIDITY;
+use crate::tasks::{Task, Tasks};
 
 /// Cached tracks database filename.
 const CACHE_TRACKS: &str = "tracks.db";
@@ -33,6 +44,27 @@ const CACHE_ARTISTS: &str = "artists.db";
 /// Cached playlists database filename.
 const CACHE_PLAYLISTS: &str = "playlists.db";
 
+/// Bookmarks of positions in tracks and episodes filename.
+const CACHE_BOOKMARKS: &str = "bookmarks.db";
+
+/// Appended to the name of cache files that can't be read, which are moved aside and fetched again.
+const QUARANTINE_SUFFIX: &str = ".corrupt";
+
+/// How long library lists aren't synced again while the data saver is enabled.
+const DATA_SAVER_SYNC_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Directory for the exported contents of deleted playlists.
+const ARCHIVE_DIRECTORY: &str = "archive";
+
+/// Directory for metadata snapshots of playlists and albums.
+const SNAPSHOT_DIRECTORY: &str = "snapshots";
+
+/// The maximum amount of artists that can be followed with one Web API request.
+const FOLLOW_BATCH_SIZE: usize = 50;
+
+/// The share of an episode that has to be listened to for it to count as played.
+const EPISODE_PLAYED_RATIO: f32 = 0.95;
+
 /// The user library with all their saved tracks, albums, playlists... High level interface to the
 /// Spotify API used to manage items in the user library.
 #[derive(Clone)]
@@ -43,35 +75,82 @@ pub struct Library {
     pub playlists: Arc<RwLock<Vec<Playlist>>>,
     pub shows: Arc<RwLock<Vec<Show>>>,
     pub is_done: Arc<RwLock<bool>>,
-    pub user_id: Option<String>,
-    pub display_name: Option<String>,
+    /// Callbacks waiting for the library to be loaded, see [Library::when_loaded].
+    loaded_callbacks: Arc<Mutex<Vec<Box<dyn FnOnce() + Send>>>>,
+    /// Saved status of items that was looked up through the API while the library was still
+    /// loading, by item id.
+    saved_status: Arc<RwLock<HashMap<String, bool>>>,
+    /// Audio features of tracks that have been looked up before, by track id.
+    audio_features: Arc<RwLock<HashMap<String, AudioFeatures>>>,
+    /// Bookmarks set with `mark add`, by the URI of the track or episode, ordered by position.
+    bookmarks: Arc<RwLock<HashMap<String, Vec<Bookmark>>>>,
+    /// The id of the logged in user, known once the library is first updated.
+    user_id: Arc<RwLock<Option<String>>>,
+    display_name: Arc<RwLock<Option<String>>>,
+    /// The operations running in the background, like updating the library.
+    pub tasks: Tasks,
+    /// Whether the library is being updated, so updates don't overlap.
+    syncing: Arc<AtomicBool>,
     ev: EventManager,
     spotify: Spotify,
     pub cfg: Arc<Config>,
 }
 
 impl Library {
+    /// Create an empty library. Its items are loaded by [Library::update_library] once the web API
+    /// can be used.
     pub fn new(ev: EventManager, spotify: Spotify, cfg: Arc<Config>) -> Self {
-        let current_user = spotify.api.current_user().ok();
-        let user_id = current_user.as_ref().map(|u| u.id.id().to_string());
-        let display_name = current_user.as_ref().and_then(|u| u.display_name.clone());
-
-        let library = Self {
+        Self {
             tracks: Arc::new(RwLock::new(Vec::new())),
             albums: Arc::new(RwLock::new(Vec::new())),
             artists: Arc::new(RwLock::new(Vec::new())),
             playlists: Arc::new(RwLock::new(Vec::new())),
             shows: Arc::new(RwLock::new(Vec::new())),
             is_done: Arc::new(RwLock::new(false)),
-            user_id,
-            display_name,
+            loaded_callbacks: Arc::new(Mutex::new(Vec::new())),
+            saved_status: Arc::new(RwLock::new(HashMap::new())),
+            audio_features: Arc::new(RwLock::new(HashMap::new())),
+            bookmarks: Arc::new(RwLock::new(load_bookmarks())),
+            user_id: Arc::new(RwLock::new(None)),
+            display_name: Arc::new(RwLock::new(None)),
+            tasks: Tasks::new(ev.clone()),
+            syncing: Arc::new(AtomicBool::new(false)),
             ev,
             spotify,
             cfg,
-        };
+        }
+    }
+
+    /// Run `callback` once the library is loaded, or right away if it already is.
+    pub fn when_loaded(&self, callback: impl FnOnce() + Send + 'static) {
+        let mut callbacks = self.loaded_callbacks.lock().unwrap();
+        if *self.is_done.read().unwrap() {
+            drop(callbacks);
+            callback();
+        } else {
+            callbacks.push(Box::new(callback));
+        }
+    }
+
+    /// The id of the logged in user.
+    pub fn user_id(&self) -> Option<String> {
+        self.user_id.read().unwrap().clone()
+    }
 
-        library.update_library();
-        library
+    /// The display name of the logged in user.
+    pub fn display_name(&self) -> Option<String> {
+        self.display_name.read().unwrap().clone()
+    }
+
+    /// Look up the logged in user if that hasn't happened yet.
+    fn fetch_current_user(&self) {
+        if self.user_id.read().unwrap().is_some() {
+            return;
+        }
+        if let Ok(user) = self.spotify.api.current_user() {
+            *self.user_id.write().unwrap() = Some(user.id.id().to_string());
+            *self.display_name.write().unwrap() = user.display_name;
+        }
     }
 
     /// Load cached items from the file at `cache_path` into the given `store`.
@@ -85,25 +164,36 @@ impl Library {
             return;
         }
 
-        if let Ok(contents) = std::fs::read_to_string(cache_path) {
-            debug!("loading cache from {}", cache_path.display());
-            // Parse from in-memory string instead of directly from the file because it's faster.
-            let parsed = serde_json::from_str::<Vec<_>>(&contents);
-            match parsed {
-                Ok(cache) => {
-                    debug!(
-                        "cache from {} loaded ({} items)",
-                        cache_path.display(),
-                        cache.len()
-                    );
-                    store.clear();
-                    store.extend(cache);
-
-                    // force refresh of UI (if visible)
-                    self.trigger_redraw();
-                }
-                Err(e) => {
-                    error!("can't parse cache: {}", e);
+        debug!("loading cache from {}", cache_path.display());
+        match read_cache(cache_path) {
+            Ok(Some(cache)) => {
+                debug!(
+                    "cache from {} loaded ({} items)",
+                    cache_path.display(),
+                    cache.len()
+                );
+                store.clear();
+                store.extend(cache);
+
+                // force refresh of UI (if visible)
+                self.trigger_redraw();
+            }
+            Ok(None) => {}
+            Err(CacheError::Unreadable(e)) => {
+                error!("can't read cache {}: {}", cache_path.display(), e);
+            }
+            Err(CacheError::Corrupt(e)) => {
+                // Without the file, the list is fetched again even while the data saver is on.
+                match quarantine(cache_path) {
+                    Ok(quarantined) => error!(
+                        "can't parse cache: {}, moved it to {}",
+                        e,
+                        quarantined.display()
+                    ),
+                    Err(move_error) => error!(
+                        "can't parse cache: {}, could not move it aside: {}",
+                        e, move_error
+                    ),
                 }
             }
         }
@@ -111,8 +201,9 @@ impl Library {
 
     /// Save the items from `store` in the file at `cache_path`.
     fn save_cache<T: Serialize>(&self, cache_path: &Path, store: &[T]) {
-        let cache_file = File::create(cache_path).unwrap();
-        let serialize_result = serde_json::to_writer(cache_file, store);
+        let serialize_result = write_atomic(cache_path, |writer| {
+            serde_json::to_writer(writer, store).map_err(|e| e.to_string())
+        });
         if let Err(message) = serialize_result {
             error!("could not write cache: {message:?}");
         }
@@ -144,10 +235,10 @@ impl Library {
         store.len() - 1
     }
 
-    /// Delete the playlist with the given `id` if it exists.
-    pub fn delete_playlist(&self, id: &str) {
+    /// Delete the playlist with the given `id`, or unfollow it if it was created by another user.
+    pub fn delete_playlist(&self, id: &str) -> Result<(), String> {
         if !*self.is_done.read().unwrap() {
-            return;
+            return Err(tr("The library is still loading").to_string());
         }
 
         let position = self
@@ -155,17 +246,180 @@ impl Library {
             .read()
             .unwrap()
             .iter()
-            .position(|i| i.id == id);
-
-        if let Some(position) = position {
-            if self.spotify.api.delete_playlist(id).is_ok() {
-                self.playlists.write().unwrap().remove(position);
-                self.save_cache(
-                    &config::cache_path(CACHE_PLAYLISTS),
-                    &self.playlists.read().unwrap(),
-                );
-            }
+            .position(|i| i.id == id)
+            .ok_or_else(|| tr_args("Playlist {} not found", &[id]))?;
+
+        self.spotify
+            .api
+            .delete_playlist(id)
+            .map_err(|_| tr_args("Could not delete playlist {}", &[id]))?;
+        self.set_saved_status([id], false);
+        self.playlists.write().unwrap().remove(position);
+        self.save_cache(
+            &config::cache_path(CACHE_PLAYLISTS),
+            &self.playlists.read().unwrap(),
+        );
+        Ok(())
+    }
+
+    /// Remove `track` from the playlist with `playlist_id`, preferring the occurrence at the same
+    /// position if the track is in the playlist multiple times. Returns whether it was removed.
+    pub fn remove_from_playlist(&self, playlist_id: &str, track: &Playable) -> bool {
+        let playlist = self
+            .playlists
+            .read()
+            .unwrap()
+            .iter()
+            .find(|playlist| playlist.id == playlist_id)
+            .cloned();
+        let Some(mut playlist) = playlist else {
+            return false;
+        };
+        playlist.load_tracks(&self.spotify);
+
+        let tracks = playlist.tracks.as_deref().unwrap_or_default();
+        let index = tracks
+            .iter()
+            .position(|t| t.id() == track.id() && t.list_index() == track.list_index())
+            .or_else(|| tracks.iter().position(|t| t.id() == track.id()));
+        index.is_some_and(|index| playlist.delete_track(index, self.spotify.clone(), self))
+    }
+
+    /// Export the playlist with `id` and its tracks to a file in the archive directory, then
+    /// delete it. The playlist can be recreated with [Library::restore_playlist].
+    pub fn archive_playlist(&self, id: &str) -> Result<(), String> {
+        let mut playlist = self
+            .playlists
+            .read()
+            .unwrap()
+            .iter()
+            .find(|playlist| playlist.id == id)
+            .cloned()
+            .ok_or_else(|| tr_args("Playlist {} not found", &[id]))?;
+        playlist.load_tracks(&self.spotify);
+
+        let archive_error = |e: String| tr_args("Could not archive playlist: {}", &[&e]);
+        let directory = config::cache_path(ARCHIVE_DIRECTORY);
+        std::fs::create_dir_all(&directory).map_err(|e| archive_error(e.to_string()))?;
+        let file = File::create(directory.join(format!("{id}.json")))
+            .map_err(|e| archive_error(e.to_string()))?;
+        serde_json::to_writer_pretty(file, &playlist).map_err(|e| archive_error(e.to_string()))?;
+        info!(
+            "archived playlist {} to {}",
+            playlist.name,
+            directory.display()
+        );
+
+        self.delete_playlist(id)
+    }
+
+    /// All playlists that were archived before they were deleted, sorted by name.
+    pub fn archived_playlists(&self) -> Vec<Playlist> {
+        let Ok(entries) = std::fs::read_dir(config::cache_path(ARCHIVE_DIRECTORY)) else {
+            return Vec::new();
+        };
+        let mut playlists: Vec<Playlist> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let contents = std::fs::read_to_string(entry.path()).ok()?;
+                serde_json::from_str(&contents)
+                    .map_err(|e| error!("can't parse archived playlist: {}", e))
+                    .ok()
+            })
+            .collect();
+        playlists.sort_by(|a, b| a.name.cmp(&b.name));
+        playlists
+    }
+
+    /// Recreate an archived `playlist` with its tracks and remove it from the archive.
+    pub fn restore_playlist(&self, playlist: &Playlist) -> Result<(), String> {
+        let id = self
+            .spotify
+            .api
+            .create_playlist(&playlist.name, None, None)
+            .map_err(|_| tr_args("Could not create playlist {}", &[&playlist.name]))?;
+        self.overwrite_playlist(&id, playlist.tracks.as_deref().unwrap_or_default());
+
+        let mut path = config::cache_path(ARCHIVE_DIRECTORY);
+        path.push(format!("{}.json", playlist.id));
+        std::fs::remove_file(path)
+            .map_err(|e| tr_args("Could not remove archived playlist: {}", &[&e.to_string()]))
+    }
+
+    /// Store `snapshot` in the cache directory and return the path of its file.
+    pub fn save_snapshot(&self, snapshot: &Snapshot) -> Result<PathBuf, String> {
+        let directory = config::cache_path(SNAPSHOT_DIRECTORY);
+        std::fs::create_dir_all(&directory).map_err(|e| e.to_string())?;
+        let path = directory.join(snapshot.file_name());
+        let file = File::create(&path).map_err(|e| e.to_string())?;
+        serde_json::to_writer_pretty(file, snapshot).map_err(|e| e.to_string())?;
+        info!(
+            "saved snapshot of {} to {}",
+            snapshot.source.name,
+            path.display()
+        );
+        Ok(path)
+    }
+
+    /// All stored snapshots, newest first.
+    pub fn snapshots(&self) -> Vec<Snapshot> {
+        let Ok(entries) = std::fs::read_dir(config::cache_path(SNAPSHOT_DIRECTORY)) else {
+            return Vec::new();
+        };
+        let mut snapshots: Vec<Snapshot> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let contents = std::fs::read_to_string(entry.path()).ok()?;
+                serde_json::from_str(&contents)
+                    .map_err(|e| error!("can't parse snapshot: {}", e))
+                    .ok()
+            })
+            .collect();
+        snapshots.sort_by(|a, b| b.taken_at.cmp(&a.taken_at));
+        snapshots
+    }
+
+    /// The bookmarks of the track or episode with `uri`, ordered by position.
+    pub fn bookmarks(&self, uri: &str) -> Vec<Bookmark> {
+        self.bookmarks
+            .read()
+            .unwrap()
+            .get(uri)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Bookmark `position` in the track or episode with `uri` as `name`, replacing an earlier
+    /// bookmark with that name.
+    pub fn add_bookmark(&self, uri: &str, name: &str, position: u32) {
+        let mut bookmarks = self.bookmarks.write().unwrap();
+        let item = bookmarks.entry(uri.to_string()).or_default();
+        item.retain(|bookmark| bookmark.name != name);
+        item.push(Bookmark {
+            name: name.to_string(),
+            position,
+        });
+        item.sort_by_key(|bookmark| bookmark.position);
+        save_bookmarks(&bookmarks);
+    }
+
+    /// Remove the bookmark `name` of the track or episode with `uri`. Returns whether there was
+    /// such a bookmark.
+    pub fn remove_bookmark(&self, uri: &str, name: &str) -> bool {
+        let mut bookmarks = self.bookmarks.write().unwrap();
+        let Some(item) = bookmarks.get_mut(uri) else {
+            return false;
+        };
+        let count = item.len();
+        item.retain(|bookmark| bookmark.name != name);
+        let removed = item.len() < count;
+        if item.is_empty() {
+            bookmarks.remove(uri);
         }
+        if removed {
+            save_bookmarks(&bookmarks);
+        }
+        removed
     }
 
     /// Set the playlist with `id` to contain only `tracks`. If the playlist already contains
@@ -174,7 +428,7 @@ impl Library {
         debug!("saving {} tracks to list {}", tracks.len(), id);
         self.spotify.api.overwrite_playlist(id, tracks);
 
-        self.fetch_playlists();
+        self.fetch_playlists(None);
         self.save_cache(
             &config::cache_path(CACHE_PLAYLISTS),
             &self.playlists.read().unwrap(),
@@ -190,72 +444,106 @@ impl Library {
         }
     }
 
-    /// Update the local library and its cache on disk.
-    pub fn update_library(&self) {
+    /// Whether the list cached at `cache_path` has to be fetched again. While the data saver is
+    /// enabled, lists that were synced recently are only fetched if `force` is set.
+    fn needs_sync(&self, cache_path: &Path, force: bool) -> bool {
+        if force || !self.spotify.data_saver() {
+            return true;
+        }
+        let modified = std::fs::metadata(cache_path).and_then(|m| m.modified());
+        match modified.map(|m| SystemTime::now().duration_since(m)) {
+            Ok(Ok(age)) => age > DATA_SAVER_SYNC_INTERVAL,
+            _ => true,
+        }
+    }
+
+    /// Update the local library and its cache on disk. Returns `false` if the library is already
+    /// being updated.
+    pub fn update_library(&self) -> bool {
+        self.sync(true)
+    }
+
+    /// Like [Library::update_library], but while the data saver is enabled, lists that were
+    /// synced recently are only loaded from the cache.
+    pub fn sync_library(&self) -> bool {
+        self.sync(false)
+    }
+
+    fn sync(&self, force: bool) -> bool {
+        if self.syncing.swap(true, Ordering::SeqCst) {
+            info!("library is already being updated");
+            return false;
+        }
         *self.is_done.write().unwrap() = false;
 
         let library = self.clone();
         thread::spawn(move || {
+            let task = library.tasks.start(tr("Updating library").to_string());
+            let progress = Arc::new(SyncProgress::new(task));
+            library.spotify.api.ensure_token(BATCH_TOKEN_VALIDITY);
+            library.fetch_current_user();
+
             let t_tracks = {
                 let library = library.clone();
+                let progress = progress.clone();
                 thread::spawn(move || {
-                    library.load_cache(
-                        &config::cache_path(CACHE_TRACKS),
-                        library.tracks.write().unwrap().as_mut(),
-                    );
-                    library.fetch_tracks();
-                    library.save_cache(
-                        &config::cache_path(CACHE_TRACKS),
-                        &library.tracks.read().unwrap(),
-                    );
+                    let cache_path = config::cache_path(CACHE_TRACKS);
+                    library.load_cache(&cache_path, library.tracks.write().unwrap().as_mut());
+                    if library.needs_sync(&cache_path, force) && library.fetch_tracks(&progress) {
+                        library.save_cache(&cache_path, &library.tracks.read().unwrap());
+                    }
+                    progress.finish("Tracks");
                 })
             };
 
             let t_albums = {
                 let library = library.clone();
+                let progress = progress.clone();
                 thread::spawn(move || {
-                    library.load_cache(
-                        &config::cache_path(CACHE_ALBUMS),
-                        library.albums.write().unwrap().as_mut(),
-                    );
-                    library.fetch_albums();
-                    library.save_cache(
-                        &config::cache_path(CACHE_ALBUMS),
-                        &library.albums.read().unwrap(),
-                    );
+                    let cache_path = config::cache_path(CACHE_ALBUMS);
+                    library.load_cache(&cache_path, library.albums.write().unwrap().as_mut());
+                    if library.needs_sync(&cache_path, force) && library.fetch_albums(&progress) {
+                        library.save_cache(&cache_path, &library.albums.read().unwrap());
+                    }
+                    progress.finish("Albums");
                 })
             };
 
             let t_artists = {
                 let library = library.clone();
+                let progress = progress.clone();
                 thread::spawn(move || {
-                    library.load_cache(
-                        &config::cache_path(CACHE_ARTISTS),
-                        library.artists.write().unwrap().as_mut(),
-                    );
-                    library.fetch_artists();
+                    let cache_path = config::cache_path(CACHE_ARTISTS);
+                    library.load_cache(&cache_path, library.artists.write().unwrap().as_mut());
+                    if library.needs_sync(&cache_path, force) {
+                        library.fetch_artists(&progress);
+                    }
+                    progress.finish("Artists");
                 })
             };
 
             let t_playlists = {
                 let library = library.clone();
+                let progress = progress.clone();
                 thread::spawn(move || {
-                    library.load_cache(
-                        &config::cache_path(CACHE_PLAYLISTS),
-                        library.playlists.write().unwrap().as_mut(),
-                    );
-                    library.fetch_playlists();
-                    library.save_cache(
-                        &config::cache_path(CACHE_PLAYLISTS),
-                        &library.playlists.read().unwrap(),
-                    );
+                    let cache_path = config::cache_path(CACHE_PLAYLISTS);
+                    library.load_cache(&cache_path, library.playlists.write().unwrap().as_mut());
+                    library.pin_favorite_playlists();
+                    if library.needs_sync(&cache_path, force)
+                        && library.fetch_playlists(Some(&progress))
+                    {
+                        library.save_cache(&cache_path, &library.playlists.read().unwrap());
+                    }
+                    progress.finish("Playlists");
                 })
             };
 
             let t_shows = {
                 let library = library.clone();
+                let progress = progress.clone();
                 thread::spawn(move || {
-                    library.fetch_shows();
+                    library.fetch_shows(&progress);
+                    progress.finish("Podcasts");
                 })
             };
 
@@ -263,31 +551,48 @@ impl Library {
             t_artists.join().unwrap();
 
             library.populate_artists();
-            library.save_cache(
-                &config::cache_path(CACHE_ARTISTS),
-                &library.artists.read().unwrap(),
-            );
+            if !progress.is_cancelled() {
+                library.save_cache(
+                    &config::cache_path(CACHE_ARTISTS),
+                    &library.artists.read().unwrap(),
+                );
+            }
 
             t_albums.join().unwrap();
             t_playlists.join().unwrap();
             t_shows.join().unwrap();
 
-            let mut is_done = library.is_done.write().unwrap();
-            *is_done = true;
+            if progress.is_cancelled() {
+                info!("library update cancelled");
+            }
+
+            *library.is_done.write().unwrap() = true;
+            library.syncing.store(false, Ordering::SeqCst);
+
+            let callbacks = std::mem::take(&mut *library.loaded_callbacks.lock().unwrap());
+            for callback in callbacks {
+                callback();
+            }
 
             library.ev.trigger();
         });
+        true
     }
 
-    /// Fetch the shows from the web API and save them to the local library.
-    fn fetch_shows(&self) {
+    /// Fetch the shows from the web API and save them to the local library. Returns `false` if
+    /// the update was cancelled.
+    fn fetch_shows(&self, progress: &SyncProgress) -> bool {
         debug!("loading shows");
 
         let mut saved_shows: Vec<Show> = Vec::new();
         let mut shows_result = self.spotify.api.get_saved_shows(0).ok();
 
         while let Some(shows) = shows_result {
+            if progress.is_cancelled() {
+                return false;
+            }
             saved_shows.extend(shows.items.iter().map(|show| (&show.show).into()));
+            progress.report("Podcasts", saved_shows.len(), shows.total as usize);
 
             // load next batch if necessary
             shows_result = match shows.next {
@@ -303,11 +608,13 @@ impl Library {
         }
 
         *self.shows.write().unwrap() = saved_shows;
+        true
     }
 
     /// Fetch the playlists from the web API and save them to the local library. This synchronizes
-    /// the local version with the remote, pruning removed playlists in the process.
-    fn fetch_playlists(&self) {
+    /// the local version with the remote, pruning removed playlists in the process. Returns
+    /// `false` if the update was cancelled, before anything was pruned.
+    fn fetch_playlists(&self, progress: Option<&SyncProgress>) -> bool {
         debug!("loading playlists");
         let mut stale_lists = self.playlists.read().unwrap().clone();
         let mut list_order = Vec::new();
@@ -316,6 +623,9 @@ impl Library {
         let mut lists_batch = Some(lists_page.items.read().unwrap().clone());
         while let Some(lists) = lists_batch {
             for (index, remote) in lists.iter().enumerate() {
+                if progress.is_some_and(SyncProgress::is_cancelled) {
+                    return false;
+                }
                 list_order.push(remote.id.clone());
 
                 // remove from stale playlists so we won't prune it later on
@@ -332,6 +642,9 @@ impl Library {
                     // trigger redraw
                     self.trigger_redraw();
                 }
+                if let Some(progress) = progress {
+                    progress.report("Playlists", list_order.len(), lists_page.total as usize);
+                }
             }
             lists_batch = lists_page.next();
         }
@@ -356,28 +669,36 @@ impl Library {
             let b_index = list_order.iter().position(|x| x == &b.id);
             a_index.cmp(&b_index)
         });
+        self.pin_favorite_playlists();
 
         // trigger redraw
         self.trigger_redraw();
+        true
     }
 
-    /// Fetch the artists from the web API and save them to the local library.
-    fn fetch_artists(&self) {
+    /// Fetch the artists from the web API and save them to the local library. Returns `false` if
+    /// the update was cancelled or failed.
+    fn fetch_artists(&self, progress: &SyncProgress) -> bool {
         let mut artists: Vec<Artist> = Vec::new();
         let mut last: Option<&str> = None;
         let mut i = 0u32;
 
         loop {
+            if progress.is_cancelled() {
+                return false;
+            }
             let page = self.spotify.api.current_user_followed_artists(last);
             debug!("artists page: {}", i);
             i += 1;
             if page.is_err() {
                 error!("Failed to fetch artists.");
-                return;
+                return false;
             }
             let page = page.unwrap();
 
             artists.extend(page.items.iter().map(|fa| fa.into()));
+            let total = page.total.unwrap_or_default() as usize;
+            progress.report("Artists", artists.len(), total);
 
             if page.next.is_some() {
                 last = artists.last().unwrap().id.as_deref();
@@ -399,6 +720,7 @@ impl Library {
 
             store.push(artist);
         }
+        true
     }
 
     /// Add the artist with `id` and `name` to the user library, but don't sync with the API.
@@ -416,12 +738,16 @@ impl Library {
         }
     }
 
-    /// Fetch the albums from the web API and store them in the local library.
-    fn fetch_albums(&self) {
+    /// Fetch the albums from the web API and store them in the local library. Returns `false` if
+    /// the update was cancelled or failed.
+    fn fetch_albums(&self, progress: &SyncProgress) -> bool {
         let mut albums: Vec<Album> = Vec::new();
         let mut i = 0u32;
 
         loop {
+            if progress.is_cancelled() {
+                return false;
+            }
             let page = self
                 .spotify
                 .api
@@ -432,11 +758,12 @@ impl Library {
 
             if page.is_err() {
                 error!("Failed to fetch albums.");
-                return;
+                return false;
             }
 
             let page = page.unwrap();
             albums.extend(page.items.iter().map(|a| a.into()));
+            progress.report("Albums", albums.len(), page.total as usize);
 
             if page.next.is_none() {
                 break;
@@ -457,14 +784,19 @@ impl Library {
         });
 
         *self.albums.write().unwrap() = albums;
+        true
     }
 
-    /// Fetch the tracks from the web API and save them in the local library.
-    fn fetch_tracks(&self) {
+    /// Fetch the tracks from the web API and save them in the local library. Returns `false` if
+    /// the update was cancelled or failed.
+    fn fetch_tracks(&self, progress: &SyncProgress) -> bool {
         let mut tracks = Vec::new();
         let mut i = 0u32;
 
         loop {
+            if progress.is_cancelled() {
+                return false;
+            }
             let page = self
                 .spotify
                 .api
@@ -475,7 +807,7 @@ impl Library {
 
             if page.is_err() {
                 error!("Failed to fetch tracks.");
-                return;
+                return false;
             }
             let page = page.unwrap();
 
@@ -492,11 +824,12 @@ impl Library {
                         .enumerate()
                         .any(|(i, t)| t.track.id.as_ref().map(|id| id.to_string()) != store[i].id)
                 {
-                    return;
+                    return true;
                 }
             }
 
             tracks.extend(page.items.iter().map(|t| t.into()));
+            progress.report("Tracks", tracks.len(), page.total as usize);
 
             if page.next.is_none() {
                 break;
@@ -504,6 +837,7 @@ impl Library {
         }
 
         *self.tracks.write().unwrap() = tracks;
+        true
     }
 
     fn populate_artists(&self) {
@@ -594,7 +928,9 @@ impl Library {
     /// Check whether `track` is saved in the user's library.
     pub fn is_saved_track(&self, track: &Playable) -> bool {
         if !*self.is_done.read().unwrap() {
-            return false;
+            return track
+                .id()
+                .map_or(false, |id| self.looked_up_saved_status(&id));
         }
 
         let tracks = self.tracks.read().unwrap();
@@ -615,6 +951,7 @@ impl Library {
         if save_tracks_result.is_err() {
             return;
         }
+        self.set_saved_status(tracks.iter().filter_map(|t| t.id.as_deref()), true);
 
         {
             let mut store = self.tracks.write().unwrap();
@@ -657,6 +994,7 @@ impl Library {
         {
             return;
         }
+        self.set_saved_status(tracks.iter().filter_map(|t| t.id.as_deref()), false);
 
         {
             let mut store = self.tracks.write().unwrap();
@@ -682,7 +1020,10 @@ impl Library {
     /// Check whether `album` is saved to the user's library.
     pub fn is_saved_album(&self, album: &Album) -> bool {
         if !*self.is_done.read().unwrap() {
-            return false;
+            return album
+                .id
+                .as_ref()
+                .map_or(false, |id| self.looked_up_saved_status(id));
         }
 
         let albums = self.albums.read().unwrap();
@@ -704,6 +1045,7 @@ impl Library {
             {
                 return;
             }
+            self.set_saved_status([album_id.as_str()], true);
         }
 
         {
@@ -737,6 +1079,7 @@ impl Library {
             {
                 return;
             }
+            self.set_saved_status([album_id.as_str()], false);
         }
 
         {
@@ -753,7 +1096,10 @@ impl Library {
     /// Check whether the user follows `artist`.
     pub fn is_followed_artist(&self, artist: &Artist) -> bool {
         if !*self.is_done.read().unwrap() {
-            return false;
+            return artist
+                .id
+                .as_ref()
+                .map_or(false, |id| self.looked_up_saved_status(id));
         }
 
         let artists = self.artists.read().unwrap();
@@ -775,6 +1121,7 @@ impl Library {
             {
                 return;
             }
+            self.set_saved_status([artist_id.as_str()], true);
         }
 
         {
@@ -796,6 +1143,58 @@ impl Library {
         );
     }
 
+    /// Follow all of `artists` that the logged in user doesn't follow yet, in batches. Returns the
+    /// amount of newly followed artists, or an error if one of the batches couldn't be followed.
+    pub fn follow_artists(&self, artists: &[Artist]) -> Result<usize, ()> {
+        if !*self.is_done.read().unwrap() {
+            return Err(());
+        }
+
+        let mut unfollowed: Vec<&Artist> = Vec::new();
+        for artist in artists {
+            if artist.id.is_some()
+                && !self.is_followed_artist(artist)
+                && !unfollowed.iter().any(|a| a.id == artist.id)
+            {
+                unfollowed.push(artist);
+            }
+        }
+
+        let mut followed = 0;
+        let mut result = Ok(());
+        for batch in unfollowed.chunks(FOLLOW_BATCH_SIZE) {
+            let ids = batch.iter().filter_map(|a| a.id.as_deref()).collect();
+            if self.spotify.api.user_follow_artists(ids).is_err() {
+                result = Err(());
+                break;
+            }
+            self.set_saved_status(batch.iter().filter_map(|a| a.id.as_deref()), true);
+
+            let mut store = self.artists.write().unwrap();
+            for artist in batch {
+                if let Some(i) = store.iter().position(|a| a.id == artist.id) {
+                    store[i].is_followed = true;
+                } else {
+                    let mut artist = (*artist).clone();
+                    artist.is_followed = true;
+                    store.push(artist);
+                }
+            }
+            followed += batch.len();
+        }
+
+        if followed > 0 {
+            self.populate_artists();
+
+            self.save_cache(
+                &config::cache_path(CACHE_ARTISTS),
+                &self.artists.read().unwrap(),
+            );
+        }
+
+        result.map(|_| followed)
+    }
+
     /// Unfollow `artist` as the logged in user.
     pub fn unfollow_artist(&self, artist: &Artist) {
         if !*self.is_done.read().unwrap() {
@@ -811,6 +1210,7 @@ impl Library {
             {
                 return;
             }
+            self.set_saved_status([artist_id.as_str()], false);
         }
 
         {
@@ -831,7 +1231,7 @@ impl Library {
     /// Check whether `playlist` is saved in the user's library.
     pub fn is_saved_playlist(&self, playlist: &Playlist) -> bool {
         if !*self.is_done.read().unwrap() {
-            return false;
+            return self.looked_up_saved_status(&playlist.id);
         }
 
         let playlists = self.playlists.read().unwrap();
@@ -840,12 +1240,55 @@ impl Library {
 
     /// Check whether `playlist` is in the library but not created by the library's owner.
     pub fn is_followed_playlist(&self, playlist: &Playlist) -> bool {
-        self.user_id
-            .as_ref()
-            .map(|id| id != &playlist.owner_id)
+        self.user_id()
+            .map(|id| id != playlist.owner_id)
             .unwrap_or(false)
     }
 
+    /// Check whether `playlist` is one of the favorites, which are pinned to the top of the
+    /// playlists.
+    pub fn is_favorite_playlist(&self, playlist: &Playlist) -> bool {
+        self.cfg.state().favorite_playlists.contains(&playlist.id)
+    }
+
+    /// Add `playlist` to the favorites or remove it from them. Returns whether it is a favorite
+    /// now.
+    pub fn toggle_favorite_playlist(&self, playlist: &Playlist) -> bool {
+        let favorite = !self.is_favorite_playlist(playlist);
+        self.cfg.with_state_mut(|state| {
+            if favorite {
+                state.favorite_playlists.push(playlist.id.clone());
+            } else {
+                state.favorite_playlists.retain(|id| *id != playlist.id);
+            }
+        });
+        self.pin_favorite_playlists();
+        favorite
+    }
+
+    /// The favorite playlists that are in the library, in the order they were marked as
+    /// favorites. The `playlist` command opens them by their position.
+    pub fn favorite_playlists(&self) -> Vec<Playlist> {
+        let playlists = self.playlists.read().unwrap();
+        self.cfg
+            .state()
+            .favorite_playlists
+            .iter()
+            .filter_map(|id| playlists.iter().find(|p| p.id == *id).cloned())
+            .collect()
+    }
+
+    /// Move the favorite playlists to the top of the playlists, keeping the order of the others.
+    pub fn pin_favorite_playlists(&self) {
+        let favorites = self.cfg.state().favorite_playlists.clone();
+        self.playlists.write().unwrap().sort_by_key(|p| {
+            favorites
+                .iter()
+                .position(|id| *id == p.id)
+                .unwrap_or(usize::MAX)
+        });
+    }
+
     /// Add `playlist` to the user's library by following it as the logged in user.
     pub fn follow_playlist(&self, mut playlist: Playlist) {
         if !*self.is_done.read().unwrap() {
@@ -857,6 +1300,7 @@ impl Library {
         if follow_playlist_result.is_err() {
             return;
         }
+        self.set_saved_status([playlist.id.as_str()], true);
 
         playlist.load_tracks(&self.spotify);
 
@@ -866,6 +1310,7 @@ impl Library {
                 store.insert(0, playlist);
             }
         }
+        self.pin_favorite_playlists();
 
         self.save_cache(
             &config::cache_path(CACHE_PLAYLISTS),
@@ -911,8 +1356,320 @@ impl Library {
         }
     }
 
+    /// Remember how far `episode` has been listened to. The progress never decreases, so skipping
+    /// back doesn't make a played episode unplayed.
+    pub fn record_episode_progress(&self, episode: &Episode, progress: u32) {
+        let progress = progress.min(episode.duration);
+        self.cfg.with_state_mut(|state| {
+            let stored = state
+                .episode_progress
+                .entry(episode.id.clone())
+                .or_insert(0);
+            *stored = (*stored).max(progress);
+        });
+    }
+
+    /// Check whether `episode` has been listened to almost completely.
+    pub fn is_episode_played(&self, episode: &Episode) -> bool {
+        self.cfg
+            .state()
+            .episode_progress
+            .get(&episode.id)
+            .is_some_and(|&progress| {
+                progress as f32 >= episode.duration as f32 * EPISODE_PLAYED_RATIO
+            })
+    }
+
+    /// Mark `episode` as completely played or not played at all.
+    pub fn set_episode_played(&self, episode: &Episode, played: bool) {
+        self.cfg.with_state_mut(|state| {
+            if played {
+                state
+                    .episode_progress
+                    .insert(episode.id.clone(), episode.duration);
+            } else {
+                state.episode_progress.remove(&episode.id);
+            }
+        });
+    }
+
+    /// Look up whether `tracks` are saved through the API, so their status can be shown before the
+    /// library is loaded. Does nothing once the library is loaded, as it is authoritative then.
+    pub fn look_up_saved_tracks(&self, tracks: &[Track]) {
+        let ids: Vec<&str> = tracks.iter().filter_map(|t| t.id.as_deref()).collect();
+        self.look_up_saved_status(&ids, |ids| self.spotify.api.saved_tracks_contain(ids));
+    }
+
+    /// Look up whether `albums` are saved through the API, see [Self::look_up_saved_tracks].
+    pub fn look_up_saved_albums(&self, albums: &[Album]) {
+        let ids: Vec<&str> = albums.iter().filter_map(|a| a.id.as_deref()).collect();
+        self.look_up_saved_status(&ids, |ids| self.spotify.api.saved_albums_contain(ids));
+    }
+
+    /// Look up whether `artists` are followed through the API, see [Self::look_up_saved_tracks].
+    pub fn look_up_followed_artists(&self, artists: &[Artist]) {
+        let ids: Vec<&str> = artists.iter().filter_map(|a| a.id.as_deref()).collect();
+        self.look_up_saved_status(&ids, |ids| self.spotify.api.followed_artists_contain(ids));
+    }
+
+    /// Look up whether `playlists` are followed through the API, see [Self::look_up_saved_tracks].
+    /// The API can only check one playlist per request, so the user's playlists are listed a page
+    /// at a time instead, until all of `playlists` were found. Playlists that were looked up before
+    /// are skipped.
+    pub fn look_up_followed_playlists(&self, playlists: &[Playlist]) {
+        let ids: Vec<&str> = {
+            let status = self.saved_status.read().unwrap();
+            playlists
+                .iter()
+                .map(|p| p.id.as_str())
+                .filter(|id| !status.contains_key(*id))
+                .collect()
+        };
+        self.look_up_saved_status(&ids, |ids| {
+            let mut missing: HashSet<&str> = ids.iter().copied().collect();
+            let mut followed = Vec::with_capacity(ids.len());
+
+            let lists_page = self.spotify.api.current_user_playlist();
+            let mut lists_batch = Some(lists_page.items.read().unwrap().clone());
+            while let Some(lists) = lists_batch {
+                for list in lists {
+                    if missing.remove(list.id.as_str()) {
+                        followed.push((list.id, true));
+                    }
+                }
+                if missing.is_empty() {
+                    break;
+                }
+                lists_batch = lists_page.next();
+                if lists_batch.is_none() && !lists_page.at_end() {
+                    return Err(());
+                }
+            }
+
+            followed.extend(missing.into_iter().map(|id| (id.to_string(), false)));
+            Ok(followed)
+        });
+    }
+
+    fn look_up_saved_status<F>(&self, ids: &[&str], contains: F)
+    where
+        F: Fn(&[&str]) -> Result<Vec<(String, bool)>, ()>,
+    {
+        if *self.is_done.read().unwrap() || ids.is_empty() {
+            return;
+        }
+
+        match contains(ids) {
+            Ok(saved) => {
+                let mut status = self.saved_status.write().unwrap();
+                status.extend(saved);
+                self.trigger_redraw();
+            }
+            Err(_) => error!("could not look up saved status"),
+        }
+    }
+
+    /// Remember the new status of the items with the given `ids` after they were saved or removed,
+    /// so it stays correct while the library is reloaded.
+    fn set_saved_status<'a>(&self, ids: impl IntoIterator<Item = &'a str>, saved: bool) {
+        let mut status = self.saved_status.write().unwrap();
+        status.extend(ids.into_iter().map(|id| (id.to_string(), saved)));
+    }
+
+    fn looked_up_saved_status(&self, id: &str) -> bool {
+        self.saved_status
+            .read()
+            .unwrap()
+            .get(id)
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// Get the audio features of the tracks with the given `track_ids`. Only features that haven't
+    /// been looked up before are fetched from the API.
+    pub fn audio_features(&self, track_ids: &[&str]) -> HashMap<String, AudioFeatures> {
+        let missing: Vec<&str> = {
+            let cache = self.audio_features.read().unwrap();
+            track_ids
+                .iter()
+                .filter(|id| !cache.contains_key(**id))
+                .copied()
+                .collect()
+        };
+
+        if !missing.is_empty() {
+            match self.spotify.api.audio_features(&missing) {
+                Ok(fetched) => {
+                    let mut cache = self.audio_features.write().unwrap();
+                    cache.extend(fetched.into_iter().map(|f| (f.id.clone(), f)));
+                }
+                Err(_) => error!("could not fetch audio features"),
+            }
+        }
+
+        let cache = self.audio_features.read().unwrap();
+        track_ids
+            .iter()
+            .filter_map(|id| cache.get(*id).map(|f| (id.to_string(), f.clone())))
+            .collect()
+    }
+
     /// Force redraw the user interface.
     pub fn trigger_redraw(&self) {
         self.ev.trigger();
     }
+
+    /// Run `callback` in the thread of the user interface.
+    pub fn run_in_ui(&self, callback: impl FnOnce(&mut Cursive) + Send + 'static) {
+        self.ev.run_in_ui(callback);
+    }
+}
+
+/// The result of checking a cache file with [verify_caches].
+pub enum CacheCheck {
+    Missing,
+    /// The file could be read and holds the given amount of items.
+    Valid(usize),
+    /// The file couldn't be read for the given reason and was left in place.
+    Unreadable(String),
+    /// The file couldn't be parsed for the given reason and was moved to the path.
+    Quarantined(PathBuf, String),
+    /// The file couldn't be parsed for the first reason, nor moved aside for the second one.
+    Corrupt(String, String),
+}
+
+/// Check whether the cached library lists can be read, and move the ones that can't aside so they
+/// are fetched again on the next start.
+pub fn verify_caches() -> Vec<(&'static str, CacheCheck)> {
+    vec![
+        (CACHE_TRACKS, check_cache::<Track>(CACHE_TRACKS)),
+        (CACHE_ALBUMS, check_cache::<Album>(CACHE_ALBUMS)),
+        (CACHE_ARTISTS, check_cache::<Artist>(CACHE_ARTISTS)),
+        (CACHE_PLAYLISTS, check_cache::<Playlist>(CACHE_PLAYLISTS)),
+    ]
+}
+
+fn check_cache<T: DeserializeOwned>(file: &str) -> CacheCheck {
+    let path = config::cache_path(file);
+    match read_cache::<T>(&path) {
+        Ok(None) => CacheCheck::Missing,
+        Ok(Some(items)) => CacheCheck::Valid(items.len()),
+        Err(CacheError::Unreadable(e)) => CacheCheck::Unreadable(e),
+        Err(CacheError::Corrupt(e)) => match quarantine(&path) {
+            Ok(quarantined) => CacheCheck::Quarantined(quarantined, e),
+            Err(move_error) => CacheCheck::Corrupt(e, move_error),
+        },
+    }
+}
+
+/// The reason a cache file couldn't be read by [read_cache].
+enum CacheError {
+    /// The file couldn't be read, e.g. for lack of permissions.
+    Unreadable(String),
+    /// The file could be read, but not parsed.
+    Corrupt(String),
+}
+
+/// Read the list cached in the file at `cache_path`, or `None` if there is no such file. Fails if
+/// the file can't be parsed, e.g. because writing it was interrupted.
+fn read_cache<T: DeserializeOwned>(cache_path: &Path) -> Result<Option<Vec<T>>, CacheError> {
+    let contents = match std::fs::read_to_string(cache_path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        // Invalid UTF-8 means the file is corrupt, anything else that it can't be accessed.
+        Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+            return Err(CacheError::Corrupt(e.to_string()))
+        }
+        Err(e) => return Err(CacheError::Unreadable(e.to_string())),
+    };
+    // Parse from in-memory string instead of directly from the file because it's faster.
+    serde_json::from_str(&contents)
+        .map(Some)
+        .map_err(|e| CacheError::Corrupt(e.to_string()))
+}
+
+/// Read the bookmarks stored in the cache directory.
+fn load_bookmarks() -> HashMap<String, Vec<Bookmark>> {
+    let path = config::cache_path(CACHE_BOOKMARKS);
+    let Ok(contents) = std::fs::read_to_string(&path) else {
+        return HashMap::new();
+    };
+    serde_json::from_str(&contents)
+        .map_err(|e| error!("can't parse bookmarks: {}", e))
+        .unwrap_or_default()
+}
+
+/// Store `bookmarks` in the cache directory.
+fn save_bookmarks(bookmarks: &HashMap<String, Vec<Bookmark>>) {
+    let result = write_atomic(config::cache_path(CACHE_BOOKMARKS), |writer| {
+        serde_json::to_writer(writer, bookmarks).map_err(|e| e.to_string())
+    });
+    if let Err(e) = result {
+        error!("could not save bookmarks: {}", e);
+    }
+}
+
+/// Move the cache file at `cache_path` aside, replacing earlier corrupt versions of it, and return
+/// its new path.
+fn quarantine(cache_path: &Path) -> Result<PathBuf, String> {
+    let mut quarantined = cache_path.as_os_str().to_owned();
+    quarantined.push(QUARANTINE_SUFFIX);
+    let quarantined = PathBuf::from(quarantined);
+    std::fs::rename(cache_path, &quarantined).map_err(|e| e.to_string())?;
+    Ok(quarantined)
+}
+
+/// The progress of a library update, reported per category in the details of its task.
+struct SyncProgress {
+    task: Task,
+    /// The category names along with the amount of fetched items and their total amount.
+    categories: RwLock<Vec<(&'static str, usize, usize)>>,
+}
+
+impl SyncProgress {
+    /// The amount of categories that are updated: tracks, albums, artists, playlists and podcasts.
+    const CATEGORIES: usize = 5;
+
+    fn new(task: Task) -> Self {
+        Self {
+            task,
+            categories: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Report that `done` of `total` items of `category` were fetched.
+    fn report(&self, category: &'static str, done: usize, total: usize) {
+        let mut categories = self.categories.write().unwrap();
+        match categories.iter_mut().find(|(name, ..)| *name == category) {
+            Some(entry) => *entry = (category, done, total),
+            None => categories.push((category, done, total)),
+        }
+
+        let percentages: Vec<(&'static str, usize)> = categories
+            .iter()
+            .map(|(name, done, total)| match total {
+                0 => (*name, 100),
+                total => (*name, (done * 100 / total).min(100)),
+            })
+            .collect();
+        let detail = percentages
+            .iter()
+            .filter(|(_, percentage)| *percentage < 100)
+            .map(|(name, percentage)| format!("{} {percentage}%", tr(*name)))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        let done = percentages.iter().map(|(_, percentage)| percentage).sum();
+        self.task.set_progress(done, Self::CATEGORIES * 100);
+        self.task.set_detail(detail);
+    }
+
+    /// Report that `category` is up to date, whether it was fetched or loaded from the cache.
+    fn finish(&self, category: &'static str) {
+        self.report(category, 1, 1);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.task.is_cancelled()
+    }
 }
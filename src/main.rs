@@ -9,7 +9,9 @@ use application::{setup_logging, Application};
 use config::set_configuration_base_path;
 use log::error;
 use ncspot::program_arguments;
+use spotify_url::SpotifyUrl;
 
+mod api_cache;
 mod application;
 mod authentication;
 mod cli;
@@ -17,28 +19,43 @@ mod command;
 mod commands;
 mod config;
 mod events;
+mod exec;
 mod ext_traits;
+mod i18n;
 mod library;
 mod model;
 mod panic;
 mod queue;
+mod queue_import;
 mod serialization;
 mod sharing;
 mod spotify;
 mod spotify_api;
 mod spotify_url;
 mod spotify_worker;
+mod status_file;
+mod tasks;
 mod theme;
 mod traits;
 mod ui;
 mod utils;
+mod wellbeing;
 
 #[cfg(unix)]
 mod ipc;
 
+#[cfg(feature = "inhibit")]
+mod inhibit;
+
 #[cfg(feature = "mpris")]
 mod mpris;
 
+#[cfg(feature = "tray")]
+mod tray;
+
+#[cfg(feature = "inhibit")]
+mod sleep;
+
 fn main() -> Result<(), String> {
     // Set a custom backtrace hook that writes the backtrace to a file instead of stdout, since
     // stdout is most likely in use by Cursive.
@@ -56,13 +73,30 @@ fn main() -> Result<(), String> {
     // path.
     set_configuration_base_path(matches.get_one::<PathBuf>("basepath").cloned());
 
+    // Subcommands don't load the whole configuration, but use the directories configured in it.
+    if matches.subcommand().is_some() {
+        config::load_directories(matches.get_one::<String>("config").cloned());
+    }
+
     match matches.subcommand() {
         Some(("info", _subcommand_matches)) => cli::info(),
+        Some(("cache", subcommand_matches)) => match subcommand_matches.subcommand() {
+            Some(("verify", _)) => cli::verify_cache(),
+            _ => unreachable!(),
+        },
         Some((_, _)) => unreachable!(),
         None => {
+            let url = match matches.get_one::<String>("uri") {
+                Some(uri) => Some(
+                    SpotifyUrl::from_uri_or_url(uri)
+                        .ok_or_else(|| format!("Invalid Spotify URI or URL: {uri}"))?,
+                ),
+                None => None,
+            };
+
             // Create the application.
             let mut application =
-                match Application::new(matches.get_one::<String>("config").cloned()) {
+                match Application::new(matches.get_one::<String>("config").cloned(), url) {
                     Ok(application) => application,
                     Err(error) => {
                         eprintln!("{error}");
@@ -1,7 +1,7 @@
 use rand::{seq::IteratorRandom, thread_rng};
 use rspotify::model::Id;
 use std::fmt;
-use std::sync::{Arc, RwLock};
+use std::sync::Arc;
 
 use chrono::{DateTime, Utc};
 use log::debug;
@@ -9,12 +9,13 @@ use rspotify::model::album::{FullAlbum, SavedAlbum, SimplifiedAlbum};
 
 use crate::library::Library;
 use crate::model::artist::Artist;
-use crate::model::playable::Playable;
+use crate::model::playable::{Playable, PlayableSource};
 use crate::model::track::Track;
 use crate::queue::Queue;
-use crate::spotify::Spotify;
+use crate::spotify::{Spotify, UriType};
 use crate::traits::{IntoBoxedViewExt, ListItem, ViewExt};
-use crate::ui::{album::AlbumView, listview::ListView};
+use crate::ui::album::AlbumView;
+use crate::ui::recommendations::{RecommendationSeed, RecommendationsView, MAX_SEEDS};
 
 #[derive(Clone, Deserialize, Serialize)]
 pub struct Album {
@@ -69,6 +70,29 @@ impl Album {
             self.tracks = Some(collected_tracks);
         }
     }
+
+    /// The [PlayableSource] attached to tracks that are queued from this album.
+    pub fn as_source(&self) -> Option<PlayableSource> {
+        self.id.as_ref().map(|id| PlayableSource {
+            uri_type: UriType::Album,
+            id: id.clone(),
+            name: self.title.clone(),
+        })
+    }
+
+    /// The tracks of this album as [Playable] items that remember they came from this album.
+    pub fn playables(&self) -> Vec<Playable> {
+        let source = self.as_source();
+        self.tracks
+            .iter()
+            .flatten()
+            .map(|track| {
+                let mut track = track.clone();
+                track.source.clone_from(&source);
+                Playable::Track(track)
+            })
+            .collect()
+    }
 }
 
 impl From<&SimplifiedAlbum> for Album {
@@ -192,12 +216,8 @@ impl ListItem for Album {
     fn play(&mut self, queue: &Queue) {
         self.load_all_tracks(queue.get_spotify());
 
-        if let Some(tracks) = self.tracks.as_ref() {
-            let tracks: Vec<Playable> = tracks
-                .iter()
-                .map(|track| Playable::Track(track.clone()))
-                .collect();
-            let index = queue.append_next(&tracks);
+        if self.tracks.is_some() {
+            let index = queue.append_next(&self.playables());
             queue.play(index, true, true);
         }
     }
@@ -205,20 +225,16 @@ impl ListItem for Album {
     fn play_next(&mut self, queue: &Queue) {
         self.load_all_tracks(queue.get_spotify());
 
-        if let Some(tracks) = self.tracks.as_ref() {
-            for t in tracks.iter().rev() {
-                queue.insert_after_current(Playable::Track(t.clone()));
-            }
+        for t in self.playables().into_iter().rev() {
+            queue.insert_after_current(t);
         }
     }
 
     fn queue(&mut self, queue: &Queue) {
         self.load_all_tracks(queue.get_spotify());
 
-        if let Some(tracks) = self.tracks.as_ref() {
-            for t in tracks {
-                queue.append(Playable::Track(t.clone()));
-            }
+        for t in self.playables() {
+            queue.append(t);
         }
     }
 
@@ -248,46 +264,39 @@ impl ListItem for Album {
         library: Arc<Library>,
     ) -> Option<Box<dyn ViewExt>> {
         self.load_all_tracks(queue.get_spotify());
-        const MAX_SEEDS: usize = 5;
-        let track_ids: Vec<&str> = self
+        let mut seeds: Vec<RecommendationSeed> = self
             .tracks
             .as_ref()?
             .iter()
-            .filter_map(|t| t.id.as_deref())
+            .filter_map(|t| {
+                t.id.clone().map(|id| RecommendationSeed::Track {
+                    id,
+                    name: t.title.clone(),
+                })
+            })
             // spotify allows at max 5 seed items, so choose 4 random tracks...
             .choose_multiple(&mut thread_rng(), MAX_SEEDS - 1);
 
-        let artist_id: Option<String> = self
+        let artist = self
             .artist_ids
             .iter()
-            .cloned()
+            .zip(self.artists.iter())
             // ...and one artist
             .choose(&mut thread_rng());
 
-        if track_ids.is_empty() && artist_id.is_some() {
+        if seeds.is_empty() && artist.is_some() {
             return None;
         }
 
-        let spotify = queue.get_spotify();
-        let recommendations: Option<Vec<Track>> = spotify
-            .api
-            .recommendations(
-                artist_id.as_ref().map(|aid| vec![aid.as_str()]),
-                None,
-                Some(track_ids),
-            )
-            .ok()
-            .map(|r| r.tracks)
-            .map(|tracks| tracks.iter().map(Track::from).collect());
-        recommendations.map(|tracks| {
-            ListView::new(
-                Arc::new(RwLock::new(tracks)),
-                queue.clone(),
-                library.clone(),
-            )
-            .with_title(&format!("Similar to Album \"{}\"", self.title))
-            .into_boxed_view_ext()
-        })
+        if let Some((id, name)) = artist {
+            seeds.push(RecommendationSeed::Artist {
+                id: id.clone(),
+                name: name.clone(),
+            });
+        }
+
+        let title = format!("Similar to Album \"{}\"", self.title);
+        Some(RecommendationsView::new(queue, library, title, seeds).into_boxed_view_ext())
     }
 
     fn share_url(&self) -> Option<String> {
@@ -296,6 +305,14 @@ impl ListItem for Album {
             .map(|id| format!("https://open.spotify.com/album/{id}"))
     }
 
+    fn cover_url(&self) -> Option<String> {
+        self.cover_url.clone()
+    }
+
+    fn metadata(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+
     fn artists(&self) -> Option<Vec<Artist>> {
         Some(
             self.artist_ids
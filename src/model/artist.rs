@@ -1,16 +1,19 @@
 use std::fmt;
-use std::sync::{Arc, RwLock};
+use std::sync::Arc;
 
 use rspotify::model::artist::{FullArtist, SimplifiedArtist};
-use rspotify::model::Id;
+use rspotify::model::{AlbumType, Id};
 
 use crate::library::Library;
+use crate::model::album::Album;
 use crate::model::playable::Playable;
 use crate::model::track::Track;
 use crate::queue::Queue;
 use crate::spotify::Spotify;
+use crate::spotify_api::BATCH_TOKEN_VALIDITY;
 use crate::traits::{IntoBoxedViewExt, ListItem, ViewExt};
-use crate::ui::{artist::ArtistView, listview::ListView};
+use crate::ui::artist::ArtistView;
+use crate::ui::recommendations::{RecommendationSeed, RecommendationsView};
 
 #[derive(Clone, Deserialize, Serialize)]
 pub struct Artist {
@@ -39,6 +42,62 @@ impl Artist {
             }
         }
     }
+
+    /// Replace the queue with the top tracks of this artist and play them. Returns `false` if
+    /// they couldn't be loaded.
+    pub fn play_top_tracks(&mut self, queue: &Queue) -> bool {
+        self.load_top_tracks(queue.get_spotify());
+        let Some(tracks) = self.tracks.as_ref().filter(|tracks| !tracks.is_empty()) else {
+            return false;
+        };
+
+        queue.clear();
+        for track in tracks {
+            queue.append(Playable::Track(track.clone()));
+        }
+        queue.play(0, true, false);
+        true
+    }
+
+    /// Append the tracks of all albums and singles of this artist to the queue, oldest release
+    /// first. As this may take many API calls, `progress` is called with the amount of processed
+    /// albums and the total amount of albums after each album, and stops the queueing by returning
+    /// `false`. Returns the amount of queued tracks.
+    pub fn queue_discography(
+        &self,
+        queue: &Queue,
+        progress: impl Fn(usize, usize) -> bool,
+    ) -> usize {
+        let Some(ref artist_id) = self.id else {
+            return 0;
+        };
+        let spotify = queue.get_spotify();
+        spotify.api.ensure_token(BATCH_TOKEN_VALIDITY);
+
+        let mut albums: Vec<Album> = Vec::new();
+        for album_type in [AlbumType::Album, AlbumType::Single] {
+            let result = spotify.api.artist_albums(artist_id, Some(album_type));
+            while !result.at_end() && result.next().is_some() {}
+            albums.extend(result.items.read().unwrap().iter().cloned());
+        }
+        // The API returns the newest releases first.
+        albums.reverse();
+        albums.sort_by(|a, b| a.year.cmp(&b.year));
+
+        let total = albums.len();
+        let mut queued = 0;
+        for (index, album) in albums.iter_mut().enumerate() {
+            album.load_all_tracks(spotify.clone());
+            for playable in album.playables() {
+                queue.append(playable);
+                queued += 1;
+            }
+            if !progress(index + 1, total) {
+                break;
+            }
+        }
+        queued
+    }
 }
 
 impl From<&SimplifiedArtist> for Artist {
@@ -176,25 +235,12 @@ impl ListItem for Artist {
         queue: Arc<Queue>,
         library: Arc<Library>,
     ) -> Option<Box<dyn ViewExt>> {
-        let id = self.id.as_ref()?.to_string();
-
-        let spotify = queue.get_spotify();
-        let recommendations: Option<Vec<Track>> = spotify
-            .api
-            .recommendations(Some(vec![&id]), None, None)
-            .ok()
-            .map(|r| r.tracks)
-            .map(|tracks| tracks.iter().map(Track::from).collect());
-
-        recommendations.map(|tracks| {
-            ListView::new(
-                Arc::new(RwLock::new(tracks)),
-                queue.clone(),
-                library.clone(),
-            )
-            .with_title(&format!("Similar to Artist \"{}\"", self.name))
-            .into_boxed_view_ext()
-        })
+        let seed = RecommendationSeed::Artist {
+            id: self.id.clone()?,
+            name: self.name.clone(),
+        };
+        let title = format!("Similar to Artist \"{}\"", self.name);
+        Some(RecommendationsView::new(queue, library, title, vec![seed]).into_boxed_view_ext())
     }
 
     fn share_url(&self) -> Option<String> {
@@ -203,6 +249,14 @@ impl ListItem for Artist {
             .map(|id| format!("https://open.spotify.com/artist/{id}"))
     }
 
+    fn metadata(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+
+    fn artists(&self) -> Option<Vec<Artist>> {
+        Some(vec![self.clone()])
+    }
+
     #[inline]
     fn is_saved(&self, library: &Library) -> Option<bool> {
         Some(library.is_followed_artist(self))
@@ -0,0 +1,133 @@
+use rspotify::model::AudioAnalysis as RspotifyAudioAnalysis;
+
+/// A part of a track with a similar sound, like a verse or a chorus.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Section {
+    /// Start of the section in milliseconds.
+    pub start: u32,
+    /// End of the section in milliseconds.
+    pub end: u32,
+    /// Average loudness in dB.
+    pub loudness: f32,
+}
+
+/// The structure of a single track, as analyzed by Spotify. Positions are in milliseconds.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct AudioAnalysis {
+    pub duration: u32,
+    /// Average loudness of the whole track in dB.
+    pub loudness: f32,
+    pub end_of_fade_in: u32,
+    pub start_of_fade_out: u32,
+    pub sections: Vec<Section>,
+}
+
+impl AudioAnalysis {
+    /// Where the quiet intro of the track ends. Without a `threshold` this is the end of the fade
+    /// in, otherwise the leading sections that are at least `threshold` dB quieter than the whole
+    /// track are part of the intro as well.
+    pub fn intro_end(&self, threshold: Option<f32>) -> u32 {
+        let quiet = self.quiet_sections(self.sections.iter(), threshold);
+        quiet.fold(self.end_of_fade_in, |end, section| end.max(section.end))
+    }
+
+    /// Where the quiet outro of the track starts, like [AudioAnalysis::intro_end] but for the
+    /// fade out and the trailing sections.
+    pub fn outro_start(&self, threshold: Option<f32>) -> u32 {
+        let quiet = self.quiet_sections(self.sections.iter().rev(), threshold);
+        quiet.fold(self.start_of_fade_out, |start, section| {
+            start.min(section.start)
+        })
+    }
+
+    fn quiet_sections<'a>(
+        &self,
+        sections: impl Iterator<Item = &'a Section>,
+        threshold: Option<f32>,
+    ) -> impl Iterator<Item = &'a Section> {
+        let limit = threshold.map(|threshold| self.loudness - threshold);
+        sections.take_while(move |section| limit.is_some_and(|limit| section.loudness <= limit))
+    }
+}
+
+impl From<&RspotifyAudioAnalysis> for AudioAnalysis {
+    fn from(analysis: &RspotifyAudioAnalysis) -> Self {
+        let ms = |seconds: f32| (seconds.max(0.0) * 1000.0) as u32;
+        Self {
+            duration: ms(analysis.track.duration),
+            loudness: analysis.track.loudness,
+            end_of_fade_in: ms(analysis.track.end_of_fade_in),
+            start_of_fade_out: ms(analysis.track.start_of_fade_out),
+            sections: analysis
+                .sections
+                .iter()
+                .map(|section| Section {
+                    start: ms(section.time_interval.start),
+                    end: ms(section.time_interval.start + section.time_interval.duration),
+                    loudness: section.loudness,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SkipAggressiveness;
+
+    fn section(start: u32, end: u32, loudness: f32) -> Section {
+        Section {
+            start,
+            end,
+            loudness,
+        }
+    }
+
+    /// A track with a very quiet and a slightly quiet section at either end.
+    fn analysis() -> AudioAnalysis {
+        AudioAnalysis {
+            duration: 200_000,
+            loudness: -10.0,
+            end_of_fade_in: 2_000,
+            start_of_fade_out: 195_000,
+            sections: vec![
+                section(0, 10_000, -20.0),
+                section(10_000, 30_000, -14.0),
+                section(30_000, 170_000, -8.0),
+                section(170_000, 185_000, -14.0),
+                section(185_000, 200_000, -18.0),
+            ],
+        }
+    }
+
+    #[test]
+    fn gentle_skips_only_the_fades() {
+        let threshold = SkipAggressiveness::Gentle.threshold();
+        assert_eq!(analysis().intro_end(threshold), 2_000);
+        assert_eq!(analysis().outro_start(threshold), 195_000);
+    }
+
+    #[test]
+    fn moderate_skips_clearly_quieter_sections() {
+        let threshold = SkipAggressiveness::Moderate.threshold();
+        assert_eq!(analysis().intro_end(threshold), 10_000);
+        assert_eq!(analysis().outro_start(threshold), 185_000);
+    }
+
+    #[test]
+    fn aggressive_skips_slightly_quieter_sections() {
+        let threshold = SkipAggressiveness::Aggressive.threshold();
+        assert_eq!(analysis().intro_end(threshold), 30_000);
+        assert_eq!(analysis().outro_start(threshold), 170_000);
+    }
+
+    #[test]
+    fn loud_edges_keep_the_fades() {
+        let mut analysis = analysis();
+        analysis.sections = vec![section(0, 200_000, -9.0)];
+        let threshold = SkipAggressiveness::Aggressive.threshold();
+        assert_eq!(analysis.intro_end(threshold), 2_000);
+        assert_eq!(analysis.outro_start(threshold), 195_000);
+    }
+}
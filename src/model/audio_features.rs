@@ -0,0 +1,87 @@
+use std::fmt;
+
+use rspotify::model::{AudioFeatures as RspotifyAudioFeatures, Id, Modality};
+
+/// Pitch class names in the order used by the Spotify API, starting at C = 0.
+const PITCH_CLASSES: [&str; 12] = [
+    "C", "C♯", "D", "D♯", "E", "F", "F♯", "G", "G♯", "A", "A♯", "B",
+];
+
+/// Audio analysis attributes of a single track, as computed by Spotify.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct AudioFeatures {
+    pub id: String,
+    /// Tempo in beats per minute.
+    pub tempo: f32,
+    /// Pitch class of the track, if one could be detected.
+    pub key: Option<u8>,
+    pub minor: bool,
+    /// Perceived intensity between 0.0 and 1.0.
+    pub energy: f32,
+    pub danceability: f32,
+    pub valence: f32,
+}
+
+impl AudioFeatures {
+    /// The musical key of the track, e.g. `F♯ minor`.
+    pub fn key_name(&self) -> Option<String> {
+        let pitch = PITCH_CLASSES.get(self.key? as usize)?;
+        let mode = if self.minor { "minor" } else { "major" };
+        Some(format!("{pitch} {mode}"))
+    }
+
+    /// The position of the key on the Camelot wheel (1-12), where neighbouring positions are a
+    /// fifth apart.
+    fn camelot(&self) -> Option<u8> {
+        let offset = if self.minor { 4 } else { 7 };
+        Some((self.key? * 7 + offset) % 12 + 1)
+    }
+
+    /// How well a transition from this track to `next` would mix, lower is better. Combines the
+    /// distance of the keys on the Camelot wheel with the tempo difference in percent, where half
+    /// and double time count as the same tempo.
+    pub fn mix_distance(&self, next: &Self) -> f32 {
+        let key_distance = match (self.camelot(), next.camelot()) {
+            (Some(a), Some(b)) => {
+                let steps = a.abs_diff(b).min(12 - a.abs_diff(b));
+                f32::from(steps) + if self.minor == next.minor { 0.0 } else { 1.0 }
+            }
+            _ => 3.0,
+        };
+
+        let tempo_distance = [next.tempo, next.tempo * 2.0, next.tempo / 2.0]
+            .iter()
+            .map(|tempo| (self.tempo - tempo).abs() / self.tempo.max(1.0) * 100.0)
+            .fold(f32::INFINITY, f32::min);
+
+        key_distance + tempo_distance / 2.0
+    }
+}
+
+impl fmt::Display for AudioFeatures {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "BPM: {:.0}", self.tempo)?;
+        writeln!(
+            f,
+            "Key: {}",
+            self.key_name().unwrap_or_else(|| "unknown".to_string())
+        )?;
+        writeln!(f, "Energy: {:.0}%", self.energy * 100.0)?;
+        writeln!(f, "Danceability: {:.0}%", self.danceability * 100.0)?;
+        write!(f, "Valence: {:.0}%", self.valence * 100.0)
+    }
+}
+
+impl From<&RspotifyAudioFeatures> for AudioFeatures {
+    fn from(features: &RspotifyAudioFeatures) -> Self {
+        Self {
+            id: features.id.id().to_string(),
+            tempo: features.tempo,
+            key: u8::try_from(features.key).ok(),
+            minor: matches!(features.mode, Modality::Minor),
+            energy: features.energy,
+            danceability: features.danceability,
+            valence: features.valence,
+        }
+    }
+}
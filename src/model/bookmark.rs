@@ -0,0 +1,7 @@
+/// A named position inside a track or episode, set with `mark add`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Bookmark {
+    pub name: String,
+    /// The position in milliseconds.
+    pub position: u32,
+}
@@ -62,6 +62,10 @@ impl ListItem for Category {
         Some(format!("https://open.spotify.com/genre/{}", self.id))
     }
 
+    fn metadata(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+
     fn as_listitem(&self) -> Box<dyn ListItem> {
         Box::new(self.clone())
     }
@@ -1,5 +1,5 @@
 use crate::library::Library;
-use crate::model::playable::Playable;
+use crate::model::playable::{Playable, PlayableSource};
 use crate::queue::Queue;
 use crate::traits::{ListItem, ViewExt};
 use crate::utils::ms_to_hms;
@@ -20,6 +20,10 @@ pub struct Episode {
     pub cover_url: Option<String>,
     pub added_at: Option<DateTime<Utc>>,
     pub list_index: usize,
+    #[serde(default)]
+    pub source: Option<PlayableSource>,
+    #[serde(default)]
+    pub explicit: bool,
 }
 
 impl Episode {
@@ -40,6 +44,8 @@ impl From<&SimplifiedEpisode> for Episode {
             cover_url: episode.images.first().map(|img| img.url.clone()),
             added_at: None,
             list_index: 0,
+            source: None,
+            explicit: episode.explicit,
         }
     }
 }
@@ -56,6 +62,8 @@ impl From<&FullEpisode> for Episode {
             cover_url: episode.images.first().map(|img| img.url.clone()),
             added_at: None,
             list_index: 0,
+            source: None,
+            explicit: episode.explicit,
         }
     }
 }
@@ -78,8 +86,13 @@ impl ListItem for Episode {
         self.name.clone()
     }
 
-    fn display_right(&self, _library: &Library) -> String {
-        format!("{} [{}]", self.duration_str(), self.release_date)
+    fn display_right(&self, library: &Library) -> String {
+        let played = if library.is_episode_played(self) {
+            "✓ "
+        } else {
+            ""
+        };
+        format!("{played}{} [{}]", self.duration_str(), self.release_date)
     }
 
     fn play(&mut self, queue: &Queue) {
@@ -109,11 +122,23 @@ impl ListItem for Episode {
         Some(format!("https://open.spotify.com/episode/{}", self.id))
     }
 
+    fn cover_url(&self) -> Option<String> {
+        self.cover_url.clone()
+    }
+
+    fn metadata(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+
     #[inline]
     fn is_playable(&self) -> bool {
         true
     }
 
+    fn episode(&self) -> Option<Self> {
+        Some(self.clone())
+    }
+
     fn as_listitem(&self) -> Box<dyn ListItem> {
         Box::new(self.clone())
     }
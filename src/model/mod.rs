@@ -1,8 +1,12 @@
 pub mod album;
 pub mod artist;
+pub mod audio_analysis;
+pub mod audio_features;
+pub mod bookmark;
 pub mod category;
 pub mod episode;
 pub mod playable;
 pub mod playlist;
 pub mod show;
+pub mod snapshot;
 pub mod track;
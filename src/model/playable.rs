@@ -7,11 +7,20 @@ use crate::model::artist::Artist;
 use crate::model::episode::Episode;
 use crate::model::track::Track;
 use crate::queue::Queue;
+use crate::spotify::UriType;
 use crate::traits::{ListItem, ViewExt};
 use crate::utils::ms_to_hms;
 use std::fmt;
 use std::sync::Arc;
 
+/// The album or playlist a [Playable] was queued from.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct PlayableSource {
+    pub uri_type: UriType,
+    pub id: String,
+    pub name: String,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(tag = "type")]
 pub enum Playable {
@@ -76,6 +85,13 @@ impl Playable {
                 },
             )
             .replace("%duration", playable.duration_str().as_str())
+            .replace(
+                "%source",
+                playable
+                    .source()
+                    .map(|source| source.name.as_str())
+                    .unwrap_or_default(),
+            )
     }
 
     pub fn id(&self) -> Option<String> {
@@ -106,6 +122,13 @@ impl Playable {
         }
     }
 
+    pub fn is_explicit(&self) -> bool {
+        match self {
+            Self::Track(track) => track.explicit,
+            Self::Episode(episode) => episode.explicit,
+        }
+    }
+
     pub fn list_index(&self) -> usize {
         match self {
             Self::Track(track) => track.list_index,
@@ -120,6 +143,13 @@ impl Playable {
         }
     }
 
+    pub fn added_at(&self) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Track(track) => track.added_at,
+            Self::Episode(episode) => episode.added_at,
+        }
+    }
+
     pub fn set_added_at(&mut self, added_at: Option<DateTime<Utc>>) {
         match self {
             Self::Track(track) => track.added_at = added_at,
@@ -127,6 +157,27 @@ impl Playable {
         }
     }
 
+    pub fn source(&self) -> Option<&PlayableSource> {
+        match self {
+            Self::Track(track) => track.source.as_ref(),
+            Self::Episode(episode) => episode.source.as_ref(),
+        }
+    }
+
+    pub fn set_source(&mut self, source: Option<PlayableSource>) {
+        match self {
+            Self::Track(track) => track.source = source,
+            Self::Episode(episode) => episode.source = source,
+        }
+    }
+
+    /// Return a copy of this [Playable] that remembers it was queued from `source`.
+    pub fn with_source(&self, source: &PlayableSource) -> Self {
+        let mut playable = self.clone();
+        playable.set_source(Some(source.clone()));
+        playable
+    }
+
     pub fn duration_str(&self) -> String {
         ms_to_hms(self.duration())
     }
@@ -221,6 +272,14 @@ impl ListItem for Playable {
         self.as_listitem().share_url()
     }
 
+    fn cover_url(&self) -> Option<String> {
+        self.as_listitem().cover_url()
+    }
+
+    fn metadata(&self) -> serde_json::Value {
+        self.as_listitem().metadata()
+    }
+
     fn album(&self, queue: &Queue) -> Option<Album> {
         self.as_listitem().album(queue)
     }
@@ -233,6 +292,10 @@ impl ListItem for Playable {
         self.as_listitem().track()
     }
 
+    fn episode(&self) -> Option<Episode> {
+        self.as_listitem().episode()
+    }
+
     fn as_listitem(&self) -> Box<dyn ListItem> {
         self.as_listitem()
     }
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, RwLock};
 use std::{cmp::Ordering, iter::Iterator};
 
@@ -8,14 +8,20 @@ use log::{debug, warn};
 use rspotify::model::playlist::{FullPlaylist, SimplifiedPlaylist};
 use rspotify::model::Id;
 
-use crate::model::playable::Playable;
+use crate::model::audio_features::AudioFeatures;
+use crate::model::playable::{Playable, PlayableSource};
 use crate::model::track::Track;
 use crate::queue::Queue;
-use crate::spotify::Spotify;
+use crate::spotify::{Spotify, UriType};
+use crate::spotify_api::{RecommendationTargets, BATCH_TOKEN_VALIDITY};
 use crate::traits::{IntoBoxedViewExt, ListItem, ViewExt};
 use crate::ui::{listview::ListView, playlist::PlaylistView};
+use crate::utils::ms_to_hms;
 use crate::{command::SortDirection, command::SortKey, library::Library};
 
+/// How many pages of tracks are fetched at once when loading a playlist.
+pub const PARALLEL_PAGES: usize = 4;
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Playlist {
     pub id: String,
@@ -26,9 +32,25 @@ pub struct Playlist {
     pub num_tracks: usize,
     pub tracks: Option<Vec<Playable>>,
     pub collaborative: bool,
+    pub image_url: Option<String>,
 }
 
 impl Playlist {
+    /// The amount of tracks, counted if they are loaded.
+    pub fn track_count(&self) -> usize {
+        self.tracks
+            .as_ref()
+            .map(Vec::len)
+            .unwrap_or(self.num_tracks)
+    }
+
+    /// The total duration of the tracks in milliseconds, once they are loaded.
+    pub fn duration(&self) -> Option<u32> {
+        self.tracks
+            .as_ref()
+            .map(|tracks| tracks.iter().map(Playable::duration).sum())
+    }
+
     pub fn load_tracks(&mut self, spotify: &Spotify) {
         if self.tracks.is_some() {
             return;
@@ -38,15 +60,24 @@ impl Playlist {
     }
 
     fn get_all_tracks(&self, spotify: &Spotify) -> Vec<Playable> {
+        // The pages are fetched at once, so the token must not need to be renewed in between.
+        spotify.api.ensure_token(BATCH_TOKEN_VALIDITY);
         let tracks_result = spotify.api.user_playlist_tracks(&self.id);
-        while !tracks_result.at_end() {
-            tracks_result.next();
-        }
+        tracks_result.fetch_all(PARALLEL_PAGES, || {});
 
         let tracks = tracks_result.items.read().unwrap();
         tracks.clone()
     }
 
+    /// The [PlayableSource] attached to tracks that are queued from this playlist.
+    pub fn as_source(&self) -> PlayableSource {
+        PlayableSource {
+            uri_type: UriType::Playlist,
+            id: self.id.clone(),
+            name: self.name.clone(),
+        }
+    }
+
     pub fn has_track(&self, track_id: &str) -> bool {
         self.tracks.as_ref().map_or(false, |tracks| {
             tracks
@@ -81,26 +112,66 @@ impl Playlist {
         }
     }
 
-    pub fn append_tracks(&mut self, new_tracks: &[Playable], spotify: &Spotify, library: &Library) {
-        let mut has_modified = false;
+    /// Move the track at index `from` to index `to`, both in Spotify and locally. Returns whether
+    /// the track could be moved.
+    pub fn move_track(
+        &mut self,
+        from: usize,
+        to: usize,
+        spotify: &Spotify,
+        library: &Library,
+    ) -> bool {
+        let Some(tracks) = self.tracks.as_mut() else {
+            return false;
+        };
+        if from == to || from >= tracks.len() || to >= tracks.len() {
+            return false;
+        }
+
+        // Items are inserted in front of `insert_before`, which is behind the target position
+        // when moving down.
+        let insert_before = if to > from { to + 1 } else { to };
+        match spotify
+            .api
+            .playlist_reorder_items(&self.id, &self.snapshot_id, from, insert_before)
+        {
+            Ok(snapshot_id) => {
+                let track = tracks.remove(from);
+                tracks.insert(to, track);
+                for (index, track) in tracks.iter_mut().enumerate() {
+                    track.set_list_index(index);
+                }
+                self.snapshot_id = snapshot_id;
+                library.playlist_update(self);
+                true
+            }
+            Err(_) => false,
+        }
+    }
 
+    /// Append `new_tracks` to the playlist. Returns whether the tracks could be added.
+    pub fn append_tracks(
+        &mut self,
+        new_tracks: &[Playable],
+        spotify: &Spotify,
+        library: &Library,
+    ) -> bool {
         if spotify
             .api
             .append_tracks(&self.id, new_tracks, None)
-            .is_ok()
+            .is_err()
         {
-            if let Some(tracks) = &mut self.tracks {
-                tracks.append(&mut new_tracks.to_vec());
-                has_modified = true;
-            }
+            return false;
         }
 
-        if has_modified {
+        if let Some(tracks) = &mut self.tracks {
+            tracks.append(&mut new_tracks.to_vec());
             library.playlist_update(self);
         }
+        true
     }
 
-    pub fn sort(&mut self, key: &SortKey, direction: &SortDirection) {
+    pub fn sort(&mut self, key: &SortKey, direction: &SortDirection, library: &Library) {
         fn compare_artists(a: &[String], b: &[String]) -> Ordering {
             let sanitize_artists_name = |x: &[String]| -> Vec<String> {
                 x.iter()
@@ -128,7 +199,39 @@ impl Playlist {
                 .then_with(|| a.track_number.cmp(&b.track_number))
         }
 
+        // Audio features are only looked up when sorting by one of them, as this needs API calls.
+        let features = match key {
+            SortKey::Bpm | SortKey::Energy => {
+                let ids: Vec<&str> = self
+                    .tracks
+                    .iter()
+                    .flatten()
+                    .filter_map(|p| match p {
+                        Playable::Track(track) => track.id.as_deref(),
+                        Playable::Episode(_) => None,
+                    })
+                    .collect();
+                library.audio_features(&ids)
+            }
+            _ => HashMap::new(),
+        };
+        let compare_feature = |a: &Track, b: &Track, value: fn(&AudioFeatures) -> f32| {
+            let a = a.id.as_ref().and_then(|id| features.get(id)).map(value);
+            let b = b.id.as_ref().and_then(|id| features.get(id)).map(value);
+            a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+        };
+
         if let Some(c) = self.tracks.as_mut() {
+            // Episodes can be added to playlists as well, so they are sorted by the date they
+            // were added along with the tracks.
+            if let SortKey::Added = key {
+                c.sort_by(|a, b| match *direction {
+                    SortDirection::Ascending => a.added_at().cmp(&b.added_at()),
+                    SortDirection::Descending => b.added_at().cmp(&a.added_at()),
+                });
+                return;
+            }
+
             c.sort_by(|a, b| match (a.track(), b.track()) {
                 (Some(a), Some(b)) => {
                     let (a, b) = match *direction {
@@ -142,6 +245,10 @@ impl Playlist {
                         SortKey::Added => a.added_at.cmp(&b.added_at),
                         SortKey::Artist => compare_artists(&a.artists, &b.artists)
                             .then_with(|| compare_album(&a, &b)),
+                        SortKey::Bpm => compare_feature(&a, &b, |f| f.tempo),
+                        SortKey::Energy => compare_feature(&a, &b, |f| f.energy),
+                        // Only playlists themselves are sorted by their amount of tracks.
+                        SortKey::Tracks => Ordering::Equal,
                     }
                 }
                 _ => std::cmp::Ordering::Equal,
@@ -161,6 +268,7 @@ impl From<&SimplifiedPlaylist> for Playlist {
             num_tracks: list.tracks.total as usize,
             tracks: None,
             collaborative: list.collaborative,
+            image_url: list.images.first().map(|image| image.url.clone()),
         }
     }
 }
@@ -176,6 +284,7 @@ impl From<&FullPlaylist> for Playlist {
             num_tracks: list.tracks.total as usize,
             tracks: None,
             collaborative: list.collaborative,
+            image_url: list.images.first().map(|image| image.url.clone()),
         }
     }
 }
@@ -199,9 +308,16 @@ impl ListItem for Playlist {
 
     fn display_left(&self, library: &Library) -> String {
         let hide_owners = library.cfg.values().hide_display_names.unwrap_or(false);
-        match (self.owner_name.as_ref(), hide_owners) {
+        let name = match (self.owner_name.as_ref(), hide_owners) {
             (Some(owner), false) => format!("{} • {}", self.name, owner),
             _ => self.name.clone(),
+        };
+        if !library.is_favorite_playlist(self) {
+            name
+        } else if library.cfg.values().use_nerdfont.unwrap_or(false) {
+            format!("\u{f04ce} {name}")
+        } else {
+            format!("★ {name}")
         }
     }
 
@@ -216,20 +332,19 @@ impl ListItem for Playlist {
             ""
         };
 
-        let num_tracks = self
-            .tracks
-            .as_ref()
-            .map(|t| t.len())
-            .unwrap_or(self.num_tracks);
+        let duration = self.duration().map(ms_to_hms).unwrap_or_default();
+        let num_tracks = self.track_count();
 
-        format!("{saved}{num_tracks:>4} tracks")
+        format!("{saved}{duration:>9} {num_tracks:>4} tracks")
     }
 
     fn play(&mut self, queue: &Queue) {
         self.load_tracks(&queue.get_spotify());
 
+        let source = self.as_source();
         if let Some(tracks) = &self.tracks {
-            let index = queue.append_next(tracks);
+            let tracks: Vec<Playable> = tracks.iter().map(|t| t.with_source(&source)).collect();
+            let index = queue.append_next(&tracks);
             queue.play(index, true, true);
         }
     }
@@ -237,9 +352,10 @@ impl ListItem for Playlist {
     fn play_next(&mut self, queue: &Queue) {
         self.load_tracks(&queue.get_spotify());
 
+        let source = self.as_source();
         if let Some(tracks) = self.tracks.as_ref() {
             for track in tracks.iter().rev() {
-                queue.insert_after_current(track.clone());
+                queue.insert_after_current(track.with_source(&source));
             }
         }
     }
@@ -247,9 +363,10 @@ impl ListItem for Playlist {
     fn queue(&mut self, queue: &Queue) {
         self.load_tracks(&queue.get_spotify());
 
+        let source = self.as_source();
         if let Some(tracks) = self.tracks.as_ref() {
             for track in tracks.iter() {
-                queue.append(track.clone());
+                queue.append(track.with_source(&source));
             }
         }
     }
@@ -261,7 +378,7 @@ impl ListItem for Playlist {
         }
 
         if library.is_saved_playlist(self) {
-            library.delete_playlist(&self.id);
+            library.delete_playlist(&self.id).ok();
         } else {
             library.follow_playlist(self.clone());
         }
@@ -272,7 +389,10 @@ impl ListItem for Playlist {
     }
 
     fn unsave(&mut self, library: &Library) {
-        library.delete_playlist(&self.id);
+        // Deleting a playlist of the user is left to the playlists view, which asks first.
+        if library.is_followed_playlist(self) {
+            library.delete_playlist(&self.id).ok();
+        }
     }
 
     fn open(&self, queue: Arc<Queue>, library: Arc<Library>) -> Option<Box<dyn ViewExt>> {
@@ -308,6 +428,7 @@ impl ListItem for Playlist {
                 None,
                 None,
                 Some(track_ids.iter().map(|t| t.as_ref()).collect()),
+                &RecommendationTargets::default(),
             )
             .ok()
             .map(|r| r.tracks)
@@ -331,6 +452,18 @@ impl ListItem for Playlist {
         ))
     }
 
+    fn cover_url(&self) -> Option<String> {
+        self.image_url.clone()
+    }
+
+    fn metadata(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+
+    fn playlist(&self) -> Option<Playlist> {
+        Some(self.clone())
+    }
+
     fn is_saved(&self, library: &Library) -> Option<bool> {
         // save status of personal playlists can't be toggled for safety
         if !library.is_followed_playlist(self) {
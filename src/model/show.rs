@@ -150,6 +150,14 @@ impl ListItem for Show {
         Some(format!("https://open.spotify.com/show/{}", self.id))
     }
 
+    fn cover_url(&self) -> Option<String> {
+        self.cover_url.clone()
+    }
+
+    fn metadata(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+
     #[inline]
     fn is_saved(&self, library: &Library) -> Option<bool> {
         Some(library.is_saved_show(self))
@@ -0,0 +1,49 @@
+use chrono::{DateTime, Utc};
+
+use crate::model::album::Album;
+use crate::model::playable::{Playable, PlayableSource};
+use crate::model::playlist::Playlist;
+use crate::spotify::Spotify;
+
+/// The metadata of all tracks of a playlist or album at a point in time.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Snapshot {
+    pub source: PlayableSource,
+    pub taken_at: DateTime<Utc>,
+    pub tracks: Vec<Playable>,
+}
+
+impl Snapshot {
+    pub fn new(source: PlayableSource, tracks: Vec<Playable>) -> Self {
+        Self {
+            source,
+            taken_at: Utc::now(),
+            tracks,
+        }
+    }
+
+    /// A snapshot of all tracks of `playlist`, which are fetched first if they aren't loaded yet.
+    pub fn of_playlist(playlist: &Playlist, spotify: &Spotify) -> Self {
+        let mut playlist = playlist.clone();
+        playlist.load_tracks(spotify);
+        let tracks = playlist.tracks.clone().unwrap_or_default();
+        Self::new(playlist.as_source(), tracks)
+    }
+
+    /// A snapshot of all tracks of `album`, which are fetched first if they aren't loaded yet.
+    /// Albums without an ID can't be snapshotted.
+    pub fn of_album(album: &Album, spotify: &Spotify) -> Option<Self> {
+        let mut album = album.clone();
+        album.load_all_tracks(spotify.clone());
+        Some(Self::new(album.as_source()?, album.playables()))
+    }
+
+    /// The name of the file this snapshot is stored in, unique per source and time.
+    pub fn file_name(&self) -> String {
+        format!(
+            "{}-{}.json",
+            self.source.id,
+            self.taken_at.format("%Y%m%dT%H%M%S")
+        )
+    }
+}
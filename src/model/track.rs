@@ -1,20 +1,21 @@
 use std::fmt;
-use std::sync::{Arc, RwLock};
+use std::sync::Arc;
 
 use crate::config;
 use crate::utils::ms_to_hms;
 use chrono::{DateTime, Utc};
 use rspotify::model::album::FullAlbum;
 use rspotify::model::track::{FullTrack, SavedTrack, SimplifiedTrack};
-use rspotify::model::Id;
+use rspotify::model::{Id, RestrictionReason};
 
+use crate::i18n::tr;
 use crate::library::Library;
 use crate::model::album::Album;
 use crate::model::artist::Artist;
-use crate::model::playable::Playable;
+use crate::model::playable::{Playable, PlayableSource};
 use crate::queue::Queue;
 use crate::traits::{IntoBoxedViewExt, ListItem, ViewExt};
-use crate::ui::listview::ListView;
+use crate::ui::recommendations::{RecommendationSeed, RecommendationsView};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Track {
@@ -35,6 +36,40 @@ pub struct Track {
     pub list_index: usize,
     pub is_local: bool,
     pub is_playable: Option<bool>,
+    #[serde(default)]
+    pub restriction: Option<Restriction>,
+    #[serde(default)]
+    pub explicit: bool,
+    #[serde(default)]
+    pub source: Option<PlayableSource>,
+}
+
+/// The reason why Spotify doesn't allow a track to be played.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum Restriction {
+    Market,
+    Product,
+    Explicit,
+}
+
+impl Restriction {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Market => tr("not available in your region"),
+            Self::Product => tr("not available with your subscription"),
+            Self::Explicit => tr("explicit content is disabled"),
+        }
+    }
+}
+
+impl From<&rspotify::model::Restriction> for Restriction {
+    fn from(restriction: &rspotify::model::Restriction) -> Self {
+        match restriction.reason {
+            RestrictionReason::Market => Self::Market,
+            RestrictionReason::Product => Self::Product,
+            RestrictionReason::Explicit => Self::Explicit,
+        }
+    }
 }
 
 impl Track {
@@ -73,12 +108,23 @@ impl Track {
             list_index: 0,
             is_local: track.is_local,
             is_playable: track.is_playable,
+            restriction: track.restrictions.as_ref().map(Restriction::from),
+            explicit: track.explicit,
+            source: None,
         }
     }
 
     pub fn duration_str(&self) -> String {
         ms_to_hms(self.duration)
     }
+
+    /// Why this track can't be played, if Spotify marked it as unplayable.
+    pub fn unplayable_reason(&self) -> Option<&'static str> {
+        (self.is_playable == Some(false)).then(|| {
+            self.restriction
+                .map_or(tr("unavailable"), |restriction| restriction.description())
+        })
+    }
 }
 
 impl From<&SimplifiedTrack> for Track {
@@ -112,6 +158,9 @@ impl From<&SimplifiedTrack> for Track {
             list_index: 0,
             is_local: track.is_local,
             is_playable: track.is_playable,
+            restriction: track.restrictions.as_ref().map(Restriction::from),
+            explicit: track.explicit,
+            source: None,
         }
     }
 }
@@ -153,6 +202,9 @@ impl From<&FullTrack> for Track {
             list_index: 0,
             is_local: track.is_local,
             is_playable: track.is_playable,
+            restriction: track.restrictions.as_ref().map(Restriction::from),
+            explicit: track.explicit,
+            source: None,
         }
     }
 }
@@ -218,7 +270,7 @@ impl ListItem for Track {
             .unwrap_or_default();
         let default = config::TrackFormat::default().right.unwrap();
         let right = formatting.right.unwrap_or_else(|| default.clone());
-        if right != default {
+        let right = if right != default {
             Playable::format(&Playable::Track(self.clone()), &right, library)
         } else {
             let saved = if library.is_saved_track(&Playable::Track(self.clone())) {
@@ -231,6 +283,11 @@ impl ListItem for Track {
                 ""
             };
             format!("{} {}", saved, self.duration_str())
+        };
+
+        match self.unplayable_reason() {
+            Some(reason) => format!("[{reason}] {right}"),
+            None => right,
         }
     }
 
@@ -272,32 +329,16 @@ impl ListItem for Track {
         queue: Arc<Queue>,
         library: Arc<Library>,
     ) -> Option<Box<dyn ViewExt>> {
-        let spotify = queue.get_spotify();
-
-        let recommendations: Option<Vec<Self>> = if let Some(id) = &self.id {
-            spotify
-                .api
-                .recommendations(None, None, Some(vec![id]))
-                .ok()
-                .map(|r| r.tracks)
-                .map(|tracks| tracks.iter().map(Self::from).collect())
-        } else {
-            None
+        let seed = RecommendationSeed::Track {
+            id: self.id.clone()?,
+            name: self.title.clone(),
         };
-
-        recommendations.map(|tracks| {
-            ListView::new(
-                Arc::new(RwLock::new(tracks)),
-                queue.clone(),
-                library.clone(),
-            )
-            .with_title(&format!(
-                "Similar to \"{} - {}\"",
-                self.artists.join(", "),
-                self.title
-            ))
-            .into_boxed_view_ext()
-        })
+        let title = format!(
+            "Similar to \"{} - {}\"",
+            self.artists.join(", "),
+            self.title
+        );
+        Some(RecommendationsView::new(queue, library, title, vec![seed]).into_boxed_view_ext())
     }
 
     fn share_url(&self) -> Option<String> {
@@ -306,6 +347,14 @@ impl ListItem for Track {
             .map(|id| format!("https://open.spotify.com/track/{id}"))
     }
 
+    fn cover_url(&self) -> Option<String> {
+        self.cover_url.clone()
+    }
+
+    fn metadata(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+
     fn album(&self, queue: &Queue) -> Option<Album> {
         let spotify = queue.get_spotify();
 
@@ -14,18 +14,12 @@ use zbus::{connection, interface};
 
 use crate::application::ASYNC_RUNTIME;
 use crate::library::Library;
-use crate::model::album::Album;
-use crate::model::episode::Episode;
 use crate::model::playable::Playable;
-use crate::model::playlist::Playlist;
-use crate::model::show::Show;
-use crate::model::track::Track;
 use crate::queue::RepeatSetting;
-use crate::spotify::UriType;
 use crate::spotify_url::SpotifyUrl;
 use crate::traits::ListItem;
 use crate::{
-    events::EventManager,
+    events::{Event, EventManager, RemoteChange},
     queue::Queue,
     spotify::{PlayerEvent, Spotify, VOLUME_PERCENT},
 };
@@ -104,7 +98,7 @@ impl MprisPlayer {
             _ => RepeatSetting::None,
         };
         self.queue.set_repeat(setting);
-        self.event.trigger();
+        self.event.send(Event::Remote(RemoteChange::Repeat));
     }
 
     #[zbus(property)]
@@ -254,6 +248,14 @@ impl MprisPlayer {
             ),
         );
 
+        // Not part of the MPRIS specification, but allows status lines to show "track N of M".
+        let (position, length) = self.queue.position().unwrap_or_default();
+        hm.insert(
+            "ncspot:queuePosition".to_string(),
+            Value::I32(position as i32),
+        );
+        hm.insert("ncspot:queueLength".to_string(), Value::I32(length as i32));
+
         hm
     }
 
@@ -265,7 +267,7 @@ impl MprisPlayer {
     #[zbus(property)]
     fn set_shuffle(&self, shuffle: bool) {
         self.queue.set_shuffle(shuffle);
-        self.event.trigger();
+        self.event.send(Event::Remote(RemoteChange::Shuffle));
     }
 
     #[zbus(property)]
@@ -279,7 +281,7 @@ impl MprisPlayer {
         volume = volume.clamp(0.0, 1.0);
         let vol = (VOLUME_PERCENT as f64) * volume * 100.0;
         self.spotify.set_volume(vol as u16, false);
-        self.event.trigger();
+        self.event.send(Event::Remote(RemoteChange::Volume));
     }
 
     #[zbus(property)]
@@ -321,12 +323,14 @@ impl MprisPlayer {
     async fn seeked(context: &SignalEmitter<'_>, position: &i64) -> zbus::Result<()>;
 
     fn next(&self) {
-        self.queue.next(true)
+        self.queue.next(true);
+        self.event.send(Event::Remote(RemoteChange::Track));
     }
 
     fn previous(&self) {
         if self.spotify.get_current_progress() < Duration::from_secs(5) {
             self.queue.previous();
+            self.event.send(Event::Remote(RemoteChange::Track));
         } else {
             self.spotify.seek(0);
         }
@@ -377,93 +381,8 @@ impl MprisPlayer {
     }
 
     fn open_uri(&self, uri: &str) {
-        let spotify_url = if uri.contains("open.spotify.com") {
-            SpotifyUrl::from_url(uri)
-        } else if let Ok(uri_type) = uri.parse() {
-            let id = &uri[uri.rfind(':').unwrap_or(0) + 1..uri.len()];
-            Some(SpotifyUrl::new(id, uri_type))
-        } else {
-            None
-        };
-
-        let id = spotify_url
-            .as_ref()
-            .map(|s| s.id.clone())
-            .unwrap_or("".to_string());
-        let uri_type = spotify_url.map(|s| s.uri_type);
-        match uri_type {
-            Some(UriType::Album) => {
-                if let Ok(a) = self.spotify.api.album(&id) {
-                    if let Some(t) = &Album::from(&a).tracks {
-                        let should_shuffle = self.queue.get_shuffle();
-                        self.queue.clear();
-                        let index = self.queue.append_next(
-                            &t.iter()
-                                .map(|track| Playable::Track(track.clone()))
-                                .collect(),
-                        );
-                        self.queue.play(index, should_shuffle, should_shuffle)
-                    }
-                }
-            }
-            Some(UriType::Track) => {
-                if let Ok(t) = self.spotify.api.track(&id) {
-                    self.queue.clear();
-                    self.queue.append(Playable::Track(Track::from(&t)));
-                    self.queue.play(0, false, false)
-                }
-            }
-            Some(UriType::Playlist) => {
-                if let Ok(p) = self.spotify.api.playlist(&id) {
-                    let mut playlist = Playlist::from(&p);
-                    playlist.load_tracks(&self.spotify);
-                    if let Some(tracks) = &playlist.tracks {
-                        let should_shuffle = self.queue.get_shuffle();
-                        self.queue.clear();
-                        let index = self.queue.append_next(tracks);
-                        self.queue.play(index, should_shuffle, should_shuffle)
-                    }
-                }
-            }
-            Some(UriType::Show) => {
-                if let Ok(s) = self.spotify.api.show(&id) {
-                    let mut show: Show = (&s).into();
-                    let spotify = self.spotify.clone();
-                    show.load_all_episodes(spotify);
-                    if let Some(e) = &show.episodes {
-                        let should_shuffle = self.queue.get_shuffle();
-                        self.queue.clear();
-                        let mut ep = e.clone();
-                        ep.reverse();
-                        let index = self.queue.append_next(
-                            &ep.iter()
-                                .map(|episode| Playable::Episode(episode.clone()))
-                                .collect(),
-                        );
-                        self.queue.play(index, should_shuffle, should_shuffle)
-                    }
-                }
-            }
-            Some(UriType::Episode) => {
-                if let Ok(e) = self.spotify.api.episode(&id) {
-                    self.queue.clear();
-                    self.queue.append(Playable::Episode(Episode::from(&e)));
-                    self.queue.play(0, false, false)
-                }
-            }
-            Some(UriType::Artist) => {
-                if let Ok(a) = self.spotify.api.artist_top_tracks(&id) {
-                    let should_shuffle = self.queue.get_shuffle();
-                    self.queue.clear();
-                    let index = self.queue.append_next(
-                        &a.iter()
-                            .map(|track| Playable::Track(track.clone()))
-                            .collect(),
-                    );
-                    self.queue.play(index, should_shuffle, should_shuffle)
-                }
-            }
-            None => {}
+        if let Some(url) = SpotifyUrl::from_uri_or_url(uri) {
+            self.queue.play_url(&url);
         }
     }
 }
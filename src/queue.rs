@@ -1,5 +1,8 @@
 use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{self, AtomicBool, AtomicUsize};
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 use log::{debug, info};
 #[cfg(feature = "notify")]
@@ -9,10 +12,23 @@ use rand::prelude::*;
 use strum_macros::Display;
 
 use crate::config::Config;
+use crate::events::{Event, EventManager};
+use crate::ext_traits::CursiveExt;
+use crate::i18n::{tr, tr_args};
 use crate::library::Library;
+use crate::model::album::Album;
+use crate::model::audio_analysis::AudioAnalysis;
+use crate::model::audio_features::AudioFeatures;
+use crate::model::episode::Episode;
 use crate::model::playable::Playable;
+use crate::model::playlist::Playlist;
+use crate::model::show::Show;
+use crate::model::track::Track;
 use crate::spotify::PlayerEvent;
-use crate::spotify::Spotify;
+use crate::spotify::{Spotify, UriType};
+use crate::spotify_api::RecommendationTargets;
+use crate::spotify_url::SpotifyUrl;
+use crate::ui::recommendations::{fetch_recommendations, RecommendationSeed, MAX_SEEDS};
 
 /// Repeat behavior for the [Queue].
 #[derive(Display, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
@@ -25,12 +41,32 @@ pub enum RepeatSetting {
     RepeatTrack,
 }
 
+/// The maximum amount of items kept in [Queue::history].
+const MAX_HISTORY: usize = 100;
+
+/// How many items have to be left in the queue for a radio to add more recommendations.
+const RADIO_MIN_UPCOMING: usize = 3;
+
+/// Intros and outros shorter than this many milliseconds are played with `auto_skip`, as skipping
+/// them would only sound like a glitch.
+const MIN_SKIP_MS: u32 = 2000;
+
 /// Events that are specific to the [Queue].
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug)]
 pub enum QueueEvent {
     /// Request the player to 'preload' a track, basically making sure that
     /// transitions between tracks can be uninterrupted.
     PreloadTrackRequest,
+    /// Recommended tracks that were fetched to start a radio, if set, or to keep it going.
+    RadioTracks(Vec<Playable>, bool),
+    /// The tracks of the `fallback_playlist`, loaded after the queue ran out.
+    FallbackTracks(Vec<Playable>),
+    /// The audio analysis of the track with the given ID, used to skip its intro and outro.
+    AudioAnalysis(String, AudioAnalysis),
+    /// The audio features of the queued tracks by their ID, used to reorder them for automix.
+    AutomixFeatures(HashMap<String, AudioFeatures>),
+    /// The items behind a URL passed to [Queue::play_url], which replace the queue.
+    UrlItems(Vec<Playable>),
 }
 
 /// The queue determines the playback order of [Playable] items, and is also used to control
@@ -39,25 +75,48 @@ pub struct Queue {
     /// The internal data, which doesn't change with shuffle or repeat. This is
     /// the raw data only.
     pub queue: Arc<RwLock<Vec<Playable>>>,
+    /// Items that were played during this session before the queue was cleared, oldest first.
+    pub history: Arc<RwLock<Vec<Playable>>>,
     /// The playback order of the queue, as indices into `self.queue`.
     random_order: RwLock<Option<Vec<usize>>>,
     current_track: RwLock<Option<usize>>,
+    /// Whether the queue is a radio, which adds recommendations whenever it's about to run out.
+    radio: AtomicBool,
+    /// Whether recommendations for the radio are being fetched.
+    radio_fetching: Arc<AtomicBool>,
+    /// How many items weren't queued as they were already in the queue, see [Queue::skipping_duplicates].
+    skipped_duplicates: AtomicUsize,
+    /// The ID of the playing track and the position its outro starts at, which is skipped with
+    /// `auto_skip`.
+    outro: RwLock<Option<(String, u32)>>,
     spotify: Spotify,
     cfg: Arc<Config>,
     library: Arc<Library>,
+    events: EventManager,
 }
 
 impl Queue {
-    pub fn new(spotify: Spotify, cfg: Arc<Config>, library: Arc<Library>) -> Self {
+    pub fn new(
+        spotify: Spotify,
+        cfg: Arc<Config>,
+        library: Arc<Library>,
+        events: EventManager,
+    ) -> Self {
         let queue_state = cfg.state().queuestate.clone();
 
         Self {
             queue: Arc::new(RwLock::new(queue_state.queue)),
+            history: Arc::new(RwLock::new(Vec::new())),
             spotify: spotify.clone(),
             current_track: RwLock::new(queue_state.current_track),
             random_order: RwLock::new(queue_state.random_order),
+            radio: AtomicBool::new(false),
+            radio_fetching: Arc::new(AtomicBool::new(false)),
+            skipped_duplicates: AtomicUsize::new(0),
+            outro: RwLock::new(None),
             cfg,
             library,
+            events,
         }
     }
 
@@ -122,9 +181,55 @@ impl Queue {
         *self.current_track.read().unwrap()
     }
 
+    /// Up to `count` items that will be played after the current one, taking into account shuffle
+    /// status.
+    pub fn upcoming(&self, count: usize) -> Vec<Playable> {
+        let Some(current) = self.get_current_index() else {
+            return Vec::new();
+        };
+        let queue = self.queue.read().unwrap();
+        let order = self
+            .get_random_order()
+            .unwrap_or_else(|| (0..queue.len()).collect());
+        let position = order.iter().position(|&i| i == current).unwrap_or(0);
+
+        order
+            .iter()
+            .skip(position + 1)
+            .take(count)
+            .filter_map(|&i| queue.get(i).cloned())
+            .collect()
+    }
+
+    /// Whether `track` shouldn't be queued, because it already is and the `dedup_queue` option is
+    /// on.
+    fn skip_duplicate(&self, track: &Playable) -> bool {
+        if !self.cfg.values().dedup_queue.unwrap_or(false) {
+            return false;
+        }
+        let uri = track.uri();
+        let duplicate = self.queue.read().unwrap().iter().any(|p| p.uri() == uri);
+        if duplicate {
+            self.skipped_duplicates
+                .fetch_add(1, atomic::Ordering::Relaxed);
+        }
+        duplicate
+    }
+
+    /// Run `add`, which adds items to the queue, and return how many of them were skipped as they
+    /// were already queued.
+    pub fn skipping_duplicates(&self, add: impl FnOnce()) -> usize {
+        self.skipped_duplicates.store(0, atomic::Ordering::Relaxed);
+        add();
+        self.skipped_duplicates.swap(0, atomic::Ordering::Relaxed)
+    }
+
     /// Insert `track` as the item that should logically follow the currently
     /// playing item, taking into account shuffle status.
     pub fn insert_after_current(&self, track: Playable) {
+        if self.skip_duplicate(&track) {
+            return;
+        }
         if let Some(index) = self.get_current_index() {
             let mut random_order = self.random_order.write().unwrap();
             if let Some(order) = random_order.as_mut() {
@@ -147,6 +252,9 @@ impl Queue {
 
     /// Add `track` to the end of the queue.
     pub fn append(&self, track: Playable) {
+        if self.skip_duplicate(&track) {
+            return;
+        }
         let mut random_order = self.random_order.write().unwrap();
         if let Some(order) = random_order.as_mut() {
             let index = order.len().saturating_sub(1);
@@ -234,9 +342,56 @@ impl Queue {
         }
     }
 
+    /// Remove items that are in the queue more than once, keeping the currently playing one or
+    /// else the first of them. Returns the amount of removed items.
+    pub fn dedup(&self) -> usize {
+        let duplicates = {
+            let queue = self.queue.read().unwrap();
+            let current = self.get_current_index();
+            let mut kept = HashMap::new();
+            for (index, item) in queue.iter().enumerate() {
+                kept.entry(item.uri()).or_insert(index);
+            }
+            if let Some(current) = current.filter(|&i| i < queue.len()) {
+                kept.insert(queue[current].uri(), current);
+            }
+            (0..queue.len())
+                .filter(|&i| kept.get(&queue[i].uri()) != Some(&i))
+                .collect::<Vec<usize>>()
+        };
+
+        for &index in duplicates.iter().rev() {
+            self.remove(index);
+        }
+        duplicates.len()
+    }
+
+    /// Shuffle the items of the queue once, unlike shuffle mode which only changes the playback
+    /// order. The currently playing item is moved to the top.
+    pub fn randomize(&self) {
+        {
+            let mut queue = self.queue.write().unwrap();
+            let mut current = self.current_track.write().unwrap();
+            let playing = current
+                .filter(|&index| index < queue.len())
+                .map(|index| queue.remove(index));
+            queue.shuffle(&mut rand::thread_rng());
+            if let Some(item) = playing {
+                queue.insert(0, item);
+                current.replace(0);
+            }
+        }
+
+        if self.get_shuffle() {
+            self.generate_random_order();
+        }
+    }
+
     /// Clear all the items from the queue and stop playback.
     pub fn clear(&self) {
+        self.archive_played();
         self.stop();
+        self.radio.store(false, atomic::Ordering::Relaxed);
 
         let mut q = self.queue.write().unwrap();
         q.clear();
@@ -247,11 +402,70 @@ impl Queue {
         }
     }
 
+    /// Move the items that were played up to and including the current one to `self.history`.
+    fn archive_played(&self) {
+        let Some(current) = self.get_current_index() else {
+            return;
+        };
+        let queue = self.queue.read().unwrap();
+        let order = self
+            .get_random_order()
+            .unwrap_or_else(|| (0..queue.len()).collect());
+        let position = order.iter().position(|&i| i == current).unwrap_or(0);
+
+        let mut history = self.history.write().unwrap();
+        history.extend(
+            order[..=position]
+                .iter()
+                .filter_map(|&i| queue.get(i).cloned()),
+        );
+        let excess = history.len().saturating_sub(MAX_HISTORY);
+        history.drain(..excess);
+    }
+
     /// The amount of items in `self.queue`.
     pub fn len(&self) -> usize {
         self.queue.read().unwrap().len()
     }
 
+    /// The 1-based position of the currently playing item in the playback order, taking into
+    /// account shuffle status, along with the amount of items in `self.queue`.
+    pub fn position(&self) -> Option<(usize, usize)> {
+        let current = self.get_current_index()?;
+        let length = self.len();
+        let position = match self.random_order.read().unwrap().as_ref() {
+            Some(order) => order.iter().position(|&i| i == current)?,
+            None => current,
+        };
+        Some((position + 1, length))
+    }
+
+    /// The playback time left until the end of `self.queue` is reached, taking into account the
+    /// shuffle order and the progress of the currently playing item.
+    pub fn remaining_duration(&self) -> Duration {
+        let queue = self.queue.read().unwrap();
+        let duration = |i: &usize| {
+            queue
+                .get(*i)
+                .map(|p| Duration::from_millis(p.duration() as u64))
+        };
+
+        let Some(current) = self.get_current_index() else {
+            return (0..queue.len()).filter_map(|i| duration(&i)).sum();
+        };
+
+        let random_order = self.random_order.read().unwrap();
+        let upcoming: Duration = match random_order.as_ref() {
+            Some(order) => {
+                let position = order.iter().position(|&i| i == current).unwrap_or(0);
+                order[position..].iter().filter_map(duration).sum()
+            }
+            None => (current..queue.len()).filter_map(|i| duration(&i)).sum(),
+        };
+
+        upcoming.saturating_sub(self.spotify.get_current_progress())
+    }
+
     /// Shift the item at `from` in `self.queue` to `to`.
     pub fn shift(&self, from: usize, to: usize) {
         let mut queue = self.queue.write().unwrap();
@@ -272,12 +486,46 @@ impl Queue {
         }
     }
 
+    /// Move the items at `indices` next to each other, starting at index `to`, keeping their
+    /// order. The playing item and the shuffle order follow the moved items. Returns the new
+    /// indices of the moved items.
+    pub fn shift_many(&self, indices: &[usize], to: usize) -> Vec<usize> {
+        let mut queue = self.queue.write().unwrap();
+        let (new_index, moved) = shifted_indices(queue.len(), indices, to);
+
+        let mut items: Vec<Option<Playable>> = (0..queue.len()).map(|_| None).collect();
+        for (previous, item) in queue.drain(..).enumerate() {
+            items[new_index[previous]] = Some(item);
+        }
+        queue.extend(items.into_iter().flatten());
+
+        let mut current = self.current_track.write().unwrap();
+        if let Some(index) = current.filter(|&index| index < new_index.len()) {
+            current.replace(new_index[index]);
+        }
+        if let Some(random_order) = self.random_order.write().unwrap().as_mut() {
+            for index in random_order.iter_mut().filter(|i| **i < new_index.len()) {
+                *index = new_index[*index];
+            }
+        }
+
+        moved
+    }
+
     /// Play the item at `index` in `self.queue`.
     ///
     /// `reshuffle`: Reshuffle the current order of the queue.
     /// `shuffle_index`: If this is true, `index` isn't actually used, but is
     /// chosen at random as a valid index in the queue.
     pub fn play(&self, mut index: usize, reshuffle: bool, shuffle_index: bool) {
+        if let Some(Playable::Episode(episode)) = self.get_current() {
+            let progress = self.spotify.get_current_progress().as_millis() as u32;
+            self.library.record_episode_progress(&episode, progress);
+        }
+        if reshuffle {
+            self.apply_playlist_playback(index);
+        }
+
         let queue_length = self.queue.read().unwrap().len();
         // The length of the queue must be bigger than 0 or gen_range panics!
         if queue_length > 0 && shuffle_index && self.get_shuffle() {
@@ -285,6 +533,15 @@ impl Queue {
             index = rng.gen_range(0..queue_length);
         }
 
+        match self.playable_index_from(index) {
+            Some(playable) => index = playable,
+            None => {
+                info!("no playable tracks left in the queue");
+                self.stop();
+                return;
+            }
+        }
+
         if let Some(track) = &self.queue.read().unwrap().get(index) {
             self.spotify.load(track, true, 0);
             let mut current = self.current_track.write().unwrap();
@@ -309,7 +566,7 @@ impl Queue {
 
                     let summary_txt = Playable::format(track, &title, &self.library);
                     let body_txt = Playable::format(track, &body, &self.library);
-                    let cover_url = track.cover_url();
+                    let cover_url = track.cover_url().filter(|_| !self.spotify.data_saver());
                     move || send_notification(&summary_txt, &body_txt, cover_url)
                 });
             }
@@ -322,6 +579,284 @@ impl Queue {
         if reshuffle && self.get_shuffle() {
             self.generate_random_order()
         }
+        self.extend_radio();
+        self.analyze_current();
+    }
+
+    /// Fetch the audio analysis of the playing track in the background if `auto_skip` is enabled,
+    /// to skip its intro and outro once it arrives.
+    fn analyze_current(&self) {
+        *self.outro.write().unwrap() = None;
+        if self.cfg.values().auto_skip.is_none() {
+            return;
+        }
+        let Some(id) = self.get_current().and_then(|playable| match playable {
+            Playable::Track(track) => track.id,
+            Playable::Episode(_) => None,
+        }) else {
+            return;
+        };
+
+        let spotify = self.spotify.clone();
+        let events = self.events.clone();
+        std::thread::spawn(move || {
+            if let Ok(analysis) = spotify.api.audio_analysis(&id) {
+                events.send(Event::Queue(QueueEvent::AudioAnalysis(id, analysis)));
+            }
+        });
+    }
+
+    /// Skip the quiet intro of the playing track and remember where its outro starts.
+    fn skip_intro(&self, id: String, analysis: &AudioAnalysis) {
+        let Some(auto_skip) = self.cfg.values().auto_skip.clone() else {
+            return;
+        };
+        // Another item may have been started while the analysis was loading.
+        let Some(current) = self
+            .get_current()
+            .filter(|playable| playable.id().as_ref() == Some(&id))
+        else {
+            return;
+        };
+
+        let threshold = auto_skip.aggressiveness.unwrap_or_default().threshold();
+        let max = auto_skip.max_seconds.unwrap_or(30).saturating_mul(1000);
+        let duration = current.duration();
+        let intro_end = if auto_skip.intro.unwrap_or(true) {
+            analysis.intro_end(threshold).min(max)
+        } else {
+            0
+        };
+        let outro_start = if auto_skip.outro.unwrap_or(true) {
+            analysis
+                .outro_start(threshold)
+                .max(duration.saturating_sub(max))
+        } else {
+            duration
+        };
+        if intro_end >= outro_start {
+            return;
+        }
+
+        let progress = self.spotify.get_current_progress().as_millis() as u32;
+        if intro_end >= progress + MIN_SKIP_MS {
+            debug!("skipping the intro of {} until {}ms", current, intro_end);
+            self.spotify.seek(intro_end);
+        }
+        if duration >= outro_start + MIN_SKIP_MS {
+            *self.outro.write().unwrap() = Some((id, outro_start));
+        }
+    }
+
+    /// Continue with the next item once the outro of the playing track is reached, if it is
+    /// skipped with `auto_skip`.
+    pub fn skip_outro(&self) {
+        let Some((id, outro_start)) = self.outro.read().unwrap().clone() else {
+            return;
+        };
+        if !matches!(self.spotify.get_current_status(), PlayerEvent::Playing(_))
+            || (self.spotify.get_current_progress().as_millis() as u32) < outro_start
+        {
+            return;
+        }
+        *self.outro.write().unwrap() = None;
+        if self.get_current().and_then(|playable| playable.id()) == Some(id) {
+            debug!("skipping the outro at {}ms", outro_start);
+            self.next(false);
+        }
+    }
+
+    /// Replace the queue with the item at `url` and play it, like opening a URI through MPRIS.
+    /// Artists play their top tracks. The item is loaded in the background, and failures are
+    /// shown in the result bar.
+    pub fn play_url(&self, url: &SpotifyUrl) {
+        let url = url.clone();
+        let spotify = self.spotify.clone();
+        let events = self.events.clone();
+        let library = self.library.clone();
+        std::thread::spawn(move || match Self::fetch_url(&spotify, &url) {
+            Some(items) => events.send(Event::Queue(QueueEvent::UrlItems(items))),
+            None => {
+                let message = tr_args("Could not load {}", &[&url.to_string()]);
+                library.run_in_ui(move |s| {
+                    s.on_layout(|_, mut layout| layout.set_result(Err(message)));
+                });
+            }
+        });
+    }
+
+    /// The items to play for `url`, in the order they are played.
+    fn fetch_url(spotify: &Spotify, url: &SpotifyUrl) -> Option<Vec<Playable>> {
+        let items: Vec<Playable> = match url.uri_type {
+            UriType::Album => {
+                let album = Album::from(&spotify.api.album(&url.id).ok()?);
+                album.tracks?.into_iter().map(Playable::Track).collect()
+            }
+            UriType::Track => {
+                let track = spotify.api.track(&url.id).ok()?;
+                vec![Playable::Track(Track::from(&track))]
+            }
+            UriType::Playlist => {
+                let mut playlist = Playlist::from(&spotify.api.playlist(&url.id).ok()?);
+                playlist.load_tracks(spotify);
+                playlist.tracks?
+            }
+            UriType::Show => {
+                let mut show: Show = (&spotify.api.show(&url.id).ok()?).into();
+                show.load_all_episodes(spotify.clone());
+                show.episodes?
+                    .into_iter()
+                    .rev()
+                    .map(Playable::Episode)
+                    .collect()
+            }
+            UriType::Episode => {
+                let episode = spotify.api.episode(&url.id).ok()?;
+                vec![Playable::Episode(Episode::from(&episode))]
+            }
+            UriType::Artist => spotify
+                .api
+                .artist_top_tracks(&url.id)
+                .ok()?
+                .into_iter()
+                .map(Playable::Track)
+                .collect(),
+        };
+        Some(items).filter(|items| !items.is_empty())
+    }
+
+    /// Replace the queue with tracks recommended for `seed` and play them once they are fetched
+    /// in the background. Until the queue is cleared, more recommendations are added whenever it's
+    /// about to run out. Failures are shown in the result bar.
+    pub fn start_radio(&self, seed: RecommendationSeed) {
+        let spotify = self.spotify.clone();
+        let events = self.events.clone();
+        let library = self.library.clone();
+        std::thread::spawn(move || {
+            match fetch_recommendations(&spotify, &[seed], &RecommendationTargets::default()) {
+                Ok(tracks) => events.send(Event::Queue(QueueEvent::RadioTracks(
+                    tracks.into_iter().map(Playable::Track).collect(),
+                    true,
+                ))),
+                Err(_) => {
+                    let message = tr("Could not load recommendations").to_string();
+                    library.run_in_ui(move |s| {
+                        s.on_layout(|_, mut layout| layout.set_result(Err(message)));
+                    });
+                }
+            }
+        });
+    }
+
+    /// Fetch more recommendations in the background if the queue is a radio and about to run out.
+    fn extend_radio(&self) {
+        if !self.radio.load(atomic::Ordering::Relaxed)
+            || self.upcoming(RADIO_MIN_UPCOMING).len() >= RADIO_MIN_UPCOMING
+            || self.radio_fetching.swap(true, atomic::Ordering::Relaxed)
+        {
+            return;
+        }
+
+        // The latest tracks seed the recommendations, so the radio drifts along with them.
+        let seeds: Vec<RecommendationSeed> = self
+            .queue
+            .read()
+            .unwrap()
+            .iter()
+            .rev()
+            .filter_map(|playable| match playable {
+                Playable::Track(track) => Some(RecommendationSeed::Track {
+                    id: track.id.clone()?,
+                    name: track.title.clone(),
+                }),
+                Playable::Episode(_) => None,
+            })
+            .take(MAX_SEEDS)
+            .collect();
+        debug!(
+            "extending radio with recommendations for {} tracks",
+            seeds.len()
+        );
+
+        let spotify = self.spotify.clone();
+        let events = self.events.clone();
+        let fetching = self.radio_fetching.clone();
+        std::thread::spawn(move || {
+            match fetch_recommendations(&spotify, &seeds, &RecommendationTargets::default()) {
+                Ok(tracks) => events.send(Event::Queue(QueueEvent::RadioTracks(
+                    tracks.into_iter().map(Playable::Track).collect(),
+                    false,
+                ))),
+                Err(_) => fetching.store(false, atomic::Ordering::Relaxed),
+            }
+        });
+    }
+
+    /// The first item in playback order, starting at `index`, that can be played. Tracks that
+    /// Spotify marked as unplayable, e.g. because of region restrictions, are skipped, as well as
+    /// explicit items in clean mode.
+    fn playable_index_from(&self, index: usize) -> Option<usize> {
+        let clean_mode = self.cfg.state().clean_mode;
+        let queue = self.queue.read().unwrap();
+        let order = self
+            .get_random_order()
+            .unwrap_or_else(|| (0..queue.len()).collect());
+        let Some(start) = order.iter().position(|&i| i == index) else {
+            return Some(index);
+        };
+
+        order[start..]
+            .iter()
+            .copied()
+            .find(|&i| match queue.get(i) {
+                Some(Playable::Track(track)) if track.is_playable == Some(false) => {
+                    info!(
+                        "skipping unplayable track {} ({:?})",
+                        track.uri, track.restriction
+                    );
+                    false
+                }
+                Some(playable) if clean_mode && playable.is_explicit() => {
+                    info!("skipping explicit item {}", playable.uri());
+                    false
+                }
+                _ => true,
+            })
+    }
+
+    /// Apply the shuffle and repeat settings configured for the playlist the item at `index` was
+    /// queued from, unless playback continues within that playlist.
+    fn apply_playlist_playback(&self, index: usize) {
+        let source = self
+            .queue
+            .read()
+            .unwrap()
+            .get(index)
+            .and_then(|playable| playable.source().cloned());
+        let Some(source) = source.filter(|source| source.uri_type == UriType::Playlist) else {
+            return;
+        };
+        if self.get_current().as_ref().and_then(Playable::source) == Some(&source) {
+            return;
+        }
+
+        let playback = {
+            let values = self.cfg.values();
+            let settings = values.playlist_playback.as_ref();
+            settings
+                .and_then(|s| s.get(&source.id).or_else(|| s.get(&source.name)))
+                .cloned()
+        };
+        let Some(playback) = playback else {
+            return;
+        };
+
+        if let Some(shuffle) = playback.shuffle.filter(|&s| s != self.get_shuffle()) {
+            self.set_shuffle(shuffle);
+        }
+        if let Some(repeat) = playback.repeat {
+            self.set_repeat(repeat);
+        }
     }
 
     /// Toggle the playback. If playback is currently stopped, this will either
@@ -352,6 +887,12 @@ impl Queue {
     /// used, and the next track will actually be played. This should be used
     /// when going to the next entry in the queue is the wanted behavior.
     pub fn next(&self, manual: bool) {
+        if !manual {
+            if let Some(Playable::Episode(episode)) = self.get_current() {
+                self.library.set_episode_played(&episode, true);
+            }
+        }
+
         let q = self.queue.read().unwrap();
         let current = *self.current_track.read().unwrap();
         let repeat = self.cfg.state().repeat;
@@ -373,10 +914,55 @@ impl Queue {
                 false,
             );
         } else {
+            self.load_fallback_playlist();
             self.spotify.stop();
         }
     }
 
+    /// Load the tracks of the `fallback_playlist` in the background after the queue ran out, so
+    /// playback continues with them. A radio adds recommendations instead.
+    fn load_fallback_playlist(&self) {
+        if self.radio.load(atomic::Ordering::Relaxed) {
+            return;
+        }
+        let Some(fallback) = self.cfg.values().fallback_playlist.clone() else {
+            return;
+        };
+        let playlist = self
+            .library
+            .playlists
+            .read()
+            .unwrap()
+            .iter()
+            .find(|p| p.id == fallback.playlist || p.name == fallback.playlist)
+            .cloned();
+        let Some(mut playlist) = playlist else {
+            info!(
+                "fallback playlist {} is not in the library",
+                fallback.playlist
+            );
+            return;
+        };
+
+        debug!("queue ran out, loading fallback playlist {}", playlist.name);
+        let spotify = self.spotify.clone();
+        let events = self.events.clone();
+        std::thread::spawn(move || {
+            playlist.load_tracks(&spotify);
+            let source = playlist.as_source();
+            let mut tracks: Vec<Playable> = playlist
+                .tracks
+                .iter()
+                .flatten()
+                .map(|track| track.with_source(&source))
+                .collect();
+            if fallback.shuffle.unwrap_or(false) {
+                tracks.shuffle(&mut rand::thread_rng());
+            }
+            events.send(Event::Queue(QueueEvent::FallbackTracks(tracks)));
+        });
+    }
+
     /// Play the previous item in the queue.
     pub fn previous(&self) {
         let q = self.queue.read().unwrap();
@@ -451,6 +1037,81 @@ impl Queue {
         }
     }
 
+    /// Get whether automix is enabled.
+    pub fn get_automix(&self) -> bool {
+        self.cfg.state().automix
+    }
+
+    /// Enable or disable automix. When enabled, the upcoming items are reordered for smooth
+    /// transitions once their audio features are loaded, and the player fades each track out and
+    /// the next one in.
+    pub fn set_automix(&self, new: bool) {
+        self.cfg.with_state_mut(|s| s.automix = new);
+        if new {
+            self.set_shuffle(false);
+            self.load_automix_features();
+        }
+        self.spotify.update_fade();
+    }
+
+    /// Load the audio features of the queued tracks in the background. The queue is reordered once
+    /// they arrive as [QueueEvent::AutomixFeatures].
+    fn load_automix_features(&self) {
+        let ids: Vec<String> = self
+            .queue
+            .read()
+            .unwrap()
+            .iter()
+            .filter_map(|p| match p {
+                Playable::Track(track) => track.id.clone(),
+                Playable::Episode(_) => None,
+            })
+            .collect();
+
+        let library = self.library.clone();
+        let events = self.events.clone();
+        std::thread::spawn(move || {
+            let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+            let features = library.audio_features(&ids);
+            events.send(Event::Queue(QueueEvent::AutomixFeatures(features)));
+        });
+    }
+
+    /// Reorder the items after the currently playing one so that each track is followed by the one
+    /// with the most similar tempo and key. Items without audio `features` are moved to the end.
+    fn automix(&self, features: &HashMap<String, AudioFeatures>) {
+        let features_of = |p: &Playable| match p {
+            Playable::Track(track) => track.id.as_ref().and_then(|id| features.get(id)),
+            Playable::Episode(_) => None,
+        };
+
+        let mut queue = self.queue.write().unwrap();
+        let start = self.get_current_index().map_or(0, |i| i + 1);
+        if start >= queue.len() {
+            return;
+        }
+
+        let (mut upcoming, unknown): (Vec<Playable>, Vec<Playable>) =
+            queue.drain(start..).partition(|p| features_of(p).is_some());
+        let mut previous = start
+            .checked_sub(1)
+            .and_then(|i| features_of(&queue[i]))
+            .cloned();
+
+        while !upcoming.is_empty() {
+            let next = previous.as_ref().map_or(0, |previous| {
+                let distance = |p: &Playable| previous.mix_distance(features_of(p).unwrap());
+                (0..upcoming.len())
+                    .min_by(|a, b| distance(&upcoming[*a]).total_cmp(&distance(&upcoming[*b])))
+                    .unwrap_or(0)
+            });
+            let item = upcoming.remove(next);
+            previous = features_of(&item).cloned();
+            queue.push(item);
+        }
+        queue.extend(unknown);
+    }
+
     /// Handle events that are specific to the queue.
     pub fn handle_event(&self, event: QueueEvent) {
         match event {
@@ -461,6 +1122,87 @@ impl Queue {
                     self.spotify.preload(&track);
                 }
             }
+            QueueEvent::RadioTracks(tracks, true) => {
+                self.clear();
+                for track in tracks {
+                    self.append(track);
+                }
+                self.radio.store(true, atomic::Ordering::Relaxed);
+                self.play(0, true, false);
+                self.library.trigger_redraw();
+            }
+            QueueEvent::RadioTracks(tracks, false) => {
+                self.radio_fetching.store(false, atomic::Ordering::Relaxed);
+                if !self.radio.load(atomic::Ordering::Relaxed) {
+                    return;
+                }
+                let queued: HashSet<String> = self
+                    .queue
+                    .read()
+                    .unwrap()
+                    .iter()
+                    .filter_map(Playable::id)
+                    .collect();
+                for track in tracks {
+                    if track.id().map_or(true, |id| !queued.contains(&id)) {
+                        self.append(track);
+                    }
+                }
+                self.library.trigger_redraw();
+            }
+            QueueEvent::AudioAnalysis(id, analysis) => self.skip_intro(id, &analysis),
+            QueueEvent::AutomixFeatures(features) => {
+                // Automix may have been disabled while the features were loading.
+                if !self.get_automix() {
+                    return;
+                }
+                self.automix(&features);
+                // The item that was preloaded before may not be the next one anymore.
+                if self.get_current_index().is_some() {
+                    if let Some(next_index) = self.next_index() {
+                        let track = self.queue.read().unwrap()[next_index].clone();
+                        self.spotify.preload(&track);
+                    }
+                }
+                self.library.trigger_redraw();
+            }
+            QueueEvent::UrlItems(items) => {
+                // Single tracks and episodes are played as they are, without shuffling.
+                let should_shuffle = self.get_shuffle() && items.len() > 1;
+                self.clear();
+                let index = self.append_next(&items);
+                self.play(index, should_shuffle, should_shuffle);
+                self.library.trigger_redraw();
+            }
+            QueueEvent::FallbackTracks(tracks) => {
+                // Something else may have been started while the playlist was loading.
+                let stopped = matches!(
+                    self.spotify.get_current_status(),
+                    PlayerEvent::Stopped | PlayerEvent::FinishedTrack
+                );
+                if !stopped || tracks.is_empty() {
+                    return;
+                }
+                let index = self.append_next(&tracks);
+                self.play(index, true, true);
+                self.library.trigger_redraw();
+            }
+        }
+    }
+
+    /// Restart the player, e.g. after switching its audio device, and continue the current item
+    /// where it was.
+    pub fn restart_player(&self) {
+        let status = self.spotify.get_current_status();
+        let progress = self.spotify.get_current_progress().as_millis() as u32;
+        self.spotify.restart_player();
+
+        if let Some(playable) = self.get_current() {
+            match status {
+                PlayerEvent::Playing(_) => self.spotify.load(&playable, true, progress),
+                PlayerEvent::Paused(_) => self.spotify.load(&playable, false, progress),
+                PlayerEvent::Stopped | PlayerEvent::FinishedTrack => {}
+            }
         }
     }
 
@@ -508,3 +1250,90 @@ pub fn send_notification(summary_txt: &str, body_txt: &str, cover_url: Option<St
         Err(e) => log::error!("Failed to send notification cover: {}", e),
     }
 }
+
+/// Where the items of a queue with `len` items end up when the ones at `indices` are moved next to
+/// each other, starting at index `to`, keeping their order. Returns the new index of each item by
+/// its previous index, along with the new indices of the moved items.
+fn shifted_indices(len: usize, indices: &[usize], to: usize) -> (Vec<usize>, Vec<usize>) {
+    let mut moved: Vec<usize> = indices.iter().copied().filter(|&i| i < len).collect();
+    moved.sort_unstable();
+    moved.dedup();
+    let to = to.min(len - moved.len());
+
+    // The previous index of the item at each new index.
+    let mut order: Vec<usize> = (0..len)
+        .filter(|i| moved.binary_search(i).is_err())
+        .collect();
+    order.splice(to..to, moved.iter().copied());
+
+    let mut new_index = vec![0; len];
+    for (new, &previous) in order.iter().enumerate() {
+        new_index[previous] = new;
+    }
+    (new_index, (to..to + moved.len()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Move the items at `indices` of `items` to `to`, like [Queue::shift_many] does.
+    fn shift(items: &str, indices: &[usize], to: usize) -> (String, Vec<usize>, Vec<usize>) {
+        let (new_index, moved) = shifted_indices(items.len(), indices, to);
+        let mut shifted = vec![' '; items.len()];
+        for (previous, item) in items.chars().enumerate() {
+            shifted[new_index[previous]] = item;
+        }
+        (shifted.into_iter().collect(), new_index, moved)
+    }
+
+    #[test]
+    fn contiguous_items_are_moved() {
+        let (items, _, moved) = shift("abcdef", &[1, 2], 3);
+        assert_eq!(items, "adebcf");
+        assert_eq!(moved, vec![3, 4]);
+    }
+
+    #[test]
+    fn non_contiguous_items_are_moved_together_in_order() {
+        let (items, _, moved) = shift("abcdef", &[4, 0, 2], 1);
+        assert_eq!(items, "bacedf");
+        assert_eq!(moved, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn items_are_moved_to_the_end() {
+        let (items, _, moved) = shift("abcdef", &[0, 3], usize::MAX);
+        assert_eq!(items, "bcefad");
+        assert_eq!(moved, vec![4, 5]);
+    }
+
+    #[test]
+    fn invalid_and_duplicate_indices_are_ignored() {
+        let (items, _, moved) = shift("abc", &[2, 2, 7], 0);
+        assert_eq!(items, "cab");
+        assert_eq!(moved, vec![0]);
+    }
+
+    #[test]
+    fn playing_item_follows_the_move() {
+        // `c` is playing and is one of the moved items, `e` is playing and stays behind.
+        let (items, new_index, _) = shift("abcdef", &[2, 5], 0);
+        assert_eq!(items, "cfabde");
+        assert_eq!(items.chars().nth(new_index[2]), Some('c'));
+        assert_eq!(items.chars().nth(new_index[4]), Some('e'));
+    }
+
+    #[test]
+    fn shuffle_order_keeps_playing_the_same_items() {
+        let before = "abcdef";
+        let random_order = [3, 0, 5, 1, 4, 2];
+        let (after, new_index, _) = shift(before, &[1, 4], 5);
+
+        let remapped: Vec<usize> = random_order.iter().map(|&i| new_index[i]).collect();
+        let played = |items: &str, order: &[usize]| -> String {
+            order.iter().map(|&i| items.as_bytes()[i] as char).collect()
+        };
+        assert_eq!(played(&after, &remapped), played(before, &random_order));
+    }
+}
@@ -0,0 +1,226 @@
+//! Importing the queue of another player for the `import queue` command, either another running
+//! ncspot instance or an MPD server.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use cursive::{CbSink, Cursive};
+use log::{debug, info};
+use rspotify::model::search::SearchResult;
+use rspotify::model::SearchType;
+
+use crate::command::ImportSource;
+use crate::config::Config;
+use crate::ext_traits::CursiveExt;
+use crate::i18n::{tr, tr_args};
+use crate::model::playable::Playable;
+use crate::model::track::Track;
+use crate::queue::Queue;
+use crate::spotify::Spotify;
+use crate::spotify_api::BATCH_TOKEN_VALIDITY;
+use crate::tasks::Tasks;
+
+/// The MPD server that is used if none is configured.
+const DEFAULT_MPD_HOST: &str = "localhost";
+const DEFAULT_MPD_PORT: u16 = 6600;
+
+/// How long to wait for the other player to answer.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// An entry of the MPD queue, with the tags needed to find it on Spotify.
+#[derive(Default, Debug)]
+struct MpdEntry {
+    file: String,
+    artist: Option<String>,
+    title: Option<String>,
+}
+
+/// Fetch the queue of another ncspot instance through its IPC socket. Without a `path`, the
+/// sockets in the runtime directory are tried until one belongs to another instance.
+#[cfg(unix)]
+fn ncspot_queue(path: Option<&str>) -> Result<Vec<Playable>, String> {
+    use std::os::unix::net::UnixStream;
+    use std::path::PathBuf;
+
+    use crate::ipc::{QueueResponse, QUEUE_REQUEST};
+
+    let request = |path: &PathBuf| -> std::io::Result<QueueResponse> {
+        let mut stream = UnixStream::connect(path)?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        writeln!(stream, "{QUEUE_REQUEST}")?;
+        // Status updates are sent on the same connection, so skip lines until the answer arrives.
+        for line in BufReader::new(stream).lines() {
+            if let Ok(response) = serde_json::from_str::<QueueResponse>(&line?) {
+                return Ok(response);
+            }
+        }
+        Err(std::io::ErrorKind::UnexpectedEof.into())
+    };
+
+    if let Some(path) = path {
+        return request(&PathBuf::from(path))
+            .map(|response| response.queue)
+            .map_err(|e| e.to_string());
+    }
+
+    let sockets = crate::utils::user_runtime_directory()
+        .and_then(|directory| std::fs::read_dir(directory).ok())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "sock"));
+    for socket in sockets {
+        match request(&socket) {
+            Ok(response) if response.pid != std::process::id() => return Ok(response.queue),
+            Ok(_) => {}
+            Err(e) => debug!("can't read queue from {:?}: {}", socket, e),
+        }
+    }
+    Err(tr("No other ncspot instance found").to_string())
+}
+
+/// Fetch the queue of the MPD server at `host` and `port`.
+fn mpd_queue(host: &str, port: u16) -> Result<Vec<MpdEntry>, String> {
+    let stream = TcpStream::connect((host, port)).map_err(|e| e.to_string())?;
+    stream
+        .set_read_timeout(Some(TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    let mut reader = BufReader::new(stream.try_clone().map_err(|e| e.to_string())?);
+    let mut writer = stream;
+
+    let mut greeting = String::new();
+    reader.read_line(&mut greeting).map_err(|e| e.to_string())?;
+    if !greeting.starts_with("OK MPD") {
+        return Err(format!("unexpected greeting: {}", greeting.trim()));
+    }
+    writeln!(writer, "playlistinfo").map_err(|e| e.to_string())?;
+
+    let mut entries: Vec<MpdEntry> = Vec::new();
+    for line in reader.lines() {
+        let line = line.map_err(|e| e.to_string())?;
+        if line == "OK" {
+            break;
+        } else if let Some(error) = line.strip_prefix("ACK ") {
+            return Err(error.to_string());
+        }
+
+        let Some((key, value)) = line.split_once(": ") else {
+            continue;
+        };
+        match key {
+            // Every entry starts with its file.
+            "file" => entries.push(MpdEntry {
+                file: value.to_string(),
+                ..Default::default()
+            }),
+            "Artist" => {
+                if let Some(entry) = entries.last_mut() {
+                    entry.artist.get_or_insert_with(|| value.to_string());
+                }
+            }
+            "Title" => {
+                if let Some(entry) = entries.last_mut() {
+                    entry.title = Some(value.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(entries)
+}
+
+/// Find the Spotify track of an MPD `entry`. Entries that are already Spotify tracks, as
+/// played by Mopidy, are looked up directly, others are searched by their artist and title.
+fn resolve(spotify: &Spotify, entry: &MpdEntry) -> Option<Playable> {
+    if let Some(id) = entry.file.strip_prefix("spotify:track:") {
+        return spotify
+            .api
+            .track(id)
+            .ok()
+            .map(|track| Playable::Track(Track::from(&track)));
+    }
+
+    let title = entry.title.as_ref()?;
+    let query = match &entry.artist {
+        Some(artist) => format!("track:\"{title}\" artist:\"{artist}\""),
+        None => format!("track:\"{title}\""),
+    };
+    match spotify.api.search(SearchType::Track, &query, 1, 0) {
+        Ok(SearchResult::Tracks(results)) => results
+            .items
+            .first()
+            .map(|track| Playable::Track(Track::from(track))),
+        _ => None,
+    }
+}
+
+/// Fetch the queue from `source` and append it to `queue` in the background. The progress and
+/// outcome are shown in the result bar. It runs as a task, so it can be cancelled.
+pub fn import(
+    source: ImportSource,
+    queue: Arc<Queue>,
+    config: Arc<Config>,
+    tasks: &Tasks,
+    sink: CbSink,
+) {
+    let task = tasks.start(tr("Importing queue").to_string());
+    thread::spawn(move || {
+        let report = |result: Result<Option<String>, String>| {
+            sink.send(Box::new(move |s: &mut Cursive| {
+                s.on_layout(|_, mut layout| layout.set_result(result));
+            }))
+            .ok();
+        };
+
+        report(Ok(Some(tr("Importing queue…").to_string())));
+        let (items, total) = match source {
+            #[cfg(unix)]
+            ImportSource::Ncspot(path) => match ncspot_queue(path.as_deref()) {
+                Ok(items) => {
+                    let total = items.len();
+                    (items, total)
+                }
+                Err(e) => return report(Err(tr_args("Could not import queue: {}", &[&e]))),
+            },
+            ImportSource::Mpd => {
+                let host = config.values().mpd_host.clone();
+                let port = config.values().mpd_port.unwrap_or(DEFAULT_MPD_PORT);
+                let entries = match mpd_queue(host.as_deref().unwrap_or(DEFAULT_MPD_HOST), port) {
+                    Ok(entries) => entries,
+                    Err(e) => return report(Err(tr_args("Could not import queue: {}", &[&e]))),
+                };
+
+                let spotify = queue.get_spotify();
+                spotify.api.ensure_token(BATCH_TOKEN_VALIDITY);
+                let mut items = Vec::new();
+                for (index, entry) in entries.iter().enumerate() {
+                    if task.is_cancelled() {
+                        return report(Ok(Some(tr("Cancelled importing the queue").to_string())));
+                    }
+                    task.set_progress(index, entries.len());
+                    report(Ok(Some(tr_args(
+                        "Importing queue: {}/{}",
+                        &[&(index + 1).to_string(), &entries.len().to_string()],
+                    ))));
+                    match resolve(&spotify, entry) {
+                        Some(playable) => items.push(playable),
+                        None => info!("no Spotify track found for {:?}", entry),
+                    }
+                }
+                (items, entries.len())
+            }
+        };
+
+        let imported = items.len();
+        for item in items {
+            queue.append(item);
+        }
+        report(Ok(Some(tr_args(
+            "Imported {} of {} items",
+            &[&imported.to_string(), &total.to_string()],
+        ))));
+    });
+}
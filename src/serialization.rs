@@ -1,4 +1,6 @@
-use std::{fs, path::Path};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
 
 pub trait Serializer {
     /// Configuration and credential file helper
@@ -56,15 +58,12 @@ impl Serializer for TomlSerializer {
     fn write<P: AsRef<Path>, T: serde::Serialize>(&self, path: P, value: T) -> Result<T, String> {
         let content =
             toml::to_string_pretty(&value).map_err(|e| format!("Failed serializing value: {e}"))?;
-        fs::write(path.as_ref(), content)
-            .map(|_| value)
-            .map_err(|e| {
-                format!(
-                    "Failed writing content to {}: {}",
-                    path.as_ref().display(),
-                    e
-                )
-            })
+        write_atomic(path, |writer| {
+            writer
+                .write_all(content.as_bytes())
+                .map_err(|e| e.to_string())
+        })
+        .map(|_| value)
     }
 }
 
@@ -86,24 +85,93 @@ impl Serializer for CborSerializer {
     }
 
     fn write<P: AsRef<Path>, T: serde::Serialize>(&self, path: P, value: T) -> Result<T, String> {
-        let file = std::fs::File::create(&path).map_err(|e| {
-            format!(
-                "Failed creating file {}: {}",
-                path.as_ref().to_string_lossy(),
-                e
-            )
-        })?;
-        serde_cbor::to_writer(file, &value)
-            .map(|_| value)
-            .map_err(|e| {
-                format!(
-                    "Failed writing content to {}: {}",
-                    path.as_ref().display(),
-                    e
-                )
-            })
+        write_atomic(path, |writer| {
+            serde_cbor::to_writer(writer, &value).map_err(|e| e.to_string())
+        })
+        .map(|_| value)
     }
 }
 
+/// Write a file through `write` without ever leaving a partially written file at `path`. The
+/// content is written to a temporary file next to `path` first, which then replaces `path` once it
+/// has been written completely. If `write` fails, `path` is left untouched.
+pub fn write_atomic<P, F>(path: P, write: F) -> Result<(), String>
+where
+    P: AsRef<Path>,
+    F: FnOnce(&mut BufWriter<File>) -> Result<(), String>,
+{
+    let path = path.as_ref();
+    let temporary_path = temporary_path(path);
+    let error = |e: String| {
+        let _ = fs::remove_file(&temporary_path);
+        format!("Failed writing content to {}: {}", path.display(), e)
+    };
+
+    let file = File::create(&temporary_path).map_err(|e| error(e.to_string()))?;
+    let mut writer = BufWriter::new(file);
+    write(&mut writer).map_err(error)?;
+    let file = writer
+        .into_inner()
+        .map_err(|e| error(e.error().to_string()))?;
+    file.sync_all().map_err(|e| error(e.to_string()))?;
+    fs::rename(&temporary_path, path).map_err(|e| error(e.to_string()))
+}
+
+/// The path of the temporary file used while writing `path`, in the same directory so the final
+/// rename doesn't cross file systems.
+fn temporary_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".tmp");
+    path.with_file_name(file_name)
+}
+
 pub static TOML: TomlSerializer = TomlSerializer {};
 pub static CBOR: CborSerializer = CborSerializer {};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_directory(name: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("ncspot-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    #[test]
+    fn interrupted_write_keeps_previous_content() {
+        let directory = test_directory("interrupted-write");
+        let path = directory.join("cache.db");
+        fs::write(&path, "previous").unwrap();
+
+        let result = write_atomic(&path, |writer| {
+            writer.write_all(b"partial").unwrap();
+            writer.flush().unwrap();
+            Err("interrupted".into())
+        });
+
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "previous");
+        assert!(!temporary_path(&path).exists());
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn leftover_temporary_file_is_ignored() {
+        let directory = test_directory("leftover-temporary");
+        let path = directory.join("userstate.cbor");
+        CBOR.write(&path, vec![1, 2, 3]).unwrap();
+        fs::write(temporary_path(&path), b"\xff\x00").unwrap();
+
+        let loaded: Vec<i32> = CBOR.load(&path).unwrap();
+        assert_eq!(loaded, vec![1, 2, 3]);
+
+        CBOR.write(&path, vec![4]).unwrap();
+        let loaded: Vec<i32> = CBOR.load(&path).unwrap();
+        assert_eq!(loaded, vec![4]);
+        assert!(!temporary_path(&path).exists());
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,85 @@
+use std::error::Error;
+
+use log::{error, info};
+use tokio_stream::StreamExt;
+use zbus::{Connection, Proxy};
+
+use crate::application::ASYNC_RUNTIME;
+use crate::spotify::{PlayerEvent, Spotify};
+
+/// Watch for the system going to sleep and the screen getting locked. If `pause` is set, playback
+/// is paused then, and not resumed automatically afterwards. Once the system resumes, the API
+/// token is renewed before the next request, as it may have expired during sleep.
+///
+/// Sleep is reported by logind on the system bus and locking by the screensaver on the session
+/// bus. Both are watched independently, so one of them being unavailable doesn't affect the other.
+pub fn watch_sleep_and_lock(spotify: Spotify, pause: bool) {
+    let runtime = ASYNC_RUNTIME.get().unwrap();
+
+    let sleep_spotify = spotify.clone();
+    runtime.spawn(async move {
+        if let Err(e) = watch_sleep(sleep_spotify, pause).await {
+            error!("sleep watcher error: {e}");
+        }
+    });
+
+    if pause {
+        runtime.spawn(async move {
+            if let Err(e) = watch_lock(spotify).await {
+                error!("screen lock watcher error: {e}");
+            }
+        });
+    }
+}
+
+async fn watch_sleep(spotify: Spotify, pause: bool) -> Result<(), Box<dyn Error + Sync + Send>> {
+    let system = Connection::system().await?;
+    let login = Proxy::new(
+        &system,
+        "org.freedesktop.login1",
+        "/org/freedesktop/login1",
+        "org.freedesktop.login1.Manager",
+    )
+    .await?;
+    let mut sleep = login.receive_signal("PrepareForSleep").await?;
+
+    while let Some(message) = sleep.next().await {
+        // The signal carries a single boolean which is true when going to sleep.
+        if message.body().deserialize::<bool>()? {
+            if pause {
+                pause_playback(&spotify);
+            }
+        } else {
+            info!("system resumed from sleep, renewing the API token");
+            spotify.api.expire_token();
+        }
+    }
+    Ok(())
+}
+
+async fn watch_lock(spotify: Spotify) -> Result<(), Box<dyn Error + Sync + Send>> {
+    let session = Connection::session().await?;
+    let screensaver = Proxy::new(
+        &session,
+        "org.freedesktop.ScreenSaver",
+        "/org/freedesktop/ScreenSaver",
+        "org.freedesktop.ScreenSaver",
+    )
+    .await?;
+    let mut lock = screensaver.receive_signal("ActiveChanged").await?;
+
+    while let Some(message) = lock.next().await {
+        // The signal carries a single boolean which is true when the screen gets locked.
+        if message.body().deserialize::<bool>()? {
+            pause_playback(&spotify);
+        }
+    }
+    Ok(())
+}
+
+fn pause_playback(spotify: &Spotify) {
+    if matches!(spotify.get_current_status(), PlayerEvent::Playing(_)) {
+        info!("system is going to sleep or being locked, pausing playback");
+        spotify.pause();
+    }
+}
@@ -13,6 +13,7 @@ use librespot_playback::audio_backend;
 use librespot_playback::audio_backend::SinkBuilder;
 use librespot_playback::config::Bitrate;
 use librespot_playback::config::PlayerConfig;
+use librespot_playback::config::VolumeCtrl;
 use librespot_playback::mixer::softmixer::SoftMixer;
 use librespot_playback::mixer::MixerConfig;
 use librespot_playback::player::Player;
@@ -29,11 +30,15 @@ use crate::model::playable::Playable;
 use crate::mpris::{MprisCommand, MprisManager};
 use crate::spotify_api::WebApi;
 use crate::spotify_worker::{Worker, WorkerCommand};
+use crate::wellbeing::ListeningTime;
 
 /// One percent of the maximum supported [Player] volume, used when setting the volume to a certain
 /// percent.
 pub const VOLUME_PERCENT: u16 = ((u16::MAX as f64) * 1.0 / 100.0) as u16;
 
+/// The bitrate that is streamed at while the data saver is enabled, in kbit/s.
+pub const DATA_SAVER_BITRATE: u32 = 96;
+
 /// Events sent by the [Player].
 #[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum PlayerEvent {
@@ -43,6 +48,20 @@ pub enum PlayerEvent {
     FinishedTrack,
 }
 
+/// State of the audio buffer of the [Player], to help diagnose stuttering playback.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BufferState {
+    #[default]
+    Ready,
+    /// The current track is being buffered before it can be played.
+    Loading,
+    /// The next track has been preloaded, so it can start without a gap.
+    Preloaded,
+    /// The position of the current track had to be corrected, usually because the audio buffer
+    /// ran empty.
+    Underrun,
+}
+
 /// Wrapper around a worker thread that exposes methods to safely control it.
 #[derive(Clone)]
 pub struct Spotify {
@@ -61,6 +80,18 @@ pub struct Spotify {
     since: Arc<RwLock<Option<SystemTime>>>,
     /// Channel to send commands to the worker thread.
     channel: Arc<RwLock<Option<mpsc::UnboundedSender<WorkerCommand>>>>,
+    /// The device the audio backend plays on, or the backend's default if unset.
+    audio_device: Arc<RwLock<Option<String>>>,
+    /// The bitrate tracks are streamed at, in kbit/s.
+    bitrate: Arc<RwLock<u32>>,
+    /// Whether to use as little data as possible, e.g. on tethered connections.
+    data_saver: Arc<RwLock<bool>>,
+    /// Whether the session is private, so Spotify doesn't share what is being listened to.
+    private_session: Arc<RwLock<bool>>,
+    /// State of the audio buffer of the [Player] owned by the worker thread.
+    buffer_state: Arc<RwLock<BufferState>>,
+    /// How long has been listened today and since the last break.
+    listening: Arc<RwLock<ListeningTime>>,
 }
 
 impl Spotify {
@@ -80,37 +111,48 @@ impl Spotify {
             elapsed: Arc::new(RwLock::new(None)),
             since: Arc::new(RwLock::new(None)),
             channel: Arc::new(RwLock::new(None)),
+            audio_device: Arc::new(RwLock::new(cfg.values().backend_device.clone())),
+            bitrate: Arc::new(RwLock::new(320)),
+            data_saver: Arc::new(RwLock::new(cfg.values().data_saver.unwrap_or(false))),
+            private_session: Arc::new(RwLock::new(cfg.values().private_session.unwrap_or(false))),
+            buffer_state: Arc::new(RwLock::new(BufferState::Ready)),
+            listening: Default::default(),
         };
 
-        let (user_tx, user_rx) = oneshot::channel();
-        spotify.start_worker(Some(user_tx))?;
-        let user = ASYNC_RUNTIME.get().unwrap().block_on(user_rx).ok();
-        let volume = cfg.state().volume;
-        spotify.set_volume(volume, true);
+        let bitrate = cfg.values().bitrate.unwrap_or(320);
+        if Bitrate::from_str(&bitrate.to_string()).is_ok() {
+            *spotify.bitrate.write().unwrap() = bitrate;
+        } else {
+            error!("invalid bitrate, will use 320 instead")
+        }
 
         spotify.api.set_worker_channel(spotify.channel.clone());
-        spotify
-            .api
-            .update_token()
-            .map(move |h| ASYNC_RUNTIME.get().unwrap().block_on(h).ok());
+        spotify.start_worker()?;
+        let volume = spotify.device_volume().unwrap_or(cfg.state().volume);
+        spotify.set_volume(volume, true);
 
-        spotify.api.set_user(user);
+        if let Some(ttl) = cfg.values().api_cache_ttl {
+            spotify.api.set_cache_ttl(ttl);
+        }
+        if let Some(market) = cfg.values().market.as_deref() {
+            if let Err(e) = spotify.api.set_market(market) {
+                error!("{}", e);
+            }
+        }
 
         Ok(spotify)
     }
 
-    /// Start the worker thread. If `user_tx` is given, it will receive the username of the logged
-    /// in user.
-    pub fn start_worker(
-        &self,
-        user_tx: Option<oneshot::Sender<String>>,
-    ) -> Result<(), Box<dyn Error>> {
+    /// Start the worker thread. [Event::Connected] is sent once its session is established, or
+    /// [Event::SessionDied] if that fails.
+    pub fn start_worker(&self) -> Result<(), Box<dyn Error>> {
+        let (user_tx, user_rx) = oneshot::channel();
         let (tx, rx) = mpsc::unbounded_channel();
         *self.channel.write().unwrap() = Some(tx);
         let worker_channel = self.channel.clone();
         let cfg = self.cfg.clone();
         let events = self.events.clone();
-        let volume = self.volume();
+        let volume = self.mixer_volume(self.volume());
         let credentials = self.credentials.clone();
         let backend_name = cfg.values().backend.clone();
         let backend = Self::init_backend(backend_name)?;
@@ -120,13 +162,120 @@ impl Spotify {
             rx,
             cfg,
             credentials,
+            self.private_session(),
             user_tx,
             volume,
             backend,
+            self.audio_device.clone(),
+            self.bitrate.clone(),
+            self.data_saver.clone(),
         ));
+        self.update_fade();
+
+        // Finish connecting in the background, so the user interface can be shown in the meantime.
+        let api = self.api.clone();
+        let events = self.events.clone();
+        ASYNC_RUNTIME.get().unwrap().spawn(async move {
+            // If the session can't be created, the worker reports why.
+            let Ok(user) = user_rx.await else {
+                return;
+            };
+            api.set_user(Some(user));
+            if let Some(handle) = api.update_token() {
+                handle.await.ok();
+            }
+            events.send(Event::Connected);
+        });
         Ok(())
     }
 
+    /// The device the audio backend plays on, or `None` for the backend's default.
+    pub fn audio_device(&self) -> Option<String> {
+        self.audio_device.read().unwrap().clone()
+    }
+
+    /// Play on `device` from now on, or on the backend's default if `None`. Only takes effect
+    /// once the player is restarted.
+    pub fn set_audio_device(&self, device: Option<String>) {
+        info!("switching audio device to {:?}", device);
+        *self.audio_device.write().unwrap() = device;
+        if let Some(volume) = self.device_volume() {
+            self.set_volume(volume, true);
+        }
+    }
+
+    /// Identifies the audio backend and device to remember their volume.
+    fn device_key(&self) -> String {
+        format!(
+            "{}:{}",
+            self.cfg.values().backend.as_deref().unwrap_or("default"),
+            self.audio_device().as_deref().unwrap_or("default")
+        )
+    }
+
+    /// The volume that was last used with the current audio backend and device.
+    fn device_volume(&self) -> Option<u16> {
+        self.cfg
+            .state()
+            .device_volumes
+            .get(&self.device_key())
+            .copied()
+    }
+
+    /// The bitrate tracks are streamed at, in kbit/s.
+    pub fn bitrate(&self) -> u32 {
+        Self::effective_bitrate(&self.bitrate, &self.data_saver)
+    }
+
+    fn effective_bitrate(bitrate: &RwLock<u32>, data_saver: &RwLock<bool>) -> u32 {
+        let bitrate = *bitrate.read().unwrap();
+        if *data_saver.read().unwrap() {
+            bitrate.min(DATA_SAVER_BITRATE)
+        } else {
+            bitrate
+        }
+    }
+
+    /// Stream at `bitrate` kbit/s from now on. Only takes effect once the player is restarted.
+    pub fn set_bitrate(&self, bitrate: u32) {
+        info!("switching bitrate to {}", bitrate);
+        *self.bitrate.write().unwrap() = bitrate;
+    }
+
+    /// Whether the data saver is enabled.
+    pub fn data_saver(&self) -> bool {
+        *self.data_saver.read().unwrap()
+    }
+
+    /// Enable or disable the data saver. It lowers the bitrate once the player is restarted, and
+    /// skips downloads of cover art and library lists that were synced recently.
+    pub fn set_data_saver(&self, enabled: bool) {
+        info!("data saver: {}", enabled);
+        *self.data_saver.write().unwrap() = enabled;
+    }
+
+    /// Whether the session is private.
+    pub fn private_session(&self) -> bool {
+        *self.private_session.read().unwrap()
+    }
+
+    /// Enable or disable the private session. Only takes effect once the session is reconnected.
+    pub fn set_private_session(&self, enabled: bool) {
+        info!("private session: {}", enabled);
+        *self.private_session.write().unwrap() = enabled;
+    }
+
+    /// Recreate the player with the current audio device and bitrate. The current track has to be
+    /// loaded again afterwards.
+    pub fn restart_player(&self) {
+        self.send_worker(WorkerCommand::RestartPlayer);
+    }
+
+    /// Whether the worker thread is running.
+    pub fn has_worker(&self) -> bool {
+        self.channel.read().unwrap().is_some()
+    }
+
     /// Generate the librespot [SessionConfig] used when creating a [Session].
     pub fn session_config(cfg: &config::Config) -> SessionConfig {
         let mut session_config = librespot_core::SessionConfig {
@@ -161,10 +310,11 @@ impl Spotify {
     }
 
     /// Create a [Session] that respects the user configuration in `cfg` and with the given
-    /// credentials.
+    /// credentials. A `private_session` isn't shared with the followers of the user.
     async fn create_session(
         cfg: &config::Config,
         credentials: Credentials,
+        private_session: bool,
     ) -> Result<Session, librespot_core::Error> {
         let librespot_cache_path = config::cache_path("librespot");
         let audio_cache_path = if let Some(false) = cfg.values().audio_cache {
@@ -184,7 +334,12 @@ impl Spotify {
         debug!("opening spotify session");
         let session_config = Self::session_config(cfg);
         let session = Session::new(session_config, Some(cache));
-        session.connect(credentials, true).await.map(|_| session)
+        session.connect(credentials, true).await?;
+        if private_session {
+            debug!("starting private session");
+            session.set_user_attribute("private-session", "1");
+        }
+        Ok(session)
     }
 
     /// Create and initialize the requested audio backend.
@@ -222,57 +377,69 @@ impl Spotify {
         commands: mpsc::UnboundedReceiver<WorkerCommand>,
         cfg: Arc<config::Config>,
         credentials: Credentials,
-        user_tx: Option<oneshot::Sender<String>>,
+        private_session: bool,
+        user_tx: oneshot::Sender<String>,
         volume: u16,
         backend: SinkBuilder,
+        audio_device: Arc<RwLock<Option<String>>>,
+        bitrate: Arc<RwLock<u32>>,
+        data_saver: Arc<RwLock<bool>>,
     ) {
-        let bitrate_str = cfg.values().bitrate.unwrap_or(320).to_string();
-        let bitrate = Bitrate::from_str(&bitrate_str);
-        if bitrate.is_err() {
-            error!("invalid bitrate, will use 320 instead")
-        }
-
         let player_config = PlayerConfig {
             gapless: cfg.values().gapless.unwrap_or(true),
-            bitrate: bitrate.unwrap_or(Bitrate::Bitrate320),
             normalisation: cfg.values().volnorm.unwrap_or(false),
             normalisation_pregain_db: cfg.values().volnorm_pregain.unwrap_or(0.0),
             ..Default::default()
         };
 
-        let session = Self::create_session(&cfg, credentials)
-            .await
-            .expect("Could not create session");
-        user_tx.map(|tx| tx.send(session.username()));
+        let session = match Self::create_session(&cfg, credentials, private_session).await {
+            Ok(session) => session,
+            Err(e) => {
+                error!("could not create session: {}", e);
+                *worker_channel.write().unwrap() = None;
+                events.send(Event::SessionDied(e.to_string()));
+                return;
+            }
+        };
+        user_tx.send(session.username()).ok();
 
         let create_mixer = librespot_playback::mixer::find(Some(SoftMixer::NAME))
             .expect("could not create softvol mixer");
-        let mixer = create_mixer(MixerConfig::default());
+        // The volume curve is applied by `mixer_volume()`.
+        let mixer = create_mixer(MixerConfig {
+            volume_ctrl: VolumeCtrl::Linear,
+            ..Default::default()
+        });
         mixer.set_volume(volume);
 
-        let audio_format: librespot_playback::config::AudioFormat = Default::default();
-        let player = Player::new(
-            player_config,
-            session.clone(),
-            mixer.get_soft_volume(),
-            move || (backend)(cfg.values().backend_device.clone(), audio_format),
-        );
-        let player_events = player.get_player_event_channel();
-
-        let mut worker = Worker::new(
-            events.clone(),
-            player_events,
-            commands,
-            session,
-            player,
-            mixer,
-        );
+        let create_player = {
+            let session = session.clone();
+            let mixer = mixer.clone();
+            Box::new(move || {
+                let device = audio_device.read().unwrap().clone();
+                let bitrate = Self::effective_bitrate(&bitrate, &data_saver);
+                let bitrate =
+                    Bitrate::from_str(&bitrate.to_string()).unwrap_or(Bitrate::Bitrate320);
+                let audio_format: librespot_playback::config::AudioFormat = Default::default();
+                Player::new(
+                    PlayerConfig {
+                        bitrate,
+                        ..player_config.clone()
+                    },
+                    session.clone(),
+                    mixer.get_soft_volume(),
+                    move || (backend)(device, audio_format),
+                )
+            })
+        };
+
+        let mut worker = Worker::new(events.clone(), commands, session, create_player, mixer);
         debug!("worker thread ready.");
-        worker.run_loop().await;
+        let reason = worker.run_loop().await;
 
-        error!("worker thread died, requesting restart");
+        error!("worker thread died: {}", reason);
         *worker_channel.write().unwrap() = None;
-        events.send(Event::SessionDied)
+        events.send(Event::SessionDied(reason))
     }
 
     /// Get the current playback status of the [Player].
@@ -281,6 +448,25 @@ impl Spotify {
         (*status).clone()
     }
 
+    /// Get the state of the audio buffer of the [Player].
+    pub fn get_buffer_state(&self) -> BufferState {
+        *self.buffer_state.read().unwrap()
+    }
+
+    pub fn update_buffer_state(&self, state: BufferState) {
+        *self.buffer_state.write().unwrap() = state;
+    }
+
+    /// Get the time that has been listened today and since the last break.
+    pub fn listening_time(&self) -> ListeningTime {
+        *self.listening.read().unwrap()
+    }
+
+    /// Get the reminder to take a break or about the listening goal that is due, if any.
+    pub fn listening_reminder(&self) -> Option<String> {
+        self.listening.write().unwrap().reminder(&self.cfg.values())
+    }
+
     /// Get the total amount of the current [Playable] that has been played.
     pub fn get_current_progress(&self) -> Duration {
         self.get_elapsed().unwrap_or_else(|| Duration::from_secs(0))
@@ -332,12 +518,20 @@ impl Spotify {
             PlayerEvent::Paused(position) => {
                 self.set_elapsed(Some(position));
                 self.set_since(None);
+                self.listening.write().unwrap().stop();
             }
             PlayerEvent::Playing(playback_start) => {
                 self.set_since(Some(playback_start));
                 self.set_elapsed(None);
+                self.listening.write().unwrap().start();
             }
-            PlayerEvent::Stopped | PlayerEvent::FinishedTrack => {
+            PlayerEvent::Stopped => {
+                self.set_elapsed(None);
+                self.set_since(None);
+                self.listening.write().unwrap().stop();
+            }
+            // The next track usually starts right away, so the listening time keeps counting.
+            PlayerEvent::FinishedTrack => {
                 self.set_elapsed(None);
                 self.set_since(None);
             }
@@ -426,11 +620,38 @@ impl Spotify {
         self.seek(std::cmp::max(0, new) as u32);
     }
 
+    /// Seek to `percent` of the current [Playable], which is `duration_ms` long.
+    pub fn seek_percent(&self, percent: u32, duration_ms: u32) {
+        let position = u64::from(duration_ms) * u64::from(percent.min(100)) / 100;
+        self.seek(position as u32);
+    }
+
+    /// Seek relatively by `delta` percent of the current [Playable], which is `duration_ms` long.
+    pub fn seek_relative_percent(&self, delta: i32, duration_ms: u32) {
+        let delta = i64::from(duration_ms) * i64::from(delta) / 100;
+        self.seek_relative(delta as i32);
+    }
+
     /// Get the current volume of the [Player].
     pub fn volume(&self) -> u16 {
         self.cfg.state().volume
     }
 
+    /// Map `volume` to the volume of the mixer with the configured volume curve.
+    fn mixer_volume(&self, volume: u16) -> u16 {
+        let cfg = self.cfg.values();
+        cfg.volume_curve
+            .unwrap_or_default()
+            .apply(volume, cfg.volume_curve_factor)
+    }
+
+    /// Let MPRIS clients know the metadata changed, e.g. the position of the playing item in the
+    /// queue.
+    #[cfg(feature = "mpris")]
+    pub fn notify_metadata(&self) {
+        self.send_mpris(MprisCommand::EmitMetadataStatus);
+    }
+
     /// Send a Seeked signal on Mpris interface
     #[cfg(feature = "mpris")]
     pub fn notify_seeked(&self, position_ms: u32) {
@@ -448,8 +669,12 @@ impl Spotify {
     /// the update.
     pub fn set_volume(&self, volume: u16, notify: bool) {
         info!("setting volume to {}", volume);
-        self.cfg.with_state_mut(|s| s.volume = volume);
-        self.send_worker(WorkerCommand::SetVolume(volume));
+        let device = self.device_key();
+        self.cfg.with_state_mut(|s| {
+            s.volume = volume;
+            s.device_volumes.insert(device.clone(), volume);
+        });
+        self.send_worker(WorkerCommand::SetVolume(self.mixer_volume(volume)));
         // HACK: This is a bit of a hack to prevent duplicate update signals when updating from the
         // MPRIS implementation.
         if notify {
@@ -458,6 +683,15 @@ impl Spotify {
         }
     }
 
+    /// Tell the [Player] whether to fade tracks out and in, which is the case while automix is
+    /// enabled.
+    pub fn update_fade(&self) {
+        let automix = self.cfg.state().automix;
+        let fade = automix
+            .then(|| Duration::from_secs(self.cfg.values().automix_fade.unwrap_or(8).into()));
+        self.send_worker(WorkerCommand::SetFade(fade));
+    }
+
     /// Preload the given [Playable] in the [Player]. This makes sure it can be played immediately
     /// after the current [Playable] is finished.
     pub fn preload(&self, track: &Playable) {
@@ -2,43 +2,116 @@ use std::collections::HashSet;
 use std::iter::FromIterator;
 use std::sync::{Arc, RwLock};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crate::application::ASYNC_RUNTIME;
-use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use chrono::{DateTime, Utc};
 use log::{debug, error, info};
 use rspotify::http::HttpError;
 use rspotify::model::{
-    AlbumId, AlbumType, ArtistId, CursorBasedPage, EpisodeId, FullAlbum, FullArtist, FullEpisode,
-    FullPlaylist, FullShow, FullTrack, ItemPositions, Market, Page, PlayableId, PlaylistId,
-    PlaylistResult, PrivateUser, Recommendations, SavedAlbum, SavedTrack, SearchResult, SearchType,
-    Show, ShowId, SimplifiedTrack, TrackId, UserId,
+    AlbumId, AlbumType, ArtistId, Country, CursorBasedPage, EpisodeId, FullAlbum, FullArtist,
+    FullEpisode, FullPlaylist, FullShow, FullTrack, ItemPositions, Market, Page, PlayableId,
+    PlaylistId, PlaylistResult, PrivateUser, Recommendations, RecommendationsAttribute, SavedAlbum,
+    SavedTrack, SearchResult, SearchType, Show, ShowId, SimplifiedTrack, TrackId, UserId,
 };
 use rspotify::{prelude::*, AuthCodeSpotify, ClientError, ClientResult, Config, Token};
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 
+use crate::api_cache::{self, ApiCache};
 use crate::model::album::Album;
 use crate::model::artist::Artist;
+use crate::model::audio_analysis::AudioAnalysis;
+use crate::model::audio_features::AudioFeatures;
 use crate::model::category::Category;
 use crate::model::episode::Episode;
 use crate::model::playable::Playable;
 use crate::model::playlist::Playlist;
 use crate::model::track::Track;
+use crate::spotify::UriType;
+use crate::spotify_url::SpotifyUrl;
 use crate::spotify_worker::WorkerCommand;
 use crate::ui::pagination::{ApiPage, ApiResult};
 
+/// How long the token has to stay valid to not be renewed before a request.
+const TOKEN_RENEWAL_MARGIN: Duration = Duration::from_secs(5 * 60);
+
+/// How long the token has to stay valid to not be renewed before a batch of requests that can
+/// take a while, like fetching the whole library.
+pub const BATCH_TOKEN_VALIDITY: Duration = Duration::from_secs(20 * 60);
+
+/// How long the current token is valid for.
+#[derive(Clone, Copy, Debug)]
+struct TokenValidity {
+    /// When the token was received, on the monotonic and on the system clock.
+    received: (Instant, DateTime<Utc>),
+    /// How long the token was valid for when it was received.
+    lifetime: Duration,
+}
+
+impl TokenValidity {
+    /// A token that has already expired, so it's renewed before the next request.
+    fn expired() -> Self {
+        Self {
+            received: (Instant::now(), Utc::now()),
+            lifetime: Duration::ZERO,
+        }
+    }
+
+    /// How long the token stays valid from now on.
+    fn remaining(&self) -> Duration {
+        let (instant, time) = self.received;
+        // The system clock can drift or be changed, so the monotonic clock is used. That clock
+        // stops while the system is suspended though, which the system clock catches.
+        let elapsed = instant
+            .elapsed()
+            .max((Utc::now() - time).to_std().unwrap_or_default());
+        self.lifetime.saturating_sub(elapsed)
+    }
+}
+
+/// Target values for the audio attributes of recommended tracks.
+#[derive(Clone, Debug, Default)]
+pub struct RecommendationTargets {
+    /// Perceptual measure of intensity and activity, from 0.0 to 1.0.
+    pub energy: Option<f32>,
+    /// Tempo in beats per minute.
+    pub tempo: Option<f32>,
+    /// Musical positiveness, from 0.0 to 1.0.
+    pub valence: Option<f32>,
+}
+
+impl RecommendationTargets {
+    fn attributes(&self) -> Vec<RecommendationsAttribute> {
+        let mut attributes = Vec::new();
+        if let Some(energy) = self.energy {
+            attributes.push(RecommendationsAttribute::TargetEnergy(energy));
+        }
+        if let Some(tempo) = self.tempo {
+            attributes.push(RecommendationsAttribute::TargetTempo(tempo));
+        }
+        if let Some(valence) = self.valence {
+            attributes.push(RecommendationsAttribute::TargetValence(valence));
+        }
+        attributes
+    }
+}
+
 /// Convenient wrapper around the rspotify web API functionality.
 #[derive(Clone)]
 pub struct WebApi {
     /// Rspotify web API.
     api: AuthCodeSpotify,
-    /// The username of the logged in user.
-    user: Option<String>,
+    /// The username of the logged in user, known once the session is established.
+    user: Arc<RwLock<Option<String>>>,
     /// Sender of the mpsc channel to the [Spotify](crate::spotify::Spotify) worker thread.
     worker_channel: Arc<RwLock<Option<mpsc::UnboundedSender<WorkerCommand>>>>,
-    /// Time at which the token expires.
-    token_expiration: Arc<RwLock<DateTime<Utc>>>,
+    /// How long the token is valid for.
+    token_validity: Arc<RwLock<TokenValidity>>,
+    /// The market to request content for, instead of the one of the user's account.
+    market: Option<Country>,
+    /// Responses of lookups that rarely change.
+    cache: Arc<ApiCache>,
 }
 
 impl Default for WebApi {
@@ -54,9 +127,11 @@ impl Default for WebApi {
         );
         Self {
             api,
-            user: None,
+            user: Arc::new(RwLock::new(None)),
             worker_channel: Arc::new(RwLock::new(None)),
-            token_expiration: Arc::new(RwLock::new(Utc::now())),
+            token_validity: Arc::new(RwLock::new(TokenValidity::expired())),
+            market: None,
+            cache: Arc::new(ApiCache::new(api_cache::DEFAULT_TTL)),
         }
     }
 }
@@ -67,8 +142,64 @@ impl WebApi {
     }
 
     /// Set the username for use with the API.
-    pub fn set_user(&mut self, user: Option<String>) {
-        self.user = user;
+    pub fn set_user(&self, user: Option<String>) {
+        *self.user.write().unwrap() = user;
+    }
+
+    /// Request content for the market with the ISO 3166-1 alpha-2 `country` code instead of the
+    /// one of the user's account, e.g. when connecting through a VPN.
+    pub fn set_market(&mut self, country: &str) -> Result<(), String> {
+        let code = serde_json::Value::String(country.to_uppercase());
+        let country =
+            serde_json::from_value(code).map_err(|_| format!("Invalid market \"{country}\""))?;
+        self.market = Some(country);
+        Ok(())
+    }
+
+    fn market(&self) -> Market {
+        self.market.map_or(Market::FromToken, Market::Country)
+    }
+
+    /// The key of the cached response for the item of `kind` with `id`. Responses differ between
+    /// markets, so the market is part of the key.
+    fn cache_key(&self, kind: &str, id: &str) -> String {
+        match &self.market {
+            Some(country) => format!("{kind}:{id}:{country:?}"),
+            None => format!("{kind}:{id}"),
+        }
+    }
+
+    /// Keep responses of lookups for `ttl` seconds, or don't cache them at all if it is 0.
+    pub fn set_cache_ttl(&mut self, ttl: u64) {
+        self.cache = Arc::new(ApiCache::new(ttl));
+    }
+
+    /// Drop all cached responses, so they are fetched again.
+    pub fn clear_cache(&self) {
+        self.cache.clear();
+    }
+
+    /// Drop the cached response for the item at `url` and fetch it again. Returns whether it could
+    /// be fetched.
+    pub fn refetch(&self, url: &SpotifyUrl) -> bool {
+        let kind = match url.uri_type {
+            UriType::Album => "album",
+            UriType::Artist => "artist",
+            UriType::Track => "track",
+            UriType::Show => "show",
+            UriType::Episode => "episode",
+            // Playlists aren't cached, as they change too often.
+            UriType::Playlist => return true,
+        };
+        self.cache.remove(&self.cache_key(kind, &url.id));
+        match url.uri_type {
+            UriType::Album => self.album(&url.id).is_ok(),
+            UriType::Artist => self.artist(&url.id).is_ok(),
+            UriType::Track => self.track(&url.id).is_ok(),
+            UriType::Show => self.show(&url.id).is_ok(),
+            UriType::Episode => self.episode(&url.id).is_ok(),
+            UriType::Playlist => true,
+        }
     }
 
     /// Set the sending end of the channel to the worker thread, managed by
@@ -82,25 +213,44 @@ impl WebApi {
 
     /// Update the authentication token when it expires.
     pub fn update_token(&self) -> Option<JoinHandle<()>> {
-        {
-            let token_expiration = self.token_expiration.read().unwrap();
-            let now = Utc::now();
-            let delta = *token_expiration - now;
-
-            // token is valid for 5 more minutes, renewal is not necessary yet
-            if delta.num_seconds() > 60 * 5 {
-                return None;
-            }
+        self.update_token_if_expiring(TOKEN_RENEWAL_MARGIN)
+    }
 
-            info!("Token will expire in {}, renewing", delta);
+    /// Update the authentication token if it expires within `validity`.
+    fn update_token_if_expiring(&self, validity: Duration) -> Option<JoinHandle<()>> {
+        let remaining = self.token_validity.read().unwrap().remaining();
+        if remaining > validity {
+            return None;
+        }
+        info!("Token will expire in {:?}, renewing", remaining);
+        self.renew_token()
+    }
+
+    /// Make sure the token stays valid for at least `validity`, renewing it and waiting for the
+    /// new one if necessary. Used before batches of requests, which could otherwise all try to
+    /// renew the token at once or run into its expiration halfway through.
+    pub fn ensure_token(&self, validity: Duration) {
+        let handle = self.update_token_if_expiring(validity);
+        // Blocking on the runtime from one of its own threads would panic.
+        if let (Some(handle), Err(_)) = (handle, tokio::runtime::Handle::try_current()) {
+            ASYNC_RUNTIME.get().unwrap().block_on(handle).ok();
         }
+    }
+
+    /// Consider the token expired, so it's renewed before the next request. Used when the
+    /// system resumes from suspend, as the token may have expired in the meantime.
+    pub fn expire_token(&self) {
+        *self.token_validity.write().unwrap() = TokenValidity::expired();
+    }
 
+    /// Request a new authentication token, regardless of when the current one expires.
+    fn renew_token(&self) -> Option<JoinHandle<()>> {
         let (token_tx, token_rx) = std::sync::mpsc::channel();
         let cmd = WorkerCommand::RequestToken(token_tx);
         if let Some(channel) = self.worker_channel.read().unwrap().as_ref() {
             channel.send(cmd).unwrap();
             let api_token = self.api.token.clone();
-            let api_token_expiration = self.token_expiration.clone();
+            let api_token_validity = self.token_validity.clone();
             Some(ASYNC_RUNTIME.get().unwrap().spawn_blocking(move || {
                 if let Ok(Some(token)) = token_rx.recv() {
                     *api_token.lock().unwrap() = Some(Token {
@@ -110,8 +260,10 @@ impl WebApi {
                         expires_at: None,
                         refresh_token: None,
                     });
-                    *api_token_expiration.write().unwrap() =
-                        Utc::now() + ChronoDuration::from_std(token.expires_in).unwrap();
+                    *api_token_validity.write().unwrap() = TokenValidity {
+                        received: (Instant::now(), Utc::now()),
+                        lifetime: token.expires_in,
+                    };
                 } else {
                     error!("Failed to update token");
                 }
@@ -142,8 +294,10 @@ impl WebApi {
                             api_call(&self.api).ok()
                         }
                         401 => {
+                            // The token was rejected, so it has expired no matter what the
+                            // clocks say.
                             debug!("token unauthorized. trying refresh..");
-                            self.update_token()
+                            self.renew_token()
                                 .and_then(move |_| api_call(&self.api).ok())
                         }
                         _ => {
@@ -215,6 +369,28 @@ impl WebApi {
         .ok_or(())
     }
 
+    /// Move the item at position `range_start` of the playlist with `playlist_id` in front of the
+    /// item at position `insert_before`. Returns the new snapshot id of the playlist.
+    pub fn playlist_reorder_items(
+        &self,
+        playlist_id: &str,
+        snapshot_id: &str,
+        range_start: usize,
+        insert_before: usize,
+    ) -> Result<String, ()> {
+        self.api_with_retry(|api| {
+            api.playlist_reorder_items(
+                PlaylistId::from_id(playlist_id).unwrap(),
+                Some(range_start as i32),
+                Some(insert_before as i32),
+                None,
+                Some(snapshot_id),
+            )
+        })
+        .map(|result| result.snapshot_id)
+        .ok_or(())
+    }
+
     /// Set the playlist with `id` to contain only `tracks`. If the playlist already contains
     /// tracks, they will be removed.
     pub fn overwrite_playlist(&self, id: &str, tracks: &[Playable]) {
@@ -276,9 +452,10 @@ impl WebApi {
         public: Option<bool>,
         description: Option<&str>,
     ) -> Result<String, ()> {
+        let user = self.user.read().unwrap().clone().ok_or(())?;
         let result = self.api_with_retry(|api| {
             api.user_playlist_create(
-                UserId::from_id(self.user.as_ref().unwrap()).unwrap(),
+                UserId::from_id(&user).unwrap(),
                 name,
                 public,
                 None,
@@ -290,53 +467,98 @@ impl WebApi {
 
     /// Fetch the album with the given `album_id`.
     pub fn album(&self, album_id: &str) -> Result<FullAlbum, ()> {
-        debug!("fetching album {}", album_id);
         let aid = AlbumId::from_id(album_id).map_err(|_| ())?;
-        self.api_with_retry(|api| api.album(aid.clone(), Some(Market::FromToken)))
-            .ok_or(())
+        self.cache
+            .get_or_fetch(&self.cache_key("album", album_id), || {
+                debug!("fetching album {}", album_id);
+                self.api_with_retry(|api| api.album(aid.clone(), Some(self.market())))
+                    .ok_or(())
+            })
     }
 
     /// Fetch the artist with the given `artist_id`.
     pub fn artist(&self, artist_id: &str) -> Result<FullArtist, ()> {
         let aid = ArtistId::from_id(artist_id).map_err(|_| ())?;
-        self.api_with_retry(|api| api.artist(aid.clone())).ok_or(())
+        self.cache
+            .get_or_fetch(&self.cache_key("artist", artist_id), || {
+                self.api_with_retry(|api| api.artist(aid.clone())).ok_or(())
+            })
     }
 
     /// Fetch the playlist with the given `playlist_id`.
     pub fn playlist(&self, playlist_id: &str) -> Result<FullPlaylist, ()> {
         let pid = PlaylistId::from_id(playlist_id).map_err(|_| ())?;
-        self.api_with_retry(|api| api.playlist(pid.clone(), None, Some(Market::FromToken)))
+        self.api_with_retry(|api| api.playlist(pid.clone(), None, Some(self.market())))
             .ok_or(())
     }
 
     /// Fetch the track with the given `track_id`.
     pub fn track(&self, track_id: &str) -> Result<FullTrack, ()> {
         let tid = TrackId::from_id(track_id).map_err(|_| ())?;
-        self.api_with_retry(|api| api.track(tid.clone(), Some(Market::FromToken)))
-            .ok_or(())
+        self.cache
+            .get_or_fetch(&self.cache_key("track", track_id), || {
+                self.api_with_retry(|api| api.track(tid.clone(), Some(self.market())))
+                    .ok_or(())
+            })
     }
 
     /// Fetch the show with the given `show_id`.
     pub fn show(&self, show_id: &str) -> Result<FullShow, ()> {
         let sid = ShowId::from_id(show_id).map_err(|_| ())?;
-        self.api_with_retry(|api| api.get_a_show(sid.clone(), Some(Market::FromToken)))
-            .ok_or(())
+        self.cache
+            .get_or_fetch(&self.cache_key("show", show_id), || {
+                self.api_with_retry(|api| api.get_a_show(sid.clone(), Some(self.market())))
+                    .ok_or(())
+            })
     }
 
     /// Fetch the episode with the given `episode_id`.
     pub fn episode(&self, episode_id: &str) -> Result<FullEpisode, ()> {
         let eid = EpisodeId::from_id(episode_id).map_err(|_| ())?;
-        self.api_with_retry(|api| api.get_an_episode(eid.clone(), Some(Market::FromToken)))
-            .ok_or(())
+        self.cache
+            .get_or_fetch(&self.cache_key("episode", episode_id), || {
+                self.api_with_retry(|api| api.get_an_episode(eid.clone(), Some(self.market())))
+                    .ok_or(())
+            })
+    }
+
+    /// Fetch the audio features of the tracks with the given `track_ids`. Tracks without audio
+    /// features are omitted from the result.
+    pub fn audio_features(&self, track_ids: &[&str]) -> Result<Vec<AudioFeatures>, ()> {
+        let mut features = Vec::new();
+        // The API accepts at most 100 ids per request.
+        for chunk in track_ids.chunks(100) {
+            let ids = chunk
+                .iter()
+                .filter_map(|id| TrackId::from_id(*id).ok())
+                .collect::<Vec<TrackId>>();
+            let batch = self
+                .api_with_retry(|api| api.tracks_features(ids.clone()))
+                .ok_or(())?;
+            features.extend(batch.iter().flatten().map(AudioFeatures::from));
+        }
+        Ok(features)
+    }
+
+    /// Fetch the audio analysis of the track with the given `track_id`.
+    pub fn audio_analysis(&self, track_id: &str) -> Result<AudioAnalysis, ()> {
+        let tid = TrackId::from_id(track_id).map_err(|_| ())?;
+        self.cache
+            .get_or_fetch(&self.cache_key("analysis", track_id), || {
+                self.api_with_retry(|api| api.track_analysis(tid.clone()))
+                    .map(|analysis| AudioAnalysis::from(&analysis))
+                    .ok_or(())
+            })
     }
 
     /// Get recommendations based on the seeds provided with `seed_artists`, `seed_genres` and
-    /// `seed_tracks`.
+    /// `seed_tracks`, tuned towards the audio attributes in `targets`.
     pub fn recommendations(
         &self,
         seed_artists: Option<Vec<&str>>,
         seed_genres: Option<Vec<&str>>,
         seed_tracks: Option<Vec<&str>>,
+        targets: &RecommendationTargets,
     ) -> Result<Recommendations, ()> {
         self.api_with_retry(|api| {
             let seed_artistids = seed_artists.as_ref().map(|artistids| {
@@ -352,11 +574,11 @@ impl WebApi {
                     .collect::<Vec<TrackId>>()
             });
             api.recommendations(
-                std::iter::empty(),
+                targets.attributes(),
                 seed_artistids,
                 seed_genres.clone(),
                 seed_trackids,
-                Some(Market::FromToken),
+                Some(self.market()),
                 Some(100),
             )
         })
@@ -376,7 +598,7 @@ impl WebApi {
             api.search(
                 query,
                 searchtype,
-                Some(Market::FromToken),
+                Some(self.market()),
                 None,
                 Some(limit),
                 Some(offset),
@@ -420,7 +642,7 @@ impl WebApi {
                 match api.playlist_items_manual(
                     PlaylistId::from_id(&playlist_id).unwrap(),
                     None,
-                    Some(Market::FromToken),
+                    Some(spotify.market()),
                     Some(MAX_LIMIT),
                     Some(offset),
                 ) {
@@ -461,7 +683,7 @@ impl WebApi {
         self.api_with_retry(|api| {
             api.album_track_manual(
                 AlbumId::from_id(album_id).unwrap(),
-                Some(Market::FromToken),
+                Some(self.market()),
                 Some(limit),
                 Some(offset),
             )
@@ -485,7 +707,7 @@ impl WebApi {
                 match api.artist_albums_manual(
                     ArtistId::from_id(&artist_id).unwrap(),
                     album_type.as_ref().copied(),
-                    Some(Market::FromToken),
+                    Some(spotify.market()),
                     Some(MAX_SIZE),
                     Some(offset),
                 ) {
@@ -517,7 +739,7 @@ impl WebApi {
             spotify.api_with_retry(|api| {
                 match api.get_shows_episodes_manual(
                     ShowId::from_id(&show_id).unwrap(),
-                    Some(Market::FromToken),
+                    Some(spotify.market()),
                     Some(50),
                     Some(offset),
                 ) {
@@ -559,7 +781,7 @@ impl WebApi {
                 ids.iter()
                     .map(|id| ShowId::from_id(*id).unwrap())
                     .collect::<Vec<ShowId>>(),
-                Some(Market::FromToken),
+                Some(self.market()),
             )
         })
         .ok_or(())
@@ -599,10 +821,67 @@ impl WebApi {
         .ok_or(())
     }
 
+    /// Check whether the tracks with the given `ids` are saved in the user's library. Invalid ids,
+    /// e.g. of local tracks, are left out of the returned pairs of id and status.
+    pub fn saved_tracks_contain(&self, ids: &[&str]) -> Result<Vec<(String, bool)>, ()> {
+        let ids: Vec<TrackId> = ids
+            .iter()
+            .filter_map(|id| TrackId::from_id(*id).ok())
+            .collect();
+        let mut contained = Vec::with_capacity(ids.len());
+        for chunk in ids.chunks(50) {
+            let batch = self
+                .api_with_retry(|api| {
+                    api.current_user_saved_tracks_contains(chunk.iter().map(|id| id.as_ref()))
+                })
+                .ok_or(())?;
+            contained.extend(chunk.iter().map(|id| id.id().to_string()).zip(batch));
+        }
+        Ok(contained)
+    }
+
+    /// Check whether the albums with the given `ids` are saved in the user's library. Invalid ids
+    /// are left out of the returned pairs of id and status.
+    pub fn saved_albums_contain(&self, ids: &[&str]) -> Result<Vec<(String, bool)>, ()> {
+        let ids: Vec<AlbumId> = ids
+            .iter()
+            .filter_map(|id| AlbumId::from_id(*id).ok())
+            .collect();
+        let mut contained = Vec::with_capacity(ids.len());
+        for chunk in ids.chunks(20) {
+            let batch = self
+                .api_with_retry(|api| {
+                    api.current_user_saved_albums_contains(chunk.iter().map(|id| id.as_ref()))
+                })
+                .ok_or(())?;
+            contained.extend(chunk.iter().map(|id| id.id().to_string()).zip(batch));
+        }
+        Ok(contained)
+    }
+
+    /// Check whether the logged in user follows the artists with the given `ids`. Invalid ids are
+    /// left out of the returned pairs of id and status.
+    pub fn followed_artists_contain(&self, ids: &[&str]) -> Result<Vec<(String, bool)>, ()> {
+        let ids: Vec<ArtistId> = ids
+            .iter()
+            .filter_map(|id| ArtistId::from_id(*id).ok())
+            .collect();
+        let mut contained = Vec::with_capacity(ids.len());
+        for chunk in ids.chunks(50) {
+            let batch = self
+                .api_with_retry(|api| {
+                    api.user_artist_check_follow(chunk.iter().map(|id| id.as_ref()))
+                })
+                .ok_or(())?;
+            contained.extend(chunk.iter().map(|id| id.id().to_string()).zip(batch));
+        }
+        Ok(contained)
+    }
+
     /// Get the user's saved albums, starting at the given `offset`. The result is paginated.
     pub fn current_user_saved_albums(&self, offset: u32) -> Result<Page<SavedAlbum>, ()> {
         self.api_with_retry(|api| {
-            api.current_user_saved_albums_manual(Some(Market::FromToken), Some(50), Some(offset))
+            api.current_user_saved_albums_manual(Some(self.market()), Some(50), Some(offset))
         })
         .ok_or(())
     }
@@ -634,7 +913,7 @@ impl WebApi {
     /// Get the user's saved tracks, starting at the given `offset`. The result is paginated.
     pub fn current_user_saved_tracks(&self, offset: u32) -> Result<Page<SavedTrack>, ()> {
         self.api_with_retry(|api| {
-            api.current_user_saved_tracks_manual(Some(Market::FromToken), Some(50), Some(offset))
+            api.current_user_saved_tracks_manual(Some(self.market()), Some(50), Some(offset))
         })
         .ok_or(())
     }
@@ -672,7 +951,7 @@ impl WebApi {
     /// Get the top tracks of the artist with the given `id`.
     pub fn artist_top_tracks(&self, id: &str) -> Result<Vec<Track>, ()> {
         self.api_with_retry(|api| {
-            api.artist_top_tracks(ArtistId::from_id(id).unwrap(), Some(Market::FromToken))
+            api.artist_top_tracks(ArtistId::from_id(id).unwrap(), Some(self.market()))
         })
         .map(|ft| ft.iter().map(|t| t.into()).collect())
         .ok_or(())
@@ -695,7 +974,7 @@ impl WebApi {
             spotify.api_with_retry(|api| {
                 match api.categories_manual(
                     None,
-                    Some(Market::FromToken),
+                    Some(spotify.market()),
                     Some(MAX_LIMIT),
                     Some(offset),
                 ) {
@@ -721,7 +1000,7 @@ impl WebApi {
             spotify.api_with_retry(|api| {
                 match api.category_playlists_manual(
                     &category_id,
-                    Some(Market::FromToken),
+                    Some(spotify.market()),
                     Some(MAX_LIMIT),
                     Some(offset),
                 ) {
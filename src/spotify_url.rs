@@ -76,6 +76,18 @@ impl SpotifyUrl {
 
         Some(Self::new(id, uri_type))
     }
+
+    /// Get media id and type from a `spotify:` URI or an open.spotify.com url.
+    pub fn from_uri_or_url<S: AsRef<str>>(s: S) -> Option<Self> {
+        let s = s.as_ref();
+        if s.contains("open.spotify.com") {
+            Self::from_url(s)
+        } else {
+            let uri_type = s.parse().ok()?;
+            let id = &s[s.rfind(':').unwrap_or(0) + 1..];
+            Some(Self::new(id, uri_type))
+        }
+    }
 }
 
 #[cfg(test)]
@@ -123,4 +135,25 @@ mod tests {
             assert_eq!(result.uri_type, case.1.uri_type);
         }
     }
+
+    #[test]
+    fn test_uris() {
+        let result = SpotifyUrl::from_uri_or_url("spotify:track:6fRJg3R90w0juYoCJXxj2d").unwrap();
+        assert_eq!(result.id, "6fRJg3R90w0juYoCJXxj2d");
+        assert_eq!(result.uri_type, UriType::Track);
+
+        let result =
+            SpotifyUrl::from_uri_or_url("spotify:user:villainy:playlist:0OgoSs65CLDPn6AF6tsZVg")
+                .unwrap();
+        assert_eq!(result.id, "0OgoSs65CLDPn6AF6tsZVg");
+        assert_eq!(result.uri_type, UriType::Playlist);
+
+        let result =
+            SpotifyUrl::from_uri_or_url("https://open.spotify.com/album/1XFxe8bkTryTODn0lk4CNa")
+                .unwrap();
+        assert_eq!(result.uri_type, UriType::Album);
+
+        assert!(SpotifyUrl::from_uri_or_url("spotify:podcast:123").is_none());
+        assert!(SpotifyUrl::from_uri_or_url("not a uri").is_none());
+    }
 }
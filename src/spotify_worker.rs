@@ -1,7 +1,8 @@
 use crate::events::{Event, EventManager};
+use crate::i18n::tr;
 use crate::model::playable::Playable;
 use crate::queue::QueueEvent;
-use crate::spotify::PlayerEvent;
+use crate::spotify::{BufferState, PlayerEvent};
 use futures::Future;
 use futures::FutureExt;
 use librespot_core::session::Session;
@@ -27,8 +28,11 @@ pub(crate) enum WorkerCommand {
     Stop,
     Seek(u32),
     SetVolume(u16),
+    SetFade(Option<Duration>),
     RequestToken(Sender<Option<Token>>),
     Preload(Playable),
+    /// Recreate the player, e.g. to play on another audio device.
+    RestartPlayer,
     Shutdown,
 }
 
@@ -44,29 +48,76 @@ pub struct Worker {
     commands: UnboundedReceiverStream<WorkerCommand>,
     session: Session,
     player: Arc<Player>,
+    create_player: Box<dyn Fn() -> Arc<Player> + Send>,
     token_task: Pin<Box<dyn Future<Output = ()> + Send>>,
     player_status: PlayerStatus,
     mixer: Arc<dyn Mixer>,
+    /// The volume set by the user, which fades are relative to.
+    volume: u16,
+    /// How long to fade in and out at the start and end of each track, if at all.
+    fade: Option<Duration>,
+    /// The duration of the loaded track.
+    track_duration: Option<Duration>,
+    /// When the current track would have started if it had been played without interruption.
+    /// Only set while playing.
+    playback_start: Option<SystemTime>,
+    buffer_state: BufferState,
 }
 
 impl Worker {
     pub(crate) fn new(
         events: EventManager,
-        player_events: mpsc::UnboundedReceiver<LibrespotPlayerEvent>,
         commands: mpsc::UnboundedReceiver<WorkerCommand>,
         session: Session,
-        player: Arc<Player>,
+        create_player: Box<dyn Fn() -> Arc<Player> + Send>,
         mixer: Arc<dyn Mixer>,
     ) -> Self {
+        let volume = mixer.volume();
+        let player = create_player();
         Self {
             events,
-            player_events: UnboundedReceiverStream::new(player_events),
+            player_events: UnboundedReceiverStream::new(player.get_player_event_channel()),
             commands: UnboundedReceiverStream::new(commands),
             player,
+            create_player,
             session,
             token_task: Box::pin(futures::future::pending()),
             player_status: PlayerStatus::Stopped,
             mixer,
+            volume,
+            fade: None,
+            track_duration: None,
+            playback_start: None,
+            buffer_state: BufferState::Ready,
+        }
+    }
+
+    /// Report a change of the buffer state to the user interface.
+    fn set_buffer_state(&mut self, state: BufferState) {
+        if self.buffer_state != state {
+            self.buffer_state = state;
+            self.events.send(Event::Buffer(state));
+        }
+    }
+
+    /// Scale the volume down towards the start and the end of the current track while fading is
+    /// enabled.
+    fn apply_fade(&self) {
+        let (Some(fade), Some(duration), Some(start)) =
+            (self.fade, self.track_duration, self.playback_start)
+        else {
+            return;
+        };
+        if fade.is_zero() {
+            return;
+        }
+
+        let elapsed = start.elapsed().unwrap_or_default();
+        let edge = elapsed.min(duration.saturating_sub(elapsed));
+        let factor = (edge.as_secs_f32() / fade.as_secs_f32()).min(1.0);
+        let volume = (f32::from(self.volume) * factor) as u16;
+        if self.mixer.volume() != volume {
+            self.mixer.set_volume(volume);
         }
     }
 
@@ -79,14 +130,16 @@ impl Worker {
             .await;
     }
 
-    pub async fn run_loop(&mut self) {
+    /// Handle commands and player events until the session ends. Returns why it ended.
+    pub async fn run_loop(&mut self) -> String {
         let mut ui_refresh = time::interval(Duration::from_millis(400));
+        let mut fade_step = time::interval(Duration::from_millis(100));
 
         loop {
             if self.session.is_invalid() {
                 info!("Librespot session invalidated, terminating worker");
                 self.events.send(Event::Player(PlayerEvent::Stopped));
-                break;
+                return tr("the session was closed").to_string();
             }
 
             tokio::select! {
@@ -96,9 +149,11 @@ impl Worker {
                             Ok(id) => {
                                 info!("player loading track: {:?}", id);
                                 if !id.is_playable() {
-                                    warn!("track is not playable");
+                                    warn!("track {} is not playable, skipping", playable.uri());
                                     self.events.send(Event::Player(PlayerEvent::FinishedTrack));
                                 } else {
+                                    self.track_duration =
+                                        Some(Duration::from_millis(playable.duration().into()));
                                     self.player.load(id, start_playing, position_ms);
                                 }
                             }
@@ -121,8 +176,15 @@ impl Worker {
                         self.player.seek(pos);
                     }
                     Some(WorkerCommand::SetVolume(volume)) => {
+                        self.volume = volume;
                         self.mixer.set_volume(volume);
                     }
+                    Some(WorkerCommand::SetFade(fade)) => {
+                        self.fade = fade;
+                        if fade.is_none() {
+                            self.mixer.set_volume(self.volume);
+                        }
+                    }
                     Some(WorkerCommand::RequestToken(sender)) => {
                         self.token_task = Box::pin(Self::get_token(self.session.clone(), sender));
                     }
@@ -132,6 +194,15 @@ impl Worker {
                             self.player.preload(id);
                         }
                     }
+                    Some(WorkerCommand::RestartPlayer) => {
+                        self.player.stop();
+                        self.player = (self.create_player)();
+                        self.player_events =
+                            UnboundedReceiverStream::new(self.player.get_player_event_channel());
+                        self.player_status = PlayerStatus::Stopped;
+                        self.playback_start = None;
+                        self.set_buffer_state(BufferState::Ready);
+                    }
                     Some(WorkerCommand::Shutdown) => {
                         self.player.stop();
                         self.session.shutdown();
@@ -149,6 +220,10 @@ impl Worker {
                         self.events
                             .send(Event::Player(PlayerEvent::Playing(playback_start)));
                         self.player_status = PlayerStatus::Playing;
+                        self.playback_start = Some(playback_start);
+                        if self.buffer_state == BufferState::Loading {
+                            self.set_buffer_state(BufferState::Ready);
+                        }
                     }
                     Some(LibrespotPlayerEvent::Paused {
                         play_request_id: _,
@@ -159,10 +234,23 @@ impl Worker {
                         self.events
                             .send(Event::Player(PlayerEvent::Paused(position)));
                         self.player_status = PlayerStatus::Paused;
+                        self.playback_start = None;
                     }
                     Some(LibrespotPlayerEvent::Stopped { .. }) => {
                         self.events.send(Event::Player(PlayerEvent::Stopped));
                         self.player_status = PlayerStatus::Stopped;
+                        self.playback_start = None;
+                        self.set_buffer_state(BufferState::Ready);
+                    }
+                    Some(LibrespotPlayerEvent::Loading { .. }) => {
+                        self.set_buffer_state(BufferState::Loading);
+                    }
+                    Some(LibrespotPlayerEvent::Preloading { .. }) => {
+                        self.set_buffer_state(BufferState::Preloaded);
+                    }
+                    Some(LibrespotPlayerEvent::PositionCorrection { .. }) => {
+                        warn!("playback position had to be corrected, the buffer may have run empty");
+                        self.set_buffer_state(BufferState::Underrun);
                     }
                     Some(LibrespotPlayerEvent::EndOfTrack { .. }) => {
                         self.events.send(Event::Player(PlayerEvent::FinishedTrack));
@@ -176,6 +264,7 @@ impl Worker {
                         let event = match self.player_status {
                             PlayerStatus::Playing => {
                                 let playback_start = SystemTime::now() - position;
+                                self.playback_start = Some(playback_start);
                                 PlayerEvent::Playing(playback_start)
                             },
                             PlayerStatus::Paused => PlayerEvent::Paused(position),
@@ -188,7 +277,7 @@ impl Worker {
                     }
                     None => {
                         warn!("Librespot player event channel died, terminating worker");
-                        break
+                        return tr("the player stopped responding").to_string();
                     },
                 },
                 // Update animated parts of the UI (e.g. statusbar during playback).
@@ -197,6 +286,9 @@ impl Worker {
                         self.events.trigger();
                     }
                 },
+                _ = fade_step.tick(), if self.fade.is_some() => {
+                    self.apply_fade();
+                },
                 _ = self.token_task.as_mut() => {
                     info!("token updated!");
                     self.token_task = Box::pin(futures::future::pending());
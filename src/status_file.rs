@@ -0,0 +1,85 @@
+//! Writes a line about the playing item to a file or named pipe, so status lines like the ones of
+//! tmux or polybar can show it without talking to the IPC socket.
+
+use std::io::Write;
+use std::path::PathBuf;
+
+use log::error;
+
+use crate::serialization::write_atomic;
+
+/// The file configured with `status_file`, along with the line that was last written to it.
+pub struct StatusFile {
+    path: PathBuf,
+    last_line: Option<String>,
+}
+
+impl StatusFile {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            last_line: None,
+        }
+    }
+
+    /// Write `line` to the file, unless it is already the last line that was written.
+    pub fn update(&mut self, line: String) {
+        if self.last_line.as_ref() == Some(&line) {
+            return;
+        }
+        if self.write(&line) {
+            self.last_line = Some(line);
+        }
+    }
+
+    /// Replace the content of a regular file with `line`, or send it to a named pipe. Returns
+    /// `false` if it couldn't be written, e.g. because nothing reads from the pipe.
+    fn write(&self, line: &str) -> bool {
+        if self.is_pipe() {
+            return self.write_pipe(line);
+        }
+
+        let result = write_atomic(&self.path, |writer| {
+            writeln!(writer, "{line}").map_err(|e| e.to_string())
+        });
+        if let Err(e) = &result {
+            error!("Could not write the status file: {e}");
+        }
+        result.is_ok()
+    }
+
+    #[cfg(unix)]
+    fn is_pipe(&self) -> bool {
+        use std::os::unix::fs::FileTypeExt;
+        std::fs::metadata(&self.path).is_ok_and(|metadata| metadata.file_type().is_fifo())
+    }
+
+    #[cfg(not(unix))]
+    fn is_pipe(&self) -> bool {
+        false
+    }
+
+    /// Send `line` to the named pipe without waiting for a reader, which would block the UI.
+    #[cfg(unix)]
+    fn write_pipe(&self, line: &str) -> bool {
+        use std::fs::OpenOptions;
+        use std::os::unix::fs::OpenOptionsExt;
+
+        let pipe = OpenOptions::new()
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(&self.path);
+        match pipe.and_then(|mut pipe| writeln!(pipe, "{line}")) {
+            Ok(()) => true,
+            Err(e) => {
+                log::debug!("Could not write to the status pipe: {e}");
+                false
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn write_pipe(&self, _line: &str) -> bool {
+        false
+    }
+}
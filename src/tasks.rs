@@ -0,0 +1,127 @@
+//! Long-running operations that run in the background, like updating the library or queueing the
+//! discography of an artist. Their progress is shown in the statusbar and they can be cancelled
+//! from the `tasks` dialog.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+use crate::events::EventManager;
+
+/// The state of a running task, shared between the task and the user interface.
+pub struct TaskState {
+    pub id: usize,
+    pub name: String,
+    /// The amount of finished steps and the total amount of steps, once known.
+    progress: RwLock<Option<(usize, usize)>>,
+    /// What the task is busy with, in addition to its name.
+    detail: RwLock<Option<String>>,
+    cancelled: AtomicBool,
+}
+
+impl TaskState {
+    pub fn progress(&self) -> Option<(usize, usize)> {
+        *self.progress.read().unwrap()
+    }
+
+    /// The progress in percent, once the total amount of steps is known.
+    pub fn percentage(&self) -> Option<usize> {
+        self.progress()
+            .filter(|(_, total)| *total > 0)
+            .map(|(done, total)| (done * 100 / total).min(100))
+    }
+
+    pub fn detail(&self) -> Option<String> {
+        self.detail.read().unwrap().clone()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// The handle of a running task, given to the code doing the work. The task is removed from the
+/// running tasks once the handle is dropped.
+pub struct Task {
+    state: Arc<TaskState>,
+    tasks: Tasks,
+}
+
+impl Task {
+    /// Report that `done` of `total` steps are finished.
+    pub fn set_progress(&self, done: usize, total: usize) {
+        *self.state.progress.write().unwrap() = Some((done, total));
+        self.tasks.ev.trigger();
+    }
+
+    /// Describe what the task is busy with, e.g. the progress of its parts.
+    pub fn set_detail(&self, detail: String) {
+        *self.state.detail.write().unwrap() = Some(detail);
+        self.tasks.ev.trigger();
+    }
+
+    /// Whether the user asked to cancel the task. Tasks check this between steps and stop early.
+    pub fn is_cancelled(&self) -> bool {
+        self.state.is_cancelled()
+    }
+}
+
+impl Drop for Task {
+    fn drop(&mut self) {
+        self.tasks
+            .running
+            .write()
+            .unwrap()
+            .retain(|state| state.id != self.state.id);
+        self.tasks.ev.trigger();
+    }
+}
+
+/// Keeps track of the running tasks.
+#[derive(Clone)]
+pub struct Tasks {
+    running: Arc<RwLock<Vec<Arc<TaskState>>>>,
+    next_id: Arc<AtomicUsize>,
+    ev: EventManager,
+}
+
+impl Tasks {
+    pub fn new(ev: EventManager) -> Self {
+        Self {
+            running: Arc::new(RwLock::new(Vec::new())),
+            next_id: Arc::new(AtomicUsize::new(0)),
+            ev,
+        }
+    }
+
+    /// Register a new task called `name`. It runs until the returned handle is dropped.
+    pub fn start(&self, name: String) -> Task {
+        let state = Arc::new(TaskState {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            name,
+            progress: RwLock::new(None),
+            detail: RwLock::new(None),
+            cancelled: AtomicBool::new(false),
+        });
+        self.running.write().unwrap().push(state.clone());
+        self.ev.trigger();
+        Task {
+            state,
+            tasks: self.clone(),
+        }
+    }
+
+    /// The running tasks, oldest first.
+    pub fn running(&self) -> Vec<Arc<TaskState>> {
+        self.running.read().unwrap().clone()
+    }
+
+    /// Ask the task with the given `id` to stop. Returns `false` if it isn't running anymore.
+    pub fn cancel(&self, id: usize) -> bool {
+        let running = self.running.read().unwrap();
+        let Some(state) = running.iter().find(|state| state.id == id) else {
+            return false;
+        };
+        state.cancelled.store(true, Ordering::Relaxed);
+        true
+    }
+}
@@ -1,12 +1,18 @@
 #![allow(clippy::enum_glob_use)]
 
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use chrono::{Local, NaiveTime};
 use cursive::theme::BaseColor::*;
 use cursive::theme::Color::*;
 use cursive::theme::PaletteColor::*;
 use cursive::theme::*;
-use log::warn;
+use cursive::{CbSink, Cursive};
+use log::{info, warn};
 
-use crate::config::ConfigTheme;
+use crate::config::{Config, ConfigTheme, ThemePreset};
 
 /// Get the given color from the given [ConfigTheme]. The first argument is the [ConfigTheme] to get
 /// the color out of. The second argument is the name of the color to get and is an identifier. The
@@ -35,8 +41,115 @@ macro_rules! load_color {
     };
 }
 
-/// Create a [cursive::theme::Theme] from `theme_cfg`.
-pub fn load(theme_cfg: &Option<ConfigTheme>) -> Theme {
+/// Fill in the listed members that `$theme` doesn't set from `$base`, keeping its other members.
+macro_rules! based_on {
+    ( $theme: expr, $base: expr, $( $member: ident ),* ) => {
+        ConfigTheme {
+            $( $member: $theme.$member.or($base.$member), )*
+            ..$theme
+        }
+    };
+}
+
+impl ThemePreset {
+    /// Guess from the environment whether the terminal supports colors.
+    pub fn from_env() -> Self {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        let term = std::env::var("TERM").unwrap_or_default();
+        if no_color || term == "dumb" || term.ends_with("-m") {
+            Self::Monochrome
+        } else {
+            Self::Default
+        }
+    }
+
+    /// Whether selected and playing rows are emphasized with bold and reverse text, as their
+    /// colors may not tell them apart.
+    pub fn uses_effects(&self) -> bool {
+        !matches!(self, Self::Default)
+    }
+
+    /// The colors of the preset. The default preset uses the defaults of [load].
+    fn colors(&self) -> ConfigTheme {
+        let color = |c: &str| Some(c.to_string());
+        match self {
+            Self::Default => ConfigTheme::default(),
+            Self::HighContrast => ConfigTheme {
+                background: color("black"),
+                primary: color("light white"),
+                secondary: color("white"),
+                title: color("light yellow"),
+                playing: color("light cyan"),
+                playing_selected: color("black"),
+                playing_bg: color("black"),
+                highlight: color("black"),
+                highlight_bg: color("light white"),
+                highlight_inactive_bg: color("white"),
+                error: color("light white"),
+                error_bg: color("red"),
+                statusbar_progress: color("light yellow"),
+                statusbar_progress_bg: color("white"),
+                statusbar: color("light white"),
+                statusbar_bg: color("black"),
+                cmdline: color("light white"),
+                cmdline_bg: color("black"),
+                search_match: color("light yellow"),
+                ..Default::default()
+            },
+            Self::Monochrome => {
+                let default = color("default");
+                ConfigTheme {
+                    background: default.clone(),
+                    primary: default.clone(),
+                    secondary: default.clone(),
+                    title: default.clone(),
+                    playing: default.clone(),
+                    playing_selected: default.clone(),
+                    playing_bg: default.clone(),
+                    highlight: default.clone(),
+                    highlight_bg: default.clone(),
+                    highlight_inactive_bg: default.clone(),
+                    error: default.clone(),
+                    error_bg: default.clone(),
+                    statusbar_progress: default.clone(),
+                    statusbar_progress_bg: default.clone(),
+                    statusbar: default.clone(),
+                    statusbar_bg: default.clone(),
+                    cmdline: default.clone(),
+                    cmdline_bg: default.clone(),
+                    search_match: default,
+                    ..Default::default()
+                }
+            }
+        }
+    }
+}
+
+/// Create a [cursive::theme::Theme] from `theme_cfg`, based on the colors of `preset`.
+pub fn load(theme_cfg: &Option<ConfigTheme>, preset: ThemePreset) -> Theme {
+    let theme_cfg = &Some(based_on!(
+        theme_cfg.clone().unwrap_or_default(),
+        preset.colors(),
+        background,
+        primary,
+        secondary,
+        title,
+        playing,
+        playing_selected,
+        playing_bg,
+        highlight,
+        highlight_bg,
+        highlight_inactive_bg,
+        error,
+        error_bg,
+        statusbar_progress,
+        statusbar_progress_bg,
+        statusbar,
+        statusbar_bg,
+        cmdline,
+        cmdline_bg,
+        search_match
+    ));
     let mut palette = Palette::default();
     let borders = BorderStyle::Simple;
 
@@ -88,3 +201,263 @@ pub fn load(theme_cfg: &Option<ConfigTheme>) -> Theme {
         borders,
     }
 }
+
+/// Whether the light or dark colors of a theme are used.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Appearance {
+    Light,
+    Dark,
+}
+
+/// How often the appearance is checked for changes.
+const APPEARANCE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The appearance for `theme`: dark during its `dark_hours` if they are set, otherwise the
+/// preference of the system or, failing that, the background of the terminal.
+pub fn appearance(theme: &ConfigTheme) -> Option<Appearance> {
+    match theme.dark_hours.as_deref() {
+        Some(hours) => scheduled_appearance(hours, Local::now().time()),
+        None => system_appearance().or_else(terminal_appearance),
+    }
+}
+
+/// The appearance at `now` for the time range `hours`, like `19:00-07:00`, in which it is dark.
+fn scheduled_appearance(hours: &str, now: NaiveTime) -> Option<Appearance> {
+    let parse = |time: &str| NaiveTime::parse_from_str(time.trim(), "%H:%M").ok();
+    let Some((start, end)) = hours
+        .split_once('-')
+        .and_then(|(start, end)| Some((parse(start)?, parse(end)?)))
+    else {
+        warn!(
+            "Invalid dark_hours \"{}\", expected e.g. \"19:00-07:00\"",
+            hours
+        );
+        return None;
+    };
+    // The range may wrap around midnight.
+    let dark = if start <= end {
+        start <= now && now < end
+    } else {
+        now >= start || now < end
+    };
+    Some(if dark {
+        Appearance::Dark
+    } else {
+        Appearance::Light
+    })
+}
+
+/// The light or dark mode preference of the operating system.
+#[cfg(target_os = "macos")]
+fn system_appearance() -> Option<Appearance> {
+    // The key only exists in dark mode.
+    let output = std::process::Command::new("defaults")
+        .args(["read", "-g", "AppleInterfaceStyle"])
+        .output()
+        .ok()?;
+    Some(
+        if String::from_utf8_lossy(&output.stdout).trim() == "Dark" {
+            Appearance::Dark
+        } else {
+            Appearance::Light
+        },
+    )
+}
+
+/// The light or dark mode preference of the operating system.
+#[cfg(windows)]
+fn system_appearance() -> Option<Appearance> {
+    let output = std::process::Command::new("reg")
+        .args([
+            "query",
+            r"HKCU\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize",
+            "/v",
+            "AppsUseLightTheme",
+        ])
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let value = stdout.split_whitespace().last()?;
+    Some(if value == "0x0" {
+        Appearance::Dark
+    } else {
+        Appearance::Light
+    })
+}
+
+/// The color scheme preference of the desktop, read from the XDG desktop portal.
+#[cfg(all(unix, not(target_os = "macos"), feature = "portal"))]
+fn system_appearance() -> Option<Appearance> {
+    use crate::application::ASYNC_RUNTIME;
+
+    async fn color_scheme() -> zbus::Result<u32> {
+        let conn = zbus::Connection::session().await?;
+        let reply = conn
+            .call_method(
+                Some("org.freedesktop.portal.Desktop"),
+                "/org/freedesktop/portal/desktop",
+                Some("org.freedesktop.portal.Settings"),
+                "ReadOne",
+                &("org.freedesktop.appearance", "color-scheme"),
+            )
+            .await?;
+        let value: zbus::zvariant::OwnedValue = reply.body().deserialize()?;
+        Ok(u32::try_from(value)?)
+    }
+
+    // This can't block inside the runtime, and the terminal background is a good enough guess.
+    if tokio::runtime::Handle::try_current().is_ok() {
+        return None;
+    }
+    match ASYNC_RUNTIME.get()?.block_on(color_scheme()) {
+        Ok(1) => Some(Appearance::Dark),
+        Ok(2) => Some(Appearance::Light),
+        Ok(_) => None,
+        Err(e) => {
+            log::debug!("could not read the color scheme from the desktop portal: {e}");
+            None
+        }
+    }
+}
+
+/// The light or dark mode preference of the operating system, which can't be read here.
+#[cfg(all(unix, not(target_os = "macos"), not(feature = "portal")))]
+fn system_appearance() -> Option<Appearance> {
+    None
+}
+
+/// The background of the terminal, as reported in `COLORFGBG` by some terminals.
+fn terminal_appearance() -> Option<Appearance> {
+    colorfgbg_appearance(&std::env::var("COLORFGBG").ok()?)
+}
+
+/// The appearance for the `colors` in `COLORFGBG`, like `15;0`, of which the last is the
+/// background.
+fn colorfgbg_appearance(colors: &str) -> Option<Appearance> {
+    let background: u8 = colors.rsplit(';').next()?.parse().ok()?;
+    // The dark colors of the 16 color palette, except for white.
+    Some(if background < 7 || background == 8 {
+        Appearance::Dark
+    } else {
+        Appearance::Light
+    })
+}
+
+/// `theme` with the colors of its light or dark variant for `appearance` filled in.
+pub fn with_appearance(theme: ConfigTheme, appearance: Option<Appearance>) -> ConfigTheme {
+    let variant = match appearance {
+        Some(Appearance::Light) => theme.light.clone(),
+        Some(Appearance::Dark) => theme.dark.clone(),
+        None => None,
+    };
+    match variant {
+        Some(variant) => {
+            let variant = *variant;
+            based_on!(
+                variant,
+                theme,
+                background,
+                primary,
+                secondary,
+                title,
+                playing,
+                playing_selected,
+                playing_bg,
+                highlight,
+                highlight_bg,
+                highlight_inactive_bg,
+                error,
+                error_bg,
+                statusbar_progress,
+                statusbar_progress_bg,
+                statusbar,
+                statusbar_bg,
+                cmdline,
+                cmdline_bg,
+                search_match,
+                preset
+            )
+        }
+        None => theme,
+    }
+}
+
+/// Check the appearance regularly and apply the theme again once it changes, like reloading the
+/// configuration does.
+pub fn watch_appearance(cfg: Arc<Config>, sink: CbSink) {
+    thread::spawn(move || loop {
+        thread::sleep(APPEARANCE_CHECK_INTERVAL);
+        if !cfg.update_appearance() {
+            continue;
+        }
+        info!("appearance changed, applying theme");
+        let cfg = cfg.clone();
+        let update = Box::new(move |s: &mut Cursive| s.set_theme(cfg.build_theme()));
+        if sink.send(update).is_err() {
+            break;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(time: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(time, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn scheduled_appearance_within_a_day() {
+        let hours = "08:00-18:00";
+        assert_eq!(
+            scheduled_appearance(hours, time("07:59")),
+            Some(Appearance::Light)
+        );
+        assert_eq!(
+            scheduled_appearance(hours, time("08:00")),
+            Some(Appearance::Dark)
+        );
+        assert_eq!(
+            scheduled_appearance(hours, time("18:00")),
+            Some(Appearance::Light)
+        );
+    }
+
+    #[test]
+    fn scheduled_appearance_around_midnight() {
+        let hours = "19:00 - 07:00";
+        assert_eq!(
+            scheduled_appearance(hours, time("18:59")),
+            Some(Appearance::Light)
+        );
+        assert_eq!(
+            scheduled_appearance(hours, time("23:30")),
+            Some(Appearance::Dark)
+        );
+        assert_eq!(
+            scheduled_appearance(hours, time("06:59")),
+            Some(Appearance::Dark)
+        );
+        assert_eq!(
+            scheduled_appearance(hours, time("07:00")),
+            Some(Appearance::Light)
+        );
+    }
+
+    #[test]
+    fn scheduled_appearance_invalid() {
+        assert_eq!(scheduled_appearance("19:00", time("20:00")), None);
+        assert_eq!(scheduled_appearance("7pm-7am", time("20:00")), None);
+    }
+
+    #[test]
+    fn terminal_appearance_from_colorfgbg() {
+        assert_eq!(colorfgbg_appearance("15;0"), Some(Appearance::Dark));
+        assert_eq!(colorfgbg_appearance("15;default;8"), Some(Appearance::Dark));
+        assert_eq!(colorfgbg_appearance("0;15"), Some(Appearance::Light));
+        assert_eq!(colorfgbg_appearance("0;7"), Some(Appearance::Light));
+        assert_eq!(colorfgbg_appearance("15;default"), None);
+        assert_eq!(colorfgbg_appearance(""), None);
+    }
+}
@@ -9,6 +9,8 @@ use crate::commands::CommandResult;
 use crate::library::Library;
 use crate::model::album::Album;
 use crate::model::artist::Artist;
+use crate::model::episode::Episode;
+use crate::model::playlist::Playlist;
 use crate::model::track::Track;
 use crate::queue::Queue;
 
@@ -35,6 +37,14 @@ pub trait ListItem: Sync + Send + 'static {
     }
     fn share_url(&self) -> Option<String>;
 
+    /// The URL of the cover image of the item, as shown by `cover`.
+    fn cover_url(&self) -> Option<String> {
+        None
+    }
+
+    /// All fields of the item, as copied by `copy metadata`.
+    fn metadata(&self) -> serde_json::Value;
+
     /// Get the album that contains this [ListItem].
     fn album(&self, _queue: &Queue) -> Option<Album> {
         None
@@ -48,6 +58,14 @@ pub trait ListItem: Sync + Send + 'static {
         None
     }
 
+    fn episode(&self) -> Option<Episode> {
+        None
+    }
+
+    fn playlist(&self) -> Option<Playlist> {
+        None
+    }
+
     #[allow(unused_variables)]
     #[inline]
     fn is_saved(&self, library: &Library) -> Option<bool> {
@@ -73,9 +91,30 @@ pub trait ViewExt: View {
 
     fn on_leave(&self) {}
 
+    /// Fetch the data shown in the view again. Returns `false` if the view has nothing to fetch.
+    fn refresh(&mut self) -> bool {
+        false
+    }
+
+    /// The item shown in the view, remembered in the history of visited views so it can be
+    /// opened again.
+    fn history_item(&self) -> Option<Box<dyn ListItem>> {
+        None
+    }
+
+    /// The commands that are most useful in the view, whose keys are shown in the hint line.
+    fn key_hints(&self) -> Vec<Command> {
+        Vec::new()
+    }
+
     fn on_command(&mut self, _s: &mut Cursive, _cmd: &Command) -> Result<CommandResult, String> {
         Ok(CommandResult::Ignored)
     }
+
+    /// Run `cmd` after it was confirmed in a dialog that the view showed for it.
+    fn on_confirmed(&mut self, s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
+        self.on_command(s, cmd)
+    }
 }
 
 impl<V: ViewExt> ViewExt for NamedView<V> {
@@ -91,9 +130,25 @@ impl<V: ViewExt> ViewExt for NamedView<V> {
         self.with_view(|v| v.on_leave());
     }
 
+    fn refresh(&mut self) -> bool {
+        self.with_view_mut(|v| v.refresh()).unwrap_or(false)
+    }
+
+    fn history_item(&self) -> Option<Box<dyn ListItem>> {
+        self.with_view(|v| v.history_item()).flatten()
+    }
+
+    fn key_hints(&self) -> Vec<Command> {
+        self.with_view(|v| v.key_hints()).unwrap_or_default()
+    }
+
     fn on_command(&mut self, s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
         self.with_view_mut(move |v| v.on_command(s, cmd)).unwrap()
     }
+
+    fn on_confirmed(&mut self, s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
+        self.with_view_mut(move |v| v.on_confirmed(s, cmd)).unwrap()
+    }
 }
 
 pub trait IntoBoxedViewExt {
@@ -177,7 +232,23 @@ impl ViewExt for BoxedViewExt {
         self.boxed_view.on_leave();
     }
 
+    fn refresh(&mut self) -> bool {
+        self.boxed_view.refresh()
+    }
+
+    fn history_item(&self) -> Option<Box<dyn ListItem>> {
+        self.boxed_view.history_item()
+    }
+
+    fn key_hints(&self) -> Vec<Command> {
+        self.boxed_view.key_hints()
+    }
+
     fn on_command(&mut self, s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
         self.boxed_view.on_command(s, cmd)
     }
+
+    fn on_confirmed(&mut self, s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
+        self.boxed_view.on_confirmed(s, cmd)
+    }
 }
@@ -0,0 +1,371 @@
+#![allow(clippy::use_self)]
+
+//! A system tray icon using the StatusNotifierItem D-Bus protocol, which is supported by most
+//! Linux and BSD desktops. It shows the current track and has a menu to control playback.
+//!
+//! <https://www.freedesktop.org/wiki/Specifications/StatusNotifierItem/>
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+
+use log::{info, warn};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tokio_stream::StreamExt;
+use zbus::object_server::SignalEmitter;
+use zbus::zvariant::{ObjectPath, OwnedValue, Value};
+use zbus::{connection, interface};
+
+use crate::application::ASYNC_RUNTIME;
+use crate::events::{Event, EventManager};
+use crate::i18n::tr;
+use crate::library::Library;
+use crate::model::playable::Playable;
+use crate::queue::Queue;
+use crate::spotify::PlayerEvent;
+
+const ITEM_PATH: &str = "/StatusNotifierItem";
+const MENU_PATH: &str = "/MenuBar";
+
+/// The entries of the tray menu, identified by their position.
+const MENU_TRACK: i32 = 1;
+const MENU_SEPARATOR: i32 = 2;
+const MENU_PLAYPAUSE: i32 = 3;
+const MENU_PREVIOUS: i32 = 4;
+const MENU_NEXT: i32 = 5;
+
+/// Convert `value` to a menu property.
+fn owned<'a>(value: impl Into<Value<'a>>) -> OwnedValue {
+    value
+        .into()
+        .try_into()
+        .expect("menu properties don't contain file descriptors")
+}
+
+/// The state shown by the tray icon, shared by its interfaces.
+#[derive(Clone)]
+struct TrayState {
+    event: EventManager,
+    queue: Arc<Queue>,
+    library: Arc<Library>,
+}
+
+impl TrayState {
+    fn is_playing(&self) -> bool {
+        matches!(
+            self.queue.get_spotify().get_current_status(),
+            PlayerEvent::Playing(_)
+        )
+    }
+
+    fn current_track(&self) -> Option<String> {
+        self.queue
+            .get_current()
+            .map(|playable| Playable::format(&playable, "%artists - %title", &self.library))
+    }
+
+    /// Run `command` in the running instance, as if it was entered on the command line.
+    fn dispatch(&self, command: &str) {
+        info!("tray: {}", command);
+        self.event.send(Event::IpcInput(command.to_string()));
+    }
+
+    /// The properties of the menu entry `id`.
+    fn menu_properties(&self, id: i32) -> HashMap<String, OwnedValue> {
+        let mut properties = HashMap::new();
+        match id {
+            MENU_TRACK => {
+                let label = self
+                    .current_track()
+                    .unwrap_or_else(|| tr("Stopped").to_string());
+                properties.insert("label".to_string(), owned(label.replace('_', "__")));
+                properties.insert("enabled".to_string(), owned(false));
+            }
+            MENU_SEPARATOR => {
+                properties.insert("type".to_string(), owned("separator"));
+            }
+            MENU_PLAYPAUSE => {
+                let label = if self.is_playing() {
+                    tr("Pause")
+                } else {
+                    tr("Play")
+                };
+                properties.insert("label".to_string(), owned(label));
+            }
+            MENU_PREVIOUS => {
+                properties.insert("label".to_string(), owned(tr("Previous")));
+            }
+            MENU_NEXT => {
+                properties.insert("label".to_string(), owned(tr("Next")));
+            }
+            _ => {
+                properties.insert("children-display".to_string(), owned("submenu"));
+            }
+        }
+        properties
+    }
+}
+
+struct StatusNotifierItem {
+    state: TrayState,
+}
+
+#[interface(name = "org.kde.StatusNotifierItem")]
+impl StatusNotifierItem {
+    #[zbus(property)]
+    fn category(&self) -> &str {
+        "ApplicationStatus"
+    }
+
+    #[zbus(property)]
+    fn id(&self) -> &str {
+        "ncspot"
+    }
+
+    #[zbus(property)]
+    fn title(&self) -> String {
+        self.state
+            .current_track()
+            .unwrap_or_else(|| "ncspot".to_string())
+    }
+
+    #[zbus(property)]
+    fn status(&self) -> &str {
+        "Active"
+    }
+
+    #[zbus(property)]
+    fn icon_name(&self) -> &str {
+        if self.state.is_playing() {
+            "media-playback-start"
+        } else {
+            "media-playback-pause"
+        }
+    }
+
+    #[zbus(property)]
+    fn tool_tip(&self) -> (String, Vec<(i32, i32, Vec<u8>)>, String, String) {
+        (
+            String::new(),
+            Vec::new(),
+            "ncspot".to_string(),
+            self.state.current_track().unwrap_or_default(),
+        )
+    }
+
+    #[zbus(property)]
+    fn item_is_menu(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn menu(&self) -> ObjectPath<'_> {
+        ObjectPath::from_static_str_unchecked(MENU_PATH)
+    }
+
+    /// A click on the icon toggles playback, the menu is opened with a right click.
+    fn activate(&self, _x: i32, _y: i32) {
+        self.state.dispatch("playpause");
+    }
+
+    fn secondary_activate(&self, _x: i32, _y: i32) {
+        self.state.dispatch("playpause");
+    }
+
+    fn context_menu(&self, _x: i32, _y: i32) {}
+
+    fn scroll(&self, delta: i32, orientation: &str) {
+        if orientation.eq_ignore_ascii_case("vertical") && delta != 0 {
+            let command = if delta < 0 { "volup 5" } else { "voldown 5" };
+            self.state.dispatch(command);
+        }
+    }
+
+    #[zbus(signal)]
+    async fn new_title(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    async fn new_icon(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    async fn new_tool_tip(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+}
+
+/// The tray menu, exported with the dbusmenu protocol.
+struct Menu {
+    state: TrayState,
+    revision: u32,
+}
+
+#[interface(name = "com.canonical.dbusmenu")]
+impl Menu {
+    #[zbus(property)]
+    fn version(&self) -> u32 {
+        3
+    }
+
+    #[zbus(property)]
+    fn text_direction(&self) -> &str {
+        "ltr"
+    }
+
+    #[zbus(property)]
+    fn status(&self) -> &str {
+        "normal"
+    }
+
+    #[zbus(property)]
+    fn icon_theme_path(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// The menu is flat, so the layout of the root contains all entries.
+    fn get_layout(
+        &self,
+        parent_id: i32,
+        _recursion_depth: i32,
+        _property_names: Vec<String>,
+    ) -> (u32, (i32, HashMap<String, OwnedValue>, Vec<OwnedValue>)) {
+        let children = if parent_id == 0 {
+            [
+                MENU_TRACK,
+                MENU_SEPARATOR,
+                MENU_PLAYPAUSE,
+                MENU_PREVIOUS,
+                MENU_NEXT,
+            ]
+            .into_iter()
+            .map(|id| owned((id, self.state.menu_properties(id), Vec::<OwnedValue>::new())))
+            .collect()
+        } else {
+            Vec::new()
+        };
+        (
+            self.revision,
+            (parent_id, self.state.menu_properties(parent_id), children),
+        )
+    }
+
+    fn get_group_properties(
+        &self,
+        ids: Vec<i32>,
+        _property_names: Vec<String>,
+    ) -> Vec<(i32, HashMap<String, OwnedValue>)> {
+        ids.into_iter()
+            .map(|id| (id, self.state.menu_properties(id)))
+            .collect()
+    }
+
+    fn get_property(&self, id: i32, name: &str) -> OwnedValue {
+        self.state
+            .menu_properties(id)
+            .remove(name)
+            .unwrap_or_else(|| owned(""))
+    }
+
+    fn event(&self, id: i32, event_id: &str, _data: OwnedValue, _timestamp: u32) {
+        if event_id != "clicked" {
+            return;
+        }
+        match id {
+            MENU_PLAYPAUSE => self.state.dispatch("playpause"),
+            MENU_PREVIOUS => self.state.dispatch("previous"),
+            MENU_NEXT => self.state.dispatch("next"),
+            _ => {}
+        }
+    }
+
+    fn about_to_show(&self, _id: i32) -> bool {
+        false
+    }
+
+    #[zbus(signal)]
+    async fn layout_updated(
+        emitter: &SignalEmitter<'_>,
+        revision: u32,
+        parent: i32,
+    ) -> zbus::Result<()>;
+}
+
+/// A tray icon that internally manages a task which can be told to refresh it. This is internally
+/// shared and cloning it will yield a reference to the same icon.
+#[derive(Clone)]
+pub struct TrayIcon {
+    tx: mpsc::UnboundedSender<()>,
+}
+
+impl TrayIcon {
+    pub fn new(event: EventManager, queue: Arc<Queue>, library: Arc<Library>) -> Self {
+        let state = TrayState {
+            event,
+            queue,
+            library,
+        };
+        let (tx, rx) = mpsc::unbounded_channel::<()>();
+
+        ASYNC_RUNTIME.get().unwrap().spawn(async {
+            let result = Self::serve(UnboundedReceiverStream::new(rx), state).await;
+            if let Err(e) = result {
+                log::error!("tray icon error: {e}");
+            }
+        });
+
+        Self { tx }
+    }
+
+    async fn serve(
+        mut rx: UnboundedReceiverStream<()>,
+        state: TrayState,
+    ) -> Result<(), Box<dyn Error + Sync + Send>> {
+        let name = format!("org.kde.StatusNotifierItem-{}-1", std::process::id());
+        let item = StatusNotifierItem {
+            state: state.clone(),
+        };
+        let menu = Menu { state, revision: 0 };
+        let conn = connection::Builder::session()?
+            .name(name.as_str())?
+            .serve_at(ITEM_PATH, item)?
+            .serve_at(MENU_PATH, menu)?
+            .build()
+            .await?;
+
+        if let Err(e) = conn
+            .call_method(
+                Some("org.kde.StatusNotifierWatcher"),
+                "/StatusNotifierWatcher",
+                Some("org.kde.StatusNotifierWatcher"),
+                "RegisterStatusNotifierItem",
+                &(name.as_str(),),
+            )
+            .await
+        {
+            warn!("no system tray available: {e}");
+        }
+
+        let object_server = conn.object_server();
+        let item_ref = object_server
+            .interface::<_, StatusNotifierItem>(ITEM_PATH)
+            .await?;
+        let menu_ref = object_server.interface::<_, Menu>(MENU_PATH).await?;
+
+        while rx.next().await.is_some() {
+            let item_ctx = item_ref.signal_emitter();
+            StatusNotifierItem::new_title(item_ctx).await?;
+            StatusNotifierItem::new_icon(item_ctx).await?;
+            StatusNotifierItem::new_tool_tip(item_ctx).await?;
+
+            let mut menu = menu_ref.get_mut().await;
+            menu.revision += 1;
+            Menu::layout_updated(menu_ref.signal_emitter(), menu.revision, 0).await?;
+        }
+        Err("tray icon command channel closed".into())
+    }
+
+    /// Show the current track and playback status.
+    pub fn update(&self) {
+        if let Err(e) = self.tx.send(()) {
+            warn!("Could not update the tray icon: {e}");
+        }
+    }
+}
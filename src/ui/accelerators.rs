@@ -0,0 +1,62 @@
+//! Number keys that choose the entries of menus and the buttons of dialogs with a single key
+//! press, instead of navigating to them first.
+
+use cursive::event::{Event, EventResult, Key};
+use cursive::traits::Nameable;
+use cursive::views::{Dialog, NamedView, OnEventView, SelectView};
+use cursive::View;
+
+/// The keys that choose the first entries, in order.
+const KEYS: [char; 10] = ['1', '2', '3', '4', '5', '6', '7', '8', '9', '0'];
+
+/// Prefix the label of each entry of `select` with its key, and submit an entry when its key is
+/// pressed. Call this once all entries were added. The select view can be found by `name`.
+pub fn numbered_select<T: Send + Sync + 'static>(
+    mut select: SelectView<T>,
+    name: &str,
+) -> OnEventView<NamedView<SelectView<T>>> {
+    for (i, key) in KEYS.iter().enumerate() {
+        if let Some((label, _)) = select.get_item_mut(i) {
+            *label = format!("{key} {}", label.source()).into();
+        }
+    }
+
+    let mut view = OnEventView::new(select.with_name(name));
+    for (i, key) in KEYS.iter().enumerate() {
+        view.set_on_pre_event_inner(*key, move |named: &mut NamedView<SelectView<T>>, _| {
+            let mut select = named.get_mut();
+            if i >= select.len() {
+                return None;
+            }
+            select.set_selection(i);
+            Some(select.on_event(Event::Key(Key::Enter)))
+        });
+    }
+    view
+}
+
+/// Prefix the label of each button of `dialog` with its key, and press a button when its key is
+/// pressed.
+pub fn numbered_buttons(mut dialog: Dialog) -> OnEventView<Dialog> {
+    for (button, key) in dialog.buttons_mut().zip(KEYS) {
+        // Labels are decorated as `<label>`, which is added again when setting them.
+        let label = button
+            .label()
+            .trim_start_matches('<')
+            .trim_end_matches('>')
+            .to_string();
+        button.set_label(format!("{key} {label}"));
+    }
+
+    let mut view = OnEventView::new(dialog);
+    for (i, key) in KEYS.iter().enumerate() {
+        view.set_on_pre_event_inner(*key, move |dialog: &mut Dialog, _| {
+            let button = dialog.buttons_mut().nth(i)?;
+            match button.on_event(Event::Key(Key::Enter)) {
+                EventResult::Ignored => None,
+                result => Some(result),
+            }
+        });
+    }
+    view
+}
@@ -5,30 +5,32 @@ use cursive::Cursive;
 
 use crate::command::Command;
 use crate::commands::CommandResult;
+use crate::i18n::{tr, tr_args};
 use crate::library::Library;
 use crate::model::album::Album;
 use crate::model::artist::Artist;
+use crate::model::snapshot::Snapshot;
+use crate::model::track::Track;
 use crate::queue::Queue;
-use crate::traits::ViewExt;
+use crate::traits::{ListItem, ViewExt};
+use crate::ui::header::{wrap_with_header, Header};
 use crate::ui::listview::ListView;
 use crate::ui::tabbedview::TabbedView;
 
 pub struct AlbumView {
     album: Album,
     tabs: TabbedView,
+    /// The release date, label, duration and popularity of the album, shown above the tabs.
+    header: Header,
+    tracks: Arc<RwLock<Vec<Track>>>,
+    queue: Arc<Queue>,
+    library: Arc<Library>,
 }
 
 impl AlbumView {
     pub fn new(queue: Arc<Queue>, library: Arc<Library>, album: &Album) -> Self {
         let mut album = album.clone();
-
-        album.load_all_tracks(queue.get_spotify());
-
-        let tracks = if let Some(t) = album.tracks.as_ref() {
-            t.clone()
-        } else {
-            Vec::new()
-        };
+        let tracks = Arc::new(RwLock::new(Self::load_tracks(&mut album, &queue)));
 
         let artists = album
             .artist_ids
@@ -39,24 +41,77 @@ impl AlbumView {
 
         let mut tabs = TabbedView::new();
         tabs.add_tab(
-            "Tracks",
+            tr("Tracks"),
+            ListView::new(tracks.clone(), queue.clone(), library.clone()),
+        );
+        tabs.add_tab(
+            tr("Artists"),
             ListView::new(
-                Arc::new(RwLock::new(tracks)),
+                Arc::new(RwLock::new(artists)),
                 queue.clone(),
                 library.clone(),
             ),
         );
-        tabs.add_tab(
-            "Artists",
-            ListView::new(Arc::new(RwLock::new(artists)), queue, library),
-        );
 
-        Self { album, tabs }
+        let header = Header::new(queue.clone(), library.clone());
+        let view = Self {
+            album,
+            tabs,
+            header,
+            tracks,
+            queue,
+            library,
+        };
+        view.load_details();
+        view
+    }
+
+    /// Show the details of the album in the header. They come with the tracks of the album, which
+    /// were just fetched, so this is served from the cache.
+    fn load_details(&self) {
+        let Some(id) = self.album.id.as_ref() else {
+            return;
+        };
+        let Ok(album) = self.queue.get_spotify().api.album(id) else {
+            return;
+        };
+        let mut details = vec![album.release_date];
+        details.extend(album.label);
+        details.push(tr_args("Popularity {}%", &[&album.popularity.to_string()]));
+
+        let content = self.header.content();
+        content.set(None, details, self.album.cover_url.clone());
+        content.set_summary(crate::utils::format_duration(&self.duration()));
+    }
+
+    /// The total duration of the tracks of the album.
+    fn duration(&self) -> std::time::Duration {
+        let tracks = self.tracks.read().unwrap();
+        let duration_secs = tracks.iter().map(|t| t.duration as u64 / 1000).sum();
+        std::time::Duration::from_secs(duration_secs)
+    }
+
+    fn load_tracks(album: &mut Album, queue: &Queue) -> Vec<Track> {
+        album.load_all_tracks(queue.get_spotify());
+
+        // Remember where tracks that get queued from this view came from.
+        let source = album.as_source();
+        if let Some(t) = album.tracks.as_ref() {
+            t.iter()
+                .cloned()
+                .map(|mut t| {
+                    t.source.clone_from(&source);
+                    t
+                })
+                .collect()
+        } else {
+            Vec::new()
+        }
     }
 }
 
 impl ViewWrapper for AlbumView {
-    wrap_impl!(self.tabs: TabbedView);
+    wrap_with_header!(self.header, self.tabs: TabbedView);
 }
 
 impl ViewExt for AlbumView {
@@ -75,7 +130,37 @@ impl ViewExt for AlbumView {
         }
     }
 
+    fn on_leave(&self) {
+        self.header.on_leave();
+    }
+
+    fn refresh(&mut self) -> bool {
+        self.album.tracks = None;
+        *self.tracks.write().unwrap() = Self::load_tracks(&mut self.album, &self.queue);
+        self.load_details();
+        true
+    }
+
+    fn history_item(&self) -> Option<Box<dyn ListItem>> {
+        Some(self.album.as_listitem())
+    }
+
+    fn key_hints(&self) -> Vec<Command> {
+        self.tabs.key_hints()
+    }
+
     fn on_command(&mut self, s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
+        if let Command::Snapshot = cmd {
+            let spotify = self.queue.get_spotify();
+            let Some(snapshot) = Snapshot::of_album(&self.album, &spotify) else {
+                return Ok(CommandResult::Consumed(None));
+            };
+            return self.library.save_snapshot(&snapshot).map(|path| {
+                let path = path.display().to_string();
+                CommandResult::Consumed(Some(tr_args("Saved snapshot to {}", &[&path])))
+            });
+        }
+
         self.tabs.on_command(s, cmd)
     }
 }
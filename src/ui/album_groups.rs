@@ -0,0 +1,254 @@
+use std::sync::{Arc, RwLock};
+
+use cursive::view::{View, ViewWrapper};
+use cursive::{Cursive, Vec2};
+
+use crate::command::Command;
+use crate::commands::CommandResult;
+use crate::i18n::{tr, tr_args};
+use crate::library::Library;
+use crate::model::album::Album;
+use crate::model::artist::Artist;
+use crate::model::playable::Playable;
+use crate::queue::Queue;
+use crate::traits::{ListItem, ViewExt};
+use crate::ui::treelistview::{TreeItem, TreeListView};
+
+/// A row of the grouped albums: either an artist, or one of the albums of an expanded artist.
+#[derive(Clone)]
+pub enum AlbumGroupRow {
+    Artist { name: String, albums: Vec<Album> },
+    Album(Album),
+}
+
+impl TreeItem for AlbumGroupRow {
+    fn tree_key(&self) -> String {
+        match self {
+            Self::Artist { name, .. } => name.clone(),
+            Self::Album(album) => album.id.clone().unwrap_or_else(|| album.title.clone()),
+        }
+    }
+
+    fn has_children(&self) -> bool {
+        matches!(self, Self::Artist { .. })
+    }
+
+    fn load_children(&self, _queue: &Queue, _library: &Library) -> Vec<Self> {
+        match self {
+            Self::Artist { albums, .. } => albums.iter().cloned().map(Self::Album).collect(),
+            Self::Album(_) => Vec::new(),
+        }
+    }
+}
+
+impl ListItem for AlbumGroupRow {
+    fn is_playing(&self, queue: &Queue) -> bool {
+        match self {
+            Self::Artist { .. } => false,
+            Self::Album(album) => album.is_playing(queue),
+        }
+    }
+
+    fn display_left(&self, _library: &Library) -> String {
+        match self {
+            Self::Artist { name, .. } => name.clone(),
+            Self::Album(album) => album.title.clone(),
+        }
+    }
+
+    fn display_right(&self, library: &Library) -> String {
+        match self {
+            Self::Artist { albums, .. } => tr_args("{} albums", &[&albums.len().to_string()]),
+            Self::Album(album) => album.display_right(library),
+        }
+    }
+
+    fn play(&mut self, queue: &Queue) {
+        match self {
+            Self::Artist { albums, .. } => {
+                let mut playables: Vec<Playable> = Vec::new();
+                for album in albums.iter_mut() {
+                    album.load_all_tracks(queue.get_spotify());
+                    playables.extend(album.playables());
+                }
+                if !playables.is_empty() {
+                    let index = queue.append_next(&playables);
+                    queue.play(index, true, true);
+                }
+            }
+            Self::Album(album) => album.play(queue),
+        }
+    }
+
+    fn play_next(&mut self, queue: &Queue) {
+        match self {
+            Self::Artist { albums, .. } => {
+                for album in albums.iter_mut().rev() {
+                    album.play_next(queue);
+                }
+            }
+            Self::Album(album) => album.play_next(queue),
+        }
+    }
+
+    fn queue(&mut self, queue: &Queue) {
+        match self {
+            Self::Artist { albums, .. } => {
+                for album in albums.iter_mut() {
+                    album.queue(queue);
+                }
+            }
+            Self::Album(album) => album.queue(queue),
+        }
+    }
+
+    fn toggle_saved(&mut self, library: &Library) {
+        if let Self::Album(album) = self {
+            album.toggle_saved(library);
+        }
+    }
+
+    fn save(&mut self, library: &Library) {
+        if let Self::Album(album) = self {
+            album.save(library);
+        }
+    }
+
+    fn unsave(&mut self, library: &Library) {
+        if let Self::Album(album) = self {
+            album.unsave(library);
+        }
+    }
+
+    fn open(&self, queue: Arc<Queue>, library: Arc<Library>) -> Option<Box<dyn ViewExt>> {
+        match self {
+            Self::Artist { .. } => None,
+            Self::Album(album) => album.open(queue, library),
+        }
+    }
+
+    fn share_url(&self) -> Option<String> {
+        match self {
+            Self::Artist { .. } => None,
+            Self::Album(album) => album.share_url(),
+        }
+    }
+
+    fn metadata(&self) -> serde_json::Value {
+        match self {
+            Self::Artist { name, albums, .. } => serde_json::json!({
+                "artist": name,
+                "albums": albums.iter().map(Album::metadata).collect::<Vec<_>>(),
+            }),
+            Self::Album(album) => album.metadata(),
+        }
+    }
+
+    fn album(&self, _queue: &Queue) -> Option<Album> {
+        match self {
+            Self::Artist { .. } => None,
+            Self::Album(album) => Some(album.clone()),
+        }
+    }
+
+    fn artists(&self) -> Option<Vec<Artist>> {
+        match self {
+            Self::Artist { albums, .. } => albums
+                .first()
+                .and_then(|album| album.artists())
+                .map(|artists| artists.into_iter().take(1).collect()),
+            Self::Album(album) => album.artists(),
+        }
+    }
+
+    #[inline]
+    fn is_saved(&self, library: &Library) -> Option<bool> {
+        match self {
+            Self::Artist { .. } => None,
+            Self::Album(album) => album.is_saved(library),
+        }
+    }
+
+    #[inline]
+    fn is_playable(&self) -> bool {
+        true
+    }
+
+    fn as_listitem(&self) -> Box<dyn ListItem> {
+        Box::new(self.clone())
+    }
+}
+
+/// The saved albums, grouped by their first artist. Artists are expanded and collapsed with
+/// `open`.
+pub struct AlbumGroupsView {
+    tree: TreeListView<AlbumGroupRow>,
+    artists: Arc<RwLock<Vec<AlbumGroupRow>>>,
+    library: Arc<Library>,
+    albums_len: Option<usize>,
+}
+
+impl AlbumGroupsView {
+    pub fn new(queue: Arc<Queue>, library: Arc<Library>) -> Self {
+        let artists = Arc::new(RwLock::new(Vec::new()));
+        let tree = TreeListView::new(artists.clone(), queue, library.clone());
+        let mut view = Self {
+            tree,
+            artists,
+            library,
+            albums_len: None,
+        };
+        view.update_artists();
+        view
+    }
+
+    /// Group the saved albums again. The albums are already sorted by artist, so each artist's
+    /// albums follow each other.
+    fn update_artists(&mut self) {
+        let albums = self.library.albums.read().unwrap();
+        let mut artists: Vec<AlbumGroupRow> = Vec::new();
+        for album in albums.iter() {
+            let name = album.artists.first().cloned().unwrap_or_default();
+            match artists.last_mut() {
+                Some(AlbumGroupRow::Artist {
+                    name: last, albums, ..
+                }) if *last == name => albums.push(album.clone()),
+                _ => artists.push(AlbumGroupRow::Artist {
+                    name,
+                    albums: vec![album.clone()],
+                }),
+            }
+        }
+
+        self.albums_len = Some(albums.len());
+        *self.artists.write().unwrap() = artists;
+        self.tree.reload();
+    }
+}
+
+impl ViewWrapper for AlbumGroupsView {
+    wrap_impl!(self.tree: TreeListView<AlbumGroupRow>);
+
+    fn wrap_layout(&mut self, size: Vec2) {
+        // The saved albums are loaded in the background and change when albums are saved.
+        let albums_len = self.library.albums.read().unwrap().len();
+        if self.albums_len != Some(albums_len) {
+            self.update_artists();
+        }
+        self.tree.layout(size);
+    }
+}
+
+impl ViewExt for AlbumGroupsView {
+    fn title(&self) -> String {
+        tr("Albums").to_string()
+    }
+
+    fn key_hints(&self) -> Vec<Command> {
+        self.tree.key_hints()
+    }
+
+    fn on_command(&mut self, s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
+        self.tree.on_command(s, cmd)
+    }
+}
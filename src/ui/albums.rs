@@ -0,0 +1,98 @@
+use std::sync::{Arc, RwLock};
+
+use cursive::view::{View, ViewWrapper};
+use cursive::{Cursive, Vec2};
+
+use crate::command::{Command, YearRange};
+use crate::commands::CommandResult;
+use crate::i18n::tr;
+use crate::library::Library;
+use crate::model::album::Album;
+use crate::queue::Queue;
+use crate::traits::ViewExt;
+use crate::ui::listview::ListView;
+
+/// The saved albums, narrowed down to the ones released in the years of `filter` if it is set.
+pub struct AlbumsView {
+    list: ListView<Album>,
+    albums: Arc<RwLock<Vec<Album>>>,
+    library: Arc<Library>,
+    filter: Arc<RwLock<Option<YearRange>>>,
+    /// The amount of saved albums and the filter when the shown albums were last updated.
+    shown: Option<(usize, Option<YearRange>)>,
+}
+
+impl AlbumsView {
+    pub fn new(
+        queue: Arc<Queue>,
+        library: Arc<Library>,
+        filter: Arc<RwLock<Option<YearRange>>>,
+    ) -> Self {
+        let albums = Arc::new(RwLock::new(Vec::new()));
+        let list = ListView::new(albums.clone(), queue, library.clone());
+        let mut view = Self {
+            list,
+            albums,
+            library,
+            filter,
+            shown: None,
+        };
+        view.update_albums();
+        view
+    }
+
+    fn update_albums(&mut self) {
+        let filter = *self.filter.read().unwrap();
+        let albums = self.library.albums.read().unwrap();
+        let shown: Vec<Album> = albums
+            .iter()
+            .filter(|album| match filter {
+                Some(range) => album
+                    .year
+                    .parse::<u16>()
+                    .is_ok_and(|year| range.contains(year)),
+                None => true,
+            })
+            .cloned()
+            .collect();
+
+        if self
+            .shown
+            .is_some_and(|(_, shown_filter)| shown_filter != filter)
+        {
+            self.list.move_focus_to(0);
+        }
+        self.shown = Some((albums.len(), filter));
+        *self.albums.write().unwrap() = shown;
+    }
+}
+
+impl ViewWrapper for AlbumsView {
+    wrap_impl!(self.list: ListView<Album>);
+
+    fn wrap_layout(&mut self, size: Vec2) {
+        // The saved albums are loaded in the background and change when albums are saved.
+        let current = (
+            self.library.albums.read().unwrap().len(),
+            *self.filter.read().unwrap(),
+        );
+        if self.shown != Some(current) {
+            self.update_albums();
+        }
+        self.list.layout(size);
+    }
+}
+
+impl ViewExt for AlbumsView {
+    fn title(&self) -> String {
+        tr("Albums").to_string()
+    }
+
+    fn key_hints(&self) -> Vec<Command> {
+        self.list.key_hints()
+    }
+
+    fn on_command(&mut self, s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
+        self.list.on_command(s, cmd)
+    }
+}
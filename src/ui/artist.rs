@@ -7,18 +7,21 @@ use rspotify::model::AlbumType;
 
 use crate::command::Command;
 use crate::commands::CommandResult;
+use crate::i18n::tr;
 use crate::library::Library;
 use crate::model::album::Album;
 use crate::model::artist::Artist;
 use crate::model::track::Track;
 use crate::queue::Queue;
-use crate::traits::ViewExt;
+use crate::traits::{ListItem, ViewExt};
 use crate::ui::listview::ListView;
 use crate::ui::tabbedview::TabbedView;
 
 pub struct ArtistView {
     artist: Artist,
     tabs: TabbedView,
+    queue: Arc<Queue>,
+    library: Arc<Library>,
 }
 
 impl ArtistView {
@@ -31,33 +34,39 @@ impl ArtistView {
             Self::albums_view(artist, AlbumType::Single, queue.clone(), library.clone());
 
         let top_tracks: Arc<RwLock<Vec<Track>>> = Arc::new(RwLock::new(Vec::new()));
-        {
-            let top_tracks = top_tracks.clone();
+        let top_tracks_view = ListView::new(top_tracks.clone(), queue.clone(), library.clone());
+        if let Some(id) = artist.id.clone() {
+            let loading = top_tracks_view.get_loading().clone();
             let spotify = spotify.clone();
-            let id = artist.id.clone();
             let library = library.clone();
+            loading.start();
             thread::spawn(move || {
-                if let Some(id) = id {
-                    if let Ok(tracks) = spotify.api.artist_top_tracks(&id) {
+                match spotify.api.artist_top_tracks(&id) {
+                    Ok(tracks) => {
                         top_tracks.write().unwrap().extend(tracks);
-                        library.trigger_redraw();
+                        loading.finish();
                     }
+                    Err(_) => loading.fail(tr("Could not load top tracks")),
                 }
+                library.trigger_redraw();
             });
         }
 
         let related: Arc<RwLock<Vec<Artist>>> = Arc::new(RwLock::new(Vec::new()));
-        {
-            let related = related.clone();
-            let id = artist.id.clone();
+        let related_view = ListView::new(related.clone(), queue.clone(), library.clone());
+        if let Some(id) = artist.id.clone() {
+            let loading = related_view.get_loading().clone();
             let library = library.clone();
+            loading.start();
             thread::spawn(move || {
-                if let Some(id) = id {
-                    if let Ok(artists) = spotify.api.artist_related_artists(&id) {
+                match spotify.api.artist_related_artists(&id) {
+                    Ok(artists) => {
                         related.write().unwrap().extend(artists);
-                        library.trigger_redraw();
+                        loading.finish();
                     }
+                    Err(_) => loading.fail(tr("Could not load related artists")),
                 }
+                library.trigger_redraw();
             });
         }
 
@@ -67,7 +76,7 @@ impl ArtistView {
             let tracks = tracks.clone();
 
             tabs.add_tab(
-                "Saved Tracks",
+                tr("Saved Tracks"),
                 ListView::new(
                     Arc::new(RwLock::new(tracks)),
                     queue.clone(),
@@ -75,17 +84,16 @@ impl ArtistView {
                 ),
             );
         }
-        tabs.add_tab(
-            "Top 10",
-            ListView::new(top_tracks, queue.clone(), library.clone()),
-        );
-        tabs.add_tab("Albums", albums_view);
-        tabs.add_tab("Singles", singles_view);
-        tabs.add_tab("Related Artists", ListView::new(related, queue, library));
+        tabs.add_tab(tr("Top 10"), top_tracks_view);
+        tabs.add_tab(tr("Albums"), albums_view);
+        tabs.add_tab(tr("Singles"), singles_view);
+        tabs.add_tab(tr("Related Artists"), related_view);
 
         Self {
             artist: artist.clone(),
             tabs,
+            queue,
+            library,
         }
     }
 
@@ -117,6 +125,21 @@ impl ViewExt for ArtistView {
         self.artist.name.clone()
     }
 
+    fn refresh(&mut self) -> bool {
+        let selected = self.tabs.selected();
+        *self = Self::new(self.queue.clone(), self.library.clone(), &self.artist);
+        self.tabs.set_selected(selected);
+        true
+    }
+
+    fn history_item(&self) -> Option<Box<dyn ListItem>> {
+        Some(self.artist.as_listitem())
+    }
+
+    fn key_hints(&self) -> Vec<Command> {
+        self.tabs.key_hints()
+    }
+
     fn on_command(&mut self, s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
         self.tabs.on_command(s, cmd)
     }
@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use cursive::traits::{Nameable, Resizable};
+use cursive::view::Margins;
+use cursive::views::{Dialog, EditView, ScrollView, SelectView};
+use cursive::Cursive;
+
+use crate::i18n::tr;
+use crate::queue::Queue;
+use crate::ui::modal::Modal;
+
+/// An entry of the audio device dialog.
+#[derive(Clone)]
+enum DeviceChoice {
+    Device(Option<String>),
+    Other,
+}
+
+/// Switch the audio backend to `device` and continue the current track where it was.
+fn switch_device(queue: &Queue, device: Option<String>) {
+    queue.get_spotify().set_audio_device(device);
+    queue.restart_player();
+}
+
+/// Dialog to choose the device the audio backend plays on. librespot can't list the devices of
+/// a backend, so besides the default and the current device any other name can be entered.
+pub fn audio_device_dialog(queue: Arc<Queue>) -> Modal<Dialog> {
+    let current = queue.get_spotify().audio_device();
+
+    let mut list_select: SelectView<DeviceChoice> = SelectView::new().autojump();
+    list_select.add_item(tr("Default device"), DeviceChoice::Device(None));
+    if let Some(device) = current.clone() {
+        list_select.add_item(device.clone(), DeviceChoice::Device(Some(device)));
+        list_select.set_selection(1);
+    }
+    list_select.add_item(tr("[Other device…]"), DeviceChoice::Other);
+
+    list_select.set_on_submit(move |s, choice: &DeviceChoice| {
+        s.pop_layer();
+        match choice {
+            DeviceChoice::Device(device) => switch_device(&queue, device.clone()),
+            DeviceChoice::Other => {
+                let queue = queue.clone();
+                let edit = EditView::new()
+                    .content(current.clone().unwrap_or_default())
+                    .on_submit(move |s: &mut Cursive, name| {
+                        s.pop_layer();
+                        let device = Some(name.trim().to_string()).filter(|d| !d.is_empty());
+                        switch_device(&queue, device);
+                    })
+                    .with_name("device")
+                    .fixed_width(30);
+                let dialog = Dialog::new()
+                    .title(tr("Enter device name"))
+                    .dismiss_button(tr("Cancel"))
+                    .padding(Margins::lrtb(1, 1, 1, 0))
+                    .content(edit);
+                s.add_layer(Modal::new(dialog));
+            }
+        }
+    });
+
+    let dialog = Dialog::new()
+        .title(tr("Audio device"))
+        .dismiss_button(tr("Cancel"))
+        .padding(Margins::lrtb(1, 1, 1, 0))
+        .content(ScrollView::new(list_select));
+    Modal::new(dialog)
+}
@@ -5,8 +5,10 @@ use cursive::Cursive;
 
 use crate::command::Command;
 use crate::commands::CommandResult;
+use crate::i18n::tr;
 use crate::library::Library;
 use crate::model::category::Category;
+use crate::model::playlist::PARALLEL_PAGES;
 use crate::queue::Queue;
 use crate::traits::ViewExt;
 
@@ -19,6 +21,25 @@ pub struct BrowseView {
 impl BrowseView {
     pub fn new(queue: Arc<Queue>, library: Arc<Library>) -> Self {
         let categories = queue.get_spotify().api.categories();
+        let allowed = library
+            .cfg
+            .values()
+            .clean_categories
+            .clone()
+            .filter(|_| library.cfg.state().clean_mode);
+
+        // In clean mode only the configured categories are shown, which requires all of them.
+        if let Some(allowed) = allowed {
+            categories.fetch_all(PARALLEL_PAGES, || {});
+            categories
+                .items
+                .write()
+                .unwrap()
+                .retain(|category| allowed.contains(&category.id));
+            let list = ListView::new(categories.items.clone(), queue, library);
+            return Self { list };
+        }
+
         let list = ListView::new(categories.items.clone(), queue, library);
         categories.apply_pagination(list.get_pagination());
 
@@ -32,7 +53,11 @@ impl ViewWrapper for BrowseView {
 
 impl ViewExt for BrowseView {
     fn title(&self) -> String {
-        "Browse".to_string()
+        tr("Browse").to_string()
+    }
+
+    fn key_hints(&self) -> Vec<Command> {
+        self.list.key_hints()
     }
 
     fn on_command(&mut self, s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
@@ -1,23 +1,29 @@
 use std::sync::Arc;
+use std::thread;
 
 use cursive::view::{Margins, ViewWrapper};
 use cursive::views::{Dialog, NamedView, ScrollView, SelectView};
 use cursive::Cursive;
 
 use crate::commands::CommandResult;
-use crate::ext_traits::SelectViewExt;
+use crate::ext_traits::{CursiveExt, SelectViewExt};
+use crate::i18n::{tr, tr_args};
 use crate::library::Library;
+use crate::model::album::Album;
 use crate::model::artist::Artist;
-use crate::model::playable::Playable;
+use crate::model::episode::Episode;
+use crate::model::playable::{Playable, PlayableSource};
 use crate::model::playlist::Playlist;
 use crate::model::track::Track;
 use crate::queue::Queue;
 #[cfg(feature = "share_clipboard")]
 use crate::sharing::write_share;
-use crate::spotify::PlayerEvent;
+use crate::spotify::{PlayerEvent, UriType};
 use crate::traits::{ListItem, ViewExt};
+use crate::ui::accelerators;
 use crate::ui::layout::Layout;
 use crate::ui::modal::Modal;
+use crate::ui::recommendations::RecommendationSeed;
 use crate::{command::Command, spotify::Spotify};
 use cursive::traits::{Finder, Nameable};
 
@@ -37,15 +43,30 @@ pub struct SelectArtistActionMenu {
     dialog: Modal<Dialog>,
 }
 
+/// The actions offered in the menu of a single artist.
+enum ArtistAction {
+    View,
+    ToggleFollow,
+    PlayTopTracks,
+    QueueDiscography,
+    StartRadio,
+}
+
 enum ContextMenuAction {
     ShowItem(Box<dyn ListItem>),
+    ShowSource(PlayableSource),
     SelectArtist(Vec<Artist>),
     SelectArtistAction(Artist),
     #[cfg(feature = "share_clipboard")]
     ShareUrl(String),
     AddToPlaylist(Box<Track>),
+    MoveToPlaylist(Box<Track>, PlayableSource),
     ShowRecommendations(Box<Track>),
+    StartRadio(RecommendationSeed),
+    ShowAudioFeatures(String),
+    FollowArtists(Box<Playlist>),
     ToggleSavedStatus(Box<dyn ListItem>),
+    SetEpisodePlayed(Box<Episode>, bool),
     Play(Box<dyn ListItem>),
     PlayNext(Box<dyn ListItem>),
     TogglePlayback,
@@ -57,48 +78,85 @@ impl ContextMenu {
         library: Arc<Library>,
         spotify: Spotify,
         track: Track,
+    ) -> NamedView<AddToPlaylistMenu> {
+        Self::playlist_dialog(library, spotify, track, None)
+    }
+
+    /// Dialog to add `track` to another playlist and remove it from the `origin` playlist.
+    pub fn move_track_dialog(
+        library: Arc<Library>,
+        spotify: Spotify,
+        track: Track,
+        origin: PlayableSource,
+    ) -> NamedView<AddToPlaylistMenu> {
+        Self::playlist_dialog(library, spotify, track, Some(origin))
+    }
+
+    fn playlist_dialog(
+        library: Arc<Library>,
+        spotify: Spotify,
+        track: Track,
+        origin: Option<PlayableSource>,
     ) -> NamedView<AddToPlaylistMenu> {
         let mut list_select: SelectView<Playlist> = SelectView::new();
-        let current_user_id = library.user_id.as_ref().unwrap();
+        let current_user_id = library.user_id();
 
         for list in library.playlists.read().unwrap().iter() {
-            if current_user_id == &list.owner_id || list.collaborative {
+            let is_origin = origin.as_ref().is_some_and(|origin| origin.id == list.id);
+            let is_owner = current_user_id.as_ref() == Some(&list.owner_id);
+            if (is_owner || list.collaborative) && !is_origin {
                 list_select.add_item(list.name.clone(), list.clone());
             }
         }
 
+        let title = if origin.is_some() {
+            tr("Move track to playlist")
+        } else {
+            tr("Add track to playlist")
+        };
+
         list_select.set_on_submit(move |s, selected| {
             let track = track.clone();
-            let mut playlist = selected.clone();
+            let playlist = selected.clone();
             let spotify = spotify.clone();
             let library = library.clone();
+            let origin = origin.clone();
 
             if playlist.has_track(track.id.as_ref().unwrap_or(&String::new())) {
                 let mut already_added_dialog = Self::track_already_added();
 
-                already_added_dialog.add_button("Add anyway", move |c| {
-                    let mut playlist = playlist.clone();
-
-                    playlist.append_tracks(&[Playable::Track(track.clone())], &spotify, &library);
+                already_added_dialog.add_button(tr("Add anyway"), move |c| {
                     c.pop_layer();
 
                     // Close add_track_dialog too
                     c.pop_layer();
+
+                    Self::add_to_playlist(
+                        c,
+                        playlist.clone(),
+                        track.clone(),
+                        origin.as_ref(),
+                        &spotify,
+                        &library,
+                    );
                 });
 
-                let modal = Modal::new(already_added_dialog);
+                let modal = Modal::new(accelerators::numbered_buttons(already_added_dialog));
                 s.add_layer(modal);
             } else {
-                playlist.append_tracks(&[Playable::Track(track)], &spotify, &library);
                 s.pop_layer();
+                Self::add_to_playlist(s, playlist, track, origin.as_ref(), &spotify, &library);
             }
         });
 
         let dialog = Dialog::new()
-            .title("Add track to playlist")
-            .dismiss_button("Close")
+            .title(title)
+            .dismiss_button(tr("Close"))
             .padding(Margins::lrtb(1, 1, 1, 0))
-            .content(ScrollView::new(list_select.with_name("addplaylist_select")));
+            .content(ScrollView::new(accelerators::numbered_select(
+                list_select,
+                "addplaylist_select",
+            )));
 
         AddToPlaylistMenu {
             dialog: Modal::new_ext(dialog),
@@ -128,10 +186,13 @@ impl ContextMenu {
         });
 
         let dialog = Dialog::new()
-            .title("Select artist")
-            .dismiss_button("Close")
+            .title(tr("Select artist"))
+            .dismiss_button(tr("Close"))
             .padding(Margins::lrtb(1, 1, 1, 0))
-            .content(ScrollView::new(artist_select.with_name("artist_select")));
+            .content(ScrollView::new(accelerators::numbered_select(
+                artist_select,
+                "artist_select",
+            )));
 
         SelectArtistMenu {
             dialog: Modal::new_ext(dialog),
@@ -145,57 +206,260 @@ impl ContextMenu {
         artist: Artist,
     ) -> NamedView<SelectArtistActionMenu> {
         let moved_artist = artist.clone();
-        let mut artist_action_select = SelectView::<bool>::new();
-        artist_action_select.add_item("View Artist", true);
+        let mut artist_action_select = SelectView::<ArtistAction>::new();
+        artist_action_select.add_item(tr("View Artist"), ArtistAction::View);
         artist_action_select.add_item(
-            format!(
-                "{}ollow Artist",
-                if library.is_followed_artist(&artist) {
-                    "Unf"
-                } else {
-                    "F"
-                }
-            ),
-            false,
+            if library.is_followed_artist(&artist) {
+                tr("Unfollow Artist")
+            } else {
+                tr("Follow Artist")
+            },
+            ArtistAction::ToggleFollow,
         );
+        if artist.id.is_some() {
+            artist_action_select.add_item(tr("Play top tracks"), ArtistAction::PlayTopTracks);
+            artist_action_select.add_item(tr("Queue discography"), ArtistAction::QueueDiscography);
+            artist_action_select.add_item(tr("Start radio"), ArtistAction::StartRadio);
+        }
         artist_action_select.set_on_submit(move |s, selected_action| {
             match selected_action {
-                true => {
+                ArtistAction::View => {
                     if let Some(view) = moved_artist.clone().open(queue.clone(), library.clone()) {
                         s.call_on_name("main", |v: &mut Layout| v.push_view(view));
                     }
                 }
-                false => {
-                    if library.clone().is_followed_artist(&moved_artist) {
-                        moved_artist.clone().unsave(&library);
-                    } else {
-                        moved_artist.clone().save(&library);
+                ArtistAction::ToggleFollow => {
+                    if !Self::locked_by_party_mode(s, &library) {
+                        if library.clone().is_followed_artist(&moved_artist) {
+                            moved_artist.clone().unsave(&library);
+                        } else {
+                            moved_artist.clone().save(&library);
+                        }
+                    }
+                }
+                ArtistAction::PlayTopTracks => {
+                    Self::play_top_tracks(s, queue.clone(), moved_artist.clone())
+                }
+                ArtistAction::QueueDiscography => {
+                    Self::queue_discography(s, queue.clone(), &library, moved_artist.clone())
+                }
+                ArtistAction::StartRadio => {
+                    if let Some(id) = moved_artist.id.clone() {
+                        let seed = RecommendationSeed::Artist {
+                            id,
+                            name: moved_artist.name.clone(),
+                        };
+                        queue.start_radio(seed);
                     }
                 }
             }
             s.pop_layer();
         });
         let dialog = Dialog::new()
-            .title(format!(
+            .title(tr_args(
                 "Select action for artist: {}",
-                artist.name.as_str()
+                &[artist.name.as_str()],
             ))
-            .dismiss_button("Close")
+            .dismiss_button(tr("Close"))
             .padding(Margins::lrtb(1, 1, 1, 0))
-            .content(ScrollView::new(
-                artist_action_select.with_name("artist_action_select"),
-            ));
+            .content(ScrollView::new(accelerators::numbered_select(
+                artist_action_select,
+                "artist_action_select",
+            )));
         SelectArtistActionMenu {
             dialog: Modal::new_ext(dialog),
         }
         .with_name("selectartistaction")
     }
 
+    /// Whether changes to the library are locked by party mode, which is then shown in the result
+    /// bar.
+    fn locked_by_party_mode(s: &mut Cursive, library: &Library) -> bool {
+        let locked = library.cfg.state().party_mode;
+        if locked {
+            let message = tr("Not available in party mode").to_string();
+            s.on_layout(|_, mut layout| layout.set_result(Err(message)));
+        }
+        locked
+    }
+
+    /// Replace the queue with the top tracks of `artist` in the background. Failures are shown in
+    /// the result bar.
+    pub fn play_top_tracks(s: &mut Cursive, queue: Arc<Queue>, mut artist: Artist) {
+        let sink = s.cb_sink().clone();
+        thread::spawn(move || {
+            if !artist.play_top_tracks(&queue) {
+                let message = tr_args("Could not load the top tracks of {}", &[&artist.name]);
+                sink.send(Box::new(move |s: &mut Cursive| {
+                    s.on_layout(|_, mut layout| layout.set_result(Err(message)));
+                }))
+                .ok();
+            }
+        });
+    }
+
+    /// Queue the discography of `artist` in the background, reporting the progress in the command
+    /// line. It runs as a task, so it can be cancelled.
+    fn queue_discography(s: &mut Cursive, queue: Arc<Queue>, library: &Library, artist: Artist) {
+        let sink = s.cb_sink().clone();
+        let task = library
+            .tasks
+            .start(tr_args("Queueing discography of {}", &[&artist.name]));
+        thread::spawn(move || {
+            let report = |message: String| {
+                sink.send(Box::new(move |s: &mut Cursive| {
+                    s.on_layout(|_, mut layout| layout.set_result(Ok(Some(message))));
+                }))
+                .ok();
+            };
+
+            report(tr_args("Queueing discography of {}...", &[&artist.name]));
+            let queued = artist.queue_discography(&queue, |done, total| {
+                task.set_progress(done, total);
+                report(tr_args(
+                    "Queueing discography of {}: {}/{} albums",
+                    &[&artist.name, &done.to_string(), &total.to_string()],
+                ));
+                !task.is_cancelled()
+            });
+            report(tr_args(
+                "Queued {} tracks by {}",
+                &[&queued.to_string(), &artist.name],
+            ));
+        });
+    }
+
+    /// Load the audio features of the track with the given `id` in the background and show them
+    /// in a dialog.
+    fn show_audio_features(s: &mut Cursive, library: Arc<Library>, id: String) {
+        let sink = s.cb_sink().clone();
+        thread::spawn(move || {
+            let features = library.audio_features(&[id.as_str()]).remove(&id);
+            sink.send(Box::new(move |s: &mut Cursive| {
+                let dialog = match features {
+                    Some(features) => Dialog::text(features.to_string()),
+                    None => Dialog::text(tr("No audio features available for this track")),
+                }
+                .title(tr("Audio features"))
+                .padding(Margins::lrtb(1, 1, 1, 0))
+                .dismiss_button(tr("Close"));
+                s.add_layer(Modal::new(dialog));
+            }))
+            .ok();
+        });
+    }
+
+    /// Load the album or playlist `source` in the background and open it once it is loaded.
+    fn show_source(
+        s: &mut Cursive,
+        queue: Arc<Queue>,
+        library: Arc<Library>,
+        source: PlayableSource,
+    ) {
+        let sink = s.cb_sink().clone();
+        thread::spawn(move || {
+            let api = &queue.get_spotify().api;
+            let item: Option<Box<dyn ListItem>> = match source.uri_type {
+                UriType::Album => api
+                    .album(&source.id)
+                    .map(|album| Album::from(&album).as_listitem())
+                    .ok(),
+                UriType::Playlist => api
+                    .playlist(&source.id)
+                    .map(|playlist| Playlist::from(&playlist).as_listitem())
+                    .ok(),
+                _ => None,
+            };
+            sink.send(Box::new(move |s: &mut Cursive| match item {
+                Some(item) => {
+                    if let Some(view) = item.open(queue, library) {
+                        s.call_on_name("main", move |v: &mut Layout| v.push_view(view));
+                    }
+                }
+                None => {
+                    let message = tr_args("Could not load {}", &[&source.name]);
+                    s.on_layout(|_, mut layout| layout.set_result(Err(message)));
+                }
+            }))
+            .ok();
+        });
+    }
+
+    /// Follow the artists of all tracks in `playlist` in the background. The amount of newly
+    /// followed artists is shown in the result bar.
+    fn follow_artists(
+        s: &mut Cursive,
+        library: Arc<Library>,
+        spotify: Spotify,
+        mut playlist: Playlist,
+    ) {
+        if Self::locked_by_party_mode(s, &library) {
+            return;
+        }
+        let sink = s.cb_sink().clone();
+        thread::spawn(move || {
+            playlist.load_tracks(&spotify);
+            let artists: Vec<Artist> = playlist
+                .tracks
+                .iter()
+                .flatten()
+                .filter_map(|playable| playable.track())
+                .flat_map(|track| track.artists().unwrap_or_default())
+                .collect();
+
+            let result = match library.follow_artists(&artists) {
+                Ok(followed) => Ok(Some(tr_args(
+                    "Followed {} new artists from {}",
+                    &[&followed.to_string(), &playlist.name],
+                ))),
+                Err(()) => Err(tr_args(
+                    "Could not follow all artists from {}",
+                    &[&playlist.name],
+                )),
+            };
+            sink.send(Box::new(move |s: &mut Cursive| {
+                s.on_layout(|_, mut layout| layout.set_result(result));
+            }))
+            .ok();
+        });
+    }
+
+    /// Add `track` to `playlist` and remove it from the `origin` playlist if one is given. The
+    /// outcome is shown in the result bar.
+    fn add_to_playlist(
+        s: &mut Cursive,
+        mut playlist: Playlist,
+        track: Track,
+        origin: Option<&PlayableSource>,
+        spotify: &Spotify,
+        library: &Library,
+    ) {
+        if Self::locked_by_party_mode(s, library) {
+            return;
+        }
+        let track = Playable::Track(track);
+        let result = if !playlist.append_tracks(&[track.clone()], spotify, library) {
+            Err(tr_args("Could not add track to {}", &[&playlist.name]))
+        } else if let Some(origin) = origin {
+            if library.remove_from_playlist(&origin.id, &track) {
+                Ok(Some(tr_args("Moved track to {}", &[&playlist.name])))
+            } else {
+                Err(tr_args(
+                    "Added track to {}, but could not remove it from {}",
+                    &[&playlist.name, &origin.name],
+                ))
+            }
+        } else {
+            Ok(Some(tr_args("Added track to {}", &[&playlist.name])))
+        };
+        s.on_layout(|_, mut layout| layout.set_result(result));
+    }
+
     fn track_already_added() -> Dialog {
-        Dialog::text("This track is already in your playlist")
-            .title("Track already exists")
+        Dialog::text(tr("This track is already in your playlist"))
+            .title(tr("Track already exists"))
             .padding(Margins::lrtb(1, 1, 1, 0))
-            .dismiss_button("Close")
+            .dismiss_button(tr("Close"))
     }
 
     pub fn new(item: &dyn ListItem, queue: Arc<Queue>, library: Arc<Library>) -> NamedView<Self> {
@@ -208,20 +472,20 @@ impl ContextMenu {
                     == PlayerEvent::Paused(queue.get_spotify().get_current_progress())
             {
                 // the item is the current track, but paused
-                content.insert_item(0, "Resume", ContextMenuAction::TogglePlayback);
+                content.insert_item(0, tr("Resume"), ContextMenuAction::TogglePlayback);
             } else if !item.is_playing(&queue) {
                 // the item is not the current track
-                content.insert_item(0, "Play", ContextMenuAction::Play(item.as_listitem()));
+                content.insert_item(0, tr("Play"), ContextMenuAction::Play(item.as_listitem()));
             } else {
                 // the item is the current track and playing
-                content.insert_item(0, "Pause", ContextMenuAction::TogglePlayback);
+                content.insert_item(0, tr("Pause"), ContextMenuAction::TogglePlayback);
             }
             content.insert_item(
                 1,
-                "Play next",
+                tr("Play next"),
                 ContextMenuAction::PlayNext(item.as_listitem()),
             );
-            content.insert_item(2, "Queue", ContextMenuAction::Queue(item.as_listitem()));
+            content.insert_item(2, tr("Queue"), ContextMenuAction::Queue(item.as_listitem()));
         }
 
         if let Some(artists) = item.artists() {
@@ -233,7 +497,11 @@ impl ContextMenu {
 
             if let Some(a) = action {
                 content.add_item(
-                    format!("Artist{}", if artists.len() > 1 { "s" } else { "" }),
+                    if artists.len() > 1 {
+                        tr("Artists")
+                    } else {
+                        tr("Artist")
+                    },
                     a,
                 )
             }
@@ -241,48 +509,104 @@ impl ContextMenu {
 
         if let Some(ref a) = album {
             content.add_item(
-                "Show album",
+                tr("Show album"),
                 ContextMenuAction::ShowItem(Box::new(a.clone())),
             );
         }
 
+        if let Some(source) = item.track().and_then(|t| t.source) {
+            content.add_item(
+                tr_args("Queued from: {}", &[&source.name]),
+                ContextMenuAction::ShowSource(source),
+            );
+        }
+
         #[cfg(feature = "share_clipboard")]
         {
             if let Some(url) = item.share_url() {
-                content.add_item("Share", ContextMenuAction::ShareUrl(url));
+                content.add_item(tr("Share"), ContextMenuAction::ShareUrl(url));
             }
             if let Some(url) = album.as_ref().and_then(|a| a.share_url()) {
-                content.add_item("Share album", ContextMenuAction::ShareUrl(url));
+                content.add_item(tr("Share album"), ContextMenuAction::ShareUrl(url));
             }
         }
 
         if let Some(t) = item.track() {
             content.add_item(
-                "Add to playlist",
+                tr("Copy to playlist…"),
                 ContextMenuAction::AddToPlaylist(Box::new(t.clone())),
             );
+            // Tracks can only be moved out of playlists the user is allowed to edit.
+            let editable_source = t.source.clone().filter(|source| {
+                source.uri_type == UriType::Playlist
+                    && library.playlists.read().unwrap().iter().any(|playlist| {
+                        playlist.id == source.id
+                            && (library.user_id().as_ref() == Some(&playlist.owner_id)
+                                || playlist.collaborative)
+                    })
+            });
+            if let Some(source) = editable_source {
+                content.add_item(
+                    tr("Move to playlist…"),
+                    ContextMenuAction::MoveToPlaylist(Box::new(t.clone()), source),
+                );
+            }
+            content.add_item(
+                tr("Similar tracks"),
+                ContextMenuAction::ShowRecommendations(Box::new(t.clone())),
+            );
+            if let Some(id) = t.id.clone() {
+                let seed = RecommendationSeed::Track {
+                    id,
+                    name: t.title.clone(),
+                };
+                content.add_item(tr("Start radio"), ContextMenuAction::StartRadio(seed));
+            }
+            if let Some(id) = t.id {
+                content.add_item(
+                    tr("Audio features"),
+                    ContextMenuAction::ShowAudioFeatures(id),
+                );
+            }
+        }
+        if let Some(playlist) = item.playlist() {
             content.add_item(
-                "Similar tracks",
-                ContextMenuAction::ShowRecommendations(Box::new(t)),
-            )
+                tr("Follow all artists"),
+                ContextMenuAction::FollowArtists(Box::new(playlist)),
+            );
         }
+
         // If the item is saveable, its save state will be set
         if let Some(savestatus) = item.is_saved(&library) {
+            let is_playlist = item.playlist().is_some();
             content.add_item(
-                match savestatus {
-                    true => "Unsave",
-                    false => "Save",
+                match (savestatus, is_playlist) {
+                    (true, true) => tr("Unfollow playlist"),
+                    (false, true) => tr("Follow playlist"),
+                    (true, false) => tr("Unsave"),
+                    (false, false) => tr("Save"),
                 },
                 ContextMenuAction::ToggleSavedStatus(item.as_listitem()),
             );
         }
 
+        if let Some(episode) = item.episode() {
+            let played = library.is_episode_played(&episode);
+            content.add_item(
+                match played {
+                    true => tr("Mark as unplayed"),
+                    false => tr("Mark as played"),
+                },
+                ContextMenuAction::SetEpisodePlayed(Box::new(episode), !played),
+            );
+        }
+
         if let Some(ref a) = album {
             if let Some(savestatus) = a.is_saved(&library) {
                 content.add_item(
                     match savestatus {
-                        true => "Unsave album",
-                        false => "Save album",
+                        true => tr("Unsave album"),
+                        false => tr("Save album"),
                     },
                     ContextMenuAction::ToggleSavedStatus(a.as_listitem()),
                 );
@@ -303,6 +627,9 @@ impl ContextMenu {
                             s.call_on_name("main", move |v: &mut Layout| v.push_view(view));
                         }
                     }
+                    ContextMenuAction::ShowSource(source) => {
+                        Self::show_source(s, queue, library, source.clone())
+                    }
                     #[cfg(feature = "share_clipboard")]
                     ContextMenuAction::ShareUrl(url) => {
                         write_share(url.to_string()).ok();
@@ -312,11 +639,27 @@ impl ContextMenu {
                             Self::add_track_dialog(library, queue.get_spotify(), *track.clone());
                         s.add_layer(dialog);
                     }
+                    ContextMenuAction::MoveToPlaylist(track, source) => {
+                        let dialog = Self::move_track_dialog(
+                            library,
+                            queue.get_spotify(),
+                            *track.clone(),
+                            source.clone(),
+                        );
+                        s.add_layer(dialog);
+                    }
                     ContextMenuAction::ShowRecommendations(item) => {
                         if let Some(view) = item.to_owned().open_recommendations(queue, library) {
                             s.call_on_name("main", move |v: &mut Layout| v.push_view(view));
                         }
                     }
+                    ContextMenuAction::StartRadio(seed) => queue.start_radio(seed.clone()),
+                    ContextMenuAction::ShowAudioFeatures(id) => {
+                        Self::show_audio_features(s, library, id.clone())
+                    }
+                    ContextMenuAction::FollowArtists(playlist) => {
+                        Self::follow_artists(s, library, queue.get_spotify(), *playlist.clone())
+                    }
                     ContextMenuAction::SelectArtist(artists) => {
                         let dialog = Self::select_artist_dialog(library, queue, artists.clone());
                         s.add_layer(dialog);
@@ -327,7 +670,12 @@ impl ContextMenu {
                         s.add_layer(dialog);
                     }
                     ContextMenuAction::ToggleSavedStatus(item) => {
-                        item.as_listitem().toggle_saved(&library)
+                        if !Self::locked_by_party_mode(s, &library) {
+                            item.as_listitem().toggle_saved(&library)
+                        }
+                    }
+                    ContextMenuAction::SetEpisodePlayed(episode, played) => {
+                        library.set_episode_played(episode, *played)
                     }
                     ContextMenuAction::Play(item) => item.as_listitem().play(&queue),
                     ContextMenuAction::PlayNext(item) => item.as_listitem().play_next(&queue),
@@ -339,9 +687,9 @@ impl ContextMenu {
 
         let dialog = Dialog::new()
             .title(item.display_left(&library))
-            .dismiss_button("Close")
+            .dismiss_button(tr("Close"))
             .padding(Margins::lrtb(1, 1, 1, 0))
-            .content(content.with_name("contextmenu_select"));
+            .content(accelerators::numbered_select(content, "contextmenu_select"));
         Self {
             dialog: Modal::new_ext(dialog),
         }
@@ -371,7 +719,7 @@ impl ViewExt for SelectArtistMenu {
 
 impl ViewExt for SelectArtistActionMenu {
     fn on_command(&mut self, s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
-        handle_move_command::<bool>(&mut self.dialog, s, cmd, "artist_action_select")
+        handle_move_command::<ArtistAction>(&mut self.dialog, s, cmd, "artist_action_select")
     }
 }
 
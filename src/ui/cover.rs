@@ -5,7 +5,10 @@ use std::process::{Child, Stdio};
 
 use std::sync::{Arc, RwLock};
 
+use cursive::direction::Direction;
+use cursive::event::{Event, EventResult};
 use cursive::theme::{ColorStyle, ColorType, PaletteColor};
+use cursive::view::CannotFocus;
 use cursive::{Cursive, Printer, Vec2, View};
 use ioctl_rs::{ioctl, TIOCGWINSZ};
 use log::{debug, error};
@@ -27,6 +30,8 @@ pub struct CoverView {
     drawn_url: RwLock<Option<String>>,
     ueberzug: RwLock<Option<Child>>,
     font_size: Vec2,
+    /// The image to show instead of the cover of the playing item.
+    url: Option<Arc<RwLock<Option<String>>>>,
 }
 
 impl CoverView {
@@ -60,6 +65,20 @@ impl CoverView {
             last_size: RwLock::new(Vec2::new(0, 0)),
             drawn_url: RwLock::new(None),
             font_size,
+            url: None,
+        }
+    }
+
+    /// Show the image at `url` once it is set, instead of the cover of the playing item.
+    pub fn with_url(
+        queue: Arc<Queue>,
+        library: Arc<Library>,
+        config: &Config,
+        url: Arc<RwLock<Option<String>>>,
+    ) -> Self {
+        Self {
+            url: Some(url),
+            ..Self::new(queue, library, config)
         }
     }
 
@@ -176,6 +195,10 @@ impl CoverView {
             return Some(path);
         }
 
+        if self.queue.get_spotify().data_saver() {
+            return None;
+        }
+
         loading.insert(url.clone());
 
         let loading_thread = self.loading.clone();
@@ -204,7 +227,10 @@ impl View for CoverView {
             }
         });
 
-        let cover_url = self.queue.get_current().and_then(|t| t.cover_url());
+        let cover_url = match &self.url {
+            Some(url) => url.read().unwrap().clone(),
+            None => self.queue.get_current().and_then(|t| t.cover_url()),
+        };
 
         if let Some(url) = cover_url {
             self.draw_cover(url, printer.offset, printer.size);
@@ -279,6 +305,8 @@ impl ViewExt for CoverView {
                                 };
                             }
                         }
+                        // Handled globally, see CommandManager.
+                        GotoMode::Playing => {}
                     }
                 }
             }
@@ -288,3 +316,50 @@ impl ViewExt for CoverView {
         Ok(CommandResult::Ignored)
     }
 }
+
+/// The image at a given URL, like the cover of the selected album, shown as large as possible above
+/// the other views until a key is pressed.
+pub struct CoverModal {
+    cover: CoverView,
+}
+
+impl CoverModal {
+    pub fn new(queue: Arc<Queue>, library: Arc<Library>, url: String) -> Self {
+        let config = library.cfg.clone();
+        let url = Arc::new(RwLock::new(Some(url)));
+        Self {
+            cover: CoverView::with_url(queue, library, &config, url),
+        }
+    }
+}
+
+impl View for CoverModal {
+    fn draw(&self, printer: &Printer<'_, '_>) {
+        self.cover.draw(printer);
+    }
+
+    fn required_size(&mut self, constraint: Vec2) -> Vec2 {
+        constraint
+    }
+
+    fn take_focus(&mut self, _source: Direction) -> Result<EventResult, CannotFocus> {
+        Ok(EventResult::consumed())
+    }
+
+    fn on_event(&mut self, event: Event) -> EventResult {
+        match event {
+            Event::Key(_) | Event::Char(_) | Event::CtrlChar(_) | Event::AltChar(_) => {
+                EventResult::with_cb(|s| {
+                    s.pop_layer();
+                })
+            }
+            _ => EventResult::Ignored,
+        }
+    }
+}
+
+impl Drop for CoverModal {
+    fn drop(&mut self) {
+        self.cover.clear_cover();
+    }
+}
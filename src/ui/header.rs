@@ -0,0 +1,198 @@
+#[cfg(feature = "cover")]
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+
+use cursive::theme::{ColorStyle, PaletteColor};
+use cursive::Printer;
+
+use crate::library::Library;
+use crate::queue::Queue;
+#[cfg(feature = "cover")]
+use crate::ui::cover::CoverView;
+
+/// The height of the image next to the header text, in rows.
+#[cfg(feature = "cover")]
+const IMAGE_HEIGHT: usize = 4;
+/// The width of the image, about twice the height as characters are narrow.
+#[cfg(feature = "cover")]
+const IMAGE_WIDTH: usize = 2 * IMAGE_HEIGHT + 1;
+
+/// What is shown in a [Header]. Clones share their content, so it can be filled in by a thread
+/// that fetches it in the background.
+#[derive(Clone, Default)]
+pub struct HeaderContent {
+    description: Arc<RwLock<Option<String>>>,
+    details: Arc<RwLock<Vec<String>>>,
+    summary: Arc<RwLock<Option<String>>>,
+    image_url: Arc<RwLock<Option<String>>>,
+}
+
+impl HeaderContent {
+    /// Show the `description`, the `details` in the line below it, and the image at `image_url`.
+    pub fn set(
+        &self,
+        description: Option<String>,
+        details: Vec<String>,
+        image_url: Option<String>,
+    ) {
+        *self.description.write().unwrap() = description.filter(|d| !d.trim().is_empty());
+        *self.details.write().unwrap() = details;
+        *self.image_url.write().unwrap() = image_url;
+    }
+
+    /// Show `summary` after the details, usually the length of the content below the header, which
+    /// is only known once it is loaded.
+    pub fn set_summary(&self, summary: String) {
+        *self.summary.write().unwrap() = Some(summary);
+    }
+
+    fn lines(&self) -> (Option<String>, String) {
+        let mut details = self.details.read().unwrap().clone();
+        details.extend(self.summary.read().unwrap().clone());
+        (
+            self.description.read().unwrap().clone(),
+            details.join(" · "),
+        )
+    }
+
+    fn is_empty(&self) -> bool {
+        self.description.read().unwrap().is_none() && self.details.read().unwrap().is_empty()
+    }
+}
+
+/// A block of information shown above a list, like the description of a playlist. It is hidden
+/// until its content is set, and while the `headers` command has turned headers off.
+pub struct Header {
+    content: HeaderContent,
+    library: Arc<Library>,
+    #[cfg(feature = "cover")]
+    image: CoverView,
+    /// Whether the image is on screen and has to be removed once the header is hidden.
+    #[cfg(feature = "cover")]
+    image_drawn: AtomicBool,
+}
+
+impl Header {
+    #[cfg_attr(not(feature = "cover"), allow(unused_variables))]
+    pub fn new(queue: Arc<Queue>, library: Arc<Library>) -> Self {
+        let content = HeaderContent::default();
+        Self {
+            #[cfg(feature = "cover")]
+            image: CoverView::with_url(
+                queue,
+                library.clone(),
+                &library.cfg,
+                content.image_url.clone(),
+            ),
+            #[cfg(feature = "cover")]
+            image_drawn: AtomicBool::new(false),
+            content,
+            library,
+        }
+    }
+
+    pub fn content(&self) -> &HeaderContent {
+        &self.content
+    }
+
+    fn is_hidden(&self) -> bool {
+        self.content.is_empty() || self.library.cfg.state().compact_headers
+    }
+
+    #[cfg(feature = "cover")]
+    fn has_image(&self) -> bool {
+        self.content.image_url.read().unwrap().is_some()
+    }
+
+    /// The number of rows the header takes up, including the empty row below it.
+    pub fn height(&self) -> usize {
+        if self.is_hidden() {
+            return 0;
+        }
+        let (description, _) = self.content.lines();
+        let text_height = 1 + usize::from(description.is_some());
+        #[cfg(feature = "cover")]
+        if self.has_image() {
+            return text_height.max(IMAGE_HEIGHT) + 1;
+        }
+        text_height + 1
+    }
+
+    pub fn draw(&self, printer: &Printer<'_, '_>) {
+        if self.is_hidden() {
+            #[cfg(feature = "cover")]
+            if self.image_drawn.load(Ordering::Relaxed) {
+                self.on_leave();
+            }
+            return;
+        }
+
+        #[allow(unused_mut)]
+        let mut x = 0;
+        #[cfg(feature = "cover")]
+        if self.has_image() {
+            let height = self.height() - 1;
+            self.image.draw(&printer.cropped((IMAGE_WIDTH, height)));
+            self.image_drawn.store(true, Ordering::Relaxed);
+            x = IMAGE_WIDTH + 1;
+        }
+
+        let (description, details) = self.content.lines();
+        let mut y = 0;
+        if let Some(description) = description {
+            printer.print((x, y), &description);
+            y += 1;
+        }
+        printer.with_color(ColorStyle::from(PaletteColor::Secondary), |printer| {
+            printer.print((x, y), &details);
+        });
+    }
+
+    /// Remove the image from the screen, as it is drawn on top of the terminal.
+    pub fn on_leave(&self) {
+        #[cfg(feature = "cover")]
+        {
+            crate::traits::ViewExt::on_leave(&self.image);
+            self.image_drawn.store(false, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Implement [cursive::view::ViewWrapper] for a view that shows the [Header] `$header` above the
+/// wrapped view `$view`, like [cursive::wrap_impl].
+macro_rules! wrap_with_header {
+    (self.$header:ident, self.$view:ident: $t:ty) => {
+        cursive::wrap_impl!(self.$view: $t);
+
+        fn wrap_draw(&self, printer: &cursive::Printer<'_, '_>) {
+            let height = self.$header.height();
+            self.$header
+                .draw(&printer.cropped((printer.size.x, height)));
+            cursive::View::draw(&self.$view, &printer.offset((0, height)));
+        }
+
+        fn wrap_layout(&mut self, size: cursive::Vec2) {
+            let height = self.$header.height();
+            cursive::View::layout(&mut self.$view, size.saturating_sub((0, height)));
+        }
+
+        fn wrap_required_size(&mut self, constraint: cursive::Vec2) -> cursive::Vec2 {
+            let height = self.$header.height();
+            let constraint = constraint.saturating_sub((0, height));
+            cursive::View::required_size(&mut self.$view, constraint) + (0, height)
+        }
+
+        fn wrap_on_event(&mut self, event: cursive::event::Event) -> cursive::event::EventResult {
+            let height = self.$header.height();
+            cursive::View::on_event(&mut self.$view, event.relativized((0, height)))
+        }
+
+        fn wrap_important_area(&self, size: cursive::Vec2) -> cursive::Rect {
+            let height = self.$header.height();
+            let size = size.saturating_sub((0, height));
+            cursive::View::important_area(&self.$view, size) + (0, height)
+        }
+    };
+}
+
+pub(crate) use wrap_with_header;
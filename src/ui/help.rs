@@ -6,49 +6,107 @@ use cursive::view::ViewWrapper;
 use cursive::views::{ScrollView, TextView};
 use cursive::Cursive;
 use ncspot::CONFIGURATION_FILE_NAME;
+use strum::IntoEnumIterator;
 
-use crate::command::{Command, MoveAmount, MoveMode};
+use crate::command::{Command, CommandCategory, JumpMode, MoveAmount, MoveMode, COMMANDS};
 use crate::commands::CommandResult;
 use crate::config::config_path;
+use crate::i18n::{tr, tr_args};
 use crate::traits::ViewExt;
 use cursive::view::scroll::Scroller;
 
+/// Lists all commands by category with their description and key bindings. Entries can be
+/// filtered with a jump query (`/`).
 pub struct HelpView {
     view: ScrollView<TextView>,
+    bindings: HashMap<String, Vec<Command>>,
+    filter: String,
 }
 
 impl HelpView {
     pub fn new(bindings: HashMap<String, Vec<Command>>) -> Self {
-        let mut text = StyledString::styled("Keybindings\n\n", Effect::Bold);
+        let text = Self::content(&bindings, "");
+        Self {
+            view: ScrollView::new(TextView::new(text)),
+            bindings,
+            filter: String::new(),
+        }
+    }
+
+    /// The help text for all commands matching `filter`.
+    fn content(bindings: &HashMap<String, Vec<Command>>, filter: &str) -> StyledString {
+        let mut text = StyledString::styled(format!("{}\n\n", tr("Commands")), Effect::Bold);
 
-        let note = format!(
-            "Custom bindings can be set in {} within the [keybindings] section.\n\n",
-            config_path(CONFIGURATION_FILE_NAME)
+        let note = tr_args(
+            "Custom bindings can be set in {} within the [keybindings] section. Press / to filter.",
+            &[config_path(CONFIGURATION_FILE_NAME)
                 .to_str()
-                .unwrap_or_default()
+                .unwrap_or_default()],
         );
-        text.append(StyledString::styled(note, Effect::Italic));
+        text.append(StyledString::styled(format!("{note}\n\n"), Effect::Italic));
 
+        // Bindings are listed under the first command they execute.
         let mut keys: Vec<&String> = bindings.keys().collect();
         keys.sort();
 
-        for key in keys {
-            let commands = &bindings[key];
-            let binding = format!(
-                "{} -> {}\n",
-                key,
-                commands
+        let query = filter.to_lowercase();
+        let mut empty = true;
+        for category in CommandCategory::iter() {
+            let mut section = StyledString::new();
+            for info in COMMANDS.iter().filter(|info| info.category == category) {
+                let info_bindings: Vec<String> = keys
                     .iter()
-                    .map(|c| c.to_string())
-                    .collect::<Vec<_>>()
-                    .join("; ")
-            );
-            text.append(binding);
+                    .filter(|key| {
+                        bindings[**key]
+                            .first()
+                            .is_some_and(|c| c.basename() == info.name)
+                    })
+                    .map(|key| {
+                        let commands = bindings[*key]
+                            .iter()
+                            .map(|c| c.to_string())
+                            .collect::<Vec<_>>()
+                            .join("; ");
+                        format!("{key} -> {commands}")
+                    })
+                    .collect();
+
+                let matches = query.is_empty()
+                    || info.name.contains(&query)
+                    || info.description.to_lowercase().contains(&query)
+                    || info_bindings
+                        .iter()
+                        .any(|binding| binding.to_lowercase().contains(&query));
+                if !matches {
+                    continue;
+                }
+
+                section.append_styled(format!("  {:<14}", info.name), Effect::Bold);
+                section.append(format!("{}\n", info.description));
+                for binding in info_bindings {
+                    section.append(format!("  {:<14}{binding}\n", ""));
+                }
+            }
+
+            if !section.is_empty() {
+                text.append_styled(format!("{}\n", tr(category.title())), Effect::Underline);
+                text.append(section);
+                text.append("\n");
+                empty = false;
+            }
         }
 
-        Self {
-            view: ScrollView::new(TextView::new(text)),
+        if empty {
+            text.append(tr_args("No commands match \"{}\"", &[filter]));
         }
+        text
+    }
+
+    fn set_filter(&mut self, filter: &str) {
+        self.filter = filter.to_string();
+        let text = Self::content(&self.bindings, filter);
+        self.view.get_inner_mut().set_content(text);
+        self.view.scroll_to_top();
     }
 }
 
@@ -58,12 +116,24 @@ impl ViewWrapper for HelpView {
 
 impl ViewExt for HelpView {
     fn title(&self) -> String {
-        "Help".to_string()
+        tr("Help").to_string()
+    }
+
+    fn title_sub(&self) -> String {
+        if self.filter.is_empty() {
+            String::new()
+        } else {
+            tr_args("Filter: {}", &[&self.filter])
+        }
     }
 
     fn on_command(&mut self, _s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
         match cmd {
             Command::Help => Ok(CommandResult::Consumed(None)),
+            Command::Jump(JumpMode::Query(query)) => {
+                self.set_filter(query);
+                Ok(CommandResult::Consumed(None))
+            }
             Command::Move(mode, amount) => {
                 let scroller = self.view.get_scroller_mut();
                 let viewport = scroller.content_viewport();
@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use cursive::view::Margins;
+use cursive::views::{Dialog, ScrollView, SelectView};
+
+use crate::i18n::tr;
+use crate::library::Library;
+use crate::queue::Queue;
+use crate::traits::ListItem;
+use crate::ui::layout::Layout;
+use crate::ui::modal::Modal;
+
+/// Dialog to open one of the recently visited artists, albums and playlists again.
+pub fn history_dialog(
+    items: Vec<Box<dyn ListItem>>,
+    queue: Arc<Queue>,
+    library: Arc<Library>,
+) -> Option<Modal<Dialog>> {
+    if items.is_empty() {
+        return None;
+    }
+
+    let mut list_select: SelectView<usize> = SelectView::new().autojump();
+    for (index, item) in items.iter().enumerate() {
+        list_select.add_item(item.display_left(&library), index);
+    }
+    list_select.set_on_submit(move |s, index: &usize| {
+        s.pop_layer();
+        if let Some(view) = items[*index].open(queue.clone(), library.clone()) {
+            s.call_on_name("main", move |v: &mut Layout| v.push_view(view));
+        }
+    });
+
+    let dialog = Dialog::new()
+        .title(tr("History"))
+        .dismiss_button(tr("Cancel"))
+        .padding(Margins::lrtb(1, 1, 1, 0))
+        .content(ScrollView::new(list_select));
+    Some(Modal::new(dialog))
+}
@@ -15,19 +15,45 @@ use unicode_width::UnicodeWidthStr;
 
 use crate::application::UserData;
 use crate::command::{self, Command, JumpMode};
-use crate::commands::CommandResult;
+use crate::commands::{CommandManager, CommandResult, MouseGesture};
 use crate::config::{self, Config};
 use crate::events;
 use crate::ext_traits::CursiveExt;
-use crate::traits::{IntoBoxedViewExt, ViewExt};
+use crate::i18n::tr;
+use crate::traits::{IntoBoxedViewExt, ListItem, ViewExt};
+
+/// How many visited views are remembered for the `history` command.
+const HISTORY_LENGTH: usize = 50;
+
+/// Drawn between the keys in the hint line.
+const KEY_HINT_SEPARATOR: &str = " • ";
+
+/// Drawn in front of the breadcrumbs, clicking it goes back one view.
+const BREADCRUMB_BACK: &str = "< ";
+const BREADCRUMB_SEPARATOR: &str = " > ";
 
 pub struct Layout {
     screens: HashMap<String, Box<dyn ViewExt>>,
     stack: HashMap<String, Vec<Box<dyn ViewExt>>>,
+    /// The views closed with `back` on each screen, which `forward` opens again.
+    forward: HashMap<String, Vec<Box<dyn ViewExt>>>,
+    /// The items of the visited artist, album and playlist views, the most recent one first.
+    history: Vec<Box<dyn ListItem>>,
     statusbar: Box<dyn View>,
+    /// The preview of the queue shown instead of the screens in the mini mode.
+    mini_queue: Option<Box<dyn View>>,
+    /// Whether the mini mode was switched on or off with `mini`. Until then, it depends on the
+    /// height of the terminal.
+    mini: Option<bool>,
+    /// The keybindings, used to show the keys of the commands of the focused view.
+    bindings: HashMap<String, Vec<Command>>,
     focus: Option<String>,
     cmdline: EditView,
     cmdline_focus: bool,
+    /// Whether the next key press is described instead of executed.
+    describe_key: bool,
+    /// Whether the focused view is currently fetching its data again.
+    refreshing: bool,
     result: Result<Option<String>, String>,
     result_time: Option<SystemTime>,
     last_size: Vec2,
@@ -92,10 +118,17 @@ impl Layout {
         Self {
             screens: HashMap::new(),
             stack: HashMap::new(),
+            forward: HashMap::new(),
+            history: Vec::new(),
             statusbar: status.into_boxed_view(),
+            mini_queue: None,
+            mini: None,
+            bindings: HashMap::new(),
             focus: None,
             cmdline: command_line_input,
             cmdline_focus: false,
+            describe_key: false,
+            refreshing: false,
             result: Ok(None),
             result_time: None,
             last_size: Vec2::new(0, 0),
@@ -112,6 +145,11 @@ impl Layout {
         }
     }
 
+    /// Show the bindings of the next key press in the result bar instead of executing them.
+    pub fn describe_next_key(&mut self) {
+        self.describe_key = true;
+    }
+
     pub fn enable_jump(&mut self) {
         if !self.cmdline_focus {
             self.cmdline.set_content("/");
@@ -127,6 +165,7 @@ impl Layout {
         let s = id.into();
         self.screens.insert(s.clone(), view.into_boxed_view_ext());
         self.stack.insert(s.clone(), Vec::new());
+        self.forward.insert(s.clone(), Vec::new());
         self.focus = Some(s);
     }
 
@@ -135,6 +174,11 @@ impl Layout {
         self
     }
 
+    /// The ID of the focused screen, e.g. `queue`.
+    pub fn screen_id(&self) -> Option<String> {
+        self.focus.clone()
+    }
+
     pub fn has_screen(&self, id: &str) -> bool {
         self.screens.contains_key(id)
     }
@@ -152,11 +196,68 @@ impl Layout {
         self.ev.trigger();
     }
 
+    /// Show a spinner next to the title of the focused view while it is refreshed.
+    pub fn set_refreshing(&mut self, refreshing: bool) {
+        self.refreshing = refreshing;
+    }
+
+    /// The item shown in the focused view, if it shows one.
+    pub fn current_item(&mut self) -> Option<Box<dyn ListItem>> {
+        self.get_current_view_mut()
+            .and_then(|view| view.history_item())
+    }
+
+    /// Fetch the data of the focused view again. Returns `false` if it has nothing to fetch.
+    pub fn refresh_view(&mut self) -> bool {
+        self.get_current_view_mut()
+            .map_or(false, |view| view.refresh())
+    }
+
+    /// Run `cmd` in the focused view after it was confirmed in a dialog, see
+    /// [ViewExt::on_confirmed].
+    pub fn on_confirmed(&mut self, s: &mut Cursive, cmd: &Command) {
+        let Some(view) = self.get_current_view_mut() else {
+            return;
+        };
+        match view.on_confirmed(s, cmd) {
+            Ok(CommandResult::Consumed(message)) => self.set_result(Ok(message)),
+            Err(message) => self.set_result(Err(message)),
+            Ok(_) => {}
+        }
+    }
+
     pub fn set_result(&mut self, result: Result<Option<String>, String>) {
         self.result = result;
         self.result_time = Some(SystemTime::now());
     }
 
+    /// Show `view` above the statusbar instead of the screens in the mini mode.
+    pub fn set_mini_queue<T: IntoBoxedView>(&mut self, view: T) {
+        self.mini_queue = Some(view.into_boxed_view());
+    }
+
+    /// Switch the mini mode on or off, or toggle it if `mini` is `None`.
+    pub fn set_mini(&mut self, mini: Option<bool>) {
+        self.mini = Some(mini.unwrap_or_else(|| !self.is_mini()));
+        self.ev.trigger();
+    }
+
+    /// Whether only the statusbar and a preview of the queue are shown, either because the mini
+    /// mode was switched on or because the terminal is lower than `mini_mode_height`.
+    pub fn is_mini(&self) -> bool {
+        self.mini.unwrap_or_else(|| {
+            self.configuration
+                .values()
+                .mini_mode_height
+                .is_some_and(|height| self.last_size.y < height)
+        })
+    }
+
+    /// Use `bindings` to look up the keys shown in the hint line.
+    pub fn set_bindings(&mut self, bindings: HashMap<String, Vec<Command>>) {
+        self.bindings = bindings;
+    }
+
     pub fn clear_cmdline(&mut self) {
         self.cmdline.set_content("");
         self.cmdline_focus = false;
@@ -165,6 +266,10 @@ impl Layout {
     }
 
     fn get_result(&self) -> Result<Option<String>, String> {
+        // Screen readers need the announcement line to stay, so it can be read again.
+        if self.configuration.values().accessibility.unwrap_or(false) {
+            return self.result.clone();
+        }
         if let Some(t) = self.result_time {
             if t.elapsed().unwrap() > Duration::from_secs(5) {
                 return Ok(None);
@@ -173,11 +278,64 @@ impl Layout {
         self.result.clone()
     }
 
+    /// The number of lines below the statusbar, used by the command line and `result`. If neither
+    /// is shown, the line holds the key hints if they are enabled.
+    fn cmdline_height(&self, result: &Result<Option<String>, String>) -> usize {
+        let cmdline_visible = self.cmdline.get_content().len() > 0;
+        let result_visible = result.as_ref().map(Option::is_some).unwrap_or(true);
+        let height = usize::from(cmdline_visible) + usize::from(result_visible);
+        if height == 0 && self.configuration.values().key_hints.unwrap_or(false) {
+            1
+        } else {
+            height
+        }
+    }
+
+    /// The keys of the commands that are most useful in the focused view, taken from the actual
+    /// keybindings. Commands without a key of their own are left out.
+    fn key_hints(&self) -> String {
+        let mut commands = self
+            .get_top_view()
+            .map(|view| view.key_hints())
+            .unwrap_or_default();
+        commands.push(Command::Help);
+
+        commands
+            .iter()
+            .filter_map(|command| {
+                let command = command.to_string();
+                let key = self
+                    .bindings
+                    .iter()
+                    .filter(|(_, bound)| bound.len() == 1 && bound[0].to_string() == command)
+                    .map(|(key, _)| key)
+                    .min_by(|a, b| a.len().cmp(&b.len()).then(a.cmp(b)))?;
+                Some(format!("{key} {command}"))
+            })
+            .collect::<Vec<_>>()
+            .join(KEY_HINT_SEPARATOR)
+    }
+
     pub fn push_view(&mut self, view: Box<dyn ViewExt>) {
+        if let Some(forward) = self.focus.as_ref().and_then(|f| self.forward.get_mut(f)) {
+            forward.clear();
+        }
+        self.open_view(view);
+    }
+
+    fn open_view(&mut self, view: Box<dyn ViewExt>) {
         if let Some(view) = self.get_top_view() {
             view.on_leave();
         }
 
+        if let Some(item) = view.history_item() {
+            let url = item.share_url();
+            self.history
+                .retain(|visited| url.is_none() || visited.share_url() != url);
+            self.history.insert(0, item);
+            self.history.truncate(HISTORY_LENGTH);
+        }
+
         if let Some(stack) = self.get_focussed_stack_mut() {
             stack.push(view)
         }
@@ -188,7 +346,57 @@ impl Layout {
             view.on_leave();
         }
 
-        self.get_focussed_stack_mut().map(|stack| stack.pop());
+        let popped = self.get_focussed_stack_mut().and_then(|stack| stack.pop());
+        if let (Some(view), Some(forward)) = (
+            popped,
+            self.focus.as_ref().and_then(|f| self.forward.get_mut(f)),
+        ) {
+            forward.push(view);
+        }
+    }
+
+    /// Open the view that was last closed with `back` on the focused screen again. Returns `false`
+    /// if there is none.
+    pub fn forward_view(&mut self) -> bool {
+        let view = self
+            .focus
+            .as_ref()
+            .and_then(|f| self.forward.get_mut(f))
+            .and_then(|forward| forward.pop());
+        match view {
+            Some(view) => {
+                self.open_view(view);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The items of the recently visited views, the most recent one first.
+    pub fn history(&self) -> Vec<Box<dyn ListItem>> {
+        self.history.iter().map(|item| item.as_listitem()).collect()
+    }
+
+    /// The titles of the views below the top view of the focused stack, starting with the screen,
+    /// along with the column each of them starts at.
+    fn breadcrumbs(&self) -> Vec<(usize, String)> {
+        let Some(stack) = self.get_focussed_stack().filter(|stack| !stack.is_empty()) else {
+            return Vec::new();
+        };
+        let screen_title = self
+            .get_current_screen()
+            .map(|screen| screen.title())
+            .unwrap_or_default();
+
+        let mut column = BREADCRUMB_BACK.width() + 1;
+        std::iter::once(screen_title)
+            .chain(stack[..stack.len() - 1].iter().map(|view| view.title()))
+            .map(|title| {
+                let start = column;
+                column += title.width() + BREADCRUMB_SEPARATOR.width();
+                (start, title)
+            })
+            .collect()
     }
 
     #[allow(clippy::borrowed_box)]
@@ -211,12 +419,6 @@ impl Layout {
         self.focus.as_ref().and_then(|focus| self.stack.get(focus))
     }
 
-    fn is_current_stack_empty(&self) -> bool {
-        self.get_focussed_stack()
-            .map(|s| s.is_empty())
-            .unwrap_or(false)
-    }
-
     #[allow(clippy::borrowed_box)]
     fn get_top_view(&self) -> Option<&Box<dyn ViewExt>> {
         let focussed_stack = self.get_focussed_stack();
@@ -268,28 +470,38 @@ impl View for Layout {
         let result = self.get_result();
 
         let cmdline_visible = self.cmdline.get_content().len() > 0;
-        let mut cmdline_height = usize::from(cmdline_visible);
-        if result.as_ref().map(Option::is_some).unwrap_or(true) {
-            cmdline_height += 1;
-        }
+        let cmdline_height = self.cmdline_height(&result);
 
-        let screen_title = self
-            .get_current_screen()
-            .map(|screen| screen.title())
-            .unwrap_or_default();
-
-        if let Some(view) = self.get_top_view() {
-            // back button + title
-            if !self.is_current_stack_empty() {
+        if self.is_mini() {
+            if let Some(view) = &self.mini_queue {
+                let height = printer.size.y.saturating_sub(2 + cmdline_height);
+                view.draw(&printer.cropped((printer.size.x, height)));
+            }
+        } else if let Some(view) = self.get_top_view() {
+            // back button + the views below the current one
+            let breadcrumbs = self.breadcrumbs();
+            if !breadcrumbs.is_empty() {
                 printer.with_color(ColorStyle::title_secondary(), |printer| {
-                    printer.print((1, 0), &format!("< {screen_title}"));
+                    printer.print((1, 0), BREADCRUMB_BACK);
+                    for (i, (column, title)) in breadcrumbs.iter().enumerate() {
+                        if i > 0 {
+                            let separator_column = column - BREADCRUMB_SEPARATOR.width();
+                            printer.print((separator_column, 0), BREADCRUMB_SEPARATOR);
+                        }
+                        printer.print((*column, 0), title);
+                    }
                 });
             }
 
             // view title
             printer.with_color(ColorStyle::title_primary(), |printer| {
-                let offset = HAlign::Center.get_offset(view.title().width(), printer.size.x);
-                printer.print((offset, 0), &view.title());
+                let title = if self.refreshing {
+                    format!("{} {}", crate::utils::spinner_frame(), view.title())
+                } else {
+                    view.title()
+                };
+                let offset = HAlign::Center.get_offset(title.width(), printer.size.x);
+                printer.print((offset, 0), &title);
             });
 
             printer.with_color(ColorStyle::secondary(), |printer| {
@@ -321,6 +533,10 @@ impl View for Layout {
                 printer.print_hline((0, printer.size.y - cmdline_height), printer.size.x, " ");
                 printer.print((0, printer.size.y - cmdline_height), &format!("ERROR: {e}"));
             });
+        } else if !cmdline_visible && cmdline_height > 0 {
+            printer.with_color(ColorStyle::secondary(), |printer| {
+                printer.print((0, printer.size.y - 1), &self.key_hints());
+            });
         }
 
         if cmdline_visible {
@@ -336,7 +552,11 @@ impl View for Layout {
 
         self.cmdline.layout(Vec2::new(size.x, 1));
 
-        if let Some(view) = self.get_current_view_mut() {
+        if self.is_mini() {
+            if let Some(view) = self.mini_queue.as_mut() {
+                view.layout(Vec2::new(size.x, size.y.saturating_sub(3)));
+            }
+        } else if let Some(view) = self.get_current_view_mut() {
             view.layout(Vec2::new(size.x, size.y - 3));
         }
     }
@@ -352,6 +572,15 @@ impl View for Layout {
                 EventResult::consumed()
             }
             _ if self.cmdline_focus => self.command_line_handle_event(event),
+            _ if self.describe_key && CommandManager::key_name(&event).is_some() => {
+                self.describe_key = false;
+                EventResult::with_cb(move |s| {
+                    if let Some(data) = s.user_data::<UserData>().cloned() {
+                        let description = data.cmd.describe_key(&event);
+                        s.on_layout(|_, mut layout| layout.set_result(Ok(Some(description))));
+                    }
+                })
+            }
             Event::Char(character)
                 if !self.cmdline_focus
                     && (character
@@ -393,29 +622,30 @@ impl View for Layout {
                 ..
             } => {
                 // Handle mouse events in the command/jump area.
-                if position.y == 0 {
-                    if mouse_event == MouseEvent::Press(MouseButton::Left)
-                        && !self.is_current_stack_empty()
-                        && position.x
-                            < self
-                                .get_current_screen()
-                                .map(|screen| screen.title())
-                                .unwrap_or_default()
-                                .len()
-                                + 3
-                    {
-                        self.pop_view();
+                if position.y == 0 && !self.is_mini() {
+                    if mouse_event == MouseEvent::Press(MouseButton::Left) {
+                        // Go back to the clicked breadcrumb, or one view for the back button.
+                        let breadcrumbs = self.breadcrumbs();
+                        let clicked = breadcrumbs
+                            .iter()
+                            .rposition(|(column, title)| {
+                                position.x >= *column && position.x < column + title.width()
+                            })
+                            .or_else(|| {
+                                (!breadcrumbs.is_empty()
+                                    && position.x < 1 + BREADCRUMB_BACK.width())
+                                .then(|| breadcrumbs.len() - 1)
+                            });
+                        if let Some(index) = clicked {
+                            for _ in index..breadcrumbs.len() {
+                                self.pop_view();
+                            }
+                        }
                     }
                     return EventResult::consumed();
                 }
 
-                let result = self.get_result();
-
-                let cmdline_visible = self.cmdline.get_content().len() > 0;
-                let mut cmdline_height = usize::from(cmdline_visible);
-                if result.as_ref().map(Option::is_some).unwrap_or(true) {
-                    cmdline_height += 1;
-                }
+                let cmdline_height = self.cmdline_height(&self.get_result());
 
                 if position.y >= self.last_size.y.saturating_sub(2 + cmdline_height)
                     && position.y < self.last_size.y - cmdline_height
@@ -426,12 +656,28 @@ impl View for Layout {
                     return EventResult::consumed();
                 }
 
-                if let Some(view) = self.get_current_view_mut() {
+                if self.is_mini() {
+                    return EventResult::Ignored;
+                }
+
+                // Wheel bindings apply to all views of a screen, not only to lists.
+                let bound_wheel = MouseGesture::from_wheel(mouse_event).filter(|gesture| {
+                    gesture.is_bound_in(&self.configuration, self.focus.as_deref())
+                });
+                if let Some(gesture) = bound_wheel {
+                    EventResult::with_cb(move |s| {
+                        if let Some(data) = s.user_data::<UserData>().cloned() {
+                            data.cmd.handle_mouse(s, gesture);
+                        }
+                    })
+                } else if let Some(view) = self.get_current_view_mut() {
                     view.on_event(event.relativized((0, 1)))
                 } else {
                     EventResult::Ignored
                 }
             }
+            // The screens are hidden in the mini mode, so keys only trigger the keybindings.
+            _ if self.is_mini() => EventResult::Ignored,
             _ => {
                 if let Some(view) = self.get_current_view_mut() {
                     view.on_event(event.relativized((0, 1)))
@@ -486,6 +732,13 @@ impl ViewExt for Layout {
                 self.pop_view();
                 Ok(CommandResult::Consumed(None))
             }
+            Command::Forward => {
+                if self.forward_view() {
+                    Ok(CommandResult::Consumed(None))
+                } else {
+                    Err(tr("There is no view to go forward to").to_string())
+                }
+            }
             _ => {
                 if let Some(view) = self.get_current_view_mut() {
                     view.on_command(s, cmd)
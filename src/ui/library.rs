@@ -1,23 +1,30 @@
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use cursive::view::ViewWrapper;
 use cursive::Cursive;
 use strum::IntoEnumIterator;
 
-use crate::command::Command;
+use crate::command::{Command, YearRange};
 use crate::commands::CommandResult;
 use crate::config::LibraryTab;
+use crate::i18n::{tr, tr_args};
 use crate::library::Library;
 use crate::queue::Queue;
 use crate::traits::ViewExt;
+use crate::ui::album_groups::AlbumGroupsView;
+use crate::ui::albums::AlbumsView;
 use crate::ui::browse::BrowseView;
 use crate::ui::listview::ListView;
+use crate::ui::made_for_you::MadeForYouView;
 use crate::ui::playlists::PlaylistsView;
 use crate::ui::tabbedview::TabbedView;
 
 pub struct LibraryView {
     tabs: TabbedView,
-    display_name: Option<String>,
+    library: Arc<Library>,
+    /// The index of the albums tab if it can be filtered, and the years it is filtered by.
+    albums_tab: Option<usize>,
+    albums_filter: Arc<RwLock<Option<YearRange>>>,
 }
 
 impl LibraryView {
@@ -28,46 +35,62 @@ impl LibraryView {
             .values()
             .library_tabs
             .clone()
-            .unwrap_or_else(|| Vec::from_iter(LibraryTab::iter()));
+            .unwrap_or_else(|| {
+                // The personalized playlists are searched for, so that tab is only shown on
+                // request.
+                LibraryTab::iter()
+                    .filter(|tab| !matches!(tab, LibraryTab::MadeForYou))
+                    .collect()
+            });
+        let mut albums_tab = None;
+        let albums_filter = Arc::new(RwLock::new(None));
 
         for tab in selected_tabs {
             match tab {
                 LibraryTab::Tracks => tabview.add_tab(
-                    "Tracks",
+                    tr("Tracks"),
                     ListView::new(library.tracks.clone(), queue.clone(), library.clone()),
                 ),
-                LibraryTab::Albums => tabview.add_tab(
-                    "Albums",
-                    ListView::new(library.albums.clone(), queue.clone(), library.clone()),
-                ),
+                LibraryTab::Albums if library.cfg.values().group_albums.unwrap_or(false) => tabview
+                    .add_tab(
+                        tr("Albums"),
+                        AlbumGroupsView::new(queue.clone(), library.clone()),
+                    ),
+                LibraryTab::Albums => {
+                    albums_tab = Some(tabview.len());
+                    tabview.add_tab(
+                        tr("Albums"),
+                        AlbumsView::new(queue.clone(), library.clone(), albums_filter.clone()),
+                    )
+                }
                 LibraryTab::Artists => tabview.add_tab(
-                    "Artists",
+                    tr("Artists"),
                     ListView::new(library.artists.clone(), queue.clone(), library.clone()),
                 ),
                 LibraryTab::Playlists => tabview.add_tab(
-                    "Playlists",
+                    tr("Playlists"),
                     PlaylistsView::new(queue.clone(), library.clone()),
                 ),
                 LibraryTab::Podcasts => tabview.add_tab(
-                    "Podcasts",
+                    tr("Podcasts"),
                     ListView::new(library.shows.clone(), queue.clone(), library.clone()),
                 ),
-                LibraryTab::Browse => {
-                    tabview.add_tab("Browse", BrowseView::new(queue.clone(), library.clone()))
-                }
+                LibraryTab::Browse => tabview.add_tab(
+                    tr("Browse"),
+                    BrowseView::new(queue.clone(), library.clone()),
+                ),
+                LibraryTab::MadeForYou => tabview.add_tab(
+                    tr("Made For You"),
+                    MadeForYouView::new(queue.clone(), library.clone()),
+                ),
             }
         }
 
         Self {
             tabs: tabview,
-            display_name: {
-                let hide_username = library.cfg.values().hide_display_names.unwrap_or(false);
-                if hide_username {
-                    None
-                } else {
-                    library.display_name.clone()
-                }
-            },
+            library,
+            albums_tab,
+            albums_filter,
         }
     }
 }
@@ -78,14 +101,38 @@ impl ViewWrapper for LibraryView {
 
 impl ViewExt for LibraryView {
     fn title(&self) -> String {
-        if let Some(name) = &self.display_name {
-            format!("Library of {name}")
+        // The display name is only known once the library has been loaded.
+        let hide_username = self
+            .library
+            .cfg
+            .values()
+            .hide_display_names
+            .unwrap_or(false);
+        let display_name = self.library.display_name().filter(|_| !hide_username);
+        if let Some(name) = display_name {
+            tr_args("Library of {}", &[&name])
         } else {
-            "Library".to_string()
+            tr("Library").to_string()
         }
     }
 
+    fn key_hints(&self) -> Vec<Command> {
+        self.tabs.key_hints()
+    }
+
     fn on_command(&mut self, s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
+        if let Command::Filter(range) = cmd {
+            if let Some(index) = self.albums_tab.filter(|&i| i == self.tabs.selected()) {
+                *self.albums_filter.write().unwrap() = *range;
+                let title = match range {
+                    Some(range) => tr_args("Albums ({})", &[&range.to_string()]),
+                    None => tr("Albums").to_string(),
+                };
+                self.tabs.set_title(index, title);
+                return Ok(CommandResult::Consumed(None));
+            }
+        }
+
         self.tabs.on_command(s, cmd)
     }
 }
@@ -1,19 +1,23 @@
 use cursive::view::scroll::Scroller;
 use log::info;
 use std::cmp::{max, min, Ordering};
+use std::collections::BTreeSet;
 use std::sync::{Arc, RwLock};
+use std::thread;
 
 use cursive::align::HAlign;
 use cursive::event::{Callback, Event, EventResult, MouseButton, MouseEvent};
-use cursive::theme::{ColorStyle, ColorType, PaletteColor};
+use cursive::theme::{ColorStyle, ColorType, Effect, PaletteColor};
 use cursive::traits::View;
 use cursive::view::scroll;
 use cursive::{Cursive, Printer, Rect, Vec2};
 use unicode_width::UnicodeWidthStr;
 
+use crate::application::UserData;
 use crate::command::{Command, GotoMode, InsertSource, JumpMode, MoveAmount, MoveMode, TargetMode};
-use crate::commands::CommandResult;
+use crate::commands::{CommandResult, MouseGesture};
 use crate::ext_traits::CursiveExt;
+use crate::i18n::{tr, tr_args};
 use crate::library::Library;
 use crate::model::album::Album;
 use crate::model::artist::Artist;
@@ -30,7 +34,13 @@ use crate::traits::{IntoBoxedViewExt, ListItem, ViewExt};
 use crate::ui::album::AlbumView;
 use crate::ui::artist::ArtistView;
 use crate::ui::contextmenu::ContextMenu;
+#[cfg(feature = "cover")]
+use crate::ui::cover::CoverModal;
+use crate::ui::loading::LoadingState;
+#[cfg(feature = "cover")]
+use crate::ui::modal::Modal;
 use crate::ui::pagination::Pagination;
+use crate::utils::spinner_frame;
 
 pub struct ListView<I: ListItem> {
     content: Arc<RwLock<Vec<I>>>,
@@ -44,7 +54,18 @@ pub struct ListView<I: ListItem> {
     queue: Arc<Queue>,
     library: Arc<Library>,
     pagination: Pagination<I>,
+    loading: LoadingState,
     title: String,
+    dimmed: bool,
+    /// Whether the date an item was added is shown in front of the right column.
+    added_at: bool,
+    /// Whether the album or playlist an item was queued from is shown in front of the right
+    /// column.
+    source: bool,
+    /// Indices of the items marked to act on several of them at once, and the amount of items
+    /// when they were marked.
+    marked: BTreeSet<usize>,
+    marked_len: usize,
 }
 
 impl<I: ListItem> Scroller for ListView<I> {
@@ -71,7 +92,13 @@ impl<I: ListItem + Clone> ListView<I> {
             queue,
             library,
             pagination: Pagination::default(),
+            loading: LoadingState::default(),
             title: "".to_string(),
+            dimmed: false,
+            added_at: false,
+            source: false,
+            marked: BTreeSet::new(),
+            marked_len: 0,
         };
         result.try_paginate();
         result
@@ -82,10 +109,52 @@ impl<I: ListItem + Clone> ListView<I> {
         self
     }
 
+    /// Draw all items greyed out, e.g. because they were already played.
+    pub fn dimmed(mut self) -> Self {
+        self.dimmed = true;
+        self
+    }
+
+    /// Show the date the items were added, e.g. to a playlist.
+    pub fn with_added_at(mut self) -> Self {
+        self.added_at = true;
+        self
+    }
+
+    /// Show the album or playlist the items were queued from.
+    pub fn with_source(mut self) -> Self {
+        self.source = true;
+        self
+    }
+
+    /// Mark the selected item, or unmark it if it is already marked.
+    pub fn toggle_marked(&mut self) {
+        if !self.marked.remove(&self.selected) {
+            self.marked.insert(self.selected);
+        }
+        self.marked_len = self.content.read().unwrap().len();
+    }
+
+    /// The indices of the marked items that are still in the list, in ascending order.
+    pub fn marked(&self) -> Vec<usize> {
+        let len = self.content.read().unwrap().len();
+        self.marked.iter().copied().filter(|&i| i < len).collect()
+    }
+
+    /// Mark exactly the items at `indices`, e.g. after they were moved.
+    pub fn set_marked(&mut self, indices: impl IntoIterator<Item = usize>) {
+        self.marked = indices.into_iter().collect();
+        self.marked_len = self.content.read().unwrap().len();
+    }
+
     pub fn get_pagination(&self) -> &Pagination<I> {
         &self.pagination
     }
 
+    pub fn get_loading(&self) -> &LoadingState {
+        &self.loading
+    }
+
     /// Return the current amount of items in `content`
     ///
     /// If `include_paginator` is `true`, the pagination entry will be included
@@ -93,14 +162,27 @@ impl<I: ListItem + Clone> ListView<I> {
     pub fn content_len(&self, include_paginator: bool) -> usize {
         let content_len = self.content.read().unwrap().len();
 
-        // add 1 more row for paginator if we can paginate
-        if self.can_paginate() && include_paginator {
+        // add 1 more row for paginator if we can paginate, or for the loading status
+        let extra_row = self.can_paginate() || self.status_row(content_len).is_some();
+        if extra_row && include_paginator {
             content_len + 1
         } else {
             content_len
         }
     }
 
+    /// The row shown after the content while its first items are loading or after loading
+    /// failed, along with whether it is an error.
+    fn status_row(&self, content_len: usize) -> Option<(String, bool)> {
+        if let Some(error) = self.loading.error() {
+            Some((error, true))
+        } else if self.loading.is_loading() && content_len == 0 {
+            Some((format!("{} {}", spinner_frame(), tr("Loading…")), false))
+        } else {
+            None
+        }
+    }
+
     /// Return wether there are still items that aren't shown in the listview.
     ///
     /// `true` if there are unloaded items
@@ -216,14 +298,33 @@ impl<I: ListItem + Clone> View for ListView<I> {
                 printer.with_color(style, |printer| {
                     printer.print((0, 0), &buf);
                 });
+            } else if i == content.len() {
+                if let Some((text, is_error)) = self.status_row(content.len()) {
+                    let style = if is_error {
+                        ColorStyle::new(
+                            ColorType::Color(*printer.theme.palette.custom("error").unwrap()),
+                            ColorType::Color(*printer.theme.palette.custom("error_bg").unwrap()),
+                        )
+                    } else {
+                        ColorStyle::secondary()
+                    };
+                    printer.with_color(style, |printer| {
+                        printer.print((0, 0), &text);
+                    });
+                }
             } else if i < content.len() {
                 let item = &content[i];
-                let currently_playing =
-                    item.is_playing(&self.queue) && self.queue.get_current_index() == Some(i);
+                let currently_playing = !self.dimmed
+                    && item.is_playing(&self.queue)
+                    && self.queue.get_current_index() == Some(i);
                 let is_local = item.track().map(|t| t.is_local).unwrap_or_default();
                 let is_playable = item.track().map(|t| t.is_playable).unwrap_or_default();
 
-                let style = if self.selected == i {
+                // Dimmed lists only show their selection while focused, as they usually sit next
+                // to another list.
+                let selected = self.selected == i && (printer.focused || !self.dimmed);
+                let marked = self.marked.contains(&i);
+                let style = if selected {
                     if currently_playing {
                         ColorStyle::new(
                             *printer.theme.palette.custom("playing_selected").unwrap(),
@@ -237,85 +338,135 @@ impl<I: ListItem + Clone> View for ListView<I> {
                     } else {
                         ColorStyle::highlight()
                     }
+                } else if marked {
+                    ColorStyle::highlight_inactive()
                 } else if currently_playing {
                     ColorStyle::new(
                         ColorType::Color(*printer.theme.palette.custom("playing").unwrap()),
                         ColorType::Color(*printer.theme.palette.custom("playing_bg").unwrap()),
                     )
-                } else if is_local || is_playable == Some(false) {
+                } else if self.dimmed || is_local || is_playable == Some(false) {
                     ColorStyle::secondary()
                 } else {
                     ColorStyle::primary()
                 };
 
-                let left = item.display_left(&self.library);
-                let center = item.display_center(&self.library);
-                let right = item.display_right(&self.library);
-                let draw_center = !center.is_empty();
-
-                // draw left string
-                printer.with_color(style, |printer| {
-                    printer.print_hline((0, 0), printer.size.x, " ");
-                    printer.print((0, 0), &left);
-                });
-
-                // if line contains search query match, draw on top with
-                // highlight color
-                if self.search_indexes.contains(&i) {
-                    let fg = *printer.theme.palette.custom("search_match").unwrap();
-                    let matched_style = ColorStyle::new(fg, style.back);
-
-                    let matches: Vec<(usize, usize)> = left
-                        .to_lowercase()
-                        .match_indices(&self.search_query)
-                        .map(|i| (i.0, i.0 + i.1.len()))
-                        .collect();
-
-                    for m in matches {
-                        printer.with_color(matched_style, |printer| {
-                            printer.print((left[0..m.0].width(), 0), &left[m.0..m.1]);
-                        });
+                let draw_row = |printer: &Printer<'_, '_>| {
+                    let mut left = item.display_left(&self.library);
+                    // Spell out what is otherwise only shown by colors, for screen readers.
+                    if self.library.cfg.values().accessibility.unwrap_or(false) {
+                        let marker = if marked {
+                            tr("[selected] ")
+                        } else if currently_playing {
+                            tr("[playing] ")
+                        } else if is_playable == Some(false) {
+                            tr("[unavailable] ")
+                        } else if is_local {
+                            tr("[local] ")
+                        } else {
+                            ""
+                        };
+                        left.insert_str(0, marker);
                     }
-                }
-
-                // left string cut off indicator
-                let center_offset = printer.size.x / 2;
-                let left_max_length = if draw_center {
-                    center_offset.saturating_sub(1)
-                } else {
-                    printer.size.x.saturating_sub(right.width() + 1)
-                };
+                    let center = item.display_center(&self.library);
+                    let mut right = item.display_right(&self.library);
+                    if self.added_at {
+                        let added_at = match (item.track(), item.episode()) {
+                            (Some(track), _) => track.added_at,
+                            (_, Some(episode)) => episode.added_at,
+                            _ => None,
+                        };
+                        if let Some(added_at) = added_at {
+                            right.insert_str(0, &format!("{} ", added_at.format("%Y-%m-%d")));
+                        }
+                    }
+                    if self.source {
+                        let source = match (item.track(), item.episode()) {
+                            (Some(track), _) => track.source,
+                            (_, Some(episode)) => episode.source,
+                            _ => None,
+                        };
+                        if let Some(source) = source {
+                            right.insert_str(0, &format!("{} ", source.name));
+                        }
+                    }
+                    let draw_center = !center.is_empty();
 
-                if left_max_length < left.width() {
-                    let offset = left_max_length.saturating_sub(1);
+                    // draw left string
                     printer.with_color(style, |printer| {
-                        printer.print_hline((offset, 0), printer.size.x, " ");
-                        printer.print((offset, 0), "..");
+                        printer.print_hline((0, 0), printer.size.x, " ");
+                        printer.print((0, 0), &left);
                     });
-                }
 
-                // draw center string
-                if draw_center {
-                    printer.with_color(style, |printer| {
-                        printer.print((center_offset, 0), &center);
-                    });
+                    // if line contains search query match, draw on top with
+                    // highlight color
+                    if self.search_indexes.contains(&i) {
+                        let fg = *printer.theme.palette.custom("search_match").unwrap();
+                        let matched_style = ColorStyle::new(fg, style.back);
+
+                        let matches: Vec<(usize, usize)> = left
+                            .to_lowercase()
+                            .match_indices(&self.search_query)
+                            .map(|i| (i.0, i.0 + i.1.len()))
+                            .collect();
+
+                        for m in matches {
+                            printer.with_color(matched_style, |printer| {
+                                printer.print((left[0..m.0].width(), 0), &left[m.0..m.1]);
+                            });
+                        }
+                    }
+
+                    // left string cut off indicator
+                    let center_offset = printer.size.x / 2;
+                    let left_max_length = if draw_center {
+                        center_offset.saturating_sub(1)
+                    } else {
+                        printer.size.x.saturating_sub(right.width() + 1)
+                    };
 
-                    // center string cut off indicator
-                    let max_length = printer.size.x.saturating_sub(right.width() + 1);
-                    if max_length < center_offset + center.width() {
-                        let offset = max_length.saturating_sub(1);
+                    if left_max_length < left.width() {
+                        let offset = left_max_length.saturating_sub(1);
                         printer.with_color(style, |printer| {
+                            printer.print_hline((offset, 0), printer.size.x, " ");
                             printer.print((offset, 0), "..");
                         });
                     }
-                }
 
-                // draw right string
-                let offset = HAlign::Right.get_offset(right.width(), printer.size.x);
+                    // draw center string
+                    if draw_center {
+                        printer.with_color(style, |printer| {
+                            printer.print((center_offset, 0), &center);
+                        });
 
-                printer.with_color(style, |printer| {
-                    printer.print((offset, 0), &right);
-                });
+                        // center string cut off indicator
+                        let max_length = printer.size.x.saturating_sub(right.width() + 1);
+                        if max_length < center_offset + center.width() {
+                            let offset = max_length.saturating_sub(1);
+                            printer.with_color(style, |printer| {
+                                printer.print((offset, 0), "..");
+                            });
+                        }
+                    }
+
+                    // draw right string
+                    let offset = HAlign::Right.get_offset(right.width(), printer.size.x);
+
+                    printer.with_color(style, |printer| {
+                        printer.print((offset, 0), &right);
+                    });
+                };
+
+                // Colors alone may not tell rows apart, e.g. on monochrome terminals.
+                let effects = self.library.cfg.theme_preset().uses_effects();
+                match (effects && selected, effects && currently_playing) {
+                    (true, true) => printer.with_effect(Effect::Reverse, |printer| {
+                        printer.with_effect(Effect::Bold, &draw_row)
+                    }),
+                    (true, false) => printer.with_effect(Effect::Reverse, &draw_row),
+                    (false, true) => printer.with_effect(Effect::Bold, &draw_row),
+                    (false, false) => draw_row(printer),
+                }
             }
         });
     }
@@ -323,6 +474,11 @@ impl<I: ListItem + Clone> View for ListView<I> {
     fn layout(&mut self, size: Vec2) {
         self.last_size = size;
 
+        // The marks point to other items once items were added or removed.
+        if self.content.read().unwrap().len() != self.marked_len {
+            self.marked.clear();
+        }
+
         let relayout_scroller = self.content_len(false) != self.last_content_len;
         self.last_content_len = self.content_len(true);
 
@@ -345,6 +501,7 @@ impl<I: ListItem + Clone> View for ListView<I> {
 
     fn on_event(&mut self, e: Event) -> EventResult {
         match e {
+            // Wheel bindings are handled by the layout for all views of a screen.
             Event::Mouse {
                 event: MouseEvent::WheelUp,
                 ..
@@ -392,6 +549,13 @@ impl<I: ListItem + Clone> View for ListView<I> {
                             // The selected position was already focused. Play the item at the
                             // position as if Enter was pressed. This sort of emulates double
                             // clicking, which isn't supported by Cursive.
+                            if MouseGesture::DoubleClick.is_bound(&self.library.cfg) {
+                                return gesture_result(MouseGesture::DoubleClick, |s| {
+                                    if let Some(data) = s.user_data::<UserData>().cloned() {
+                                        data.cmd.handle(s, Command::Play);
+                                    }
+                                });
+                            }
                             self.queue.clear();
 
                             if !self.attempt_play_all_tracks() {
@@ -421,14 +585,27 @@ impl<I: ListItem + Clone> View for ListView<I> {
                 }
             }
             Event::Mouse {
-                event: MouseEvent::Press(MouseButton::Right),
+                event: MouseEvent::Press(button @ (MouseButton::Right | MouseButton::Middle)),
                 position,
                 offset,
             } => {
+                let gesture = if button == MouseButton::Right {
+                    MouseGesture::RightClick
+                } else {
+                    MouseGesture::MiddleClick
+                };
+                let bound = gesture.is_bound(&self.library.cfg);
+                if gesture == MouseGesture::MiddleClick && !bound {
+                    return EventResult::Ignored;
+                }
+
                 let viewport = self.scroller.content_viewport().top_left();
                 let selected_row = position.checked_sub(offset).map(|p| p.y + viewport.y);
                 if let Some(y) = selected_row.filter(|row| row < &self.content_len(false)) {
                     self.move_focus_to(y);
+                    if gesture == MouseGesture::MiddleClick {
+                        return gesture_result(gesture, |_| {});
+                    }
 
                     let queue = self.queue.clone();
                     let library = self.library.clone();
@@ -437,9 +614,13 @@ impl<I: ListItem + Clone> View for ListView<I> {
                         content.get(self.selected).map(|t| t.as_listitem())
                     } {
                         let contextmenu = ContextMenu::new(&*target, queue, library);
-                        return EventResult::Consumed(Some(Callback::from_fn_once(move |s| {
-                            s.add_layer(contextmenu)
-                        })));
+                        let show_contextmenu = move |s: &mut Cursive| s.add_layer(contextmenu);
+                        if bound {
+                            return gesture_result(gesture, show_contextmenu);
+                        }
+                        return EventResult::Consumed(Some(Callback::from_fn_once(
+                            show_contextmenu,
+                        )));
                     }
                 }
             }
@@ -476,12 +657,40 @@ impl<I: ListItem + Clone> View for ListView<I> {
     }
 }
 
+/// Run the commands bound to `gesture` on the selected item, see
+/// [crate::commands::CommandManager::handle_mouse], or `fallback` if it isn't bound in the focused
+/// screen.
+fn gesture_result(
+    gesture: MouseGesture,
+    fallback: impl FnOnce(&mut Cursive) + 'static,
+) -> EventResult {
+    EventResult::Consumed(Some(Callback::from_fn_once(move |s| {
+        let handled = s
+            .user_data::<UserData>()
+            .cloned()
+            .is_some_and(|data| data.cmd.handle_mouse(s, gesture));
+        if !handled {
+            fallback(s);
+        }
+    })))
+}
+
 impl<I: ListItem + Clone> ViewExt for ListView<I> {
     fn title(&self) -> String {
         self.title.clone()
     }
 
-    fn on_command(&mut self, _s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
+    fn key_hints(&self) -> Vec<Command> {
+        vec![
+            Command::Play,
+            Command::Queue,
+            Command::PlayNext,
+            Command::Open(TargetMode::Selected),
+            Command::Save,
+        ]
+    }
+
+    fn on_command(&mut self, s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
         match cmd {
             Command::Play => {
                 self.queue.clear();
@@ -495,20 +704,74 @@ impl<I: ListItem + Clone> ViewExt for ListView<I> {
             Command::PlayNext => {
                 info!("played next");
                 let mut content = self.content.write().unwrap();
-                if let Some(item) = content.get_mut(self.selected) {
-                    item.play_next(&self.queue);
-                }
+                let skipped = self.queue.skipping_duplicates(|| {
+                    if let Some(item) = content.get_mut(self.selected) {
+                        item.play_next(&self.queue);
+                    }
+                });
 
-                return Ok(CommandResult::Consumed(None));
+                return Ok(CommandResult::Consumed(skipped_message(skipped)));
             }
             Command::Queue => {
                 let mut content = self.content.write().unwrap();
-                if let Some(item) = content.get_mut(self.selected) {
-                    item.queue(&self.queue);
-                }
+                let skipped = self.queue.skipping_duplicates(|| {
+                    if let Some(item) = content.get_mut(self.selected) {
+                        item.queue(&self.queue);
+                    }
+                });
 
+                return Ok(CommandResult::Consumed(skipped_message(skipped)));
+            }
+            Command::PlayTop => {
+                let artist = self
+                    .content
+                    .read()
+                    .unwrap()
+                    .get(self.selected)
+                    .and_then(|item| item.artists())
+                    .and_then(|artists| artists.into_iter().next());
+                let Some(artist) = artist else {
+                    return Err(tr("The selected item has no artist").to_string());
+                };
+                ContextMenu::play_top_tracks(s, self.queue.clone(), artist);
                 return Ok(CommandResult::Consumed(None));
             }
+            Command::QueueAll | Command::PlayAll => {
+                let mut items = self.content.read().unwrap().clone();
+                let count = items.len();
+                let play = matches!(cmd, Command::PlayAll);
+                if play {
+                    self.queue.clear();
+                }
+
+                // Albums, playlists and the like fetch their tracks when queued, so this is done
+                // in the background.
+                let queue = self.queue.clone();
+                let library = self.library.clone();
+                let sink = s.cb_sink().clone();
+                thread::spawn(move || {
+                    let skipped = queue.skipping_duplicates(|| {
+                        for item in items.iter_mut() {
+                            item.queue(&queue);
+                        }
+                    });
+                    if play && queue.len() > 0 {
+                        queue.play(0, true, false);
+                    }
+                    if let Some(message) = skipped_message(skipped) {
+                        sink.send(Box::new(move |s: &mut Cursive| {
+                            s.on_layout(|_, mut layout| layout.set_result(Ok(Some(message))));
+                        }))
+                        .ok();
+                    }
+                    library.trigger_redraw();
+                });
+
+                return Ok(CommandResult::Consumed(Some(tr_args(
+                    "Adding {} items to the queue",
+                    &[&count.to_string()],
+                ))));
+            }
             Command::Save => {
                 let mut item = {
                     let content = self.content.read().unwrap();
@@ -570,6 +833,49 @@ impl<I: ListItem + Clone> ViewExt for ListView<I> {
 
                 return Ok(CommandResult::Consumed(None));
             }
+            #[cfg(feature = "cover")]
+            Command::Cover(None) => {
+                let url =
+                    self.content.read().ok().and_then(|content| {
+                        content.get(self.selected).and_then(ListItem::cover_url)
+                    });
+                let Some(url) = url else {
+                    return Err(tr("The selected item has no cover").to_string());
+                };
+                let modal = CoverModal::new(self.queue.clone(), self.library.clone(), url);
+                return Ok(CommandResult::Modal(Box::new(Modal::new(modal))));
+            }
+            Command::CopyMetadata(mode, path) => {
+                let metadata = match mode {
+                    TargetMode::Selected => self
+                        .content
+                        .read()
+                        .ok()
+                        .and_then(|content| content.get(self.selected).map(ListItem::metadata)),
+                    TargetMode::Current => self.queue.get_current().map(|t| t.metadata()),
+                };
+                let Some(metadata) = metadata else {
+                    return Ok(CommandResult::Consumed(None));
+                };
+                let json = serde_json::to_string_pretty(&metadata).map_err(|e| e.to_string())?;
+
+                let message = match path {
+                    Some(path) => {
+                        std::fs::write(path, json).map_err(|e| e.to_string())?;
+                        tr_args("Saved metadata to {}", &[path.as_str()])
+                    }
+                    #[cfg(feature = "share_clipboard")]
+                    None => {
+                        write_share(json).map_err(|e| e.to_string())?;
+                        tr("Copied metadata to the clipboard").to_string()
+                    }
+                    #[cfg(not(feature = "share_clipboard"))]
+                    None => {
+                        return Err(tr("Give a file to save the metadata to").to_string());
+                    }
+                };
+                return Ok(CommandResult::Consumed(Some(message)));
+            }
             Command::Jump(mode) => match mode {
                 JumpMode::Query(query) => {
                     self.search_query = query.to_lowercase();
@@ -701,6 +1007,8 @@ impl<I: ListItem + Clone> ViewExt for ListView<I> {
                                 };
                             }
                         }
+                        // Handled globally, see CommandManager.
+                        GotoMode::Playing => {}
                     }
                 }
             }
@@ -791,3 +1099,13 @@ impl<I: ListItem + Clone> ViewExt for ListView<I> {
         Ok(CommandResult::Ignored)
     }
 }
+
+/// The message shown when `skipped` items weren't queued as they were already in the queue.
+fn skipped_message(skipped: usize) -> Option<String> {
+    (skipped > 0).then(|| {
+        tr_args(
+            "Skipped {} tracks that are already in the queue",
+            &[&skipped.to_string()],
+        )
+    })
+}
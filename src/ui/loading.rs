@@ -0,0 +1,36 @@
+use std::sync::{Arc, RwLock};
+
+/// Tracks whether the content of a list is still being fetched in the background or failed to
+/// load. Clones share their state, so the fetching thread can report to the list that shows it.
+#[derive(Clone, Default)]
+pub struct LoadingState {
+    loading: Arc<RwLock<bool>>,
+    error: Arc<RwLock<Option<String>>>,
+}
+
+impl LoadingState {
+    /// Mark the content as being fetched, clearing earlier errors.
+    pub fn start(&self) {
+        *self.loading.write().unwrap() = true;
+        *self.error.write().unwrap() = None;
+    }
+
+    /// Mark the content as completely fetched.
+    pub fn finish(&self) {
+        *self.loading.write().unwrap() = false;
+    }
+
+    /// Stop loading and show `error` after the content that could be fetched so far.
+    pub fn fail(&self, error: impl Into<String>) {
+        *self.loading.write().unwrap() = false;
+        *self.error.write().unwrap() = Some(error.into());
+    }
+
+    pub fn is_loading(&self) -> bool {
+        *self.loading.read().unwrap()
+    }
+
+    pub fn error(&self) -> Option<String> {
+        self.error.read().unwrap().clone()
+    }
+}
@@ -0,0 +1,124 @@
+use std::sync::{Arc, RwLock};
+use std::thread;
+
+use cursive::view::ViewWrapper;
+use cursive::Cursive;
+use rspotify::model::search::SearchResult;
+use rspotify::model::SearchType;
+
+use crate::command::Command;
+use crate::commands::CommandResult;
+use crate::i18n::tr;
+use crate::library::Library;
+use crate::model::playlist::Playlist;
+use crate::queue::Queue;
+use crate::traits::ViewExt;
+use crate::ui::listview::ListView;
+
+/// The names of the playlists Spotify personalizes for each user, in the order they are listed.
+const PERSONALIZED: [&str; 6] = [
+    "Discover Weekly",
+    "Release Radar",
+    "Daily Mix",
+    "On Repeat",
+    "Repeat Rewind",
+    "daylist",
+];
+
+/// The id of the user that owns the personalized playlists.
+const SPOTIFY_USER: &str = "spotify";
+
+/// The position of `playlist` in [PERSONALIZED], if it is a personalized playlist.
+fn personalized_rank(playlist: &Playlist) -> Option<usize> {
+    if playlist.owner_id != SPOTIFY_USER {
+        return None;
+    }
+    let name = playlist.name.to_lowercase();
+    PERSONALIZED
+        .iter()
+        .position(|prefix| name.starts_with(&prefix.to_lowercase()))
+}
+
+/// The playlists Spotify made for the user, like Discover Weekly and the Daily Mixes, taken from
+/// the followed playlists and from searching for them.
+pub struct MadeForYouView {
+    list: ListView<Playlist>,
+    playlists: Arc<RwLock<Vec<Playlist>>>,
+    queue: Arc<Queue>,
+    library: Arc<Library>,
+}
+
+impl MadeForYouView {
+    pub fn new(queue: Arc<Queue>, library: Arc<Library>) -> Self {
+        let playlists = Arc::new(RwLock::new(Vec::new()));
+        let list = ListView::new(playlists.clone(), queue.clone(), library.clone());
+        let view = Self {
+            list,
+            playlists,
+            queue,
+            library,
+        };
+        view.load();
+        view
+    }
+
+    /// Collect the personalized playlists in the background.
+    fn load(&self) {
+        let loading = self.list.get_loading().clone();
+        let playlists = self.playlists.clone();
+        let spotify = self.queue.get_spotify();
+        let library = self.library.clone();
+        loading.start();
+        thread::spawn(move || {
+            let mut found: Vec<Playlist> = Vec::new();
+            for name in PERSONALIZED {
+                if let Ok(SearchResult::Playlists(results)) =
+                    spotify.api.search(SearchType::Playlist, name, 50, 0)
+                {
+                    found.extend(results.items.iter().map(Playlist::from));
+                }
+            }
+
+            // The followed playlists are only known once the library is loaded.
+            library.clone().when_loaded(move || {
+                found.extend(library.playlists.read().unwrap().iter().cloned());
+
+                found.retain(|playlist| personalized_rank(playlist).is_some());
+                found.sort_by(|a, b| {
+                    personalized_rank(a)
+                        .cmp(&personalized_rank(b))
+                        .then_with(|| a.name.cmp(&b.name))
+                        .then_with(|| a.id.cmp(&b.id))
+                });
+                found.dedup_by(|a, b| a.id == b.id);
+
+                *playlists.write().unwrap() = found;
+                loading.finish();
+                library.trigger_redraw();
+            });
+        });
+    }
+}
+
+impl ViewWrapper for MadeForYouView {
+    wrap_impl!(self.list: ListView<Playlist>);
+}
+
+impl ViewExt for MadeForYouView {
+    fn title(&self) -> String {
+        tr("Made For You").to_string()
+    }
+
+    fn refresh(&mut self) -> bool {
+        self.load();
+        true
+    }
+
+    fn key_hints(&self) -> Vec<Command> {
+        self.list.key_hints()
+    }
+
+    fn on_command(&mut self, s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
+        self.list.on_command(s, cmd)
+    }
+}
@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use cursive::theme::ColorStyle;
+use cursive::{Printer, Vec2, View};
+
+use crate::i18n::tr;
+use crate::library::Library;
+use crate::model::playable::Playable;
+use crate::queue::Queue;
+
+/// The amount of upcoming queue items shown in the mini mode.
+const PREVIEW_LENGTH: usize = 3;
+
+/// The items that are played after the current one, shown above the statusbar in the mini mode.
+pub struct MiniQueueView {
+    queue: Arc<Queue>,
+    library: Arc<Library>,
+}
+
+impl MiniQueueView {
+    pub fn new(queue: Arc<Queue>, library: Arc<Library>) -> Self {
+        Self { queue, library }
+    }
+}
+
+impl View for MiniQueueView {
+    fn draw(&self, printer: &Printer<'_, '_>) {
+        let upcoming = self.queue.upcoming(PREVIEW_LENGTH.min(printer.size.y));
+        if upcoming.is_empty() {
+            printer.with_color(ColorStyle::secondary(), |printer| {
+                printer.print((1, 0), tr("Nothing is queued after this"));
+            });
+            return;
+        }
+
+        for (i, item) in upcoming.iter().enumerate() {
+            let line = Playable::format(item, "%artists - %title", &self.library);
+            printer.print((1, i), &line);
+        }
+    }
+
+    fn required_size(&mut self, constraint: Vec2) -> Vec2 {
+        Vec2::new(constraint.x, PREVIEW_LENGTH)
+    }
+}
@@ -1,30 +1,47 @@
+use std::io::Write;
+
 use cursive::{Cursive, CursiveRunner};
 use ncspot::BIN_NAME;
 
+pub mod accelerators;
 pub mod album;
+pub mod album_groups;
+pub mod albums;
 pub mod artist;
+pub mod audio_device;
 pub mod browse;
 pub mod contextmenu;
+pub mod header;
 pub mod help;
+pub mod history;
 pub mod layout;
 pub mod library;
 pub mod listview;
+pub mod loading;
+pub mod made_for_you;
+pub mod mini;
 pub mod modal;
+pub mod nowplaying;
 pub mod pagination;
 pub mod playlist;
 pub mod playlists;
 pub mod queue;
+pub mod recommendations;
 pub mod search;
 pub mod search_results;
 pub mod show;
+pub mod snapshots;
 pub mod statusbar;
 pub mod tabbedview;
+pub mod tasks;
+pub mod treelistview;
 
 #[cfg(feature = "cover")]
 pub mod cover;
 
 /// Create a CursiveRunner which implements the drawing logic and event loop.
 pub fn create_cursive() -> Result<CursiveRunner<Cursive>, Box<dyn std::error::Error>> {
+    save_window_title();
     let backend = cursive::backends::try_default()?;
     let mut cursive_runner = CursiveRunner::new(cursive::Cursive::new(), backend);
 
@@ -32,3 +49,16 @@ pub fn create_cursive() -> Result<CursiveRunner<Cursive>, Box<dyn std::error::Er
 
     Ok(cursive_runner)
 }
+
+/// Push the title of the terminal window onto the title stack of the terminal, so it can be
+/// restored once ncspot quits. Terminals without a title stack ignore this.
+fn save_window_title() {
+    print!("\x1b[22;0t");
+    let _ = std::io::stdout().flush();
+}
+
+/// Restore the title the terminal window had before ncspot started.
+pub fn restore_window_title() {
+    print!("\x1b[23;0t");
+    let _ = std::io::stdout().flush();
+}
@@ -0,0 +1,187 @@
+use std::sync::Arc;
+
+use cursive::theme::{ColorStyle, ColorType, Effect, PaletteColor};
+use cursive::{Cursive, Printer, Vec2, View};
+use unicode_width::UnicodeWidthStr;
+
+use crate::command::{Command, GotoMode};
+use crate::commands::CommandResult;
+#[cfg(feature = "cover")]
+use crate::config::Config;
+use crate::i18n::tr;
+use crate::library::Library;
+use crate::model::playable::Playable;
+use crate::queue::Queue;
+use crate::traits::{IntoBoxedViewExt, ListItem, ViewExt};
+use crate::ui::album::AlbumView;
+use crate::ui::artist::ArtistView;
+#[cfg(feature = "cover")]
+use crate::ui::cover::CoverView;
+use crate::utils::ms_to_hms;
+
+/// The amount of upcoming queue items that are previewed.
+const UPCOMING_COUNT: usize = 10;
+
+/// A full screen overview of the currently playing item and what comes after it.
+pub struct NowPlayingView {
+    queue: Arc<Queue>,
+    library: Arc<Library>,
+    #[cfg(feature = "cover")]
+    cover: CoverView,
+}
+
+impl NowPlayingView {
+    pub fn new(
+        queue: Arc<Queue>,
+        library: Arc<Library>,
+        #[cfg(feature = "cover")] config: &Config,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "cover")]
+            cover: CoverView::new(queue.clone(), library.clone(), config),
+            queue,
+            library,
+        }
+    }
+
+    fn draw_progress(&self, printer: &Printer<'_, '_>, playable: &Playable) {
+        let elapsed = self.queue.get_spotify().get_current_progress().as_millis() as u32;
+        let elapsed_str = ms_to_hms(elapsed);
+        let duration_str = playable.duration_str();
+
+        let bar_width = printer
+            .size
+            .x
+            .saturating_sub(elapsed_str.width() + duration_str.width() + 2);
+        let filled = (bar_width as u64 * elapsed as u64)
+            .checked_div(playable.duration() as u64)
+            .unwrap_or(0)
+            .min(bar_width as u64) as usize;
+
+        printer.print((0, 0), &elapsed_str);
+        let x = elapsed_str.width() + 1;
+        printer.with_color(
+            ColorStyle::new(
+                ColorType::Color(*printer.theme.palette.custom("statusbar_progress").unwrap()),
+                ColorType::Palette(PaletteColor::Background),
+            ),
+            |printer| printer.print((x, 0), &"━".repeat(filled)),
+        );
+        printer.with_color(
+            ColorStyle::new(
+                ColorType::Color(
+                    *printer
+                        .theme
+                        .palette
+                        .custom("statusbar_progress_bg")
+                        .unwrap(),
+                ),
+                ColorType::Palette(PaletteColor::Background),
+            ),
+            |printer| printer.print((x + filled, 0), &"┉".repeat(bar_width - filled)),
+        );
+        printer.print((x + bar_width + 1, 0), &duration_str);
+    }
+
+    fn draw_info(&self, printer: &Printer<'_, '_>) {
+        let Some(playable) = self.queue.get_current() else {
+            printer.print((0, 0), tr("Nothing is playing"));
+            return;
+        };
+        let format = |format: &str| Playable::format(&playable, format, &self.library);
+
+        printer.with_effect(Effect::Bold, |printer| {
+            let style = ColorStyle::new(
+                ColorType::Color(*printer.theme.palette.custom("playing").unwrap()),
+                ColorType::Palette(PaletteColor::Background),
+            );
+            printer.with_color(style, |printer| {
+                printer.print((0, 0), &format("%title"));
+            });
+        });
+        printer.print((0, 1), &format("%artists"));
+        printer.with_effect(Effect::Italic, |printer| {
+            printer.print((0, 2), &format("%album"));
+        });
+        let saved = if self.library.is_saved_track(&playable) {
+            tr("Saved in your library")
+        } else {
+            tr("Not saved")
+        };
+        printer.print((0, 3), saved);
+
+        self.draw_progress(&printer.offset((0, 5)), &playable);
+
+        let upcoming = self.queue.upcoming(UPCOMING_COUNT);
+        if upcoming.is_empty() {
+            return;
+        }
+        printer.with_effect(Effect::Underline, |printer| {
+            printer.print((0, 7), tr("Up next"));
+        });
+        for (i, item) in upcoming.iter().enumerate() {
+            let line = Playable::format(item, "%artists - %title", &self.library);
+            printer.print((0, 8 + i), &line);
+        }
+    }
+}
+
+impl View for NowPlayingView {
+    fn draw(&self, printer: &Printer<'_, '_>) {
+        #[allow(unused_mut)]
+        let mut offset = 1;
+
+        #[cfg(feature = "cover")]
+        {
+            // The cover takes up the left half of the screen, but never more than a square.
+            let width = (printer.size.x / 2).min(printer.size.y * 2);
+            self.cover.draw(&printer.cropped((width, printer.size.y)));
+            offset += width + 1;
+        }
+
+        self.draw_info(&printer.offset((offset, 1)));
+    }
+
+    fn required_size(&mut self, constraint: Vec2) -> Vec2 {
+        constraint
+    }
+}
+
+impl ViewExt for NowPlayingView {
+    fn title(&self) -> String {
+        tr("Now Playing").to_string()
+    }
+
+    fn on_leave(&self) {
+        #[cfg(feature = "cover")]
+        self.cover.on_leave();
+    }
+
+    fn on_command(&mut self, _s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
+        let Some(mut playable) = self.queue.get_current() else {
+            return Ok(CommandResult::Ignored);
+        };
+        let queue = self.queue.clone();
+        let library = self.library.clone();
+
+        match cmd {
+            Command::Save => playable.save(&library),
+            Command::Delete => playable.unsave(&library),
+            Command::Goto(GotoMode::Album) => {
+                if let Some(album) = playable.album(&queue) {
+                    let view = AlbumView::new(queue, library, &album).into_boxed_view_ext();
+                    return Ok(CommandResult::View(view));
+                }
+            }
+            Command::Goto(GotoMode::Artist) => {
+                if let Some(artist) = playable.artists().and_then(|a| a.into_iter().next()) {
+                    let view = ArtistView::new(queue, library, &artist).into_boxed_view_ext();
+                    return Ok(CommandResult::View(view));
+                }
+            }
+            _ => return Ok(CommandResult::Ignored),
+        }
+
+        Ok(CommandResult::Consumed(None))
+    }
+}
@@ -1,7 +1,9 @@
+use crate::application::ASYNC_RUNTIME;
 use crate::library::Library;
 use crate::traits::ListItem;
 use log::debug;
 use std::sync::{Arc, RwLock};
+use tokio::sync::Semaphore;
 
 pub struct ApiPage<I> {
     pub offset: u32,
@@ -81,6 +83,53 @@ impl<I: ListItem + Clone> ApiResult<I> {
             None
         }
     }
+
+    /// Fetch all remaining pages, up to `parallelism` of them at once, and add their items in
+    /// order. `on_page` is called after the items of each page were added. Stops at the first page
+    /// that can't be fetched, so the items stay in order.
+    pub fn fetch_all(&self, parallelism: usize, on_page: impl Fn())
+    where
+        I: Send + 'static,
+    {
+        // Blocking on the runtime from one of its own threads would panic, so fall back to
+        // fetching one page after the other there.
+        if tokio::runtime::Handle::try_current().is_ok() {
+            while !self.at_end() && self.next().is_some() {
+                on_page();
+            }
+            return;
+        }
+
+        let start = self.offset() + self.limit;
+        let offsets: Vec<u32> = (start..self.total).step_by(self.limit as usize).collect();
+        debug!("fetching {} pages, {} at once", offsets.len(), parallelism);
+
+        let runtime = ASYNC_RUNTIME.get().unwrap();
+        let semaphore = Arc::new(Semaphore::new(parallelism.max(1)));
+        let pages: Vec<_> = offsets
+            .into_iter()
+            .map(|offset| {
+                let fetch_page = self.fetch_page.clone();
+                let semaphore = semaphore.clone();
+                runtime.spawn(async move {
+                    let _permit = semaphore.acquire_owned().await.ok();
+                    tokio::task::spawn_blocking(move || fetch_page(offset))
+                        .await
+                        .ok()
+                        .flatten()
+                })
+            })
+            .collect();
+
+        for page in pages {
+            let Some(page) = runtime.block_on(page).ok().flatten() else {
+                break;
+            };
+            *self.offset.write().unwrap() = page.offset;
+            self.items.write().unwrap().extend(page.items);
+            on_page();
+        }
+    }
 }
 
 pub type Paginator<I> = Box<dyn Fn(Arc<RwLock<Vec<I>>>) + Send + Sync>;
@@ -1,22 +1,39 @@
+use std::cmp::min;
 use std::sync::{Arc, RwLock};
+use std::thread;
 
-use cursive::view::ViewWrapper;
+use cursive::view::{Margins, ViewWrapper};
+use cursive::views::Dialog;
 use cursive::Cursive;
 
-use crate::command::Command;
+use crate::command::{Command, ShiftMode, SortKey};
 use crate::commands::CommandResult;
+use crate::config::SortingOrder;
+use crate::ext_traits::CursiveExt;
+use crate::i18n::{tr, tr_args};
 use crate::library::Library;
-use crate::model::playable::Playable;
-use crate::model::playlist::Playlist;
+use crate::model::playable::{Playable, PlayableSource};
+use crate::model::playlist::{Playlist, PARALLEL_PAGES};
+use crate::model::snapshot::Snapshot;
 use crate::queue::Queue;
 use crate::spotify::Spotify;
 
-use crate::traits::ViewExt;
+use crate::traits::{ListItem, ViewExt};
+use crate::ui::accelerators;
+use crate::ui::header::{wrap_with_header, Header, HeaderContent};
 use crate::ui::listview::ListView;
+use crate::ui::modal::Modal;
+use crate::ui::playlists;
+use crate::utils::strip_html;
 
 pub struct PlaylistView {
     playlist: Playlist,
+    /// The tracks shown in the list. While the playlist is loading, they are filled in the
+    /// background page by page, and `playlist.tracks` is only set once all of them are there.
+    tracks: Arc<RwLock<Vec<Playable>>>,
     list: ListView<Playable>,
+    /// The description, owner and followers of the playlist, shown above the tracks.
+    header: Header,
     spotify: Spotify,
     library: Arc<Library>,
     queue: Arc<Queue>,
@@ -24,38 +41,196 @@ pub struct PlaylistView {
 
 impl PlaylistView {
     pub fn new(queue: Arc<Queue>, library: Arc<Library>, playlist: &Playlist) -> Self {
-        let mut playlist = playlist.clone();
-        playlist.load_tracks(&queue.get_spotify());
+        let tracks = Arc::new(RwLock::new(Vec::new()));
+        let list = Self::track_list(tracks.clone(), queue.clone(), library.clone());
+        let header = Header::new(queue.clone(), library.clone());
+        let mut view = Self {
+            playlist: playlist.clone(),
+            tracks,
+            list,
+            header,
+            spotify: queue.get_spotify(),
+            library,
+            queue,
+        };
+        view.load_details();
+        view.load_tracks();
+        view
+    }
+
+    /// Fetch the details of the playlist for the header in the background.
+    fn load_details(&self) {
+        let content = self.header.content().clone();
+        let spotify = self.spotify.clone();
+        let library = self.library.clone();
+        let id = self.playlist.id.clone();
+        let owner = self.playlist.owner_name.clone();
+        thread::spawn(move || {
+            let Ok(playlist) = spotify.api.playlist(&id) else {
+                return;
+            };
+            let mut details = Vec::new();
+            if let Some(owner) = playlist.owner.display_name.or(owner) {
+                details.push(tr_args("By {}", &[&owner]));
+            }
+            details.push(tr_args(
+                "{} followers",
+                &[&playlist.followers.total.to_string()],
+            ));
+            content.set(
+                playlist.description.as_deref().map(strip_html),
+                details,
+                playlist.images.first().map(|image| image.url.clone()),
+            );
+            library.trigger_redraw();
+        });
+    }
 
-        if let Some(order) = library.cfg.state().playlist_orders.get(&playlist.id) {
-            playlist.sort(&order.key, &order.direction);
+    /// Show the tracks of the playlist. If they aren't known yet, the first page is shown right
+    /// away and the others are added in the background as they arrive.
+    fn load_tracks(&mut self) {
+        if self.playlist.tracks.is_some() {
+            Self::finish_loading(
+                &mut self.playlist,
+                &self.tracks,
+                self.header.content(),
+                &self.library,
+            );
+            return;
         }
 
-        let tracks = if let Some(t) = playlist.tracks.as_ref() {
-            t.clone()
+        let result = self.spotify.api.user_playlist_tracks(&self.playlist.id);
+        let source = self.playlist.as_source();
+        Self::set_source(&result.items, &source);
+        self.tracks = result.items.clone();
+        self.list = Self::track_list(
+            self.tracks.clone(),
+            self.queue.clone(),
+            self.library.clone(),
+        );
+
+        let loading = self.list.get_loading().clone();
+        loading.start();
+        let tracks = self.tracks.clone();
+        let header = self.header.content().clone();
+        let library = self.library.clone();
+        let mut playlist = self.playlist.clone();
+        thread::spawn(move || {
+            result.fetch_all(PARALLEL_PAGES, || {
+                Self::set_source(&tracks, &source);
+                library.trigger_redraw();
+            });
+            playlist.tracks = Some(tracks.read().unwrap().clone());
+
+            // Sorting by audio features needs them to be fetched, which is done here instead of
+            // in the thread of the user interface.
+            let order = Self::sorting_order(&library, &playlist.id);
+            if let Some(SortKey::Bpm | SortKey::Energy) = order.map(|order| order.key) {
+                let ids: Vec<String> = playlist
+                    .tracks
+                    .iter()
+                    .flatten()
+                    .filter_map(|playable| playable.track().and_then(|track| track.id))
+                    .collect();
+                library.audio_features(&ids.iter().map(String::as_str).collect::<Vec<_>>());
+            }
+
+            library.clone().run_in_ui(move |_| {
+                Self::finish_loading(&mut playlist, &tracks, &header, &library);
+                loading.finish();
+            });
+        });
+    }
+
+    /// Remove the selected track from the playlist.
+    fn delete_selected(&mut self) -> Result<CommandResult, String> {
+        let pos = self.list.get_selected_index();
+        if self
+            .playlist
+            .delete_track(pos, self.spotify.clone(), &self.library)
+        {
+            self.list.remove(pos);
+            Ok(CommandResult::Consumed(None))
         } else {
-            Vec::new()
-        };
+            Err(tr("Could not delete track.").to_string())
+        }
+    }
 
-        let spotify = queue.get_spotify();
-        let list = ListView::new(
-            Arc::new(RwLock::new(tracks)),
-            queue.clone(),
-            library.clone(),
-        );
+    /// Whether all tracks were fetched. Once they are, they are stored in the playlist.
+    fn is_loaded(&mut self) -> bool {
+        if self.playlist.tracks.is_none() && !self.list.get_loading().is_loading() {
+            self.playlist.tracks = Some(self.tracks.read().unwrap().clone());
+        }
+        self.playlist.tracks.is_some()
+    }
 
-        Self {
-            playlist,
-            list,
-            spotify,
-            library,
-            queue,
+    /// The list showing `tracks`, along with the dates they were added if configured.
+    fn track_list(
+        tracks: Arc<RwLock<Vec<Playable>>>,
+        queue: Arc<Queue>,
+        library: Arc<Library>,
+    ) -> ListView<Playable> {
+        let added_at = library.cfg.values().playlist_added_at.unwrap_or(false);
+        let list = ListView::new(tracks, queue, library);
+        if added_at {
+            list.with_added_at()
+        } else {
+            list
+        }
+    }
+
+    /// The order chosen for the playlist with `id` with `sort`, or else the configured default
+    /// order.
+    fn sorting_order(library: &Library, id: &str) -> Option<SortingOrder> {
+        let order = library.cfg.state().playlist_orders.get(id).cloned();
+        order.or_else(|| {
+            let values = library.cfg.values();
+            values
+                .default_playlist_sort
+                .as_deref()
+                .and_then(SortingOrder::parse)
+        })
+    }
+
+    /// Sort the loaded tracks of `playlist` in the order chosen for it and show them in `tracks`,
+    /// along with their duration in the `header`.
+    fn finish_loading(
+        playlist: &mut Playlist,
+        tracks: &RwLock<Vec<Playable>>,
+        header: &HeaderContent,
+        library: &Library,
+    ) {
+        if let Some(order) = Self::sorting_order(library, &playlist.id) {
+            playlist.sort(&order.key, &order.direction, library);
+        }
+
+        // Remember where tracks that get queued from this view came from.
+        let source = playlist.as_source();
+        for track in playlist.tracks.iter_mut().flatten() {
+            track.set_source(Some(source.clone()));
+        }
+        *tracks.write().unwrap() = playlist.tracks.clone().unwrap_or_default();
+
+        let loaded = playlist.tracks.as_deref().unwrap_or_default();
+        header.set_summary(crate::utils::format_duration(&Self::duration(loaded)));
+        library.trigger_redraw();
+    }
+
+    /// The total duration of `tracks`.
+    fn duration(tracks: &[Playable]) -> std::time::Duration {
+        let duration_secs = tracks.iter().map(|p| p.duration() as u64 / 1000).sum();
+        std::time::Duration::from_secs(duration_secs)
+    }
+
+    fn set_source(tracks: &RwLock<Vec<Playable>>, source: &PlayableSource) {
+        for track in tracks.write().unwrap().iter_mut() {
+            track.set_source(Some(source.clone()));
         }
     }
 }
 
 impl ViewWrapper for PlaylistView {
-    wrap_impl!(self.list: ListView<Playable>);
+    wrap_with_header!(self.header, self.list: ListView<Playable>);
 }
 
 impl ViewExt for PlaylistView {
@@ -65,36 +240,117 @@ impl ViewExt for PlaylistView {
 
     fn title_sub(&self) -> String {
         if let Some(tracks) = self.playlist.tracks.as_ref() {
-            let duration_secs = tracks.iter().map(|p| p.duration() as u64 / 1000).sum();
-            let duration = std::time::Duration::from_secs(duration_secs);
             format!(
-                "{} tracks, {}",
-                tracks.len(),
-                crate::utils::format_duration(&duration)
+                "{}, {}",
+                tr_args("{} tracks", &[&tracks.len().to_string()]),
+                crate::utils::format_duration(&Self::duration(tracks))
             )
         } else {
-            "".to_string()
+            tr_args(
+                "{} of {} tracks",
+                &[
+                    &self.tracks.read().unwrap().len().to_string(),
+                    &self.playlist.num_tracks.to_string(),
+                ],
+            )
         }
     }
 
+    fn on_leave(&self) {
+        self.header.on_leave();
+    }
+
+    fn refresh(&mut self) -> bool {
+        let selected = self.list.get_selected_index();
+        self.playlist.tracks = None;
+        self.load_tracks();
+        self.list.move_focus_to(selected);
+        true
+    }
+
+    fn history_item(&self) -> Option<Box<dyn ListItem>> {
+        Some(self.playlist.as_listitem())
+    }
+
+    fn key_hints(&self) -> Vec<Command> {
+        let mut hints = self.list.key_hints();
+        hints.push(Command::Delete);
+        hints
+    }
+
     fn on_command(&mut self, s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
+        let modifies_tracks = matches!(
+            cmd,
+            Command::Delete | Command::Shift(..) | Command::Sort(..) | Command::Snapshot
+        );
+        if !self.is_loaded() && modifies_tracks {
+            return Err(tr("The playlist is still loading").to_string());
+        }
+
         if let Command::Delete = cmd {
-            let pos = self.list.get_selected_index();
+            let confirm = self
+                .library
+                .cfg
+                .values()
+                .confirmations
+                .clone()
+                .unwrap_or_default()
+                .remove_track();
+            if !confirm {
+                return self.delete_selected();
+            }
+            let dialog = Dialog::text(tr("Remove this track from the playlist?"))
+                .padding(Margins::lrtb(1, 1, 1, 0))
+                .title(tr("Remove track"))
+                .dismiss_button(tr("No"))
+                .button(tr("Yes"), |s: &mut Cursive| {
+                    s.pop_layer();
+                    s.on_layout(|s, mut layout| layout.on_confirmed(s, &Command::Delete));
+                });
+            s.add_layer(Modal::new(accelerators::numbered_buttons(dialog)));
+            return Ok(CommandResult::Consumed(None));
+        }
+
+        if let Command::Shift(mode, amount) = cmd {
+            if Self::sorting_order(&self.library, &self.playlist.id).is_some() {
+                return Err(tr("Sorted playlists can't be reordered").to_string());
+            }
+
+            let amount = amount.unwrap_or(1).max(0) as usize;
+            let from = self.list.get_selected_index();
+            let len = self.playlist.tracks.as_ref().map_or(0, Vec::len);
+            let to = match mode {
+                ShiftMode::Up => from.saturating_sub(amount),
+                ShiftMode::Down => min(from + amount, len.saturating_sub(1)),
+            };
+            if from == to {
+                return Ok(CommandResult::Consumed(None));
+            }
 
-            return if self
+            if !self
                 .playlist
-                .delete_track(pos, self.spotify.clone(), &self.library)
+                .move_track(from, to, &self.spotify, &self.library)
             {
-                self.list.remove(pos);
-                Ok(CommandResult::Consumed(None))
-            } else {
-                Err("Could not delete track.".to_string())
-            };
+                return Err(tr("Could not move track.").to_string());
+            }
+            // Update the shown tracks in place to keep the scroll position of the list.
+            *self.tracks.write().unwrap() = self.playlist.tracks.clone().unwrap_or_default();
+            self.list.set_marked([]);
+            self.list.move_focus_to(to);
+            return Ok(CommandResult::Consumed(None));
+        }
+
+        if let Command::Favorite = cmd {
+            let message = playlists::toggle_favorite(&self.library, &self.playlist);
+            return Ok(CommandResult::Consumed(Some(message)));
         }
 
         if let Command::Sort(key, direction) = cmd {
+            if let SortKey::Tracks = key {
+                return Err(tr_args("Tracks can't be sorted by {}", &[&key.to_string()]));
+            }
             self.library.cfg.with_state_mut(|state| {
-                let order = crate::config::SortingOrder {
+                let order = SortingOrder {
                     key: key.clone(),
                     direction: direction.clone(),
                 };
@@ -103,16 +359,43 @@ impl ViewExt for PlaylistView {
                     .insert(self.playlist.id.clone(), order);
             });
 
-            self.playlist.sort(key, direction);
-            let tracks = self.playlist.tracks.as_ref().unwrap_or(&Vec::new()).clone();
-            self.list = ListView::new(
-                Arc::new(RwLock::new(tracks)),
-                self.queue.clone(),
-                self.library.clone(),
-            );
+            let selected = self
+                .tracks
+                .read()
+                .unwrap()
+                .get(self.list.get_selected_index())
+                .cloned();
+            self.playlist.sort(key, direction, &self.library);
+            let tracks = self.playlist.tracks.clone().unwrap_or_default();
+            // Keep the selected track selected where it was sorted to.
+            let position = selected.and_then(|selected| {
+                tracks.iter().position(|t| {
+                    t.id() == selected.id() && t.list_index() == selected.list_index()
+                })
+            });
+            *self.tracks.write().unwrap() = tracks;
+            self.list.set_marked([]);
+            if let Some(position) = position {
+                self.list.move_focus_to(position);
+            }
             return Ok(CommandResult::Consumed(None));
         }
 
+        if let Command::Snapshot = cmd {
+            let snapshot = Snapshot::of_playlist(&self.playlist, &self.spotify);
+            return self.library.save_snapshot(&snapshot).map(|path| {
+                let path = path.display().to_string();
+                CommandResult::Consumed(Some(tr_args("Saved snapshot to {}", &[&path])))
+            });
+        }
+
         self.list.on_command(s, cmd)
     }
+
+    fn on_confirmed(&mut self, s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
+        match cmd {
+            Command::Delete => self.delete_selected(),
+            _ => self.on_command(s, cmd),
+        }
+    }
 }
@@ -1,15 +1,18 @@
 use std::sync::Arc;
 
 use cursive::view::{Margins, ViewWrapper};
-use cursive::views::Dialog;
+use cursive::views::{Dialog, OnEventView, ScrollView, SelectView};
 use cursive::Cursive;
 
-use crate::command::Command;
+use crate::command::{Command, SortDirection, SortKey};
 use crate::commands::CommandResult;
+use crate::ext_traits::CursiveExt;
+use crate::i18n::{tr, tr_args};
 use crate::library::Library;
 use crate::model::playlist::Playlist;
 use crate::queue::Queue;
 use crate::traits::ViewExt;
+use crate::ui::accelerators;
 use crate::ui::listview::ListView;
 use crate::ui::modal::Modal;
 
@@ -26,45 +29,171 @@ impl PlaylistsView {
         }
     }
 
-    pub fn delete_dialog(&mut self) -> Option<Modal<Dialog>> {
+    pub fn delete_dialog(&mut self) -> Option<Modal<OnEventView<Dialog>>> {
         let playlists = self.library.playlists.read().unwrap();
         let current = playlists.get(self.list.get_selected_index());
 
         if let Some(playlist) = current {
             let library = self.library.clone();
             let id = playlist.id.clone();
-            let dialog = Dialog::text("Are you sure you want to delete this playlist?")
+            let dialog = Dialog::text(tr("Are you sure you want to delete this playlist?"))
                 .padding(Margins::lrtb(1, 1, 1, 0))
-                .title("Delete playlist")
-                .dismiss_button("No")
-                .button("Yes", move |s: &mut Cursive| {
-                    library.delete_playlist(&id);
+                .title(tr("Delete playlist"))
+                .dismiss_button(tr("No"))
+                .button(tr("Archive"), {
+                    let library = library.clone();
+                    let id = id.clone();
+                    move |s: &mut Cursive| {
+                        s.pop_layer();
+                        if let Err(e) = library.archive_playlist(&id) {
+                            s.on_layout(|_, mut layout| layout.set_result(Err(e)));
+                        }
+                    }
+                })
+                .button(tr("Yes"), move |s: &mut Cursive| {
                     s.pop_layer();
+                    if let Err(e) = library.delete_playlist(&id) {
+                        s.on_layout(|_, mut layout| layout.set_result(Err(e)));
+                    }
                 });
-            Some(Modal::new(dialog))
+            Some(Modal::new(accelerators::numbered_buttons(dialog)))
         } else {
             None
         }
     }
 }
 
+/// Pin `playlist` to the top of the playlists or unpin it, describing what happened.
+pub fn toggle_favorite(library: &Library, playlist: &Playlist) -> String {
+    if library.toggle_favorite_playlist(playlist) {
+        tr_args("Pinned {}", &[&playlist.name])
+    } else {
+        tr_args("Unpinned {}", &[&playlist.name])
+    }
+}
+
+/// Dialog to recreate one of the archived playlists.
+pub fn restore_dialog(library: Arc<Library>) -> Option<Modal<Dialog>> {
+    let archived = library.archived_playlists();
+    if archived.is_empty() {
+        return None;
+    }
+
+    let mut list_select: SelectView<Playlist> = SelectView::new().autojump();
+    for playlist in archived {
+        let label = tr_args(
+            "{} ({} tracks)",
+            &[
+                &playlist.name,
+                &playlist.tracks.as_ref().map_or(0, Vec::len).to_string(),
+            ],
+        );
+        list_select.add_item(label, playlist);
+    }
+    list_select.set_on_submit(move |s, playlist: &Playlist| {
+        s.pop_layer();
+        let result = library
+            .restore_playlist(playlist)
+            .map(|_| Some(tr_args("Restored playlist {}", &[&playlist.name])));
+        s.on_layout(|_, mut layout| layout.set_result(result));
+    });
+
+    let dialog = Dialog::new()
+        .title(tr("Restore archived playlist"))
+        .dismiss_button(tr("Cancel"))
+        .padding(Margins::lrtb(1, 1, 1, 0))
+        .content(ScrollView::new(list_select));
+    Some(Modal::new(dialog))
+}
+
 impl ViewWrapper for PlaylistsView {
     wrap_impl!(self.list: ListView<Playlist>);
 }
 
 impl ViewExt for PlaylistsView {
     fn title(&self) -> String {
-        "Playlists".to_string()
+        tr("Playlists").to_string()
+    }
+
+    fn key_hints(&self) -> Vec<Command> {
+        let mut hints = self.list.key_hints();
+        hints.push(Command::Delete);
+        hints
     }
 
     fn on_command(&mut self, s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
         if let Command::Delete = cmd {
-            if let Some(dialog) = self.delete_dialog() {
+            let confirm = self
+                .library
+                .cfg
+                .values()
+                .confirmations
+                .clone()
+                .unwrap_or_default()
+                .delete_playlist();
+            if !confirm {
+                let playlists = self.library.playlists.read().unwrap();
+                let id = playlists
+                    .get(self.list.get_selected_index())
+                    .map(|playlist| playlist.id.clone());
+                drop(playlists);
+                if let Some(id) = id {
+                    self.library.delete_playlist(&id)?;
+                }
+            } else if let Some(dialog) = self.delete_dialog() {
                 s.add_layer(dialog);
             }
             return Ok(CommandResult::Consumed(None));
         }
 
+        if let Command::Sort(key, direction) = cmd {
+            let mut playlists = self.library.playlists.write().unwrap();
+            match key {
+                SortKey::Title => playlists.sort_by_key(|p| p.name.to_lowercase()),
+                SortKey::Duration => playlists.sort_by_key(Playlist::duration),
+                SortKey::Tracks => playlists.sort_by_key(Playlist::track_count),
+                _ => {
+                    return Err(tr_args(
+                        "Playlists can't be sorted by {}",
+                        &[&key.to_string()],
+                    ))
+                }
+            }
+            if let SortDirection::Descending = direction {
+                playlists.reverse();
+            }
+            drop(playlists);
+            self.library.pin_favorite_playlists();
+            self.list.move_focus_to(0);
+            return Ok(CommandResult::Consumed(None));
+        }
+
+        if let Command::Favorite = cmd {
+            let selected = self
+                .library
+                .playlists
+                .read()
+                .unwrap()
+                .get(self.list.get_selected_index())
+                .cloned();
+            let Some(playlist) = selected else {
+                return Ok(CommandResult::Consumed(None));
+            };
+            let message = toggle_favorite(&self.library, &playlist);
+            // Keep the playlist selected at its new position.
+            let position = self
+                .library
+                .playlists
+                .read()
+                .unwrap()
+                .iter()
+                .position(|p| p.id == playlist.id);
+            if let Some(position) = position {
+                self.list.move_focus_to(position);
+            }
+            return Ok(CommandResult::Consumed(Some(message)));
+        }
+
         self.list.on_command(s, cmd)
     }
 }
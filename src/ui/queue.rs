@@ -1,22 +1,31 @@
+use cursive::event::{Event, EventResult};
+use cursive::theme::ColorStyle;
 use cursive::traits::{Nameable, Resizable};
-use cursive::view::{Margins, ViewWrapper};
+use cursive::view::Margins;
 use cursive::views::{Dialog, EditView, ScrollView, SelectView};
-use cursive::Cursive;
+use cursive::{Cursive, Printer, Vec2, View};
 
 use std::cmp::min;
 use std::sync::Arc;
 
-use crate::command::{Command, MoveMode, ShiftMode};
+use crate::command::{Command, MoveAmount, MoveMode, ShiftMode};
 use crate::commands::CommandResult;
+use crate::i18n::{tr, tr_args};
 use crate::library::Library;
 use crate::model::playable::Playable;
-use crate::queue::Queue;
+use crate::queue::{Queue, RepeatSetting};
 use crate::traits::ViewExt;
 use crate::ui::listview::ListView;
 use crate::ui::modal::Modal;
 
+/// The queue, with the items played earlier in this session shown greyed out above it.
 pub struct QueueView {
     list: ListView<Playable>,
+    history: ListView<Playable>,
+    history_focused: bool,
+    /// The URI of the playing item at the last layout, to follow it when the track changes.
+    playing: Option<String>,
+    last_size: Vec2,
     library: Arc<Library>,
     queue: Arc<Queue>,
 }
@@ -24,14 +33,77 @@ pub struct QueueView {
 impl QueueView {
     pub fn new(queue: Arc<Queue>, library: Arc<Library>) -> Self {
         let list = ListView::new(queue.queue.clone(), queue.clone(), library.clone());
+        let list = if library.cfg.values().queue_source.unwrap_or(false) {
+            list.with_source()
+        } else {
+            list
+        };
+        let history = ListView::new(queue.history.clone(), queue.clone(), library.clone()).dimmed();
 
         Self {
             list,
+            history,
+            history_focused: false,
+            playing: queue.get_current().map(|playable| playable.uri()),
+            last_size: Vec2::zero(),
             library,
             queue,
         }
     }
 
+    /// The amount of rows taken up by the played items, including their heading. The history
+    /// never takes up more than a third of the view.
+    fn history_height(&self, height: usize) -> usize {
+        match self.queue.history.read().unwrap().len() {
+            0 => 0,
+            len => min(len, height / 3) + 1,
+        }
+    }
+
+    /// Play the selected item from the history again, right after the current one.
+    fn replay_selected(&self) {
+        let selected = self.history.get_selected_index();
+        let item = self.queue.history.read().unwrap().get(selected).cloned();
+        if let Some(item) = item {
+            let index = self.queue.append_next(&vec![item]);
+            self.queue.play(index, false, false);
+        }
+    }
+
+    fn on_history_command(
+        &mut self,
+        s: &mut Cursive,
+        cmd: &Command,
+    ) -> Result<CommandResult, String> {
+        let last = self.queue.history.read().unwrap().len().saturating_sub(1);
+        match cmd {
+            Command::Play => {
+                self.replay_selected();
+                Ok(CommandResult::Consumed(None))
+            }
+            Command::Move(MoveMode::Down, MoveAmount::Integer(_))
+                if self.history.get_selected_index() == last =>
+            {
+                self.history_focused = false;
+                self.list.move_focus_to(0);
+                Ok(CommandResult::Consumed(None))
+            }
+            Command::Delete => {
+                self.history.remove(self.history.get_selected_index());
+                if self.queue.history.read().unwrap().is_empty() {
+                    self.history_focused = false;
+                }
+                Ok(CommandResult::Consumed(None))
+            }
+            Command::Shift(_, _) | Command::Select => Ok(CommandResult::Consumed(None)),
+            Command::Move(MoveMode::Playing, _) => {
+                self.history_focused = false;
+                self.on_command(s, cmd)
+            }
+            _ => self.history.on_command(s, cmd),
+        }
+    }
+
     fn save_dialog_cb(
         s: &mut Cursive,
         queue: Arc<Queue>,
@@ -54,8 +126,8 @@ impl QueueView {
                     .with_name("name")
                     .fixed_width(20);
                 let dialog = Dialog::new()
-                    .title("Enter name")
-                    .dismiss_button("Cancel")
+                    .title(tr("Enter name"))
+                    .dismiss_button(tr("Cancel"))
                     .padding(Margins::lrtb(1, 1, 1, 0))
                     .content(edit);
                 s.add_layer(Modal::new(dialog));
@@ -65,7 +137,7 @@ impl QueueView {
 
     fn save_dialog(queue: Arc<Queue>, library: Arc<Library>) -> Modal<Dialog> {
         let mut list_select: SelectView<Option<String>> = SelectView::new().autojump();
-        list_select.add_item("[Create new]", None);
+        list_select.add_item(tr("[Create new]"), None);
 
         for list in library.playlists.read().unwrap().iter() {
             list_select.add_item(list.name.clone(), Some(list.id.clone()));
@@ -76,21 +148,92 @@ impl QueueView {
         });
 
         let dialog = Dialog::new()
-            .title("Create new or overwrite existing playlist?")
-            .dismiss_button("Cancel")
+            .title(tr("Create new or overwrite existing playlist?"))
+            .dismiss_button(tr("Cancel"))
             .padding(Margins::lrtb(1, 1, 1, 0))
             .content(ScrollView::new(list_select));
         Modal::new(dialog)
     }
 }
 
-impl ViewWrapper for QueueView {
-    wrap_impl!(self.list: ListView<Playable>);
+impl View for QueueView {
+    fn draw(&self, printer: &Printer<'_, '_>) {
+        let height = self.history_height(printer.size.y);
+        if height > 0 {
+            printer.with_color(ColorStyle::secondary(), |printer| {
+                printer.print_hline((0, 0), printer.size.x, "─");
+                printer.print((1, 0), &format!(" {} ", tr("Played")));
+            });
+            self.history.draw(
+                &printer
+                    .offset((0, 1))
+                    .cropped((printer.size.x, height - 1))
+                    .focused(printer.focused && self.history_focused),
+            );
+        }
+        self.list.draw(
+            &printer
+                .offset((0, height))
+                .focused(printer.focused && !self.history_focused),
+        );
+    }
+
+    fn layout(&mut self, size: Vec2) {
+        self.last_size = size;
+
+        let playing = self.queue.get_current().map(|playable| playable.uri());
+        if playing != self.playing {
+            self.playing = playing;
+            let follow = self.library.cfg.values().follow_playback.unwrap_or(false);
+            if follow && !self.history_focused {
+                if let Some(index) = self.queue.get_current_index() {
+                    self.list.move_focus_to(index);
+                }
+            }
+        }
+
+        let height = self.history_height(size.y);
+        if height > 0 {
+            self.history.layout(Vec2::new(size.x, height - 1));
+        } else {
+            self.history_focused = false;
+        }
+        self.list.layout(Vec2::new(size.x, size.y - height));
+    }
+
+    fn needs_relayout(&self) -> bool {
+        self.history.needs_relayout() || self.list.needs_relayout()
+    }
+
+    fn required_size(&mut self, constraint: Vec2) -> Vec2 {
+        constraint
+    }
+
+    fn on_event(&mut self, event: Event) -> EventResult {
+        let height = self.history_height(self.last_size.y);
+        match event {
+            Event::Mouse {
+                offset, position, ..
+            } if position.y < offset.y + height => {
+                if position.y == offset.y {
+                    return EventResult::Consumed(None);
+                }
+                self.history_focused = true;
+                self.history.on_event(event.relativized((0, 1)))
+            }
+            Event::Mouse { .. } => {
+                self.history_focused = false;
+                self.list.on_event(event.relativized((0, height)))
+            }
+            _ if self.history_focused => self.history.on_event(event),
+            _ => self.list.on_event(event),
+        }
+    }
 }
 
 impl ViewExt for QueueView {
     fn title(&self) -> String {
-        "Queue".to_string()
+        tr("Queue").to_string()
     }
 
     fn title_sub(&self) -> String {
@@ -106,17 +249,47 @@ impl ViewExt for QueueView {
 
         if duration_secs > 0 {
             let duration = std::time::Duration::from_secs(duration_secs);
-            format!(
-                "{} tracks, {}",
-                track_count,
+            let mut sub = format!(
+                "{}, {}",
+                tr_args("{} tracks", &[&track_count.to_string()]),
                 crate::utils::format_duration(&duration)
-            )
+            );
+
+            // The queue never ends when repeating, so there is no point in an estimate.
+            let remaining = self.queue.remaining_duration();
+            if self.queue.get_repeat() == RepeatSetting::None && !remaining.is_zero() {
+                let end = chrono::Local::now()
+                    + chrono::Duration::from_std(remaining).unwrap_or_default();
+                sub.push_str(", ");
+                sub.push_str(&tr_args(
+                    "{} left, ends at {}",
+                    &[
+                        &crate::utils::format_duration(&remaining),
+                        &end.format("%H:%M").to_string(),
+                    ],
+                ));
+            }
+            sub
         } else {
             "".to_string()
         }
     }
 
+    fn key_hints(&self) -> Vec<Command> {
+        vec![
+            Command::Play,
+            Command::Delete,
+            Command::Save,
+            Command::SaveQueue,
+            Command::Clear,
+        ]
+    }
+
     fn on_command(&mut self, s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
+        if self.history_focused {
+            return self.on_history_command(s, cmd);
+        }
+
         match cmd {
             Command::Play => {
                 self.queue.play(self.list.get_selected_index(), true, false);
@@ -147,6 +320,21 @@ impl ViewExt for QueueView {
                 let selected = self.list.get_selected_index();
                 let len = self.queue.len();
 
+                // Several selected items are moved as a block, starting from the first of them.
+                let marked = self.list.marked();
+                if let Some(&first) = marked.first() {
+                    let to = match mode {
+                        ShiftMode::Up => first.saturating_sub(amount as usize),
+                        ShiftMode::Down => first + amount as usize,
+                    };
+                    let moved = self.queue.shift_many(&marked, to);
+                    if let Some(&first) = moved.first() {
+                        self.list.move_focus_to(first);
+                    }
+                    self.list.set_marked(moved);
+                    return Ok(CommandResult::Consumed(None));
+                }
+
                 match mode {
                     ShiftMode::Up if selected > 0 => {
                         self.queue
@@ -163,11 +351,25 @@ impl ViewExt for QueueView {
                     _ => {}
                 }
             }
+            Command::Select => {
+                self.list.toggle_marked();
+                self.list.move_focus(1);
+                return Ok(CommandResult::Consumed(None));
+            }
             Command::SaveQueue => {
                 let dialog = Self::save_dialog(self.queue.clone(), self.library.clone());
                 s.add_layer(dialog);
                 return Ok(CommandResult::Consumed(None));
             }
+            Command::Move(MoveMode::Up, MoveAmount::Integer(_))
+                if self.list.get_selected_index() == 0
+                    && !self.queue.history.read().unwrap().is_empty() =>
+            {
+                self.history_focused = true;
+                let last = self.queue.history.read().unwrap().len() - 1;
+                self.history.move_focus_to(last);
+                return Ok(CommandResult::Consumed(None));
+            }
             Command::Move(MoveMode::Playing, _) => {
                 if let Some(playing) = self.queue.get_current_index() {
                     self.list.move_focus_to(playing);
@@ -177,6 +379,6 @@ impl ViewExt for QueueView {
             _ => {}
         }
 
-        self.with_view_mut(move |v| v.on_command(s, cmd)).unwrap()
+        self.list.on_command(s, cmd)
     }
 }
@@ -0,0 +1,224 @@
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use cursive::view::ViewWrapper;
+use cursive::Cursive;
+
+use crate::command::{Command, SeedAction, TuneAttribute};
+use crate::commands::CommandResult;
+use crate::i18n::{tr, tr_args};
+use crate::library::Library;
+use crate::model::track::Track;
+use crate::queue::Queue;
+use crate::spotify::Spotify;
+use crate::spotify_api::RecommendationTargets;
+use crate::traits::ViewExt;
+use crate::ui::listview::ListView;
+
+/// The maximum amount of seeds accepted by the Spotify API.
+pub const MAX_SEEDS: usize = 5;
+
+/// An item that recommendations are based on.
+#[derive(Clone, Debug)]
+pub enum RecommendationSeed {
+    Artist { id: String, name: String },
+    Track { id: String, name: String },
+    Genre(String),
+}
+
+impl fmt::Display for RecommendationSeed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Artist { name, .. } => write!(f, "artist \"{name}\""),
+            Self::Track { name, .. } => write!(f, "track \"{name}\""),
+            Self::Genre(genre) => write!(f, "genre \"{genre}\""),
+        }
+    }
+}
+
+/// Fetch tracks recommended for `seeds` that match the `targets`.
+pub fn fetch_recommendations(
+    spotify: &Spotify,
+    seeds: &[RecommendationSeed],
+    targets: &RecommendationTargets,
+) -> Result<Vec<Track>, ()> {
+    let mut artists = Vec::new();
+    let mut tracks = Vec::new();
+    let mut genres = Vec::new();
+    for seed in seeds {
+        match seed {
+            RecommendationSeed::Artist { id, .. } => artists.push(id.as_str()),
+            RecommendationSeed::Track { id, .. } => tracks.push(id.as_str()),
+            RecommendationSeed::Genre(genre) => genres.push(genre.as_str()),
+        }
+    }
+    let non_empty = |seeds: Vec<_>| (!seeds.is_empty()).then_some(seeds);
+
+    let recommendations = spotify.api.recommendations(
+        non_empty(artists),
+        non_empty(genres),
+        non_empty(tracks),
+        targets,
+    )?;
+    Ok(recommendations.tracks.iter().map(Track::from).collect())
+}
+
+/// A list of recommended tracks whose seeds and target attributes can be changed with the `seed`
+/// and `tune` commands.
+pub struct RecommendationsView {
+    title: String,
+    seeds: Vec<RecommendationSeed>,
+    targets: RecommendationTargets,
+    tracks: Arc<RwLock<Vec<Track>>>,
+    list: ListView<Track>,
+    queue: Arc<Queue>,
+}
+
+impl RecommendationsView {
+    pub fn new(
+        queue: Arc<Queue>,
+        library: Arc<Library>,
+        title: String,
+        seeds: Vec<RecommendationSeed>,
+    ) -> Self {
+        let tracks = Arc::new(RwLock::new(Vec::new()));
+        let list = ListView::new(tracks.clone(), queue.clone(), library);
+
+        let mut view = Self {
+            title,
+            seeds,
+            targets: RecommendationTargets::default(),
+            tracks,
+            list,
+            queue,
+        };
+        if let Err(e) = view.refresh() {
+            log::error!("{e}");
+        }
+        view
+    }
+
+    /// Fetch new recommendations for the current seeds and targets.
+    pub fn refresh(&mut self) -> Result<(), String> {
+        let recommendations =
+            fetch_recommendations(&self.queue.get_spotify(), &self.seeds, &self.targets)
+                .map_err(|_| tr("Could not load recommendations").to_string())?;
+
+        *self.tracks.write().unwrap() = recommendations;
+        self.list.move_focus_to(0);
+        Ok(())
+    }
+
+    fn selected_track(&self) -> Option<Track> {
+        self.tracks
+            .read()
+            .unwrap()
+            .get(self.list.get_selected_index())
+            .cloned()
+    }
+
+    fn add_seed(&mut self, seed: RecommendationSeed) -> Result<CommandResult, String> {
+        if self.seeds.len() >= MAX_SEEDS {
+            return Err(tr_args(
+                "At most {} seeds can be used",
+                &[&MAX_SEEDS.to_string()],
+            ));
+        }
+        self.seeds.push(seed);
+        self.refresh()?;
+        Ok(CommandResult::Consumed(None))
+    }
+}
+
+impl ViewWrapper for RecommendationsView {
+    wrap_impl!(self.list: ListView<Track>);
+}
+
+impl ViewExt for RecommendationsView {
+    fn title(&self) -> String {
+        self.title.clone()
+    }
+
+    fn title_sub(&self) -> String {
+        // Seeds are numbered so they can be referred to by `seed remove`.
+        let mut parts: Vec<String> = self
+            .seeds
+            .iter()
+            .enumerate()
+            .map(|(i, seed)| format!("{}: {seed}", i + 1))
+            .collect();
+        if let Some(energy) = self.targets.energy {
+            parts.push(format!("energy {energy}"));
+        }
+        if let Some(tempo) = self.targets.tempo {
+            parts.push(format!("tempo {tempo}"));
+        }
+        if let Some(valence) = self.targets.valence {
+            parts.push(format!("valence {valence}"));
+        }
+        parts.join(", ")
+    }
+
+    fn key_hints(&self) -> Vec<Command> {
+        self.list.key_hints()
+    }
+
+    fn on_command(&mut self, s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
+        match cmd {
+            Command::Refresh => {
+                self.refresh()?;
+                Ok(CommandResult::Consumed(None))
+            }
+            Command::Tune(attribute, value) => {
+                match attribute {
+                    TuneAttribute::Energy => self.targets.energy = *value,
+                    TuneAttribute::Tempo => self.targets.tempo = *value,
+                    TuneAttribute::Valence => self.targets.valence = *value,
+                }
+                self.refresh()?;
+                Ok(CommandResult::Consumed(None))
+            }
+            Command::Seed(SeedAction::Track) => {
+                let track = self.selected_track().and_then(|track| {
+                    Some(RecommendationSeed::Track {
+                        id: track.id?,
+                        name: track.title,
+                    })
+                });
+                match track {
+                    Some(track) => self.add_seed(track),
+                    None => Ok(CommandResult::Consumed(None)),
+                }
+            }
+            Command::Seed(SeedAction::Artist) => {
+                let artist = self.selected_track().and_then(|track| {
+                    let id = track.artist_ids.first()?.clone();
+                    let name = track.artists.first()?.clone();
+                    Some(RecommendationSeed::Artist { id, name })
+                });
+                match artist {
+                    Some(artist) => self.add_seed(artist),
+                    None => Ok(CommandResult::Consumed(None)),
+                }
+            }
+            Command::Seed(SeedAction::Genre(genre)) => {
+                self.add_seed(RecommendationSeed::Genre(genre.clone()))
+            }
+            Command::Seed(SeedAction::Remove(position)) => {
+                if *position == 0 || *position > self.seeds.len() {
+                    return Err(tr_args(
+                        "There is no seed at position {}",
+                        &[&position.to_string()],
+                    ));
+                }
+                if self.seeds.len() == 1 {
+                    return Err(tr("At least one seed is required").to_string());
+                }
+                self.seeds.remove(position - 1);
+                self.refresh()?;
+                Ok(CommandResult::Consumed(None))
+            }
+            _ => self.list.on_command(s, cmd),
+        }
+    }
+}
@@ -12,6 +12,7 @@ use std::sync::{Arc, Mutex, RwLock};
 use crate::command::{Command, MoveMode};
 use crate::commands::CommandResult;
 use crate::events::EventManager;
+use crate::i18n::tr;
 use crate::library::Library;
 use crate::model::album::Album;
 use crate::model::artist::Artist;
@@ -110,7 +111,7 @@ impl View for SearchView {
 
 impl ViewExt for SearchView {
     fn title(&self) -> String {
-        "Search".to_string()
+        tr("Search").to_string()
     }
 
     fn on_command(&mut self, _s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
@@ -2,6 +2,7 @@ use crate::application::ASYNC_RUNTIME;
 use crate::command::Command;
 use crate::commands::CommandResult;
 use crate::events::EventManager;
+use crate::i18n::{tr, tr_args};
 use crate::library::Library;
 use crate::model::album::Album;
 use crate::model::artist::Artist;
@@ -14,35 +15,53 @@ use crate::spotify::{Spotify, UriType};
 use crate::spotify_url::SpotifyUrl;
 use crate::traits::{ListItem, ViewExt};
 use crate::ui::listview::ListView;
+use crate::ui::loading::LoadingState;
 use crate::ui::pagination::Pagination;
 use crate::ui::tabbedview::TabbedView;
 use cursive::view::ViewWrapper;
 use cursive::Cursive;
 use rspotify::model::search::SearchResult;
 use rspotify::model::SearchType;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, RwLock};
 
+/// The amount of results requested per page of a search.
+const SEARCH_PAGE_SIZE: u32 = 50;
+
+/// Prefix of search terms that only search for shows and episodes.
+const PODCASTS_QUALIFIER: &str = "podcasts:";
+
 pub struct SearchResultsView {
     search_term: String,
     results_tracks: Arc<RwLock<Vec<Track>>>,
     pagination_tracks: Pagination<Track>,
+    loading_tracks: LoadingState,
     results_albums: Arc<RwLock<Vec<Album>>>,
     pagination_albums: Pagination<Album>,
+    loading_albums: LoadingState,
     results_artists: Arc<RwLock<Vec<Artist>>>,
     pagination_artists: Pagination<Artist>,
+    loading_artists: LoadingState,
     results_playlists: Arc<RwLock<Vec<Playlist>>>,
     pagination_playlists: Pagination<Playlist>,
+    loading_playlists: LoadingState,
     results_shows: Arc<RwLock<Vec<Show>>>,
     pagination_shows: Pagination<Show>,
+    loading_shows: LoadingState,
     results_episodes: Arc<RwLock<Vec<Episode>>>,
     pagination_episodes: Pagination<Episode>,
+    loading_episodes: LoadingState,
     tabs: TabbedView,
     spotify: Spotify,
+    library: Arc<Library>,
     events: EventManager,
 }
 
-type SearchHandler<I> =
-    Box<dyn Fn(&Spotify, &Arc<RwLock<Vec<I>>>, &str, usize, bool) -> u32 + Send + Sync>;
+type SearchHandler<I> = Box<
+    dyn Fn(&Spotify, &Library, &Arc<RwLock<Vec<I>>>, &str, usize, bool) -> Result<u32, ()>
+        + Send
+        + Sync,
+>;
 
 impl SearchResultsView {
     pub fn new(
@@ -60,42 +79,55 @@ impl SearchResultsView {
 
         let list_tracks = ListView::new(results_tracks.clone(), queue.clone(), library.clone());
         let pagination_tracks = list_tracks.get_pagination().clone();
+        let loading_tracks = list_tracks.get_loading().clone();
         let list_albums = ListView::new(results_albums.clone(), queue.clone(), library.clone());
         let pagination_albums = list_albums.get_pagination().clone();
+        let loading_albums = list_albums.get_loading().clone();
         let list_artists = ListView::new(results_artists.clone(), queue.clone(), library.clone());
         let pagination_artists = list_artists.get_pagination().clone();
+        let loading_artists = list_artists.get_loading().clone();
         let list_playlists =
             ListView::new(results_playlists.clone(), queue.clone(), library.clone());
         let pagination_playlists = list_playlists.get_pagination().clone();
+        let loading_playlists = list_playlists.get_loading().clone();
         let list_shows = ListView::new(results_shows.clone(), queue.clone(), library.clone());
         let pagination_shows = list_shows.get_pagination().clone();
-        let list_episodes = ListView::new(results_episodes.clone(), queue.clone(), library);
+        let loading_shows = list_shows.get_loading().clone();
+        let list_episodes = ListView::new(results_episodes.clone(), queue.clone(), library.clone());
         let pagination_episodes = list_episodes.get_pagination().clone();
+        let loading_episodes = list_episodes.get_loading().clone();
 
         let mut tabs = TabbedView::new();
-        tabs.add_tab("Tracks", list_tracks);
-        tabs.add_tab("Albums", list_albums);
-        tabs.add_tab("Artists", list_artists);
-        tabs.add_tab("Playlists", list_playlists);
-        tabs.add_tab("Shows", list_shows);
-        tabs.add_tab("Episodes", list_episodes);
+        tabs.add_tab(tr("Tracks"), list_tracks);
+        tabs.add_tab(tr("Albums"), list_albums);
+        tabs.add_tab(tr("Artists"), list_artists);
+        tabs.add_tab(tr("Playlists"), list_playlists);
+        tabs.add_tab(tr("Shows"), list_shows);
+        tabs.add_tab(tr("Episodes"), list_episodes);
 
         let mut view = Self {
             search_term,
             results_tracks,
             pagination_tracks,
+            loading_tracks,
             results_albums,
             pagination_albums,
+            loading_albums,
             results_artists,
             pagination_artists,
+            loading_artists,
             results_playlists,
             pagination_playlists,
+            loading_playlists,
             results_shows,
             pagination_shows,
+            loading_shows,
             results_episodes,
             pagination_episodes,
+            loading_episodes,
             tabs,
             spotify: queue.get_spotify(),
+            library,
             events,
         };
 
@@ -105,33 +137,40 @@ impl SearchResultsView {
 
     fn get_track(
         spotify: &Spotify,
+        library: &Library,
         tracks: &Arc<RwLock<Vec<Track>>>,
         query: &str,
         _offset: usize,
         _append: bool,
-    ) -> u32 {
+    ) -> Result<u32, ()> {
         if let Ok(results) = spotify.api.track(query) {
-            let t = vec![(&results).into()];
+            let t: Vec<Track> = vec![(&results).into()];
+            library.look_up_saved_tracks(&t);
             let mut r = tracks.write().unwrap();
             *r = t;
-            return 1;
+            return Ok(1);
         }
-        0
+        Err(())
     }
 
     fn search_track(
         spotify: &Spotify,
+        library: &Library,
         tracks: &Arc<RwLock<Vec<Track>>>,
         query: &str,
         offset: usize,
         append: bool,
-    ) -> u32 {
+    ) -> Result<u32, ()> {
         if let Ok(SearchResult::Tracks(results)) =
             spotify
                 .api
-                .search(SearchType::Track, query, 50, offset as u32)
+                .search(SearchType::Track, query, SEARCH_PAGE_SIZE, offset as u32)
         {
-            let mut t = results.items.iter().map(|ft| ft.into()).collect();
+            let mut t: Vec<Track> = results.items.iter().map(|ft| ft.into()).collect();
+            if library.cfg.state().clean_mode {
+                t.retain(|track| !track.explicit);
+            }
+            library.look_up_saved_tracks(&t);
             let mut r = tracks.write().unwrap();
 
             if append {
@@ -139,40 +178,44 @@ impl SearchResultsView {
             } else {
                 *r = t;
             }
-            return results.total;
+            return Ok(results.total);
         }
-        0
+        Err(())
     }
 
     fn get_album(
         spotify: &Spotify,
+        library: &Library,
         albums: &Arc<RwLock<Vec<Album>>>,
         query: &str,
         _offset: usize,
         _append: bool,
-    ) -> u32 {
+    ) -> Result<u32, ()> {
         if let Ok(results) = spotify.api.album(query) {
-            let a = vec![(&results).into()];
+            let a: Vec<Album> = vec![(&results).into()];
+            library.look_up_saved_albums(&a);
             let mut r = albums.write().unwrap();
             *r = a;
-            return 1;
+            return Ok(1);
         }
-        0
+        Err(())
     }
 
     fn search_album(
         spotify: &Spotify,
+        library: &Library,
         albums: &Arc<RwLock<Vec<Album>>>,
         query: &str,
         offset: usize,
         append: bool,
-    ) -> u32 {
+    ) -> Result<u32, ()> {
         if let Ok(SearchResult::Albums(results)) =
             spotify
                 .api
-                .search(SearchType::Album, query, 50, offset as u32)
+                .search(SearchType::Album, query, SEARCH_PAGE_SIZE, offset as u32)
         {
-            let mut a = results.items.iter().map(|sa| sa.into()).collect();
+            let mut a: Vec<Album> = results.items.iter().map(|sa| sa.into()).collect();
+            library.look_up_saved_albums(&a);
             let mut r = albums.write().unwrap();
 
             if append {
@@ -180,40 +223,44 @@ impl SearchResultsView {
             } else {
                 *r = a;
             }
-            return results.total;
+            return Ok(results.total);
         }
-        0
+        Err(())
     }
 
     fn get_artist(
         spotify: &Spotify,
+        library: &Library,
         artists: &Arc<RwLock<Vec<Artist>>>,
         query: &str,
         _offset: usize,
         _append: bool,
-    ) -> u32 {
+    ) -> Result<u32, ()> {
         if let Ok(results) = spotify.api.artist(query) {
-            let a = vec![(&results).into()];
+            let a: Vec<Artist> = vec![(&results).into()];
+            library.look_up_followed_artists(&a);
             let mut r = artists.write().unwrap();
             *r = a;
-            return 1;
+            return Ok(1);
         }
-        0
+        Err(())
     }
 
     fn search_artist(
         spotify: &Spotify,
+        library: &Library,
         artists: &Arc<RwLock<Vec<Artist>>>,
         query: &str,
         offset: usize,
         append: bool,
-    ) -> u32 {
+    ) -> Result<u32, ()> {
         if let Ok(SearchResult::Artists(results)) =
             spotify
                 .api
-                .search(SearchType::Artist, query, 50, offset as u32)
+                .search(SearchType::Artist, query, SEARCH_PAGE_SIZE, offset as u32)
         {
-            let mut a = results.items.iter().map(|fa| fa.into()).collect();
+            let mut a: Vec<Artist> = results.items.iter().map(|fa| fa.into()).collect();
+            library.look_up_followed_artists(&a);
             let mut r = artists.write().unwrap();
 
             if append {
@@ -221,40 +268,44 @@ impl SearchResultsView {
             } else {
                 *r = a;
             }
-            return results.total;
+            return Ok(results.total);
         }
-        0
+        Err(())
     }
 
     fn get_playlist(
         spotify: &Spotify,
+        library: &Library,
         playlists: &Arc<RwLock<Vec<Playlist>>>,
         query: &str,
         _offset: usize,
         _append: bool,
-    ) -> u32 {
+    ) -> Result<u32, ()> {
         if let Ok(result) = spotify.api.playlist(query).as_ref() {
             let pls = vec![result.into()];
+            library.look_up_followed_playlists(&pls);
             let mut r = playlists.write().unwrap();
             *r = pls;
-            return 1;
+            return Ok(1);
         }
-        0
+        Err(())
     }
 
     fn search_playlist(
         spotify: &Spotify,
+        library: &Library,
         playlists: &Arc<RwLock<Vec<Playlist>>>,
         query: &str,
         offset: usize,
         append: bool,
-    ) -> u32 {
+    ) -> Result<u32, ()> {
         if let Ok(SearchResult::Playlists(results)) =
             spotify
                 .api
-                .search(SearchType::Playlist, query, 50, offset as u32)
+                .search(SearchType::Playlist, query, SEARCH_PAGE_SIZE, offset as u32)
         {
-            let mut pls = results.items.iter().map(|sp| sp.into()).collect();
+            let mut pls: Vec<Playlist> = results.items.iter().map(|sp| sp.into()).collect();
+            library.look_up_followed_playlists(&pls);
             let mut r = playlists.write().unwrap();
 
             if append {
@@ -262,38 +313,40 @@ impl SearchResultsView {
             } else {
                 *r = pls;
             }
-            return results.total;
+            return Ok(results.total);
         }
-        0
+        Err(())
     }
 
     fn get_show(
         spotify: &Spotify,
+        _library: &Library,
         shows: &Arc<RwLock<Vec<Show>>>,
         query: &str,
         _offset: usize,
         _append: bool,
-    ) -> u32 {
+    ) -> Result<u32, ()> {
         if let Ok(result) = spotify.api.show(query).as_ref() {
             let pls = vec![result.into()];
             let mut r = shows.write().unwrap();
             *r = pls;
-            return 1;
+            return Ok(1);
         }
-        0
+        Err(())
     }
 
     fn search_show(
         spotify: &Spotify,
+        _library: &Library,
         shows: &Arc<RwLock<Vec<Show>>>,
         query: &str,
         offset: usize,
         append: bool,
-    ) -> u32 {
+    ) -> Result<u32, ()> {
         if let Ok(SearchResult::Shows(results)) =
             spotify
                 .api
-                .search(SearchType::Show, query, 50, offset as u32)
+                .search(SearchType::Show, query, SEARCH_PAGE_SIZE, offset as u32)
         {
             let mut pls = results.items.iter().map(|sp| sp.into()).collect();
             let mut r = shows.write().unwrap();
@@ -303,40 +356,45 @@ impl SearchResultsView {
             } else {
                 *r = pls;
             }
-            return results.total;
+            return Ok(results.total);
         }
-        0
+        Err(())
     }
 
     fn get_episode(
         spotify: &Spotify,
+        _library: &Library,
         episodes: &Arc<RwLock<Vec<Episode>>>,
         query: &str,
         _offset: usize,
         _append: bool,
-    ) -> u32 {
+    ) -> Result<u32, ()> {
         if let Ok(result) = spotify.api.episode(query).as_ref() {
             let e = vec![result.into()];
             let mut r = episodes.write().unwrap();
             *r = e;
-            return 1;
+            return Ok(1);
         }
-        0
+        Err(())
     }
 
     fn search_episode(
         spotify: &Spotify,
+        library: &Library,
         episodes: &Arc<RwLock<Vec<Episode>>>,
         query: &str,
         offset: usize,
         append: bool,
-    ) -> u32 {
+    ) -> Result<u32, ()> {
         if let Ok(SearchResult::Episodes(results)) =
             spotify
                 .api
-                .search(SearchType::Episode, query, 50, offset as u32)
+                .search(SearchType::Episode, query, SEARCH_PAGE_SIZE, offset as u32)
         {
-            let mut e = results.items.iter().map(|se| se.into()).collect();
+            let mut e: Vec<Episode> = results.items.iter().map(|se| se.into()).collect();
+            if library.cfg.state().clean_mode {
+                e.retain(|episode| !episode.explicit);
+            }
             let mut r = episodes.write().unwrap();
 
             if append {
@@ -344,26 +402,37 @@ impl SearchResultsView {
             } else {
                 *r = e;
             }
-            return results.total;
+            return Ok(results.total);
         }
-        0
+        Err(())
     }
 
     fn perform_search<I: ListItem + Clone>(
         &self,
         handler: SearchHandler<I>,
         results: &Arc<RwLock<Vec<I>>>,
+        loading: &LoadingState,
         query: &str,
         paginator: Option<&Pagination<I>>,
     ) {
         let spotify = self.spotify.clone();
+        let library = self.library.clone();
         let query = query.to_owned();
         let results = results.clone();
         let ev = self.events.clone();
         let paginator = paginator.cloned();
+        let loading = loading.clone();
 
+        loading.start();
         std::thread::spawn(move || {
-            let total_items = handler(&spotify, &results, &query, 0, false) as usize;
+            let total_items = match handler(&spotify, &library, &results, &query, 0, false) {
+                Ok(total_items) => total_items as usize,
+                Err(()) => {
+                    loading.fail(tr("Could not load the search results"));
+                    ev.trigger();
+                    return;
+                }
+            };
 
             // register paginator if the API has more than one page of results
             if let Some(mut paginator) = paginator {
@@ -371,10 +440,13 @@ impl SearchResultsView {
                 if total_items > loaded_items {
                     let ev = ev.clone();
 
-                    // paginator callback
-                    let cb = move |items: Arc<RwLock<Vec<I>>>| {
-                        let offset = items.read().unwrap().len();
-                        handler(&spotify, &results, &query, offset, true);
+                    // Pages are requested by their offset instead of the amount of results, as
+                    // explicit results are left out in clean mode.
+                    let offset = AtomicUsize::new(0);
+                    let cb = move |_| {
+                        let page_size = SEARCH_PAGE_SIZE as usize;
+                        let offset = offset.fetch_add(page_size, Ordering::Relaxed) + page_size;
+                        handler(&spotify, &library, &results, &query, offset, true).ok();
                         ev.trigger();
                     };
                     paginator.set(loaded_items, total_items, Box::new(cb));
@@ -382,6 +454,7 @@ impl SearchResultsView {
                     paginator.clear()
                 }
             }
+            loading.finish();
             ev.trigger();
         });
     }
@@ -404,6 +477,7 @@ impl SearchResultsView {
                     self.perform_search(
                         Box::new(Self::get_track),
                         &self.results_tracks,
+                        &self.loading_tracks,
                         &query,
                         None,
                     );
@@ -413,6 +487,7 @@ impl SearchResultsView {
                     self.perform_search(
                         Box::new(Self::get_album),
                         &self.results_albums,
+                        &self.loading_albums,
                         &query,
                         None,
                     );
@@ -422,6 +497,7 @@ impl SearchResultsView {
                     self.perform_search(
                         Box::new(Self::get_artist),
                         &self.results_artists,
+                        &self.loading_artists,
                         &query,
                         None,
                     );
@@ -431,6 +507,7 @@ impl SearchResultsView {
                     self.perform_search(
                         Box::new(Self::get_playlist),
                         &self.results_playlists,
+                        &self.loading_playlists,
                         &query,
                         None,
                     );
@@ -440,6 +517,7 @@ impl SearchResultsView {
                     self.perform_search(
                         Box::new(Self::get_show),
                         &self.results_shows,
+                        &self.loading_shows,
                         &query,
                         None,
                     );
@@ -449,6 +527,7 @@ impl SearchResultsView {
                     self.perform_search(
                         Box::new(Self::get_episode),
                         &self.results_episodes,
+                        &self.loading_episodes,
                         &query,
                         None,
                     );
@@ -463,6 +542,7 @@ impl SearchResultsView {
                     self.perform_search(
                         Box::new(Self::get_track),
                         &self.results_tracks,
+                        &self.loading_tracks,
                         &url.id,
                         None,
                     );
@@ -472,6 +552,7 @@ impl SearchResultsView {
                     self.perform_search(
                         Box::new(Self::get_album),
                         &self.results_albums,
+                        &self.loading_albums,
                         &url.id,
                         None,
                     );
@@ -481,6 +562,7 @@ impl SearchResultsView {
                     self.perform_search(
                         Box::new(Self::get_artist),
                         &self.results_artists,
+                        &self.loading_artists,
                         &url.id,
                         None,
                     );
@@ -490,6 +572,7 @@ impl SearchResultsView {
                     self.perform_search(
                         Box::new(Self::get_playlist),
                         &self.results_playlists,
+                        &self.loading_playlists,
                         &url.id,
                         None,
                     );
@@ -499,6 +582,7 @@ impl SearchResultsView {
                     self.perform_search(
                         Box::new(Self::get_show),
                         &self.results_shows,
+                        &self.loading_shows,
                         &url.id,
                         None,
                     );
@@ -508,6 +592,7 @@ impl SearchResultsView {
                     self.perform_search(
                         Box::new(Self::get_episode),
                         &self.results_episodes,
+                        &self.loading_episodes,
                         &url.id,
                         None,
                     );
@@ -515,44 +600,73 @@ impl SearchResultsView {
                 }
             }
         } else {
-            self.perform_search(
-                Box::new(Self::search_track),
-                &self.results_tracks,
-                &query,
-                Some(&self.pagination_tracks),
-            );
-            self.perform_search(
-                Box::new(Self::search_album),
-                &self.results_albums,
-                &query,
-                Some(&self.pagination_albums),
-            );
-            self.perform_search(
-                Box::new(Self::search_artist),
-                &self.results_artists,
-                &query,
-                Some(&self.pagination_artists),
-            );
-            self.perform_search(
-                Box::new(Self::search_playlist),
-                &self.results_playlists,
-                &query,
-                Some(&self.pagination_playlists),
-            );
-            self.perform_search(
-                Box::new(Self::search_show),
-                &self.results_shows,
-                &query,
-                Some(&self.pagination_shows),
-            );
-            self.perform_search(
-                Box::new(Self::search_episode),
-                &self.results_episodes,
-                &query,
-                Some(&self.pagination_episodes),
-            );
+            // Shows and episodes are only searched if `search_podcasts` isn't disabled, or if
+            // nothing but them is searched for.
+            let (query, podcasts_only) = match query.strip_prefix(PODCASTS_QUALIFIER) {
+                Some(query) => (query.trim().to_string(), true),
+                None => (query, false),
+            };
+            let search_podcasts =
+                podcasts_only || self.library.cfg.values().search_podcasts.unwrap_or(true);
+
+            if podcasts_only {
+                self.tabs.set_selected(4);
+            } else {
+                self.search_music(&query);
+            }
+            if search_podcasts {
+                self.search_podcasts(&query);
+            }
         }
     }
+
+    fn search_music(&self, query: &str) {
+        self.perform_search(
+            Box::new(Self::search_track),
+            &self.results_tracks,
+            &self.loading_tracks,
+            query,
+            Some(&self.pagination_tracks),
+        );
+        self.perform_search(
+            Box::new(Self::search_album),
+            &self.results_albums,
+            &self.loading_albums,
+            query,
+            Some(&self.pagination_albums),
+        );
+        self.perform_search(
+            Box::new(Self::search_artist),
+            &self.results_artists,
+            &self.loading_artists,
+            query,
+            Some(&self.pagination_artists),
+        );
+        self.perform_search(
+            Box::new(Self::search_playlist),
+            &self.results_playlists,
+            &self.loading_playlists,
+            query,
+            Some(&self.pagination_playlists),
+        );
+    }
+
+    fn search_podcasts(&self, query: &str) {
+        self.perform_search(
+            Box::new(Self::search_show),
+            &self.results_shows,
+            &self.loading_shows,
+            query,
+            Some(&self.pagination_shows),
+        );
+        self.perform_search(
+            Box::new(Self::search_episode),
+            &self.results_episodes,
+            &self.loading_episodes,
+            query,
+            Some(&self.pagination_episodes),
+        );
+    }
 }
 
 impl ViewWrapper for SearchResultsView {
@@ -561,8 +675,18 @@ impl ViewWrapper for SearchResultsView {
 
 impl ViewExt for SearchResultsView {
     fn title(&self) -> String {
-        format!("Search: {}", self.search_term)
+        tr_args("Search: {}", &[&self.search_term])
     }
+
+    fn refresh(&mut self) -> bool {
+        self.run_search();
+        true
+    }
+
+    fn key_hints(&self) -> Vec<Command> {
+        self.tabs.key_hints()
+    }
+
     fn on_command(&mut self, s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
         self.tabs.on_command(s, cmd)
     }
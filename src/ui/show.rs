@@ -1,10 +1,11 @@
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use cursive::view::ViewWrapper;
-use cursive::Cursive;
+use cursive::{Cursive, Vec2};
 
-use crate::command::Command;
+use crate::command::{Command, SortDirection, SortKey};
 use crate::commands::CommandResult;
+use crate::i18n::tr_args;
 use crate::library::Library;
 use crate::model::episode::Episode;
 use crate::model::show::Show;
@@ -14,6 +15,14 @@ use crate::ui::listview::ListView;
 
 pub struct ShowView {
     list: ListView<Episode>,
+    episodes: Arc<RwLock<Vec<Episode>>>,
+    /// Played episodes that are currently hidden from the list.
+    hidden: Vec<Episode>,
+    hide_played: bool,
+    /// The amount of loaded episodes, shown or hidden, when the list was last sorted.
+    sorted: usize,
+    order: (SortKey, SortDirection),
+    library: Arc<Library>,
     show: Show,
 }
 
@@ -22,20 +31,83 @@ impl ShowView {
         let spotify = queue.get_spotify();
         let show = show.clone();
 
-        let list = {
-            let results = spotify.api.show_episodes(&show.id);
-            let view = ListView::new(results.items.clone(), queue, library);
-            results.apply_pagination(view.get_pagination());
+        let results = spotify.api.show_episodes(&show.id);
+        let episodes = results.items.clone();
+        let list = ListView::new(episodes.clone(), queue, library.clone());
+        results.apply_pagination(list.get_pagination());
 
-            view
-        };
+        Self {
+            list,
+            episodes,
+            hidden: Vec::new(),
+            hide_played: false,
+            sorted: 0,
+            // Spotify lists the newest episodes first.
+            order: (SortKey::Added, SortDirection::Descending),
+            library,
+            show,
+        }
+    }
+
+    fn sort(&mut self) {
+        let (key, direction) = &self.order;
+        let mut episodes = self.episodes.write().unwrap();
+        episodes.sort_by(|a, b| {
+            let ordering = match key {
+                SortKey::Title => a.name.cmp(&b.name),
+                SortKey::Duration => a.duration.cmp(&b.duration),
+                _ => a.release_date.cmp(&b.release_date),
+            };
+            match direction {
+                SortDirection::Ascending => ordering,
+                SortDirection::Descending => ordering.reverse(),
+            }
+        });
+        self.sorted = episodes.len() + self.hidden.len();
+    }
 
-        Self { list, show }
+    /// Sort the episodes of pages that were loaded since the last sort into the list, and hide
+    /// them if they were played.
+    fn update_loaded(&mut self) {
+        if self.episodes.read().unwrap().len() + self.hidden.len() == self.sorted {
+            return;
+        }
+        if self.hide_played {
+            let mut episodes = self.episodes.write().unwrap();
+            let (mut played, unplayed): (Vec<_>, Vec<_>) = episodes
+                .drain(..)
+                .partition(|e| self.library.is_episode_played(e));
+            self.hidden.append(&mut played);
+            *episodes = unplayed;
+        }
+        self.sort();
+    }
+
+    fn set_hide_played(&mut self, hide: bool) {
+        self.hide_played = hide;
+        if hide {
+            let mut episodes = self.episodes.write().unwrap();
+            let (played, unplayed) = episodes
+                .drain(..)
+                .partition(|e| self.library.is_episode_played(e));
+            self.hidden = played;
+            *episodes = unplayed;
+        } else {
+            self.episodes.write().unwrap().append(&mut self.hidden);
+            self.sort();
+        }
+        self.list.move_focus_to(0);
     }
 }
 
 impl ViewWrapper for ShowView {
     wrap_impl!(self.list: ListView<Episode>);
+
+    fn wrap_layout(&mut self, size: Vec2) {
+        // Further pages of episodes are appended to the list in the background.
+        self.update_loaded();
+        self.list.layout(size);
+    }
 }
 
 impl ViewExt for ShowView {
@@ -43,7 +115,40 @@ impl ViewExt for ShowView {
         self.show.name.clone()
     }
 
+    fn title_sub(&self) -> String {
+        if self.hide_played {
+            tr_args(
+                "{} played episodes hidden",
+                &[&self.hidden.len().to_string()],
+            )
+        } else {
+            String::new()
+        }
+    }
+
+    fn key_hints(&self) -> Vec<Command> {
+        self.list.key_hints()
+    }
+
     fn on_command(&mut self, s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
-        self.list.on_command(s, cmd)
+        match cmd {
+            Command::Sort(key, direction) => {
+                if !matches!(key, SortKey::Title | SortKey::Duration | SortKey::Added) {
+                    return Err(tr_args(
+                        "Episodes can't be sorted by {}",
+                        &[&key.to_string()],
+                    ));
+                }
+                self.order = (key.clone(), direction.clone());
+                self.sort();
+                self.list.move_focus_to(0);
+                Ok(CommandResult::Consumed(None))
+            }
+            Command::HidePlayed => {
+                self.set_hide_played(!self.hide_played);
+                Ok(CommandResult::Consumed(None))
+            }
+            _ => self.list.on_command(s, cmd),
+        }
     }
 }
@@ -0,0 +1,64 @@
+use std::sync::{Arc, RwLock};
+
+use cursive::view::Margins;
+use cursive::views::{Dialog, ScrollView, SelectView};
+
+use crate::i18n::{tr, tr_args};
+use crate::library::Library;
+use crate::model::snapshot::Snapshot;
+use crate::queue::Queue;
+use crate::traits::IntoBoxedViewExt;
+use crate::ui::layout::Layout;
+use crate::ui::listview::ListView;
+use crate::ui::modal::Modal;
+
+/// Dialog to pick one of the stored snapshots and browse its tracks.
+pub fn snapshots_dialog(queue: Arc<Queue>, library: Arc<Library>) -> Option<Modal<Dialog>> {
+    let snapshots = library.snapshots();
+    if snapshots.is_empty() {
+        return None;
+    }
+
+    let mut list_select: SelectView<Snapshot> = SelectView::new().autojump();
+    for snapshot in snapshots {
+        let label = tr_args(
+            "{} from {} ({} tracks)",
+            &[
+                &snapshot.source.name,
+                &snapshot
+                    .taken_at
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string(),
+                &snapshot.tracks.len().to_string(),
+            ],
+        );
+        list_select.add_item(label, snapshot);
+    }
+    list_select.set_on_submit(move |s, snapshot: &Snapshot| {
+        s.pop_layer();
+        let title = format!(
+            "{} ({})",
+            snapshot.source.name,
+            snapshot
+                .taken_at
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d")
+        );
+        let view = ListView::new(
+            Arc::new(RwLock::new(snapshot.tracks.clone())),
+            queue.clone(),
+            library.clone(),
+        )
+        .with_title(&title)
+        .into_boxed_view_ext();
+        s.call_on_name("main", move |v: &mut Layout| v.push_view(view));
+    });
+
+    let dialog = Dialog::new()
+        .title(tr("Snapshots"))
+        .dismiss_button(tr("Cancel"))
+        .padding(Margins::lrtb(1, 1, 1, 0))
+        .content(ScrollView::new(list_select));
+    Some(Modal::new(dialog))
+}
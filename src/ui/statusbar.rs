@@ -1,4 +1,6 @@
+use std::ops::Range;
 use std::sync::Arc;
+use std::time::Duration;
 
 use cursive::align::HAlign;
 use cursive::event::{Event, EventResult, MouseButton, MouseEvent};
@@ -8,11 +10,22 @@ use cursive::vec::Vec2;
 use cursive::Printer;
 use unicode_width::UnicodeWidthStr;
 
+use crate::application::UserData;
+use crate::command::{Command, TargetMode, TimeFormat};
+use crate::ext_traits::CursiveExt;
+use crate::i18n::tr;
 use crate::library::Library;
 use crate::model::playable::Playable;
 use crate::queue::{Queue, RepeatSetting};
-use crate::spotify::{PlayerEvent, Spotify};
-use crate::utils::ms_to_hms;
+use crate::spotify::{BufferState, PlayerEvent, Spotify};
+use crate::traits::{IntoBoxedViewExt, ListItem};
+use crate::ui::album::AlbumView;
+use crate::ui::tasks::task_label;
+use crate::utils::{ms_to_hms, spinner_frame};
+use crate::wellbeing::format_duration;
+
+/// Drawn on the seek bar at the position of each bookmark of the playing item.
+const BOOKMARK_MARKER: &str = "◆";
 
 pub struct StatusBar {
     queue: Arc<Queue>,
@@ -34,11 +47,23 @@ impl StatusBar {
     }
 
     fn use_nerdfont(&self) -> bool {
-        self.library.cfg.values().use_nerdfont.unwrap_or(false)
+        self.library.cfg.values().use_nerdfont.unwrap_or(false) && !self.accessible()
+    }
+
+    /// Whether indicators should be written out for screen readers.
+    fn accessible(&self) -> bool {
+        self.library.cfg.values().accessibility.unwrap_or(false)
     }
 
     fn playback_indicator(&self) -> &str {
         let status = self.spotify.get_current_status();
+        if self.accessible() {
+            return match status {
+                PlayerEvent::Playing(_) => tr("Playing"),
+                PlayerEvent::Paused(_) => tr("Paused"),
+                PlayerEvent::Stopped | PlayerEvent::FinishedTrack => tr("Stopped"),
+            };
+        }
         let nerdfont = self.use_nerdfont();
         let flipped = self
             .library
@@ -71,6 +96,95 @@ impl StatusBar {
         )
     }
 
+    fn buffer_indicator(&self) -> &str {
+        let nerdfont = self.use_nerdfont();
+        match self.spotify.get_buffer_state() {
+            BufferState::Ready => "",
+            BufferState::Loading if nerdfont => "\u{f01da} ",
+            BufferState::Loading => "[B] ",
+            BufferState::Preloaded if nerdfont => "\u{f012c} ",
+            BufferState::Preloaded => "[P] ",
+            BufferState::Underrun if nerdfont => "\u{f0026} ",
+            BufferState::Underrun => "[!] ",
+        }
+    }
+
+    fn private_indicator(&self) -> &str {
+        match self.spotify.private_session() {
+            false => "",
+            true if self.use_nerdfont() => "\u{f05f9} ",
+            true => "[I] ",
+        }
+    }
+
+    fn bitrate_display(&self) -> String {
+        format!(" {}k", self.spotify.bitrate())
+    }
+
+    /// The oldest running background task with its progress, and how many more are running.
+    fn tasks_display(&self) -> String {
+        let tasks = self.library.tasks.running();
+        match tasks.first() {
+            Some(task) if tasks.len() > 1 => format!(
+                "{} {} (+{}) ",
+                spinner_frame(),
+                task_label(task),
+                tasks.len() - 1
+            ),
+            Some(task) => format!("{} {} ", spinner_frame(), task_label(task)),
+            None => String::new(),
+        }
+    }
+
+    /// The time listened today, and the goal if one is set. Only shown if a break reminder or
+    /// listening goal is configured.
+    fn listening_display(&self) -> String {
+        let cfg = self.library.cfg.values();
+        if cfg.break_reminder.is_none() && cfg.listening_goal.is_none() {
+            return String::new();
+        }
+        let today = format_duration(self.spotify.listening_time().today());
+        match cfg.listening_goal {
+            Some(goal) => format!(
+                "[{}/{}] ",
+                today,
+                format_duration(Duration::from_secs(u64::from(goal) * 60))
+            ),
+            None => format!("[{today}] "),
+        }
+    }
+
+    /// The elapsed time of the playing track, along with its duration or the time that is left
+    /// depending on the format chosen with `timefmt`.
+    fn time_display(&self) -> String {
+        let Some(playable) = self.queue.get_current() else {
+            return String::new();
+        };
+        let elapsed_ms: u32 = self
+            .spotify
+            .get_current_progress()
+            .as_millis()
+            .try_into()
+            .unwrap_or(0);
+        match self.library.cfg.state().time_format {
+            TimeFormat::Total => format!("{} / {}", ms_to_hms(elapsed_ms), playable.duration_str()),
+            TimeFormat::Remaining => format!(
+                "{} / -{}",
+                ms_to_hms(elapsed_ms),
+                ms_to_hms(playable.duration().saturating_sub(elapsed_ms))
+            ),
+        }
+    }
+
+    /// The columns the playback time takes up, between the listening time and the bitrate.
+    fn time_columns(&self) -> Range<usize> {
+        let end = self
+            .last_size
+            .x
+            .saturating_sub(self.bitrate_display().width() + self.volume_display().width());
+        end.saturating_sub(self.time_display().width())..end
+    }
+
     fn format_track(&self, t: &Playable) -> String {
         let format = self
             .library
@@ -79,6 +193,10 @@ impl StatusBar {
             .statusbar_format
             .clone()
             .unwrap_or_else(|| "%artists - %title".to_string());
+        let (position, length) = self.queue.position().unwrap_or_default();
+        let format = format
+            .replace("%position", &position.to_string())
+            .replace("%queuelength", &length.to_string());
         Playable::format(t, &format, &self.library)
     }
 }
@@ -157,33 +275,37 @@ impl View for StatusBar {
             ""
         };
 
+        let tasks = self.tasks_display();
+        let private = self.private_indicator();
+        let bitrate = self.bitrate_display();
+        let buffer = self.buffer_indicator();
         let volume = self.volume_display();
+        let listening = self.listening_display();
 
         printer.with_color(style_bar_bg, |printer| {
             printer.print((0, 0), &"┉".repeat(printer.size.x));
         });
 
-        let elapsed = self.spotify.get_current_progress();
-        let elapsed_ms = elapsed.as_millis() as u32;
-
-        let formatted_elapsed = ms_to_hms(elapsed.as_millis().try_into().unwrap_or(0));
-
-        let playback_duration_status = match self.queue.get_current() {
-            Some(ref t) => format!("{} / {}", formatted_elapsed, t.duration_str()),
-            None => "".to_string(),
-        };
+        let elapsed_ms = self.spotify.get_current_progress().as_millis() as u32;
+        let playback_duration_status = self.time_display();
 
-        let right = updating.to_string()
+        let right = tasks
+            + updating
+            + private
+            + buffer
             + repeat
             + shuffle
+            + &listening
             // + saved
             + &playback_duration_status
+            + &bitrate
             + &volume;
         let offset = HAlign::Right.get_offset(right.width(), printer.size.x);
 
         printer.with_color(style, |printer| {
             if let Some(ref t) = self.queue.get_current() {
-                printer.print((4, 1), &self.format_track(t));
+                let offset = self.playback_indicator().width() + 2;
+                printer.print((offset, 1), &self.format_track(t));
             }
             printer.print((offset, 1), &right);
         });
@@ -194,6 +316,16 @@ impl View for StatusBar {
                     (((printer.size.x as u32) * elapsed_ms) / t.duration()) as usize;
                 printer.print((0, 0), &"━".repeat(duration_width + 1));
             });
+
+            // Bookmarks set with `mark add` are shown on the seek bar.
+            printer.with_color(style, |printer| {
+                let duration = u64::from(t.duration().max(1));
+                for bookmark in self.library.bookmarks(&t.uri()) {
+                    let column =
+                        (printer.size.x as u64 * u64::from(bookmark.position) / duration) as usize;
+                    printer.print((column.min(printer.size.x - 1), 0), BOOKMARK_MARKER);
+                }
+            });
         }
     }
 
@@ -249,8 +381,46 @@ impl View for StatusBar {
 
                     self.spotify.set_volume(volume, true);
                 }
-            } else if event == MouseEvent::Press(MouseButton::Left) {
-                self.queue.toggleplayback();
+            } else if self.time_columns().contains(&position.x) {
+                if event == MouseEvent::Press(MouseButton::Left) {
+                    return EventResult::with_cb(|s| {
+                        if let Some(data) = s.user_data::<UserData>().cloned() {
+                            data.cmd.handle(s, Command::TimeFormat(None));
+                        }
+                    });
+                }
+            } else if position.x < self.playback_indicator().width() + 2 {
+                if event == MouseEvent::Press(MouseButton::Left) {
+                    self.queue.toggleplayback();
+                }
+            } else if self.queue.get_current().is_some() {
+                // Clicks on the track act on it in the focused screen, without switching to the
+                // queue.
+                match event {
+                    MouseEvent::Press(MouseButton::Left) => {
+                        let queue = self.queue.clone();
+                        let library = self.library.clone();
+                        return EventResult::with_cb(move |s| {
+                            let album = queue
+                                .get_current()
+                                .and_then(|current| current.album(&queue));
+                            if let Some(album) = album {
+                                let view = AlbumView::new(queue.clone(), library.clone(), &album);
+                                s.on_layout(|_, mut layout| {
+                                    layout.push_view(view.into_boxed_view_ext())
+                                });
+                            }
+                        });
+                    }
+                    MouseEvent::Press(MouseButton::Right) => {
+                        return EventResult::with_cb(|s| {
+                            if let Some(data) = s.user_data::<UserData>().cloned() {
+                                data.cmd.handle(s, Command::Open(TargetMode::Current));
+                            }
+                        });
+                    }
+                    _ => {}
+                }
             }
 
             EventResult::Consumed(None)
@@ -21,6 +21,8 @@ use crate::{
 pub struct TabbedView {
     /// The list of tabs
     tabs: Vec<NamedView<BoxedViewExt>>,
+    /// The titles shown for `tabs`, which may change unlike their names
+    titles: Vec<String>,
     /// The index of the currently visible tab from `tabs`
     selected: usize,
     /// The size given to the last call to `layout()`
@@ -34,8 +36,17 @@ impl TabbedView {
 
     /// Add `view` as a new tab to the end of this [TabbedView].
     pub fn add_tab(&mut self, title: impl Into<String>, view: impl IntoBoxedViewExt) {
-        let tab = BoxedViewExt::new(view.into_boxed_view_ext()).with_name(title);
+        let title = title.into();
+        let tab = BoxedViewExt::new(view.into_boxed_view_ext()).with_name(title.clone());
         self.tabs.push(tab);
+        self.titles.push(title);
+    }
+
+    /// Show `title` for the tab at `index`, e.g. to show the state of its view.
+    pub fn set_title(&mut self, index: usize, title: impl Into<String>) {
+        if let Some(current) = self.titles.get_mut(index) {
+            *current = title.into();
+        }
     }
 
     /// Return a mutable reference to the tab at `index`, or None if there is no tab at `index`.
@@ -59,6 +70,11 @@ impl TabbedView {
         self.len() == 0
     }
 
+    /// Return the index of the currently visible tab.
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
     /// Set the tab at `index` as currently visible.
     pub fn set_selected(&mut self, index: usize) {
         self.selected = min(self.len().saturating_sub(1), index);
@@ -116,7 +132,7 @@ impl View for TabbedView {
         }
 
         let tabwidth = self.tab_width();
-        for (i, tab) in self.tabs.iter().enumerate() {
+        for (i, title) in self.titles.iter().enumerate() {
             let style = if self.selected == i {
                 ColorStyle::highlight()
             } else {
@@ -128,7 +144,6 @@ impl View for TabbedView {
                 width += printer.size.x % self.tabs.len();
             }
 
-            let title = tab.name();
             let offset = HAlign::Center.get_offset(title.width(), width);
 
             printer.with_color(style, |printer| {
@@ -187,6 +202,13 @@ impl View for TabbedView {
 }
 
 impl ViewExt for TabbedView {
+    fn key_hints(&self) -> Vec<Command> {
+        self.tabs
+            .get(self.selected)
+            .map(|tab| tab.key_hints())
+            .unwrap_or_default()
+    }
+
     fn on_command(&mut self, s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
         match cmd {
             Command::Move(mode, amount) if matches!(mode, MoveMode::Left | MoveMode::Right) => {
@@ -0,0 +1,48 @@
+use cursive::view::Margins;
+use cursive::views::{Dialog, ScrollView, SelectView};
+
+use crate::ext_traits::CursiveExt;
+use crate::i18n::tr;
+use crate::tasks::{TaskState, Tasks};
+use crate::ui::modal::Modal;
+
+/// The label of a running task, along with its progress once it is known.
+pub fn task_label(task: &TaskState) -> String {
+    let mut label = task.name.clone();
+    if let Some(percentage) = task.percentage() {
+        label.push_str(&format!(" {percentage}%"));
+    }
+    if let Some(detail) = task.detail() {
+        label.push_str(&format!(" ({detail})"));
+    }
+    label
+}
+
+/// Dialog listing the running tasks. Picking one of them cancels it.
+pub fn tasks_dialog(tasks: Tasks) -> Option<Modal<Dialog>> {
+    let running = tasks.running();
+    if running.is_empty() {
+        return None;
+    }
+
+    let mut list_select: SelectView<usize> = SelectView::new().autojump();
+    for task in running {
+        list_select.add_item(task_label(&task), task.id);
+    }
+    list_select.set_on_submit(move |s, id: &usize| {
+        s.pop_layer();
+        let result = if tasks.cancel(*id) {
+            Ok(Some(tr("Cancelling task…").to_string()))
+        } else {
+            Err(tr("The task already finished").to_string())
+        };
+        s.on_layout(|_, mut layout| layout.set_result(result));
+    });
+
+    let dialog = Dialog::new()
+        .title(tr("Tasks"))
+        .dismiss_button(tr("Close"))
+        .padding(Margins::lrtb(1, 1, 1, 0))
+        .content(ScrollView::new(list_select));
+    Some(Modal::new(dialog))
+}
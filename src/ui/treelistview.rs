@@ -0,0 +1,311 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread;
+
+use cursive::view::{View, ViewWrapper};
+use cursive::{Cursive, Vec2};
+
+use crate::command::{Command, TargetMode};
+use crate::commands::CommandResult;
+use crate::library::Library;
+use crate::model::album::Album;
+use crate::model::artist::Artist;
+use crate::model::episode::Episode;
+use crate::model::track::Track;
+use crate::queue::Queue;
+use crate::traits::{ListItem, ViewExt};
+use crate::ui::listview::ListView;
+
+/// An item of a [TreeListView], which may have children of the same type.
+pub trait TreeItem: ListItem + Clone {
+    /// Identifies the item among its siblings, to remember whether it is expanded.
+    fn tree_key(&self) -> String;
+
+    /// Whether the item can be expanded to show its children.
+    fn has_children(&self) -> bool {
+        false
+    }
+
+    /// The children of the item. Called in a background thread the first time the item is
+    /// expanded, so it may fetch them from the API.
+    fn load_children(&self, _queue: &Queue, _library: &Library) -> Vec<Self> {
+        Vec::new()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum NodeState {
+    Leaf,
+    Collapsed,
+    Loading,
+    Expanded,
+}
+
+/// A visible row of a [TreeListView]: an item along with its place in the tree.
+#[derive(Clone)]
+pub struct TreeRow<I: TreeItem> {
+    pub item: I,
+    path: String,
+    depth: usize,
+    state: NodeState,
+}
+
+impl<I: TreeItem> ListItem for TreeRow<I> {
+    fn is_playing(&self, queue: &Queue) -> bool {
+        self.item.is_playing(queue)
+    }
+
+    fn display_left(&self, library: &Library) -> String {
+        let marker = match self.state {
+            NodeState::Leaf => " ",
+            NodeState::Collapsed => "▸",
+            NodeState::Loading => "…",
+            NodeState::Expanded => "▾",
+        };
+        let indent = "  ".repeat(self.depth);
+        format!("{indent}{marker} {}", self.item.display_left(library))
+    }
+
+    fn display_center(&self, library: &Library) -> String {
+        self.item.display_center(library)
+    }
+
+    fn display_right(&self, library: &Library) -> String {
+        self.item.display_right(library)
+    }
+
+    fn play(&mut self, queue: &Queue) {
+        self.item.play(queue);
+    }
+
+    fn play_next(&mut self, queue: &Queue) {
+        self.item.play_next(queue);
+    }
+
+    fn queue(&mut self, queue: &Queue) {
+        self.item.queue(queue);
+    }
+
+    fn toggle_saved(&mut self, library: &Library) {
+        self.item.toggle_saved(library);
+    }
+
+    fn save(&mut self, library: &Library) {
+        self.item.save(library);
+    }
+
+    fn unsave(&mut self, library: &Library) {
+        self.item.unsave(library);
+    }
+
+    fn open(&self, queue: Arc<Queue>, library: Arc<Library>) -> Option<Box<dyn ViewExt>> {
+        self.item.open(queue, library)
+    }
+
+    fn open_recommendations(
+        &mut self,
+        queue: Arc<Queue>,
+        library: Arc<Library>,
+    ) -> Option<Box<dyn ViewExt>> {
+        self.item.open_recommendations(queue, library)
+    }
+
+    fn share_url(&self) -> Option<String> {
+        self.item.share_url()
+    }
+
+    fn metadata(&self) -> serde_json::Value {
+        self.item.metadata()
+    }
+
+    fn album(&self, queue: &Queue) -> Option<Album> {
+        self.item.album(queue)
+    }
+
+    fn artists(&self) -> Option<Vec<Artist>> {
+        self.item.artists()
+    }
+
+    fn track(&self) -> Option<Track> {
+        self.item.track()
+    }
+
+    fn episode(&self) -> Option<Episode> {
+        self.item.episode()
+    }
+
+    fn is_saved(&self, library: &Library) -> Option<bool> {
+        self.item.is_saved(library)
+    }
+
+    fn is_playable(&self) -> bool {
+        self.item.is_playable()
+    }
+
+    fn as_listitem(&self) -> Box<dyn ListItem> {
+        self.item.as_listitem()
+    }
+}
+
+/// A [ListView] of items that can be expanded to show their children below them, indented by
+/// their depth. Items are expanded and collapsed with `open`, children are loaded in the
+/// background the first time.
+pub struct TreeListView<I: TreeItem> {
+    list: ListView<TreeRow<I>>,
+    rows: Arc<RwLock<Vec<TreeRow<I>>>>,
+    roots: Arc<RwLock<Vec<I>>>,
+    children: Arc<RwLock<HashMap<String, Vec<I>>>>,
+    loading: Arc<RwLock<HashSet<String>>>,
+    expanded: HashSet<String>,
+    dirty: Arc<AtomicBool>,
+    queue: Arc<Queue>,
+    library: Arc<Library>,
+}
+
+impl<I: TreeItem> TreeListView<I> {
+    pub fn new(roots: Arc<RwLock<Vec<I>>>, queue: Arc<Queue>, library: Arc<Library>) -> Self {
+        let rows = Arc::new(RwLock::new(Vec::new()));
+        let list = ListView::new(rows.clone(), queue.clone(), library.clone());
+        let view = Self {
+            list,
+            rows,
+            roots,
+            children: Arc::new(RwLock::new(HashMap::new())),
+            loading: Arc::new(RwLock::new(HashSet::new())),
+            expanded: HashSet::new(),
+            dirty: Arc::new(AtomicBool::new(false)),
+            queue,
+            library,
+        };
+        view.update_rows();
+        view
+    }
+
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.list = self.list.with_title(title);
+        self
+    }
+
+    /// Forget the loaded children and show the roots again, e.g. after they changed. Expanded
+    /// items stay expanded and load their children again.
+    pub fn reload(&self) {
+        self.children.write().unwrap().clear();
+        self.update_rows();
+    }
+
+    /// Flatten the tree into the visible rows, starting to load the children of expanded items
+    /// that weren't loaded yet.
+    fn update_rows(&self) {
+        let mut rows = Vec::new();
+        for root in self.roots.read().unwrap().iter() {
+            self.push_rows(&mut rows, root, "", 0);
+        }
+        *self.rows.write().unwrap() = rows;
+    }
+
+    fn push_rows(&self, rows: &mut Vec<TreeRow<I>>, item: &I, parent: &str, depth: usize) {
+        let path = format!("{parent}/{}", item.tree_key());
+        let children = self.children.read().unwrap().get(&path).cloned();
+        let state = if !item.has_children() {
+            NodeState::Leaf
+        } else if !self.expanded.contains(&path) {
+            NodeState::Collapsed
+        } else if children.is_some() {
+            NodeState::Expanded
+        } else {
+            self.load_children(item, &path);
+            NodeState::Loading
+        };
+
+        rows.push(TreeRow {
+            item: item.clone(),
+            path: path.clone(),
+            depth,
+            state,
+        });
+        if state == NodeState::Expanded {
+            for child in children.unwrap_or_default().iter() {
+                self.push_rows(rows, child, &path, depth + 1);
+            }
+        }
+    }
+
+    fn load_children(&self, item: &I, path: &str) {
+        if !self.loading.write().unwrap().insert(path.to_string()) {
+            return;
+        }
+
+        let item = item.clone();
+        let path = path.to_string();
+        let children = self.children.clone();
+        let loading = self.loading.clone();
+        let dirty = self.dirty.clone();
+        let queue = self.queue.clone();
+        let library = self.library.clone();
+        thread::spawn(move || {
+            let loaded = item.load_children(&queue, &library);
+            children.write().unwrap().insert(path.clone(), loaded);
+            loading.write().unwrap().remove(&path);
+            dirty.store(true, Ordering::Relaxed);
+            library.trigger_redraw();
+        });
+    }
+
+    /// Expand or collapse the selected item, if it has children.
+    fn toggle_selected(&mut self) -> bool {
+        let index = self.list.get_selected_index();
+        let Some((path, state)) = self
+            .rows
+            .read()
+            .unwrap()
+            .get(index)
+            .map(|row| (row.path.clone(), row.state))
+        else {
+            return false;
+        };
+
+        match state {
+            NodeState::Leaf => return false,
+            NodeState::Collapsed => {
+                self.expanded.insert(path);
+            }
+            NodeState::Loading | NodeState::Expanded => {
+                self.expanded.remove(&path);
+            }
+        }
+        self.update_rows();
+        self.list.move_focus_to(index);
+        true
+    }
+}
+
+impl<I: TreeItem> ViewWrapper for TreeListView<I> {
+    wrap_impl!(self.list: ListView<TreeRow<I>>);
+
+    fn wrap_layout(&mut self, size: Vec2) {
+        if self.dirty.swap(false, Ordering::Relaxed) {
+            self.update_rows();
+        }
+        self.list.layout(size);
+    }
+}
+
+impl<I: TreeItem> ViewExt for TreeListView<I> {
+    fn title(&self) -> String {
+        self.list.title()
+    }
+
+    fn key_hints(&self) -> Vec<Command> {
+        self.list.key_hints()
+    }
+
+    fn on_command(&mut self, s: &mut Cursive, cmd: &Command) -> Result<CommandResult, String> {
+        match cmd {
+            Command::Open(TargetMode::Selected) if self.toggle_selected() => {
+                Ok(CommandResult::Consumed(None))
+            }
+            _ => self.list.on_command(s, cmd),
+        }
+    }
+}
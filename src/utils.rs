@@ -24,6 +24,43 @@ pub fn format_duration(d: &std::time::Duration) -> String {
     s.trim_end().to_string()
 }
 
+/// Returns `html` as plain text, without tags and with the common entities decoded. Spotify uses
+/// this for links in playlist descriptions.
+pub fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    [
+        ("&quot;", "\""),
+        ("&#x27;", "'"),
+        ("&#39;", "'"),
+        ("&lt;", "<"),
+        ("&gt;", ">"),
+        ("&nbsp;", " "),
+        ("&amp;", "&"),
+    ]
+    .iter()
+    .fold(text, |text, (entity, c)| text.replace(entity, c))
+}
+
+/// Returns the frame of a spinner animation for the current time, to indicate that something is
+/// loading.
+pub fn spinner_frame() -> &'static str {
+    const FRAMES: [&str; 8] = ["⣾", "⣽", "⣻", "⢿", "⡿", "⣟", "⣯", "⣷"];
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    FRAMES[(millis / 100) as usize % FRAMES.len()]
+}
+
 /// Returns a human readable String of milliseconds in the HH:MM:SS format.
 pub fn ms_to_hms(duration: u32) -> String {
     let mut formated_time = String::new();
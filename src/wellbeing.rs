@@ -0,0 +1,135 @@
+//! Keeping track of the listening time for the `break_reminder` and `listening_goal` options.
+
+use std::time::{Duration, Instant};
+
+use chrono::{Local, NaiveDate};
+
+use crate::config::ConfigValues;
+use crate::i18n::tr_args;
+
+/// Pauses at least this long end a stretch of continuous playback.
+const BREAK_LENGTH: Duration = Duration::from_secs(5 * 60);
+
+/// The listening time of the current day and the current stretch of continuous playback.
+#[derive(Clone, Copy, Debug)]
+pub struct ListeningTime {
+    day: NaiveDate,
+    /// Time listened today, not counting the ongoing playback.
+    today: Duration,
+    /// Time listened since the last break, not counting the ongoing playback.
+    stretch: Duration,
+    playing_since: Option<Instant>,
+    paused_since: Option<Instant>,
+    /// How many break reminders were shown during the current stretch.
+    reminders: u32,
+    goal_reached: bool,
+}
+
+impl Default for ListeningTime {
+    fn default() -> Self {
+        Self {
+            day: Local::now().date_naive(),
+            today: Duration::ZERO,
+            stretch: Duration::ZERO,
+            playing_since: None,
+            paused_since: None,
+            reminders: 0,
+            goal_reached: false,
+        }
+    }
+}
+
+impl ListeningTime {
+    /// Start counting, unless playback is already being counted. Playback that resumes after a
+    /// long enough pause starts a new stretch.
+    pub fn start(&mut self) {
+        if self.playing_since.is_some() {
+            return;
+        }
+        if self
+            .paused_since
+            .is_some_and(|paused| paused.elapsed() >= BREAK_LENGTH)
+        {
+            self.stretch = Duration::ZERO;
+            self.reminders = 0;
+        }
+        self.playing_since = Some(Instant::now());
+        self.paused_since = None;
+    }
+
+    /// Stop counting, as playback was paused or stopped.
+    pub fn stop(&mut self) {
+        if let Some(since) = self.playing_since.take() {
+            self.today += since.elapsed();
+            self.stretch += since.elapsed();
+            self.paused_since = Some(Instant::now());
+        }
+    }
+
+    fn ongoing(&self) -> Duration {
+        self.playing_since
+            .map(|since| since.elapsed())
+            .unwrap_or_default()
+    }
+
+    /// The time listened today.
+    pub fn today(&self) -> Duration {
+        self.today + self.ongoing()
+    }
+
+    /// The time listened since the last break.
+    pub fn stretch(&self) -> Duration {
+        self.stretch + self.ongoing()
+    }
+
+    /// Start counting the listening time of a new day once the date changes.
+    fn roll_over(&mut self) {
+        let day = Local::now().date_naive();
+        if day != self.day {
+            self.day = day;
+            self.today = Duration::ZERO;
+            self.goal_reached = false;
+            if let Some(since) = self.playing_since.as_mut() {
+                self.stretch += since.elapsed();
+                *since = Instant::now();
+            }
+        }
+    }
+
+    /// The reminder that is due according to the options in `cfg`, if any. Every reminder is
+    /// only returned once: a break is suggested again after another `break_reminder` minutes
+    /// without a break, and reaching the goal is reported once per day.
+    pub fn reminder(&mut self, cfg: &ConfigValues) -> Option<String> {
+        self.roll_over();
+
+        if let Some(minutes) = cfg.break_reminder.filter(|m| *m > 0) {
+            let interval = Duration::from_secs(u64::from(minutes) * 60);
+            if self.stretch() >= interval * (self.reminders + 1) {
+                self.reminders += 1;
+                return Some(tr_args(
+                    "You have been listening for {} without a break, maybe it's time for one",
+                    &[&format_duration(self.stretch())],
+                ));
+            }
+        }
+
+        if let Some(minutes) = cfg.listening_goal.filter(|m| *m > 0) {
+            let goal = Duration::from_secs(u64::from(minutes) * 60);
+            if !self.goal_reached && self.today() >= goal {
+                self.goal_reached = true;
+                return Some(tr_args(
+                    "You reached your listening goal of {} for today",
+                    &[&format_duration(goal)],
+                ));
+            }
+        }
+
+        None
+    }
+}
+
+/// Format `duration` in hours and minutes, e.g. `1h 05m`.
+pub fn format_duration(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}